@@ -0,0 +1,44 @@
+//! Ip installed to the cache and downloaded to the archive.
+//!
+//! ```no_run
+//! use orbit::catalog::{AnyVersion, Catalog};
+//!
+//! let cache = orbit::catalog::cache_path().unwrap();
+//! let catalog = Catalog::new().installations(&cache).unwrap();
+//! for (name, level) in catalog.inner() {
+//!     if let Some(ip) = level.get_install(&AnyVersion::Latest) {
+//!         println!("{} {}", name, ip.get_man().get_ip().get_version());
+//!     }
+//! }
+//! ```
+
+pub use crate::core::catalog::{Catalog, CatalogError, IpLevel};
+pub use crate::core::ip::{Ip, IpSpec};
+pub use crate::core::pkgid::PkgPart;
+pub use crate::core::uuid::Uuid;
+pub use crate::core::version::{AnyVersion, PartialVersion, Version};
+
+use crate::core::context::Context;
+use crate::util::anyerror::Fault;
+use crate::util::environment::{ORBIT_CACHE, ORBIT_DOWNLOADS, ORBIT_HOME};
+use std::path::PathBuf;
+
+/// Resolves the cache directory the same way the command-line interface does,
+/// respecting `ORBIT_HOME`, `ORBIT_CACHE`, and `ORBIT_LAYOUT`.
+pub fn cache_path() -> Result<PathBuf, Fault> {
+    Ok(Context::new()
+        .home(ORBIT_HOME)?
+        .cache(ORBIT_CACHE)?
+        .get_cache_path()
+        .clone())
+}
+
+/// Resolves the downloads directory the same way the command-line interface
+/// does, respecting `ORBIT_HOME`, `ORBIT_DOWNLOADS`, and `ORBIT_LAYOUT`.
+pub fn downloads_path() -> Result<PathBuf, Fault> {
+    Ok(Context::new()
+        .home(ORBIT_HOME)?
+        .downloads(ORBIT_DOWNLOADS)?
+        .get_downloads_path()
+        .clone())
+}
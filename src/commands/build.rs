@@ -1,21 +1,29 @@
+use super::plan::BLUEPRINT_DELIMITER;
 use super::plan::BLUEPRINT_FILE;
+use crate::core::context;
 use crate::core::context::Context;
 use crate::core::ip::Ip;
+use crate::core::plugin::BlueprintRule;
+use crate::core::plugin::Invocation;
 use crate::core::plugin::Plugin;
+use crate::core::plugin::PluginInput;
 use crate::core::plugin::PluginError;
 use crate::core::plugin::Process;
 use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
 use crate::util::environment;
 use crate::util::environment::EnvVar;
 use crate::util::environment::Environment;
 use crate::util::environment::ORBIT_BLUEPRINT;
 use crate::util::environment::ORBIT_BUILD_DIR;
+use crate::util::logger;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
 use crate::commands::helps::build;
+use std::path::PathBuf;
 
 #[derive(Debug, PartialEq)]
 pub struct Build {
@@ -25,6 +33,8 @@ pub struct Build {
     build_dir: Option<String>,
     args: Vec<String>,
     verbose: bool,
+    dump_env: Option<PathBuf>,
+    replay: Option<PathBuf>,
 }
 
 impl FromCli for Build {
@@ -38,6 +48,8 @@ impl FromCli for Build {
             alias: cli.check_option(Optional::new("plugin").value("alias"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
             command: cli.check_option(Optional::new("command").value("cmd"))?,
+            dump_env: cli.check_option(Optional::new("dump-env").value("file"))?,
+            replay: cli.check_option(Optional::new("replay").value("file"))?,
             // Remaining args
             args: cli.check_remainder()?,
         });
@@ -51,8 +63,8 @@ impl Command<Context> for Build {
     fn exec(&self, c: &Context) -> Self::Status {
         // try to find plugin matching `command` name under the `alias`
         let plug = if let Some(name) = &self.alias {
-            match c.get_config().get_plugins().get(name.as_str()) {
-                Some(&p) => Some(p),
+            match c.get_config().find_plugin(name.as_str()) {
+                Some(p) => Some(p),
                 None => return Err(PluginError::Missing(name.to_string()))?,
             }
         } else {
@@ -83,19 +95,47 @@ impl Command<Context> for Build {
                 "Cannot execute both a plugin and command"
             )))?;
         }
+
+        // re-run a previously captured invocation exactly as it was
+        if let Some(file) = &self.replay {
+            if self.command.is_some() || self.alias.is_some() || self.dump_env.is_some() {
+                return Err(AnyError(format!(
+                    "Cannot replay an invocation alongside a plugin, command, or capture"
+                )))?;
+            }
+            return Invocation::load(file)?.run(self.verbose);
+        }
+
+        // resolve the capture file from the original working directory
+        let dump = match &self.dump_env {
+            Some(file) => Some(std::env::current_dir()?.join(file)),
+            None => None,
+        };
+
         // verify running from an IP directory and enter IP's root directory
         c.goto_ip_path()?;
 
-        // determine the build directory based on cli priority
-        let default_build_dir = c.get_build_dir();
-        let b_dir = self.build_dir.as_ref().unwrap_or(&default_build_dir);
+        // determine the build directory based on cli priority, which the plan
+        // resolved with the same plugin
+        let plan_plugin = match plug {
+            Some(p) => Some(p.get_alias()),
+            None => c
+                .get_config()
+                .get_default_plugin()
+                .and_then(|name| c.get_config().find_plugin(name))
+                .map(|p| p.get_alias()),
+        };
+        let b_dir = context::resolve_build_dir(
+            self.build_dir.as_ref().unwrap_or(&c.get_build_dir()),
+            plan_plugin,
+        );
 
         // todo: is this necessary? -> no, but maybe add a flag/option to bypass (and also allow plugins to specify if they require blueprint in settings)
         // idea: [[plugin]] require-plan = false
         // assert a blueprint file exists in the specified build directory
         if c.get_ip_path()
             .unwrap()
-            .join(b_dir)
+            .join(&b_dir)
             .join(BLUEPRINT_FILE)
             .exists()
             == false
@@ -103,17 +143,22 @@ impl Command<Context> for Build {
             return Err(AnyError(format!("No blueprint file to build from in directory '{}'\n\nTry `orbit plan --build-dir {0}` to generate a blueprint file", b_dir)))?;
         }
 
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+
         Environment::new()
             // read config.toml for setting any env variables
             .from_config(c.get_config())?
             // read ip manifest for env variables
-            .from_ip(&Ip::load(c.get_ip_path().unwrap().clone())?)?
+            .from_ip(&ip)?
             .add(EnvVar::new().key(ORBIT_BLUEPRINT).value(BLUEPRINT_FILE))
-            .add(EnvVar::new().key(ORBIT_BUILD_DIR).value(b_dir))
+            .add(EnvVar::new().key(ORBIT_BUILD_DIR).value(&b_dir))
             .initialize();
 
         // load from .env file from the correct build dir
-        let envs = Environment::new().from_env_file(&c.get_ip_path().unwrap().join(b_dir))?;
+        let envs = Environment::new()
+            .from_env_file(&c.get_ip_path().unwrap().join(&b_dir))?
+            // read the ip's .env files for secrets and machine-specific settings
+            .from_dotenv(c.get_ip_path().unwrap())?;
 
         // check if ORBIT_PLUGIN was set and no command option was set
         let plug = match plug {
@@ -124,8 +169,8 @@ impl Command<Context> for Build {
                 if let Some(plug) = envs.get(environment::ORBIT_PLUGIN) {
                     // verify there was no command option to override default plugin call
                     if self.command.is_none() {
-                        match c.get_config().get_plugins().get(plug.get_value()) {
-                            Some(&p) => Some(p),
+                        match c.get_config().find_plugin(plug.get_value()) {
+                            Some(p) => Some(p),
                             None => {
                                 return Err(PluginError::Missing(plug.get_value().to_string()))?
                             }
@@ -139,51 +184,91 @@ impl Command<Context> for Build {
             }
         };
 
-        envs.initialize();
-
         if plug.is_none() && self.command.is_none() {
             return Err(AnyError(format!(
                 "Building requires a plugin or a command to process"
             )))?;
         }
 
+        // verify the plugin supports the arguments and has its environment
+        if let Some(p) = plug {
+            p.validate_args(&self.args)?;
+            p.validate_env(&envs)?;
+        }
+
+        envs.initialize();
+
+        // export the plugin's own environment variables
+        if let Some(p) = plug {
+            p.get_env().initialize();
+        }
+
+        // prepare the document for plugins that read their standard input
+        let input = match plug.and_then(|p| p.get_input_format()) {
+            Some(fmt) => {
+                let blueprint = std::fs::read_to_string(
+                    c.get_ip_path().unwrap().join(&b_dir).join(BLUEPRINT_FILE),
+                )?;
+                let rules = blueprint
+                    .lines()
+                    .filter_map(|line| {
+                        let mut parts = line.splitn(3, BLUEPRINT_DELIMITER);
+                        Some(BlueprintRule::new(parts.next()?, parts.next()?, parts.next()?))
+                    })
+                    .collect();
+                let vars: Vec<(String, String)> = std::env::vars().collect();
+                Some(PluginInput::new(ip.get_man(), rules, &vars).to_format(fmt)?)
+            }
+            None => None,
+        };
+
         // start command from the build directory
-        self.run(plug, &b_dir)
+        self.run(plug, &b_dir, input, dump)
     }
 }
 
 impl Build {
-    fn run(&self, plug: Option<&Plugin>, dir: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // if there is a match run with the plugin then run it
-        if let Some(p) = plug {
-            p.execute(&self.args, self.verbose, dir)
+    /// Creates a build that runs the plugin `alias` with `args` from `build_dir`.
+    pub fn with_plugin(alias: String, build_dir: String, args: Vec<String>) -> Self {
+        Self {
+            alias: Some(alias),
+            list: false,
+            command: None,
+            build_dir: Some(build_dir),
+            args,
+            verbose: false,
+            dump_env: None,
+            replay: None,
+        }
+    }
+
+    fn run(
+        &self,
+        plug: Option<&Plugin>,
+        dir: &str,
+        input: Option<String>,
+        dump: Option<PathBuf>,
+    ) -> Result<(), Fault> {
+        // capture the process for the plugin or command
+        let proc = if let Some(p) = plug {
+            p.to_invocation(&self.args, dir, input)
         } else if let Some(cmd) = &self.command {
-            if self.verbose == true {
-                let s = self
-                    .args
-                    .iter()
-                    .fold(String::new(), |x, y| x + "\"" + &y + "\" ");
-                println!("info: Running: {} {}", cmd, s);
-            }
-            let mut proc = crate::util::filesystem::invoke(
-                dir,
-                cmd,
-                &self.args,
-                Context::enable_windows_bat_file_match(),
-            )?;
-            let exit_code = proc.wait()?;
-            match exit_code.code() {
-                Some(num) => {
-                    if num != 0 {
-                        Err(AnyError(format!("Exited with error code: {}", num)))?
-                    } else {
-                        Ok(())
-                    }
-                }
-                None => Err(AnyError(format!("Terminated by signal")))?,
-            }
+            Invocation::new(cmd.clone(), self.args.clone(), dir, None)
         } else {
-            Ok(())
+            return Ok(());
+        };
+        // save the invocation to replay it later
+        if let Some(file) = dump {
+            proc.save(&file)?;
+            logger::warn(format!(
+                "invocation file {} contains the full environment, which may include secrets such as access tokens; do not share or commit it",
+                file.display()
+            ));
+            logger::info(format!(
+                "Saved invocation to {}; replay it with `orbit build --replay {0}`",
+                file.display()
+            ));
         }
+        proc.run(self.verbose)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,321 @@
+//! Generates a continuous integration pipeline that fetches, checks, plans, and
+//! builds the current ip.
+
+use crate::commands::helps::ci;
+use crate::core::context::Context;
+use crate::core::lockfile::IP_LOCK_FILE;
+use crate::core::plugin::PluginError;
+use crate::util::anyerror::AnyError;
+use crate::util::logger;
+use crate::OrbitResult;
+use clif::arg::{Flag, Optional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::path::PathBuf;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The target of the release installed by the pipeline.
+const CI_TARGET: &str = "x86_64-linux";
+
+const RELEASES_URL: &str = "https://github.com/c-rus/orbit/releases/download";
+
+#[derive(Debug, PartialEq)]
+pub struct Ci {
+    github: bool,
+    gitlab: bool,
+    force: bool,
+    plugins: Vec<String>,
+}
+
+impl FromCli for Ci {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(ci::HELP).ref_usage(2..4))?;
+        let command = Ok(Ci {
+            // Flags
+            github: cli.check_flag(Flag::new("github"))?,
+            gitlab: cli.check_flag(Flag::new("gitlab"))?,
+            force: cli.check_flag(Flag::new("force"))?,
+            // Options
+            plugins: cli
+                .check_option_all(Optional::new("plugin").value("name"))?
+                .unwrap_or(Vec::new()),
+        });
+        command
+    }
+}
+
+impl Command<Context> for Ci {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify exactly one service is provided
+        let service = match (self.github, self.gitlab) {
+            (true, false) => Service::GitHub,
+            (false, true) => Service::GitLab,
+            _ => {
+                return Err(AnyError(format!(
+                    "Select exactly one service with '--github' or '--gitlab'"
+                )))?
+            }
+        };
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+        let root = c.get_ip_path().unwrap();
+
+        // use every configured plugin unless specific plugins are requested
+        let plugins = match self.plugins.is_empty() {
+            true => {
+                let mut names: Vec<String> = c
+                    .get_config()
+                    .get_plugins()
+                    .keys()
+                    .map(|k| k.to_string())
+                    .collect();
+                names.sort();
+                names
+            }
+            false => self.plugins.clone(),
+        };
+        for name in &plugins {
+            let plug = match c.get_config().find_plugin(name.as_str()) {
+                Some(p) => p,
+                None => return Err(PluginError::Missing(name.to_string()))?,
+            };
+            // the pipeline only sees the plugins committed with the ip
+            if let Some(origin) = plug.get_origin() {
+                if origin.starts_with(root) == false {
+                    logger::warn(format!("plugin '{}' is defined outside of the ip in {:?} and will not be available to the pipeline unless it is also defined in the ip's configuration", name, origin));
+                }
+            }
+        }
+        if plugins.is_empty() == true {
+            logger::warn("no plugins are configured, so the pipeline will not build the ip");
+        }
+
+        let dest = root.join(service.get_file());
+        if dest.exists() == true && self.force == false {
+            return Err(AnyError(format!(
+                "A pipeline already exists at {:?}\n\nUse '--force' to overwrite it.",
+                dest
+            )))?;
+        }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = match service {
+            Service::GitHub => write_github(&plugins, VERSION),
+            Service::GitLab => write_gitlab(&plugins, VERSION),
+        };
+        std::fs::write(&dest, text)?;
+        logger::info(format!("wrote pipeline to {:?}", dest));
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Service {
+    GitHub,
+    GitLab,
+}
+
+impl Service {
+    /// Returns the path, relative to the repository's root, where the service
+    /// reads its pipeline.
+    fn get_file(&self) -> PathBuf {
+        match self {
+            Self::GitHub => PathBuf::from(".github/workflows/orbit.yml"),
+            Self::GitLab => PathBuf::from(".gitlab-ci.yml"),
+        }
+    }
+}
+
+/// Creates a GitHub Actions workflow that runs once for each plugin in `plugins`.
+///
+/// The cache of the default `ORBIT_HOME` is keyed on the ip's lock file.
+fn write_github(plugins: &[String], version: &str) -> String {
+    let mut text = format!(
+        "# This workflow was generated by `orbit ci --github`.
+name: orbit
+
+on:
+  push:
+  pull_request:
+
+env:
+  ORBIT_VERSION: \"{}\"
+
+jobs:
+  orbit:
+    runs-on: ubuntu-latest
+",
+        version
+    );
+    if plugins.is_empty() == false {
+        text += &format!(
+            "    strategy:
+      fail-fast: false
+      matrix:
+        plugin: [{}]
+",
+            plugins.join(", ")
+        );
+    }
+    text += &format!(
+        "    steps:
+      - uses: actions/checkout@v4
+
+      - name: Install orbit
+        run: |
+          curl -sSfL -o \"$RUNNER_TEMP/orbit.zip\" \"{0}/${{ORBIT_VERSION}}/orbit-${{ORBIT_VERSION}}-{1}.zip\"
+          unzip -q \"$RUNNER_TEMP/orbit.zip\" -d \"$RUNNER_TEMP\"
+          echo \"$RUNNER_TEMP/orbit-${{ORBIT_VERSION}}-{1}/bin\" >> \"$GITHUB_PATH\"
+
+      - name: Cache ORBIT_HOME
+        uses: actions/cache@v4
+        with:
+          path: ~/.orbit
+          key: orbit-${{{{ runner.os }}}}-${{{{ hashFiles('{2}') }}}}
+          restore-keys: orbit-${{{{ runner.os }}}}-
+
+      - name: Fetch dependencies
+        run: orbit fetch
+
+      - name: Check dependencies
+        run: orbit tree
+",
+        RELEASES_URL, CI_TARGET, IP_LOCK_FILE
+    );
+    match plugins.is_empty() {
+        true => {
+            text += "
+      - name: Plan
+        run: orbit plan
+"
+        }
+        false => {
+            text += "
+      # install the tools required by the plugin before this point
+
+      - name: Plan
+        run: orbit plan --plugin ${{ matrix.plugin }}
+
+      - name: Build
+        run: orbit build --plugin ${{ matrix.plugin }}
+"
+        }
+    }
+    text
+}
+
+/// Creates a GitLab CI/CD pipeline that runs once for each plugin in `plugins`.
+///
+/// GitLab only caches paths within the project, so `ORBIT_HOME` is placed in
+/// the project and ignored when orbit collects the ip's files.
+fn write_gitlab(plugins: &[String], version: &str) -> String {
+    let mut text = format!(
+        "# This pipeline was generated by `orbit ci --gitlab`.
+variables:
+  ORBIT_VERSION: \"{}\"
+  ORBIT_HOME: \"$CI_PROJECT_DIR/.orbit-home\"
+
+orbit:
+  image: ubuntu:22.04
+",
+        version
+    );
+    if plugins.is_empty() == false {
+        text += &format!(
+            "  parallel:
+    matrix:
+      - PLUGIN: [{}]
+",
+            plugins.join(", ")
+        );
+    }
+    text += &format!(
+        "  cache:
+    key:
+      files:
+        - {2}
+    paths:
+      - .orbit-home/
+  before_script:
+    - apt-get update && apt-get install -y curl unzip
+    - curl -sSfL -o /tmp/orbit.zip \"{0}/${{ORBIT_VERSION}}/orbit-${{ORBIT_VERSION}}-{1}.zip\"
+    - unzip -q /tmp/orbit.zip -d /opt
+    - export PATH=\"/opt/orbit-${{ORBIT_VERSION}}-{1}/bin:$PATH\"
+    - mkdir -p \"$ORBIT_HOME\"
+    - echo '*' > \"$ORBIT_HOME/.orbitignore\"
+",
+        RELEASES_URL, CI_TARGET, IP_LOCK_FILE
+    );
+    match plugins.is_empty() {
+        true => {
+            text += "  script:
+    - orbit fetch
+    - orbit tree
+    - orbit plan
+"
+        }
+        false => {
+            text += "    # install the tools required by the plugin after this point
+  script:
+    - orbit fetch
+    - orbit tree
+    - orbit plan --plugin \"$PLUGIN\"
+    - orbit build --plugin \"$PLUGIN\"
+"
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn github_workflow() {
+        let text = write_github(&[String::from("ghdl"), String::from("vivado")], "0.10.1");
+        assert_eq!(text.contains("  ORBIT_VERSION: \"0.10.1\"\n"), true);
+        assert_eq!(text.contains("        plugin: [ghdl, vivado]\n"), true);
+        assert_eq!(text.contains("https://github.com/c-rus/orbit/releases/download/${ORBIT_VERSION}/orbit-${ORBIT_VERSION}-x86_64-linux.zip"), true);
+        assert_eq!(
+            text.contains("          key: orbit-${{ runner.os }}-${{ hashFiles('Orbit.lock') }}\n"),
+            true
+        );
+        assert_eq!(
+            text.contains("        run: orbit build --plugin ${{ matrix.plugin }}\n"),
+            true
+        );
+
+        // without plugins the ip is only planned
+        let text = write_github(&[], "0.10.1");
+        assert_eq!(text.contains("matrix"), false);
+        assert_eq!(text.contains("        run: orbit plan\n"), true);
+        assert_eq!(text.contains("orbit build"), false);
+    }
+
+    #[test]
+    fn gitlab_pipeline() {
+        let text = write_gitlab(&[String::from("ghdl")], "0.10.1");
+        assert_eq!(text.contains("      - PLUGIN: [ghdl]\n"), true);
+        assert_eq!(text.contains("        - Orbit.lock\n"), true);
+        assert_eq!(
+            text.contains(
+                "    - export PATH=\"/opt/orbit-${ORBIT_VERSION}-x86_64-linux/bin:$PATH\"\n"
+            ),
+            true
+        );
+        assert_eq!(
+            text.contains("    - orbit build --plugin \"$PLUGIN\"\n"),
+            true
+        );
+
+        let text = write_gitlab(&[], "0.10.1");
+        assert_eq!(text.contains("PLUGIN"), false);
+        assert_eq!(text.contains("    - orbit plan\n"), true);
+    }
+}
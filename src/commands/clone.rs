@@ -0,0 +1,242 @@
+use crate::commands::download;
+use crate::commands::helps::clone;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::credential::Credential;
+use crate::core::ip::{Ip, PartialIpSpec};
+use crate::core::iparchive::IpArchive;
+use crate::core::manifest::{ORBIT_METADATA_FILE, ORBIT_SUM_FILE};
+use crate::core::rewrite;
+use crate::core::version::Version;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::filesystem::Standardize;
+use crate::util::logger;
+use crate::OrbitResult;
+use clif::arg::{Optional, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+#[derive(Debug, PartialEq)]
+pub struct CloneIp {
+    ip: PartialIpSpec,
+    dest: Option<PathBuf>,
+    tag: Option<String>,
+}
+
+impl FromCli for CloneIp {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(clone::HELP).ref_usage(2..4))?;
+        let command = Ok(CloneIp {
+            tag: cli.check_option(Optional::new("tag"))?,
+            ip: cli.require_positional(Positional::new("ip"))?,
+            dest: cli.check_positional(Positional::new("dir"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for CloneIp {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let dest = match &self.dest {
+            Some(d) => d.clone(),
+            None => PathBuf::from(self.ip.get_name().to_string()),
+        };
+        if dest.exists() == true {
+            return Err(AnyError(format!(
+                "destination {:?} already exists",
+                PathBuf::standardize(dest)
+            )))?;
+        }
+
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .downloads(c.get_downloads_path())?;
+
+        let target = match catalog
+            .inner()
+            .get(self.ip.get_name())
+            .and_then(|lvl| lvl.get(true, self.ip.get_version()))
+        {
+            Some(t) => t,
+            None => {
+                return Err(AnyError(format!(
+                    "ip {} does not exist in the catalog\n\nTry `orbit search {}` to see the available versions",
+                    self.ip,
+                    self.ip.get_name()
+                )))?
+            }
+        };
+        let spec = target.get_man().get_ip().into_ip_spec();
+
+        let creds = c.get_config().get_credentials();
+        let source = target
+            .get_man()
+            .get_ip()
+            .get_source()
+            .map(|src| (src, rewrite::resolve_url(src.get_url(), &c.get_config().get_rewrites(), &creds)));
+
+        match source {
+            Some((src, url)) if is_git_url(&url) == true => {
+                // an explicit tag must exist, otherwise fall back to the version's tags
+                let tags = match &self.tag {
+                    Some(t) => vec![t.clone()],
+                    None => tag_candidates(src.get_tag(), spec.get_version()),
+                };
+                let run = || {
+                    Self::clone_git(&url, &dest, &tags, self.tag.is_some())?;
+                    if c.get_config().get_init_submodules() == true {
+                        download::init_submodules(&dest)?;
+                    }
+                    Ok(())
+                };
+                match Credential::find(&creds, &url) {
+                    Some(cred) => cred.with_git_env(run)?,
+                    None => run()?,
+                }
+                if let Some(path) = src.get_path() {
+                    logger::info(format!(
+                        "Ip {} is located at {:?} within the cloned repository",
+                        spec, path
+                    ));
+                }
+            }
+            _ => {
+                if self.tag.is_some() == true {
+                    logger::warn(format!(
+                        "ignoring tag because the source for ip {} is not a git repository",
+                        spec
+                    ));
+                }
+                Self::copy_files(target, &dest)?;
+                logger::info(format!(
+                    "Copied the files of ip {} from the catalog since its source is not a git repository",
+                    spec
+                ));
+            }
+        }
+        logger::info(format!(
+            "Cloned ip {} into {:?}",
+            spec,
+            PathBuf::standardize(dest)
+        ));
+        Ok(())
+    }
+}
+
+impl CloneIp {
+    /// Clones the git repository at `url` into `dest` and checks out the first
+    /// tag within `tags` that exists.
+    ///
+    /// When `strict` is set, failing to find any of the `tags` is an error instead
+    /// of staying on the default branch.
+    fn clone_git(url: &str, dest: &PathBuf, tags: &[String], strict: bool) -> Result<(), Fault> {
+        logger::info(format!("Cloning {} ...", url));
+        let dest_str = filesystem::into_std_str(dest.clone());
+        // separate the url from the options so it is never read as one
+        if git(None, &["clone", "--quiet", "--", url, &dest_str])? == false {
+            return Err(AnyError(format!("failed to clone repository {}", url)))?;
+        }
+        for tag in tags {
+            let refname = format!("refs/tags/{}", tag);
+            if git(Some(dest), &["checkout", "--quiet", &refname])? == true {
+                return Ok(());
+            }
+        }
+        if strict == true {
+            // do not leave behind a clone at the wrong revision
+            std::fs::remove_dir_all(dest)?;
+            return Err(AnyError(format!(
+                "tag {} does not exist in repository {}",
+                tags.join(" or "),
+                url
+            )))?;
+        }
+        logger::warn(format!(
+            "no tag matching {} was found; staying on the default branch",
+            tags.join(" or ")
+        ));
+        Ok(())
+    }
+
+    /// Places the files of the `ip` from the catalog into `dest` without the
+    /// metadata orbit adds to installations.
+    fn copy_files(ip: &Ip, dest: &PathBuf) -> Result<(), Fault> {
+        match ip.get_mapping().as_bytes() {
+            Some(bytes) => IpArchive::extract(bytes, dest)?,
+            None => filesystem::copy(ip.get_root(), dest, false, None)?,
+        }
+        for file in [ORBIT_SUM_FILE, ORBIT_METADATA_FILE] {
+            let path = dest.join(file);
+            if path.exists() == true {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs git with the `args` from the `dir` directory, or the current directory
+/// if `dir` is `None`, and returns whether it succeeded.
+fn git(dir: Option<&PathBuf>, args: &[&str]) -> Result<bool, Fault> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(args).stderr(Stdio::null());
+    if let Some(d) = dir {
+        cmd.current_dir(d);
+    }
+    match cmd.status() {
+        Ok(status) => Ok(status.success()),
+        Err(e) => Err(AnyError(format!("failed to run git: {}", e)))?,
+    }
+}
+
+/// Checks if the `url` points to a git repository rather than an archive.
+fn is_git_url(url: &str) -> bool {
+    url.ends_with(".git") == true
+        || url.starts_with("git@") == true
+        || url.starts_with("git://") == true
+        || url.starts_with("ssh://") == true
+}
+
+/// Lists the tags to try checking out, in order of preference.
+fn tag_candidates(tag: Option<&String>, version: &Version) -> Vec<String> {
+    let mut tags = Vec::new();
+    if let Some(t) = tag {
+        tags.push(t.clone());
+    }
+    tags.push(version.to_string());
+    tags.push(format!("v{}", version));
+    tags
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn git_url() {
+        assert_eq!(is_git_url("https://github.com/c-rus/gates.git"), true);
+        assert_eq!(is_git_url("git@gitlab.com:c-rus/gates.git"), true);
+        assert_eq!(is_git_url("ssh://git@gitlab.com/c-rus/gates"), true);
+        assert_eq!(
+            is_git_url("https://github.com/c-rus/gates/archive/refs/tags/1.0.0.zip"),
+            false
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let version = Version::from_str("1.0.0").unwrap();
+        assert_eq!(tag_candidates(None, &version), vec!["1.0.0", "v1.0.0"]);
+        assert_eq!(
+            tag_candidates(Some(&String::from("release-1")), &version),
+            vec!["release-1", "1.0.0", "v1.0.0"]
+        );
+    }
+}
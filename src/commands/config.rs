@@ -2,10 +2,14 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::core::config::ConfigDocument;
+use crate::core::config::Configs;
+use crate::core::config::Locality;
 use crate::core::config::CONFIG_FILE;
+use crate::core::config::DEFAULT_CONFIG;
 use crate::core::context::Context;
 use crate::core::manifest::FromFile;
 use crate::util::anyerror::AnyError;
+use crate::util::filesystem;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
@@ -35,6 +39,9 @@ pub struct Config {
     append: Vec<Entry>,
     set: Vec<Entry>,
     unset: Vec<String>,
+    get: Option<String>,
+    list: bool,
+    edit: bool,
 }
 
 impl FromCli for Config {
@@ -44,7 +51,10 @@ impl FromCli for Config {
             // Flags
             global: cli.check_flag(Flag::new("global"))?,
             local: cli.check_flag(Flag::new("local"))?,
+            list: cli.check_flag(Flag::new("list"))?,
+            edit: cli.check_flag(Flag::new("edit"))?,
             // Options
+            get: cli.check_option(Optional::new("get").value("key"))?,
             append: cli
                 .check_option_all(Optional::new("append").value("key=value"))?
                 .unwrap_or(Vec::new()),
@@ -71,25 +81,41 @@ impl Command<Context> for Config {
                 "--global".yellow()
             )))?;
         }
-        let (mut cfg, file) = if self.local == true {
+
+        // display the effective configuration values and exit
+        if let Some(key) = &self.get {
+            return match c.get_config().get_value(key)? {
+                Some(toml::Value::String(v)) => Ok(println!("{}", v)),
+                Some(v) => Ok(println!("{}", v)),
+                None => Err(AnyError(format!("key '{}' is not defined in any configuration", key)))?,
+            };
+        }
+        if self.list == true {
+            return Self::list(c.get_all_configs());
+        }
+        let file = if self.local == true {
             match c.get_ip_path() {
-                Some(path) => {
-                    let file = path.join(".orbit").join(CONFIG_FILE);
-                    (ConfigDocument::from_file(&file)?, file)
-                }
+                Some(path) => path.join(".orbit").join(CONFIG_FILE),
                 None => {
                     return Err(AnyError(format!(
                         "no ip detected in the current directory to modify local configurations"
                     )))?
                 }
             }
+        } else {
+            c.get_all_configs().get_global().0.clone()
+        };
+
+        // open the configuration file in a text editor and exit
+        if self.edit == true {
+            return Self::edit(c, &file);
+        }
+
+        let mut cfg = if self.local == true {
+            ConfigDocument::from_file(&file)?
         } else {
             // duplicate the configuration so we can potentially mutate it
-            let file = c.get_all_configs().get_global().0.clone();
-            (
-                ConfigDocument::from_file(&file).expect("already should be parsed correctly"),
-                file,
-            )
+            ConfigDocument::from_file(&file).expect("already should be parsed correctly")
         };
         // modify the settings for cfg file
         self.run(&mut cfg, &file)
@@ -97,6 +123,51 @@ impl Command<Context> for Config {
 }
 
 impl Config {
+    /// Opens the configuration `file` in the user's text editor.
+    ///
+    /// The file is written with the commented default configuration if it is missing or empty.
+    fn edit(c: &Context, file: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        // the editor from the configuration takes precedence over the environment
+        let editor = match c.get_config().get_general().and_then(|g| g.get_editor()) {
+            Some(e) => e.to_string(),
+            None => match std::env::var("EDITOR") {
+                Ok(e) if e.is_empty() == false => e,
+                _ => return Err(AnyError(format!("no text editor is set\n\nTry setting the `EDITOR` environment variable or `general.editor` in a configuration file")))?,
+            },
+        };
+        let is_empty = std::fs::read_to_string(&file)
+            .map(|s| s.trim().is_empty())
+            .unwrap_or(true);
+        if is_empty == true {
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file, DEFAULT_CONFIG)?;
+        }
+        // the editor may be followed by its own arguments
+        let mut parts = editor.split_whitespace();
+        let status = std::process::Command::new(parts.next().unwrap_or_default())
+            .args(parts)
+            .arg(&file)
+            .status()?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(AnyError(format!("text editor '{}' exited with an error", editor)))?,
+        }
+    }
+
+    /// Displays every effective configuration entry along with the file it is defined in.
+    fn list(configs: &Configs) -> Result<(), Box<dyn std::error::Error>> {
+        for (key, value, path, lvl) in configs.get_entries()? {
+            let origin = match lvl {
+                Locality::Environment => String::from("environment"),
+                _ => filesystem::into_std_str(path.clone()),
+            };
+            println!("{} = {}  # {}", key, value, origin);
+        }
+        Ok(())
+    }
+
     fn run(
         &self,
         cfg: &mut ConfigDocument,
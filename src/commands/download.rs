@@ -1,6 +1,7 @@
 use crate::core::catalog::Catalog;
 use crate::core::catalog::DownloadSlot;
 use crate::core::context::Context;
+use crate::core::credential::Credential;
 use crate::core::ip::Ip;
 use crate::core::ip::IpSpec;
 use crate::core::iparchive::IpArchive;
@@ -10,12 +11,17 @@ use crate::core::manifest;
 use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::core::plugin::Process;
 use crate::core::protocol::Protocol;
+use crate::core::rewrite::{self, Rewrite};
 use crate::core::source::Source;
 use crate::core::variable::VariableTable;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
 use crate::util::environment::Environment;
+use crate::util::filelock::FileLock;
+use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
+use crate::util::logger;
+use crate::util::timing;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
@@ -23,7 +29,7 @@ use clif::Cli;
 use clif::Error as CliError;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, PathBuf};
 use tempfile::TempDir;
 use crate::commands::helps::download;
 
@@ -103,7 +109,10 @@ impl Command<Context> for Download {
             // read ip manifest for env variables
             .from_ip(&Ip::load(c.get_ip_path().unwrap().clone())?)?;
         
-        let vtable = VariableTable::new().load_environment(&env)?;
+        let vtable = VariableTable::new()
+            .load_environment(&env)?
+            // read config.toml for user-defined variables
+            .load_config(c.get_config())?;
         env.initialize();
 
         // default behavior is report only missing installations
@@ -125,6 +134,9 @@ impl Command<Context> for Download {
             Self::download_all(
                 &downloads,
                 &proto_map,
+                &c.get_config().get_credentials(),
+                &c.get_config().get_rewrites(),
+                c.get_config().get_init_submodules(),
                 vtable,
                 self.verbose,
                 self.queue_dir.as_ref(),
@@ -167,9 +179,18 @@ impl Download {
         queue: Option<&PathBuf>,
         download_dir: &PathBuf,
         protocols: &HashMap<&str, &Protocol>,
+        creds: &[&Credential],
+        rewrites: &[&Rewrite],
+        submodules: bool,
         verbose: bool,
         _force: bool,
     ) -> Result<(), Fault> {
+        let _span = timing::span(format!("download {}", spec));
+        // access the source at its rewritten url
+        let url = rewrite::resolve_url(src.get_url(), rewrites, creds);
+        if url != src.get_url() {
+            logger::debug(format!("Rewrote source url {} to {}", src.get_url(), url));
+        }
         // use the user-provided queue directory or simply use a temporary directory
         let queue = match queue {
             Some(q) => {
@@ -183,10 +204,10 @@ impl Download {
         if let Some(proto) = src.get_protocol() {
             match protocols.get(proto.as_str()) {
                 Some(&entry) => {
-                    println!(
-                        "info: Downloading {} over \"{}\" protocol ...",
+                    logger::info(format!(
+                        "Downloading {} over \"{}\" protocol ...",
                         spec, &proto
-                    );
+                    ));
                     let std_queue = PathBuf::standardize(&queue);
                     vtable.add(
                         "orbit.queue",
@@ -195,15 +216,27 @@ impl Download {
                     // update variable table for this lock entry
                     vtable.add("orbit.ip.name", spec.get_name().as_ref());
                     vtable.add("orbit.ip.version", &spec.get_version().to_string());
-                    vtable.add("orbit.ip.source.url", src.get_url());
+                    vtable.add("orbit.ip.source.url", &url);
                     vtable.add("orbit.ip.source.protocol", entry.get_name());
                     vtable.add(
                         "orbit.ip.source.tag",
                         src.get_tag().unwrap_or(&String::new()),
                     );
+                    vtable.add(
+                        "orbit.ip.source.path",
+                        &src.get_path()
+                            .map(|p| filesystem::into_std_str(p.clone()))
+                            .unwrap_or_default(),
+                    );
                     // allow the user to handle placing the code in the queue
                     let entry: Protocol = entry.clone().replace_vars_in_args(&vtable);
-                    if let Err(err) = entry.execute(&[], verbose, &std_queue.to_str().unwrap()) {
+                    let run = || entry.execute(&[], verbose, &std_queue.to_str().unwrap(), None);
+                    // authenticate with the host without passing secrets as arguments
+                    let result = match Credential::find(creds, &url) {
+                        Some(cred) => cred.with_git_env(run),
+                        None => run(),
+                    };
+                    if let Err(err) = result {
                         fs::remove_dir_all(queue)?;
                         return Err(err);
                     }
@@ -219,14 +252,19 @@ impl Download {
         }
         // try to use default protocol
         if src.is_default() == true {
-            println!("info: Downloading {} ...", spec);
-            if let Err(err) = Protocol::single_download(src.get_url(), &queue) {
+            logger::info(format!("Downloading {} ...", spec));
+            let result = match Credential::find(creds, &url) {
+                Some(cred) => cred.get_http_auth(),
+                None => Ok(None),
+            }
+            .and_then(|auth| Protocol::single_download(&url, &queue, auth.as_ref()));
+            if let Err(err) = result {
                 fs::remove_dir_all(queue)?;
                 return Err(err);
             }
         }
         // move the IP to the downloads folder
-        if let Err(err) = Self::move_to_download_dir(&queue, download_dir, spec) {
+        if let Err(err) = Self::move_to_download_dir(&queue, download_dir, spec, Some(src), submodules) {
             fs::remove_dir_all(queue)?;
             return Err(err);
         }
@@ -239,11 +277,17 @@ impl Download {
         queue: &PathBuf,
         downloads: &PathBuf,
         spec: &IpSpec,
+        src: Option<&Source>,
+        submodules: bool,
     ) -> Result<(), Fault> {
         // code is in the queue now, move it to the downloads/ folder
 
-        // find the IP
-        for entry in manifest::find_file(&queue, IP_MANIFEST_FILE, false)? {
+        // find the IP, which must be at the source's path when the source has one
+        let manifests = match src.and_then(|s| s.get_path()) {
+            Some(path) => Self::find_at_path(queue, path)?,
+            None => manifest::find_file(&queue, IP_MANIFEST_FILE, false)?,
+        };
+        for entry in manifests {
             // check if this is our IP
             match Ip::load(entry.parent().unwrap().to_path_buf()) {
                 Ok(temp) => {
@@ -251,10 +295,21 @@ impl Download {
                     if temp.get_man().get_ip().get_name() == spec.get_name()
                         && temp.get_man().get_ip().get_version() == spec.get_version()
                     {
+                        if submodules == true {
+                            init_submodules(temp.get_root())?;
+                        }
+                        for path in Self::find_empty_submodules(temp.get_root()) {
+                            logger::warn(format!(
+                                "submodule '{}' of ip {} is empty; try setting `init-submodules = true` in the configuration",
+                                path, spec
+                            ));
+                        }
                         // zip the project to the downloads directory
                         let download_slot_name =
                             DownloadSlot::new(spec.get_name(), spec.get_version(), temp.get_uuid());
                         let full_download_path = downloads.join(&download_slot_name.as_ref());
+                        // prevent another orbit process from writing to the same download
+                        let _lock = FileLock::acquire(&full_download_path)?;
                         IpArchive::write(&temp, &full_download_path)?;
                         return Ok(());
                     }
@@ -263,12 +318,62 @@ impl Download {
             }
         }
         // could not find the IP
-        Err(AnyError(format!("Failed to detect/load the IP's manifest")))?
+        match src.and_then(|s| s.get_path()) {
+            Some(path) => Err(AnyError(format!(
+                "Failed to detect/load the IP's manifest at source path {:?}",
+                path
+            )))?,
+            None => Err(AnyError(format!("Failed to detect/load the IP's manifest")))?,
+        }
+    }
+
+    /// Finds the manifest at the `path` within the `queue` or within one of the
+    /// queue's top-level directories, which archives of a repository often add.
+    fn find_at_path(queue: &PathBuf, path: &PathBuf) -> Result<Vec<PathBuf>, Fault> {
+        if path.is_absolute() == true || path.components().any(|c| c == Component::ParentDir) {
+            return Err(AnyError(format!(
+                "source path {:?} must be relative to the root of the source's contents",
+                path
+            )))?;
+        }
+        let mut roots = vec![queue.clone()];
+        for entry in fs::read_dir(queue)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() == true {
+                roots.push(entry.path());
+            }
+        }
+        Ok(roots
+            .into_iter()
+            .map(|root| root.join(path).join(IP_MANIFEST_FILE))
+            .filter(|man| man.is_file() == true)
+            .collect())
+    }
+
+    /// Lists the git submodules declared in the `.gitmodules` file at `root`
+    /// whose directories are missing or empty, which occurs when a repository is
+    /// cloned without initializing its submodules.
+    fn find_empty_submodules(root: &PathBuf) -> Vec<String> {
+        let text = match std::fs::read_to_string(root.join(GIT_MODULES_FILE)) {
+            Ok(t) => t,
+            Err(_) => return Vec::new(),
+        };
+        submodule_paths(&text)
+            .into_iter()
+            .filter(|p| match std::fs::read_dir(root.join(p)) {
+                Ok(mut entries) => entries.next().is_none(),
+                Err(_) => true,
+            })
+            .map(|p| p.to_string())
+            .collect()
     }
 
     pub fn download_all(
         downloads: &Vec<(IpSpec, &Source)>,
         proto_map: &HashMap<&str, &Protocol>,
+        creds: &[&Credential],
+        rewrites: &[&Rewrite],
+        submodules: bool,
         vtable: VariableTable,
         verbose: bool,
         queue: Option<&PathBuf>,
@@ -277,14 +382,14 @@ impl Download {
     ) -> Result<(), Fault> {
         match downloads.len() {
             0 => {
-                println!("info: No missing downloads");
+                logger::info("No missing downloads");
                 return Ok(());
             }
             1 => {
-                println!("info: Downloading 1 package ...")
+                logger::info("Downloading 1 package ...")
             }
             _ => {
-                println!("info: Downloading {} packages ...", downloads.len())
+                logger::info(format!("Downloading {} packages ...", downloads.len()))
             }
         }
         let mut vtable = vtable;
@@ -296,6 +401,9 @@ impl Download {
                 queue,
                 &download_dir,
                 &proto_map,
+                creds,
+                rewrites,
+                submodules,
                 verbose,
                 force,
             ) {
@@ -311,4 +419,124 @@ impl Download {
     }
 }
 
+/// The file that declares a git repository's submodules.
+const GIT_MODULES_FILE: &str = ".gitmodules";
+
+/// Initializes the git submodules of the repository at `root`, including any
+/// nested submodules, if it declares them within a `.gitmodules` file.
+pub fn init_submodules(root: &PathBuf) -> Result<(), Fault> {
+    if root.join(GIT_MODULES_FILE).is_file() == false {
+        return Ok(());
+    }
+    let output = std::process::Command::new("git")
+        .args(["submodule", "update", "--init", "--recursive"])
+        .current_dir(root)
+        .output();
+    match output {
+        Ok(out) if out.status.success() == true => Ok(()),
+        Ok(out) => Err(AnyError(format!(
+            "failed to initialize the git submodules at {:?}: {}",
+            PathBuf::standardize(root),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )))?,
+        Err(e) => Err(AnyError(format!("failed to run git: {}", e)))?,
+    }
+}
+
+/// Collects the `path` of every submodule declared within the `.gitmodules` `text`.
+fn submodule_paths(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .filter(|(key, _)| key.trim() == "path")
+        .map(|(_, value)| value.trim())
+        .collect()
+}
+
 // add <url> argument to download? with --protocol <alias> option?
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manifest_at_source_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = dir.path().to_path_buf();
+        let nested = queue.join("cores-1.0.0/ips/fifo");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(queue.join("cores-1.0.0").join(IP_MANIFEST_FILE), "").unwrap();
+        std::fs::write(nested.join(IP_MANIFEST_FILE), "").unwrap();
+
+        // the manifest at the repository's root is skipped
+        assert_eq!(
+            Download::find_at_path(&queue, &PathBuf::from("ips/fifo")).unwrap(),
+            vec![nested.join(IP_MANIFEST_FILE)]
+        );
+        assert_eq!(
+            Download::find_at_path(&queue, &PathBuf::from("ips/uart")).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+        assert!(Download::find_at_path(&queue, &PathBuf::from("../fifo")).is_err());
+    }
+
+    #[test]
+    fn gitmodules_paths() {
+        let text = r#"[submodule "vendor/uart"]
+	path = vendor/uart
+	url = https://github.com/c-rus/uart.git
+[submodule "lib"]
+	path=lib
+	url = ../lib.git
+"#;
+        assert_eq!(submodule_paths(text), vec!["vendor/uart", "lib"]);
+    }
+
+    #[test]
+    fn submodules_affect_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let git = |cwd: &PathBuf, args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=orbit", "-c", "user.email=orbit@test"])
+                .args(["-c", "protocol.file.allow=always"])
+                .args(args)
+                .current_dir(cwd)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        // a repository that vendors another repository as a submodule
+        let (sub, top) = (root.join("uart"), root.join("top"));
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir_all(&top).unwrap();
+        std::fs::write(sub.join("uart.vhd"), "entity uart is end entity;").unwrap();
+        git(&sub, &["init", "--quiet"]);
+        git(&sub, &["add", "."]);
+        git(&sub, &["commit", "--quiet", "-m", "init"]);
+        std::fs::write(top.join(IP_MANIFEST_FILE), "[ip]\nname = \"top\"\nversion = \"1.0.0\"\n").unwrap();
+        git(&top, &["init", "--quiet"]);
+        git(&top, &["submodule", "--quiet", "add", "../uart", "lib"]);
+        git(&top, &["add", "."]);
+        git(&top, &["commit", "--quiet", "-m", "init"]);
+
+        let (with, without) = (root.join("with"), root.join("without"));
+        git(&root, &["clone", "--quiet", "top", "with"]);
+        git(&root, &["clone", "--quiet", "top", "without"]);
+        // allow the local submodule to be cloned without changing the user's git config
+        std::env::set_var("GIT_ALLOW_PROTOCOL", "file:git:http:https:ssh");
+        init_submodules(&with).unwrap();
+        // initializing a repository without submodules does nothing
+        init_submodules(&sub).unwrap();
+
+        assert!(with.join("lib/uart.vhd").is_file());
+        assert_eq!(Download::find_empty_submodules(&with), Vec::<String>::new());
+        assert_eq!(Download::find_empty_submodules(&without), vec!["lib"]);
+        // compare only the files of the ip, not the repositories' metadata
+        for repo in [&with, &without] {
+            std::fs::remove_dir_all(repo.join(".git")).unwrap();
+        }
+        std::fs::remove_file(with.join("lib/.git")).unwrap();
+        assert_ne!(Ip::compute_checksum(&with), Ip::compute_checksum(&without));
+    }
+}
@@ -38,12 +38,22 @@ impl Command<Context> for Env {
     type Status = OrbitResult;
 
     fn exec(&self, c: &Context) -> Self::Status {
+        // the build directory that a plan with the default plugin writes to
+        let build_dir = c.get_default_build_dir();
         // assemble environment information
         let mut env = Environment::from_vec(vec![
             // @todo: context should own an `Environment` struct instead of this data transformation
             EnvVar::new()
                 .key(environment::ORBIT_HOME)
                 .value(PathBuf::standardize(c.get_home_path()).to_str().unwrap()),
+            EnvVar::new().key(environment::ORBIT_CONFIG_HOME).value(
+                PathBuf::standardize(c.get_config_home_path())
+                    .to_str()
+                    .unwrap(),
+            ),
+            EnvVar::new()
+                .key(environment::ORBIT_LAYOUT)
+                .value(&std::env::var(environment::ORBIT_LAYOUT).unwrap_or(String::new())),
             EnvVar::new()
                 .key(environment::ORBIT_CACHE)
                 .value(PathBuf::standardize(c.get_cache_path()).to_str().unwrap()),
@@ -58,7 +68,7 @@ impl Command<Context> for Env {
             //     .value(PathBuf::standardize(c.get_queue_path()).to_str().unwrap()),
             EnvVar::new()
                 .key(environment::ORBIT_BUILD_DIR)
-                .value(&c.get_build_dir()),
+                .value(&build_dir),
             EnvVar::new().key(environment::ORBIT_IP_PATH).value(
                 PathBuf::standardize(c.get_ip_path().unwrap_or(&PathBuf::new()))
                     .to_str()
@@ -90,7 +100,7 @@ impl Command<Context> for Env {
                 env = env.from_ip(&ip)?;
             }
             // check the build directory
-            env = env.from_env_file(&std::path::PathBuf::from(c.get_build_dir()))?;
+            env = env.from_env_file(&std::path::PathBuf::from(&build_dir))?;
         }
 
         self.run(env)
@@ -0,0 +1,312 @@
+//! Stable exit codes and error codes so that scripts and users can react to
+//! failures programmatically.
+
+use crate::commands::launch::LaunchError;
+use crate::commands::orbit::UpgradeError;
+use crate::commands::plan::PlanError;
+use crate::core::catalog::CatalogError;
+use crate::core::lang::vhdl::primaryunit::VhdlIdentifierError;
+use crate::core::pkgid::PkgIdError;
+use crate::core::plugin::PluginError;
+use crate::core::protocol::ProtocolError;
+use crate::core::version::VersionError;
+use crate::util::anyerror::AnyError;
+use crate::util::diagnostic::Diagnostic;
+use std::error::Error;
+use std::str::FromStr;
+
+/// The class of a failure, where each class exits the program with its own code.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExitCode {
+    Success = 0,
+    /// The command-line arguments are invalid.
+    Usage = 2,
+    /// A file or value could not be parsed.
+    Parse = 3,
+    /// A plugin, protocol, ip, or design unit could not be found or decided.
+    Resolution = 4,
+    /// A connection or download over the internet failed.
+    Network = 5,
+    /// A checksum did not match its expected value.
+    Integrity = 6,
+    /// Any other failure.
+    Failure = 101,
+}
+
+impl ExitCode {
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorCode {
+    E0101,
+    E0201,
+    E0202,
+    E0203,
+    E0301,
+    E0302,
+    E0303,
+    E0304,
+    E0305,
+    E0401,
+    E0501,
+}
+
+impl ErrorCode {
+    /// Every error code, in ascending order.
+    pub const ALL: [ErrorCode; 11] = [
+        Self::E0101,
+        Self::E0201,
+        Self::E0202,
+        Self::E0203,
+        Self::E0301,
+        Self::E0302,
+        Self::E0303,
+        Self::E0304,
+        Self::E0305,
+        Self::E0401,
+        Self::E0501,
+    ];
+
+    /// Identifies the error code for a major error, if it is one.
+    pub fn classify(err: &(dyn Error + 'static)) -> Option<Self> {
+        if let Some(d) = err.downcast_ref::<Diagnostic>() {
+            d.get_code().and_then(|c| Self::from_str(c).ok())
+        } else if let Some(e) = err.downcast_ref::<PluginError>() {
+            Some(match e {
+                PluginError::Missing(_) => Self::E0301,
+                PluginError::MissingEnv(_, _) | PluginError::UnknownOption(_, _) => Self::E0302,
+            })
+        } else if err.downcast_ref::<PlanError>().is_some() {
+            Some(Self::E0303)
+        } else if err.downcast_ref::<CatalogError>().is_some() {
+            Some(Self::E0304)
+        } else if err.downcast_ref::<ProtocolError>().is_some() {
+            Some(Self::E0305)
+        } else if err.downcast_ref::<toml::de::Error>().is_some()
+            || err.downcast_ref::<toml_edit::TomlError>().is_some()
+        {
+            Some(Self::E0201)
+        } else if err.downcast_ref::<VersionError>().is_some() {
+            Some(Self::E0202)
+        } else if err.downcast_ref::<PkgIdError>().is_some() {
+            Some(Self::E0203)
+        } else if err.downcast_ref::<curl::Error>().is_some() {
+            Some(Self::E0401)
+        } else if let Some(e) = err.downcast_ref::<UpgradeError>() {
+            match e {
+                UpgradeError::FailedConnection(_, _) | UpgradeError::FailedDownload(_, _) => {
+                    Some(Self::E0401)
+                }
+                UpgradeError::BadChecksum(_, _) => Some(Self::E0501),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Reports the error `err` as a `Diagnostic` labeled with its error code.
+    pub fn diagnose(err: &(dyn Error + 'static)) -> Diagnostic {
+        if let Some(d) = err.downcast_ref::<Diagnostic>() {
+            d.clone()
+        } else if let Some(e) = err.downcast_ref::<PlanError>() {
+            e.to_diagnostic()
+        } else {
+            let d = Diagnostic::error(&err.to_string());
+            match Self::classify(err) {
+                Some(c) => d.code(&c.to_string()),
+                None => d,
+            }
+        }
+    }
+
+    /// Reports the error `err` as one or more `Diagnostic`s, where errors that
+    /// gather several problems, such as failed pre-flight checks or conflicting
+    /// design units, report each problem on its own.
+    pub fn diagnose_all(err: &(dyn Error + 'static)) -> Vec<Diagnostic> {
+        if let Some(e) = err.downcast_ref::<LaunchError>() {
+            e.to_diagnostics()
+        } else if let Some(e) = err.downcast_ref::<VhdlIdentifierError>() {
+            e.to_diagnostics()
+        } else {
+            vec![Self::diagnose(err)]
+        }
+    }
+
+    /// Returns the class of failure, which determines the exit code.
+    pub fn get_class(&self) -> ExitCode {
+        match self {
+            Self::E0101 => ExitCode::Usage,
+            Self::E0201 | Self::E0202 | Self::E0203 => ExitCode::Parse,
+            Self::E0301 | Self::E0302 | Self::E0303 | Self::E0304 | Self::E0305 => {
+                ExitCode::Resolution
+            }
+            Self::E0401 => ExitCode::Network,
+            Self::E0501 => ExitCode::Integrity,
+        }
+    }
+
+    /// Returns a one-line description of the error.
+    pub fn get_summary(&self) -> &'static str {
+        match self {
+            Self::E0101 => "invalid command-line usage",
+            Self::E0201 => "invalid toml syntax in a manifest, lock, or configuration file",
+            Self::E0202 => "invalid version",
+            Self::E0203 => "invalid ip name",
+            Self::E0301 => "no plugin with the given name",
+            Self::E0302 => "plugin requirements are not met",
+            Self::E0303 => "unable to decide the top-level or testbench",
+            Self::E0304 => "ip is not available in the catalog",
+            Self::E0305 => "no protocol with the given name",
+            Self::E0401 => "network request failed",
+            Self::E0501 => "checksum mismatch",
+        }
+    }
+
+    /// Returns the long-form explanation of the error and how to resolve it.
+    pub fn get_explanation(&self) -> &'static str {
+        match self {
+            Self::E0101 => "\
+A subcommand, option, or argument on the command-line was not recognized or
+was given an invalid value.
+
+Check the spelling against `orbit <command> --help`. When a misspelled word
+closely matches a known subcommand or option, it is suggested in the error.",
+            Self::E0201 => "\
+A file read by orbit, such as an Orbit.toml, Orbit.lock, or config.toml, does
+not contain valid toml syntax or has an unexpected key or value type.
+
+The error message includes the line and column where parsing failed. Correct
+the file, or delete an Orbit.lock file to have it regenerated.",
+            Self::E0202 => "\
+A version must have the form `<major>.<minor>.<patch>` where each level is a
+non-negative number, such as `1.0.0`. Partial versions like `1` or `1.2` are
+accepted only where a command allows selecting the latest matching version.",
+            Self::E0203 => "\
+An ip name must begin with an alphabetic character and only contain
+alphanumeric characters, dashes (`-`), and underscores (`_`).",
+            Self::E0301 => "\
+The requested plugin alias is not defined in any loaded configuration file.
+
+Run `orbit plan --list` to see the available plugins, or define one with a
+`[[plugin]]` entry in a config.toml file.",
+            Self::E0302 => "\
+A plugin was given an option it does not declare, or a plugin requires an
+environment variable that is not set.
+
+Run `orbit plan --plugin <alias> --list` to see the plugin's options, and set
+required variables in the `[env]` table of a config.toml file.",
+            Self::E0303 => "\
+Orbit could not decide which design unit is the top-level or testbench. This
+happens when no entities exist, when more than one candidate exists, or when
+the requested unit is not an entity in the current ip.
+
+Select the unit explicitly with `--top <unit>` or `--bench <tb>`.",
+            Self::E0304 => "\
+The requested ip, or the requested version of the ip, is not installed in the
+cache or available from the downloads.
+
+Run `orbit search` to see the ip in the catalog, and install the ip with
+`orbit install`.",
+            Self::E0305 => "\
+The requested protocol is not defined in any loaded configuration file.
+
+Run `orbit install --list` to see the available protocols, or define one with
+a `[[protocol]]` entry in a config.toml file.",
+            Self::E0401 => "\
+A connection to a remote server could not be made, or the server responded
+with an unsuccessful status.
+
+Check the internet connection and the url in the error message, then try the
+command again.",
+            Self::E0501 => "\
+The checksum computed for downloaded contents did not match the expected
+checksum. The contents may have been corrupted or tampered with in transit.
+
+Try the command again; if it continues to fail, do not use the contents.",
+        }
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.to_uppercase();
+        match Self::ALL.iter().find(|c| c.to_string() == code) {
+            Some(c) => Ok(*c),
+            None => Err(AnyError(format!(
+                "Unknown error code '{}'\n\nTry `orbit explain --list` to see all error codes",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::anyerror::Fault;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(ErrorCode::from_str("E0301").unwrap(), ErrorCode::E0301);
+        assert_eq!(ErrorCode::from_str("e0501").unwrap(), ErrorCode::E0501);
+        assert!(ErrorCode::from_str("E9999").is_err());
+    }
+
+    #[test]
+    fn classify() {
+        let err: Fault = Box::new(PluginError::Missing(String::from("ghdl")));
+        let code = ErrorCode::classify(err.as_ref());
+        assert_eq!(code, Some(ErrorCode::E0301));
+        assert_eq!(code.unwrap().get_class().value(), 4);
+
+        let err: Fault = Box::new(AnyError(String::from("something else")));
+        assert_eq!(ErrorCode::classify(err.as_ref()), None);
+    }
+
+    #[test]
+    fn diagnose() {
+        let err: Fault = Box::new(PlanError::Empty);
+        let d = ErrorCode::diagnose(err.as_ref());
+        assert_eq!(d.get_code(), Some(&String::from("E0303")));
+        assert_eq!(d.get_help().is_some(), true);
+
+        let err: Fault = Box::new(Diagnostic::error("bad file").code("E0201"));
+        assert_eq!(ErrorCode::classify(err.as_ref()), Some(ErrorCode::E0201));
+
+        let err: Fault = Box::new(AnyError(String::from("something else")));
+        assert_eq!(ErrorCode::diagnose(err.as_ref()).render(false), "error: something else");
+    }
+
+    #[test]
+    fn diagnose_all() {
+        let err: Fault = Box::new(LaunchError::ChecksFailed(vec![(
+            String::from("tag is available"),
+            String::from("tag 1.0.0 already exists"),
+        )]));
+        let ds = ErrorCode::diagnose_all(err.as_ref());
+        assert_eq!(ds.len(), 2);
+        assert_eq!(
+            ds[0].get_message(),
+            "pre-flight check 'tag is available' failed: tag 1.0.0 already exists"
+        );
+        assert_eq!(ds[1].get_message(), "1 pre-flight check(s) failed");
+
+        let err: Fault = Box::new(PlanError::Empty);
+        assert_eq!(
+            ErrorCode::diagnose_all(err.as_ref()),
+            vec![ErrorCode::diagnose(err.as_ref())]
+        );
+    }
+}
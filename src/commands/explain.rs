@@ -0,0 +1,54 @@
+use crate::commands::errcode::ErrorCode;
+use crate::commands::helps::explain;
+use crate::util::anyerror::AnyError;
+use crate::OrbitResult;
+use clif::arg::{Flag, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+
+#[derive(Debug, PartialEq)]
+pub struct Explain {
+    list: bool,
+    code: Option<ErrorCode>,
+}
+
+impl FromCli for Explain {
+    fn from_cli(cli: &mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(explain::HELP).ref_usage(2..4))?;
+        let command = Ok(Explain {
+            // Flags
+            list: cli.check_flag(Flag::new("list"))?,
+            // Positionals
+            code: cli.check_positional(Positional::new("code"))?,
+        });
+        command
+    }
+}
+
+impl Command<()> for Explain {
+    type Status = OrbitResult;
+
+    fn exec(&self, _: &()) -> Self::Status {
+        if self.list == true {
+            ErrorCode::ALL
+                .iter()
+                .for_each(|c| println!("{}  {}", c, c.get_summary()));
+            return Ok(());
+        }
+        match &self.code {
+            Some(code) => {
+                println!(
+                    "{}: {}\n\n{}",
+                    code,
+                    code.get_summary(),
+                    code.get_explanation()
+                );
+                Ok(())
+            }
+            None => Err(AnyError(format!(
+                "Missing an error code to explain\n\nTry `orbit explain --list` to see all error codes"
+            )))?,
+        }
+    }
+}
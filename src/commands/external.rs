@@ -0,0 +1,206 @@
+//! An external subcommand is an executable named `orbit-<command>` found on the
+//! user's PATH that is invoked when `<command>` is not a builtin subcommand.
+
+use crate::commands::orbit::AnyResult;
+use crate::commands::orbit::Orbit;
+use crate::commands::orbit::SUBCOMMANDS;
+use crate::core::lang::vhdl::highlight::ColorMode;
+use crate::util::anyerror::AnyError;
+use crate::util::environment;
+use crate::util::environment::Environment;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The prefix every external subcommand's executable name begins with.
+pub const EXTERNAL_PREFIX: &str = "orbit-";
+
+#[derive(Debug, PartialEq)]
+pub struct External {
+    path: PathBuf,
+    args: Vec<String>,
+    profile: Option<String>,
+    color: ColorMode,
+    yes: bool,
+}
+
+impl External {
+    /// Checks the command-line `args` for an external subcommand.
+    ///
+    /// Returns `None` if the first positional argument is a builtin subcommand or
+    /// if no executable named `orbit-<command>` is found on the PATH.
+    pub fn detect(args: &[String]) -> Option<Self> {
+        // skip the program name and any global options to find the subcommand
+        let mut iter = args.iter().enumerate().skip(1);
+        let mut profile = None;
+        let mut color = ColorMode::Auto;
+        let mut yes = false;
+        let (i, name) = loop {
+            let (i, arg) = iter.next()?;
+            if arg == "--color" {
+                if let Some(mode) = iter.next().and_then(|(_, m)| ColorMode::from_str(m).ok()) {
+                    color = mode;
+                }
+            } else if arg == "--format" || arg == "--progress" || arg == "--trace" {
+                iter.next();
+            } else if arg == "--yes" || arg == "-y" {
+                yes = true;
+            } else if arg == "--profile" {
+                profile = iter.next().map(|(_, p)| p.clone());
+            } else if arg.starts_with('-') == false {
+                break (i, arg);
+            }
+        };
+        if SUBCOMMANDS.contains(&name.as_str()) == true {
+            return None;
+        }
+        Some(Self {
+            path: Self::find(name)?,
+            args: args[i + 1..].to_vec(),
+            profile,
+            color,
+            yes,
+        })
+    }
+
+    /// Searches the directories on the PATH for the executable for the subcommand `name`.
+    fn find(name: &str) -> Option<PathBuf> {
+        let file_name = format!(
+            "{}{}{}",
+            EXTERNAL_PREFIX,
+            name,
+            std::env::consts::EXE_SUFFIX
+        );
+        let paths = std::env::var_os("PATH")?;
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(&file_name))
+            .find(|p| p.is_file())
+    }
+
+    /// Collects the names and paths of every external subcommand found on the PATH.
+    ///
+    /// When more than one directory provides the same subcommand, the first one
+    /// found is kept as it is the one that runs. Subcommands sharing a name with a
+    /// builtin subcommand are skipped because they never run.
+    pub fn find_all() -> Vec<(String, PathBuf)> {
+        let paths = match std::env::var_os("PATH") {
+            Some(p) => p,
+            None => return Vec::new(),
+        };
+        let mut found: Vec<(String, PathBuf)> = Vec::new();
+        for dir in std::env::split_paths(&paths) {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.is_file() == false {
+                    continue;
+                }
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let name = match Self::to_command_name(&file_name) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                if SUBCOMMANDS.contains(&name) == false
+                    && found.iter().any(|(n, _)| n == name) == false
+                {
+                    found.push((name.to_string(), path));
+                }
+            }
+        }
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+    }
+
+    /// Extracts the subcommand name from an executable's `file_name`.
+    ///
+    /// Returns `None` if the file is not named `orbit-<command>`.
+    fn to_command_name(file_name: &str) -> Option<&str> {
+        let name = file_name
+            .strip_prefix(EXTERNAL_PREFIX)?
+            .strip_suffix(std::env::consts::EXE_SUFFIX)?;
+        // skip old binaries renamed as `orbit-<version>` during an upgrade
+        match name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+            true => None,
+            false => Some(name),
+        }
+    }
+
+    /// Runs the external subcommand with the ORBIT_* environment variables set
+    /// and returns its exit code.
+    pub fn exec(&self) -> AnyResult<u8> {
+        // follow the same coloring as the builtin subcommands
+        self.color.apply();
+        // select the profile before the configuration is loaded
+        if let Some(p) = &self.profile {
+            std::env::set_var(environment::ORBIT_PROFILE, p);
+        }
+        // let the subcommand know to not wait on prompts
+        if self.yes == true {
+            std::env::set_var(environment::ORBIT_NO_INTERACTIVE, "1");
+        }
+        // set up the context to initialize the environment variables
+        let context = Orbit::context()?;
+        Environment::new()
+            // read config.toml for setting any env variables
+            .from_config(context.get_config())?
+            .initialize();
+
+        let status = std::process::Command::new(&self.path)
+            .args(&self.args)
+            .status()?;
+        match status.code() {
+            Some(num) => Ok(to_exit_code(num)),
+            None => Err(AnyError(format!("Terminated by signal")))?,
+        }
+    }
+}
+
+/// Converts the status `num` of a subcommand into orbit's exit code.
+///
+/// A status that does not fit, such as a negative or large windows status, is
+/// reported as 1 so a failure is never mistaken for success.
+fn to_exit_code(num: i32) -> u8 {
+    u8::try_from(num).unwrap_or(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(to_exit_code(0), 0);
+        assert_eq!(to_exit_code(101), 101);
+        assert_eq!(to_exit_code(256), 1);
+        assert_eq!(to_exit_code(-1), 1);
+    }
+
+    #[test]
+    fn detect_builtin() {
+        let args: Vec<String> = vec!["orbit", "--color", "never", "plan", "--all"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(External::detect(&args), None);
+        // no executable exists for this command
+        let args: Vec<String> = vec!["orbit", "no-such-cmd-abc123"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(External::detect(&args), None);
+    }
+
+    #[test]
+    fn command_name() {
+        let suffix = std::env::consts::EXE_SUFFIX;
+        assert_eq!(
+            External::to_command_name(&format!("orbit-lint{}", suffix)),
+            Some("lint")
+        );
+        assert_eq!(External::to_command_name(&format!("orbit-{}", suffix)), None);
+        assert_eq!(External::to_command_name(&format!("orbit-0.9.8{}", suffix)), None);
+        assert_eq!(External::to_command_name("cargo-lint"), None);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::commands::helps::fetch;
+use crate::commands::plan;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::{Ip, IpSpec};
+use crate::core::lockfile::{LockEntry, LockFile};
+use crate::core::variable::VariableTable;
+use crate::core::version::AnyVersion;
+use crate::util::anyerror::AnyError;
+use crate::util::environment::Environment;
+use crate::util::logger;
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+
+#[derive(Debug, PartialEq)]
+pub struct Fetch {
+    all: bool,
+    offline: bool,
+}
+
+impl FromCli for Fetch {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(fetch::HELP).ref_usage(2..4))?;
+        let command = Ok(Fetch {
+            all: cli.check_flag(Flag::new("all"))?,
+            offline: cli.check_flag(Flag::new("offline"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Fetch {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let target = Ip::load(c.get_ip_path().unwrap().clone())?;
+
+        if target.lock_exists() == false {
+            return Err(AnyError(String::from(
+                "cannot fetch dependencies due to missing lock file\n\nTry `orbit plan --lock-only` to create the lock file",
+            )))?;
+        }
+        if target.can_use_lock() == false {
+            logger::warn("lock file is out of date with the manifest; try `orbit plan --lock-only` to update it");
+        }
+
+        let le = LockEntry::from((&target, true));
+        let lf = target.get_lock().keep_dev_dep_entries(&target, self.all);
+
+        let mut catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .downloads(c.get_downloads_path())?;
+
+        let downloaded = match self.offline {
+            true => {
+                let missing = Self::find_unavailable(&lf, &le, &catalog);
+                if missing.is_empty() == false {
+                    return Err(AnyError(format!(
+                        "cannot fetch {} ip without network access: {}\n\nTry again without `--offline` to download the missing ip",
+                        missing.len(),
+                        missing
+                            .iter()
+                            .map(|s| s.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )))?;
+                }
+                Vec::new()
+            }
+            false => {
+                let env = Environment::new()
+                    // read config.toml for setting any env variables
+                    .from_config(c.get_config())?;
+                let vtable = VariableTable::new()
+                    .load_environment(&env)?
+                    // read config.toml for user-defined variables
+                    .load_config(c.get_config())?;
+                plan::download_missing_deps(
+                    vtable,
+                    &lf,
+                    &le,
+                    &catalog,
+                    &c.get_config().get_protocols(),
+                    &c.get_config().get_credentials(),
+                    &c.get_config().get_rewrites(),
+                    c.get_config().get_init_submodules(),
+                )?
+            }
+        };
+        // recollect the downloaded items to update the catalog for installations
+        catalog = catalog.downloads(c.get_downloads_path())?;
+
+        let installed = plan::install_missing_deps(
+            &lf,
+            &le,
+            &catalog,
+            &c.get_config().get_allowed_install_hooks(),
+        )?;
+        // recollect the installations to verify them against the lock file
+        catalog = catalog.installations(c.get_cache_path())?;
+
+        // every installation must match the checksum recorded in the lock file
+        for entry in lf.inner() {
+            if entry.matches_target(&le) == true || entry.get_sum().is_none() == true {
+                continue;
+            }
+            if catalog.is_cached_slot(&entry.to_cache_slot_key(), entry.get_uuid()) == false {
+                return Err(AnyError(format!(
+                    "installation of ip {} does not match the checksum in the lock file\n\nTry `orbit plan --force` to update the lock file",
+                    entry.to_ip_spec()
+                )))?;
+            }
+        }
+
+        let total = lf
+            .inner()
+            .iter()
+            .filter(|entry| entry.matches_target(&le) == false)
+            .count();
+        println!("{}", Self::fmt_report(&downloaded, &installed, total));
+        Ok(())
+    }
+}
+
+impl Fetch {
+    /// Collects the entries of the lock file that are neither installed nor
+    /// downloaded, which require network access to be fetched.
+    fn find_unavailable(lf: &LockFile, le: &LockEntry, catalog: &Catalog) -> Vec<IpSpec> {
+        lf.inner()
+            .iter()
+            .filter(|entry| entry.matches_target(le) == false)
+            .filter(|entry| {
+                let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
+                match catalog.inner().get(entry.get_name()) {
+                    Some(lvl) => lvl.get_install(&ver).is_none() && lvl.get_download(&ver).is_none(),
+                    None => true,
+                }
+            })
+            .map(|entry| entry.to_ip_spec())
+            .collect()
+    }
+
+    /// Summarizes the ip that were downloaded and installed among the `total`
+    /// dependencies in the lock file.
+    fn fmt_report(downloaded: &[IpSpec], installed: &[IpSpec], total: usize) -> String {
+        let mut report = String::new();
+        downloaded
+            .iter()
+            .for_each(|s| report.push_str(&format!("{:>12} {}\n", "Downloaded", s)));
+        installed
+            .iter()
+            .for_each(|s| report.push_str(&format!("{:>12} {}\n", "Installed", s)));
+        report.push_str(&format!(
+            "Installed {} of {} dependencies ({} downloaded)",
+            installed.len(),
+            total,
+            downloaded.len()
+        ));
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn report() {
+        let gates = IpSpec::from_str("gates:1.0.0").unwrap();
+        let uart = IpSpec::from_str("uart:2.1.0").unwrap();
+        assert_eq!(
+            Fetch::fmt_report(&[gates.clone()], &[gates, uart], 3),
+            "  Downloaded gates:1.0.0\n   Installed gates:1.0.0\n   Installed uart:2.1.0\nInstalled 2 of 3 dependencies (1 downloaded)"
+        );
+        assert_eq!(
+            Fetch::fmt_report(&[], &[], 3),
+            "Installed 0 of 3 dependencies (0 downloaded)"
+        );
+    }
+}
@@ -3,9 +3,12 @@ use std::path::PathBuf;
 
 use crate::core::catalog::Catalog;
 use crate::core::context::Context;
+use crate::core::ip::Ip;
 use crate::core::ip::PartialIpSpec;
 use crate::core::lang::parser::Symbol;
+use crate::core::lang::Lang;
 use crate::core::lang::vhdl::interface;
+use crate::core::lang::vhdl::primaryunit::PrimaryUnit;
 use crate::core::lang::vhdl::primaryunit::VhdlIdentifierError;
 use crate::core::lang::vhdl::symbol::Architecture;
 use crate::core::lang::vhdl::symbol::Entity;
@@ -23,7 +26,7 @@ use clif::Cli;
 use clif::Error as CliError;
 use colored::Colorize;
 use crate::commands::helps::get;
-use crate::core::lang::vhdl::format::VhdlFormat;
+use crate::core::lang::vhdl::format::{Association, Codegen, InstanceStyle, VhdlFormat, VhdlStandard};
 
 #[derive(Debug, PartialEq)]
 pub struct Get {
@@ -34,8 +37,16 @@ pub struct Get {
     instance: bool,
     architectures: bool,
     json: bool,
+    testbench: bool,
     // info: bool,
     name: Option<Identifier>,
+    association: Option<Association>,
+    style: Option<InstanceStyle>,
+    prefix: Option<String>,
+    suffix: Option<String>,
+    standard: Option<VhdlStandard>,
+    indent: Option<u8>,
+    lang: Option<Lang>,
 }
 
 impl FromCli for Get {
@@ -47,9 +58,17 @@ impl FromCli for Get {
             instance: cli.check_flag(Flag::new("instance").switch('i'))?,
             architectures: cli.check_flag(Flag::new("architecture").switch('a'))?,
             json: cli.check_flag(Flag::new("json"))?,
+            testbench: cli.check_flag(Flag::new("testbench"))?,
             // info: cli.check_flag(Flag::new("info"))?, // @todo: implement
             ip: cli.check_option(Optional::new("ip").value("spec"))?,
             name: cli.check_option(Optional::new("name").value("identifier"))?,
+            association: cli.check_option(Optional::new("association").value("kind"))?,
+            style: cli.check_option(Optional::new("style").value("kind"))?,
+            prefix: cli.check_option(Optional::new("signal-prefix").value("str"))?,
+            suffix: cli.check_option(Optional::new("signal-suffix").value("str"))?,
+            standard: cli.check_option(Optional::new("std").value("edition"))?,
+            indent: cli.check_option(Optional::new("indent").value("n"))?,
+            lang: cli.check_option(Optional::new("lang").value("lang"))?,
             unit: cli.require_positional(Positional::new("unit"))?,
         });
         command
@@ -66,15 +85,35 @@ impl Command<Context> for Get {
     type Status = OrbitResult;
 
     fn exec(&self, c: &Context) -> Self::Status {
-        // --name can only be used with --instance is set
-        if self.name.is_some() && self.instance == false {
+        // --name can only be used with --instance or --testbench is set
+        if self.name.is_some() && self.instance == false && self.testbench == false {
             return Err(AnyError(format!(
-                "'{}' can only be used with '{}'",
+                "'{}' can only be used with '{}' or '{}'",
                 "--name".yellow(),
-                "--instance".yellow()
+                "--instance".yellow(),
+                "--testbench".yellow()
             )))?;
         }
 
+        // verilog code is only generated for the declarations and instance
+        if self.lang == Some(Lang::Verilog) {
+            if let Some((_, flag)) = [
+                (self.component, "--component"),
+                (self.testbench, "--testbench"),
+                (self.architectures, "--architecture"),
+                (self.json, "--json"),
+            ]
+            .iter()
+            .find(|(set, _)| *set == true)
+            {
+                return Err(AnyError(format!(
+                    "'{}' cannot be used with '{}'",
+                    flag.yellow(),
+                    "--lang verilog".yellow()
+                )))?;
+            }
+        }
+
         // @todo: load the catalog
         let catalog = Catalog::new()
             // .store(c.get_store_path())
@@ -105,19 +144,38 @@ impl Command<Context> for Get {
         // load the manifest from the path
         let man = Manifest::from_file(&ip_path.join(IP_MANIFEST_FILE))?;
 
-        let default_fmt = VhdlFormat::new();
-        let fmt = match c.get_config().get_vhdl_formatting() {
-            Some(v) => v,
-            None => &default_fmt,
+        // command-line options take precedence over the configured formatting
+        let mut fmt = VhdlFormat::default()
+            .tab_size(self.indent)
+            .instance_association(self.association)
+            .instance_style(match (self.style, self.component) {
+                (Some(style), _) => Some(style),
+                (None, true) => Some(InstanceStyle::Component),
+                (None, false) => None,
+            })
+            .signal_prefix(self.prefix.clone())
+            .signal_suffix(self.suffix.clone())
+            .standard(self.standard);
+        fmt.merge(Some(match c.get_config().get_vhdl_formatting() {
+            Some(v) => v.clone(),
+            None => VhdlFormat::new(),
+        }));
+        let codegen = match c.get_config().get_codegen() {
+            Some(cg) => {
+                let mut cg = cg.clone();
+                cg.merge(Some(Codegen::new()));
+                cg
+            }
+            None => Codegen::new(),
         };
-        self.run(man, &ip_path, &fmt)
+        self.run(man, &ip_path, &fmt, &codegen)
     }
 }
 
 impl Get {
-    fn run(&self, man: Manifest, dir: &PathBuf, fmt: &VhdlFormat) -> Result<(), Fault> {
+    fn run(&self, man: Manifest, dir: &PathBuf, fmt: &VhdlFormat, codegen: &Codegen) -> Result<(), Fault> {
         // collect all hdl files and parse them
-        let ent = match Self::fetch_entity(&self.unit, &dir, &man) {
+        let ent = match Self::fetch_entity(&self.unit, &dir, &man, self.ip.is_some()) {
             Ok(r) => r,
             Err(e) => {
                 return Err(GetError::SuggestShow(
@@ -146,6 +204,30 @@ impl Get {
             },
         };
 
+        // display the verilog code to connect to the entity and nothing else
+        if self.lang == Some(Lang::Verilog) {
+            // verilog code is not highlighted
+            colored::control::set_override(false);
+            if self.signals == true {
+                let declarations = ent.into_verilog_declarations(&fmt);
+                if declarations.is_empty() == false {
+                    println!("{}", declarations);
+                }
+            }
+            if self.instance == true {
+                println!("{}", ent.into_verilog_instance(&self.name, &fmt));
+            }
+            return Ok(());
+        }
+
+        // display a complete testbench file and nothing else
+        if self.testbench == true {
+            // the testbench is meant to be saved to a file so never color it
+            colored::control::set_override(false);
+            print!("{}", codegen.apply(&ent.into_testbench(&self.name, Some(lib), &fmt)));
+            return Ok(());
+        }
+
         // display architectures
         if self.architectures == true {
             println!("{}", ent.get_architectures());
@@ -158,34 +240,27 @@ impl Get {
 
         // display component declaration
         if self.component == true {
-            println!("{}", ent.into_component(&fmt));
-        // display library declaration line if displaying instance
-        } else if self.instance == true {
-            println!("{}", interface::library_statement(&lib));
+            println!("{}", codegen.apply(&ent.into_component(&fmt)));
+        // display library declaration line if displaying a direct instance
+        } else if self.instance == true && fmt.get_instance_style() == InstanceStyle::Entity {
+            println!("{}", codegen.apply(&interface::library_statement(&lib)));
         }
 
         // display signal declarations
         if self.signals == true {
-            let constants = ent.into_constants(&fmt);
+            let constants = codegen.apply(&ent.into_constants(&fmt));
             if constants.is_empty() == false {
                 println!("{}", constants);
             }
-            let signals = ent.into_signals(&fmt);
+            let signals = codegen.apply(&ent.into_signals(&fmt));
             if signals.is_empty() == false {
                 println!("{}", signals);
             }
         }
 
-        // only display the direct entity instantiation code if not providing component code
-        let lib = if self.component == true {
-            None
-        } else {
-            Some(lib)
-        };
-
         // display instantiation code
         if self.instance == true {
-            println!("{}", ent.into_instance(&self.name, lib, &fmt));
+            println!("{}", codegen.apply(&ent.into_instance(&self.name, Some(lib), &fmt)));
         }
 
         // print as json data
@@ -197,12 +272,36 @@ impl Get {
     }
 
     /// Parses through the vhdl files and returns a desired entity struct.
+    ///
+    /// When the ip is `installed`, only the files recorded in its metadata for the
+    /// entity and its architectures are parsed.
     fn fetch_entity(
         iden: &Identifier,
         dir: &PathBuf,
         man: &Manifest,
+        installed: bool,
     ) -> Result<symbol::Entity, Fault> {
-        let files = crate::util::filesystem::gather_current_files(&dir, false);
+        let recorded = match installed {
+            true => Ip::read_units_from_metadata(dir).and_then(|units| match units.get(iden) {
+                Some(PrimaryUnit::Entity(unit)) if unit.get_source_code_file().is_empty() == false => {
+                    let source = unit.get_source_code_file().to_string();
+                    let mut files = vec![source.clone()];
+                    files.extend(
+                        unit.get_architecture_files()
+                            .iter()
+                            .filter(|f| *f != &source)
+                            .cloned(),
+                    );
+                    Some(files)
+                }
+                _ => None,
+            }),
+            false => None,
+        };
+        let files = match recorded {
+            Some(files) => files,
+            None => crate::util::filesystem::gather_current_files(&dir, false),
+        };
         // @todo: generate all units first (store architectures, and entities, and then process)
         let mut result: Option<(String, Entity)> = None;
         // store map of all architectures while parsing all code
@@ -361,8 +460,30 @@ mod test {
     "other"
   ]
 }"#;
-        let ent = Get::fetch_entity(&Identifier::from_str("or_gate").unwrap(), &PathBuf::from("./tests/data/gates"), &Manifest::new()).unwrap();
+        let ent = Get::fetch_entity(&Identifier::from_str("or_gate").unwrap(), &PathBuf::from("./tests/data/gates"), &Manifest::new(), false).unwrap();
         let json_str = serde_json::to_string_pretty(&ent).unwrap();
         assert_eq!(json_str, EXPECTED_STR);
     }
+
+    #[test]
+    fn fetch_recorded_entity() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        for f in ["and_gate.vhd", "or_gate.vhd"] {
+            std::fs::copy(PathBuf::from("./tests/data/gates").join(f), root.join(f)).unwrap();
+        }
+        std::fs::write(root.join("alt.vhd"), "architecture alt of or_gate is begin end architecture;\n").unwrap();
+        let units = Ip::collect_units(true, &root).unwrap();
+        Ip::write_units_to_metadata(&root, &units).unwrap();
+
+        // a file added after installing is never parsed for a recorded entity
+        std::fs::write(root.join("dupe.vhd"), "entity or_gate is end entity;\n").unwrap();
+        let iden = Identifier::from_str("or_gate").unwrap();
+        assert_eq!(Get::fetch_entity(&iden, &root, &Manifest::new(), false).is_err(), true);
+
+        let ent = Get::fetch_entity(&iden, &root, &Manifest::new(), true).unwrap();
+        let json = serde_json::to_value(&ent).unwrap();
+        assert_eq!(json["architectures"].as_array().unwrap().len(), 3);
+        assert_eq!(json["ports"].as_array().unwrap().len(), 3);
+    }
 }
@@ -0,0 +1,289 @@
+//! Exports the resolved dependency graph of the current ip for review outside
+//! of orbit, such as rendering it with graphviz.
+
+use crate::commands::helps::graph;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::ip::IpSpec;
+use crate::core::lockfile::{LockEntry, LockFile};
+use crate::util::anyerror::AnyError;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use serde_derive::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Graph {
+    ip: bool,
+}
+
+impl FromCli for Graph {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(graph::HELP).ref_usage(2..4))?;
+        let command = Ok(Graph {
+            ip: cli.check_flag(Flag::new("ip"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Graph {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        if self.ip == false {
+            return Err(AnyError(String::from(
+                "only the ip-level graph can be exported\n\nTry `orbit graph --ip` or view the design unit hierarchy with `orbit tree`",
+            )))?;
+        }
+
+        // go to the ip directory
+        c.goto_ip_path()?;
+
+        let target = Ip::load(c.get_ip_path().unwrap().clone())?;
+
+        // the lock file holds the resolved versions of every dependency
+        if target.lock_exists() == false || target.can_use_lock() == false {
+            return Err(AnyError(String::from(
+                "cannot export the graph due to a missing or outdated lock file\n\nTry `orbit plan --lock-only` to update the lock file",
+            )))?;
+        }
+
+        let requirements = read_requirements(&target, c.get_cache_path());
+        let ip_graph = IpGraph::from_lock(
+            target.get_lock(),
+            &target.get_man().get_ip().into_ip_spec(),
+            &requirements,
+        );
+
+        match c.get_output_format() {
+            OutputFormat::Text => print!("{}", ip_graph.to_dot()),
+            OutputFormat::Json => println!("{}", output::to_json("graph", &ip_graph)?),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct IpGraphNode {
+    /// The node's label in the form `<name>@<version>`.
+    id: String,
+    name: String,
+    version: String,
+    root: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct IpGraphEdge {
+    from: String,
+    to: String,
+    /// The version of the dependency declared in the dependent's manifest.
+    requirement: String,
+}
+
+/// Maps the label of each dependent to the versions its manifest declares for
+/// each of its dependencies by name.
+type Requirements = HashMap<String, HashMap<String, String>>;
+
+/// Reads the dependencies declared in the manifests of the `target` and of its
+/// locked dependencies installed in the `cache`.
+///
+/// A dependency that is not installed is left out, so its edges fall back to
+/// the versions recorded in the lock file.
+fn read_requirements(target: &Ip, cache: &PathBuf) -> Requirements {
+    let le = LockEntry::from((target, true));
+    let mut requirements = Requirements::new();
+    for entry in target.get_lock().inner() {
+        let ip = match entry.matches_target(&le) {
+            true => None,
+            false => match entry
+                .to_cache_slot_key()
+                .locate(cache, entry.get_uuid())
+                .and_then(|root| Ip::load(root).ok())
+            {
+                Some(ip) => Some(ip),
+                None => continue,
+            },
+        };
+        // only the current ip's development dependencies are part of the graph
+        let declared = match &ip {
+            Some(ip) => ip.get_man().get_deps_list(false),
+            None => target.get_man().get_deps_list(true),
+        };
+        let spec = entry.to_ip_spec();
+        requirements.insert(
+            format!("{}@{}", spec.get_name(), spec.get_version()),
+            declared
+                .into_iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+        );
+    }
+    requirements
+}
+
+/// The dependency DAG at the ip level, where each edge points from an ip to one
+/// of its dependencies.
+#[derive(Debug, PartialEq, Serialize)]
+struct IpGraph {
+    nodes: Vec<IpGraphNode>,
+    edges: Vec<IpGraphEdge>,
+}
+
+impl IpGraph {
+    /// Builds the graph from the entries of the `lock` file, marking the entry
+    /// matching `root` as the root.
+    ///
+    /// Each edge is annotated with the dependent's declared version from the
+    /// `requirements`, or with the version within the lock file when the
+    /// dependent's manifest was not read.
+    ///
+    /// The nodes and edges are sorted to keep the output stable.
+    fn from_lock(lock: &LockFile, root: &IpSpec, requirements: &Requirements) -> Self {
+        let label = |spec: &IpSpec| format!("{}@{}", spec.get_name(), spec.get_version());
+
+        let mut entries: Vec<IpSpec> = lock.inner().iter().map(|e| e.to_ip_spec()).collect();
+        entries.sort_by(|a, b| {
+            a.get_name()
+                .cmp(b.get_name())
+                .then(a.get_version().cmp(b.get_version()))
+        });
+
+        let nodes = entries
+            .iter()
+            .map(|spec| IpGraphNode {
+                id: label(spec),
+                name: spec.get_name().to_string(),
+                version: spec.get_version().to_string(),
+                root: spec == root,
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for entry in lock.inner() {
+            let from = label(&entry.to_ip_spec());
+            for dep in entry.get_deps() {
+                let requirement = requirements
+                    .get(&from)
+                    .and_then(|declared| declared.get(&dep.get_name().to_string()))
+                    .cloned()
+                    .unwrap_or(dep.get_version().to_string());
+                edges.push(IpGraphEdge {
+                    from: from.clone(),
+                    // dependencies resolve to the exact version in the lock file
+                    to: match lock.get(dep.get_name(), dep.get_version()) {
+                        Some(e) => label(&e.to_ip_spec()),
+                        None => label(dep),
+                    },
+                    requirement,
+                });
+            }
+        }
+        edges.sort_by(|a, b| a.from.cmp(&b.from).then(a.to.cmp(&b.to)));
+
+        Self { nodes, edges }
+    }
+
+    /// Writes the graph in the DOT language.
+    fn to_dot(&self) -> String {
+        let mut result = String::from("digraph ip {\n");
+        for node in &self.nodes {
+            match node.root {
+                true => result.push_str(&format!("    \"{}\" [shape=box];\n", node.id)),
+                false => result.push_str(&format!("    \"{}\";\n", node.id)),
+            }
+        }
+        for edge in &self.edges {
+            result.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                edge.from, edge.to, edge.requirement
+            ));
+        }
+        result.push_str("}\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    const LOCK: &str = r#"version = 1
+
+[[ip]]
+name = "top"
+version = "1.0.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+dependencies = [
+    "uart:0.2.0",
+    "gates:0.1.0",
+]
+
+[[ip]]
+name = "gates"
+version = "0.1.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+dependencies = []
+
+[[ip]]
+name = "uart"
+version = "0.2.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+dependencies = ["gates:0.1.0"]
+"#;
+
+    fn ip_graph() -> IpGraph {
+        // the current ip declares an older uart than the one that was locked
+        let mut requirements = Requirements::new();
+        requirements.insert(
+            String::from("top@1.0.0"),
+            HashMap::from([
+                (String::from("uart"), String::from("0.1.0")),
+                (String::from("gates"), String::from("0.1.0")),
+            ]),
+        );
+        IpGraph::from_lock(
+            &LockFile::from_str(LOCK).unwrap(),
+            &IpSpec::from_str("top:1.0.0").unwrap(),
+            &requirements,
+        )
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(
+            ip_graph().to_dot(),
+            r#"digraph ip {
+    "gates@0.1.0";
+    "top@1.0.0" [shape=box];
+    "uart@0.2.0";
+    "top@1.0.0" -> "gates@0.1.0" [label="0.1.0"];
+    "top@1.0.0" -> "uart@0.2.0" [label="0.1.0"];
+    "uart@0.2.0" -> "gates@0.1.0" [label="0.1.0"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn json() {
+        let json = output::to_json("graph", &ip_graph()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["data"]["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(value["data"]["nodes"][1]["id"], "top@1.0.0");
+        assert_eq!(value["data"]["nodes"][1]["root"], true);
+        assert_eq!(value["data"]["edges"][1]["to"], "uart@0.2.0");
+        assert_eq!(value["data"]["edges"][1]["requirement"], "0.1.0");
+        assert_eq!(value["data"]["edges"][2]["from"], "uart@0.2.0");
+        assert_eq!(value["data"]["edges"][2]["requirement"], "0.1.0");
+    }
+}
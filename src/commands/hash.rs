@@ -0,0 +1,139 @@
+//! Computes the checksum of the current ip's files to detect whether anything
+//! changed since its last release.
+
+use crate::commands::helps::hash;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::version::AnyVersion;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use colored::Colorize;
+use serde_derive::Serialize;
+
+#[derive(Debug, PartialEq)]
+pub struct Hash {}
+
+impl FromCli for Hash {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(hash::HELP).ref_usage(2..4))?;
+        let command = Ok(Hash {});
+        command
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Report {
+    name: String,
+    version: String,
+    checksum: String,
+    /// The latest version of the ip found in the cache.
+    released: Option<String>,
+    /// The checksum recorded for the released version.
+    recorded: Option<String>,
+    modified: Option<bool>,
+}
+
+impl Command<Context> for Hash {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+        let catalog = Catalog::new().installations(c.get_cache_path())?;
+
+        let sum = ip.compute_minimal_checksum();
+        let released = latest_release(&ip, &catalog);
+        let recorded = released.map(|rel| {
+            Ip::read_checksum_proof(rel.get_root())
+                .unwrap_or_else(|| Ip::compute_checksum(rel.get_root()))
+        });
+
+        let report = Report {
+            name: ip.get_man().get_ip().get_name().to_string(),
+            version: ip.get_man().get_ip().get_version().to_string(),
+            checksum: sum.to_string(),
+            released: released.map(|r| r.get_man().get_ip().get_version().to_string()),
+            modified: recorded.as_ref().map(|r| r != &sum),
+            recorded: recorded.map(|r| r.to_string()),
+        };
+
+        match c.get_output_format() {
+            OutputFormat::Text => print!("{}", fmt_report(&report)),
+            OutputFormat::Json => println!("{}", output::to_json("hash", &report)?),
+        }
+        Ok(())
+    }
+}
+
+/// Finds the latest installation of the same `ip` in the `catalog`, which is
+/// treated as its last release.
+pub fn latest_release<'a>(ip: &Ip, catalog: &'a Catalog) -> Option<&'a Ip> {
+    catalog
+        .inner()
+        .get(ip.get_man().get_ip().get_name())
+        .and_then(|lvl| lvl.get_install(&AnyVersion::Latest))
+        .filter(|rel| rel.get_uuid() == ip.get_uuid())
+}
+
+fn fmt_report(report: &Report) -> String {
+    let mut text = format!("{:<12}{}\n", "Checksum:", report.checksum);
+    match (&report.released, &report.recorded) {
+        (Some(version), Some(sum)) => {
+            text.push_str(&format!("{:<12}{} ({})\n", "Released:", version, sum))
+        }
+        _ => text.push_str(&format!("{:<12}{}\n", "Released:", "none installed")),
+    }
+    if let Some(modified) = report.modified {
+        text.push_str(&format!(
+            "{:<12}{}\n",
+            "Files:",
+            match modified {
+                true => "modified since release".yellow().to_string(),
+                false => "unchanged since release".to_string(),
+            }
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_text() {
+        colored::control::set_override(false);
+        let report = Report {
+            name: String::from("gates"),
+            version: String::from("1.0.0"),
+            checksum: String::from("abc"),
+            released: Some(String::from("0.9.0")),
+            recorded: Some(String::from("def")),
+            modified: Some(true),
+        };
+        assert_eq!(
+            fmt_report(&report),
+            "Checksum:   abc\nReleased:   0.9.0 (def)\nFiles:      modified since release\n"
+        );
+
+        let report = Report {
+            name: String::from("gates"),
+            version: String::from("1.0.0"),
+            checksum: String::from("abc"),
+            released: None,
+            recorded: None,
+            modified: None,
+        };
+        assert_eq!(
+            fmt_report(&report),
+            "Checksum:   abc\nReleased:   none installed\n"
+        );
+    }
+}
@@ -28,6 +28,19 @@ enum Topic {
     Config,
     Uninstall,
     Read,
+    Explain,
+    Clone,
+    Fetch,
+    Vendor,
+    Status,
+    SelfCmd,
+    Lsp,
+    Ci,
+    Test,
+    Graph,
+    Hash,
+    Stats,
+    Mv,
 }
 
 impl std::str::FromStr for Topic {
@@ -49,6 +62,19 @@ impl std::str::FromStr for Topic {
             "config" => Self::Config,
             "uninstall" => Self::Uninstall,
             "read" => Self::Read,
+            "explain" => Self::Explain,
+            "clone" => Self::Clone,
+            "fetch" => Self::Fetch,
+            "vendor" => Self::Vendor,
+            "status" => Self::Status,
+            "self" => Self::SelfCmd,
+            "lsp" => Self::Lsp,
+            "ci" => Self::Ci,
+            "test" => Self::Test,
+            "graph" => Self::Graph,
+            "hash" => Self::Hash,
+            "stats" => Self::Stats,
+            "mv" => Self::Mv,
             _ => return Err(AnyError(format!("topic '{}' not found", s))),
         })
     }
@@ -74,6 +100,19 @@ impl Topic {
             Config => manuals::config::MANUAL,
             Uninstall => manuals::uninstall::MANUAL,
             Read => manuals::read::MANUAL,
+            Explain => manuals::explain::MANUAL,
+            Clone => manuals::clone::MANUAL,
+            Fetch => manuals::fetch::MANUAL,
+            Vendor => manuals::vendor::MANUAL,
+            Status => manuals::status::MANUAL,
+            SelfCmd => manuals::selfcmd::MANUAL,
+            Lsp => manuals::lsp::MANUAL,
+            Ci => manuals::ci::MANUAL,
+            Test => manuals::test::MANUAL,
+            Graph => manuals::graph::MANUAL,
+            Hash => manuals::hash::MANUAL,
+            Stats => manuals::stats::MANUAL,
+            Mv => manuals::mv::MANUAL,
         }
     }
 }
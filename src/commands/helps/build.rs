@@ -10,6 +10,8 @@ Options:
     --list              view available plugins
     --build-dir <dir>   set the output build directory
     --verbose           display the command being executed
+    --dump-env <file>   save the invocation to a file
+    --replay <file>     run a previously saved invocation
     args                arguments to pass to the requested command
 
 Use 'orbit help build' to read more about the command.
@@ -0,0 +1,14 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Generate a continuous integration pipeline.
+
+Usage:
+    orbit ci [options]
+
+Options:
+    --github                write a GitHub Actions workflow
+    --gitlab                write a GitLab CI/CD pipeline
+    --plugin <name>...      plugin to build in the pipeline
+    --force                 overwrite an existing pipeline
+
+Use 'orbit help ci' to read more about the command.
+"#;
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Check out an ip for development.
+
+Usage:
+    orbit clone [options] <ip> [<dir>]
+
+Args:
+    <ip>                ip specification to clone from the catalog
+    <dir>               the new directory to clone into
+
+Options:
+    --tag <tag>         the git tag to check out
+
+Use 'orbit help clone' to read more about the command.
+"#;
\ No newline at end of file
@@ -10,6 +10,9 @@ Options:
     --append <key>=<value>...   add a value to a key storing a list
     --set <key>=<value>...      write the value at the key entry
     --unset <key>...            delete the key's entry
+    --get <key>                 print the effective value of the key's entry
+    --list                      print every effective entry and its file
+    --edit                      open the configuration file in a text editor
 
 Use 'orbit help config' to read more about the command.
 "#;
\ No newline at end of file
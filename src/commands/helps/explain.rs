@@ -0,0 +1,12 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Describe an error code.
+
+Usage:
+    orbit explain [options] [<code>]
+
+Options:
+    <code>          error code to explain
+    --list          view all error codes and exit
+
+Use 'orbit help explain' to read more about the command.
+"#;
\ No newline at end of file
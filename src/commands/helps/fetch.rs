@@ -0,0 +1,12 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Restore dependencies from the lock file.
+
+Usage:
+    orbit fetch [options]
+
+Options:
+    --all               fetch all dependencies including development
+    --offline           only use dependencies available on this machine
+
+Use 'orbit help fetch' to read more about the command.
+"#;
\ No newline at end of file
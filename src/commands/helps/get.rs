@@ -15,6 +15,14 @@ Options:
     --instance,  -i         display instantation
     --architecture, -a      display detected architectures
     --name <identifier>     set the instance's identifier
+    --testbench             display a testbench for the unit
+    --association <kind>    connect by 'named' or 'positional' association
+    --style <kind>          instantiate as an 'entity' or 'component'
+    --signal-prefix <str>   prepend text to the names of connected signals
+    --signal-suffix <str>   append text to the names of connected signals
+    --std <edition>         follow the conventions of vhdl '93' or '2008'
+    --indent <n>            set the number of spaces per indentation
+    --lang <lang>           generate the code in 'vhdl' or 'verilog'
 
 Use 'orbit help get' to read more about the command.
 "#;
\ No newline at end of file
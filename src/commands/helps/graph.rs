@@ -0,0 +1,11 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Export the ip dependency graph.
+
+Usage:
+    orbit graph [options]
+
+Options:
+    --ip        export the graph at the ip level
+
+Use 'orbit help graph' to read more about the command.
+"#;
\ No newline at end of file
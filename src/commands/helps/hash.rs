@@ -0,0 +1,8 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Compute the checksum of an ip.
+
+Usage:
+    orbit hash [options]
+
+Use 'orbit help hash' to read more about the command.
+"#;
\ No newline at end of file
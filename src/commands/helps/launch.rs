@@ -0,0 +1,16 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Verify an upcoming release.
+
+Usage:
+    orbit launch [options]
+
+Options:
+    --ready             perform the launch after the checks pass
+    --next <version>    increment major, minor, patch, or set a version
+    --install           install the ip into the cache after launching
+    --force             continue the launch when checks fail
+    --changelog         verify or write the changelog entry for the version
+    --sign              create a signed tag for the version
+
+Use 'orbit help launch' to read more about the command.
+"#;
\ No newline at end of file
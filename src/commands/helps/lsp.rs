@@ -0,0 +1,11 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Run a language server for editors.
+
+Usage:
+    orbit lsp [options]
+
+Options:
+    --stdio     communicate over stdin and stdout (default)
+
+Use 'orbit help lsp' to read more about the command.
+"#;
\ No newline at end of file
@@ -12,3 +12,17 @@ pub mod download;
 pub mod install;
 pub mod env;
 pub mod config;
+pub mod explain;
+pub mod launch;
+pub mod clone;
+pub mod fetch;
+pub mod vendor;
+pub mod status;
+pub mod selfcmd;
+pub mod lsp;
+pub mod ci;
+pub mod test;
+pub mod graph;
+pub mod hash;
+pub mod stats;
+pub mod mv;
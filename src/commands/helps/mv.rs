@@ -0,0 +1,15 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Rename a design unit across an ip.
+
+Usage:
+    orbit mv [options] <old> <new>
+
+Args:
+    <old>               the entity or package to rename
+    <new>               the new name for the unit
+
+Options:
+    --dry-run           report the locations without changing any files
+
+Use 'orbit help mv' to read more about the command.
+"#;
\ No newline at end of file
@@ -9,6 +9,15 @@ Args:
 
 Options:
     --name <name>       the ip name to create
+    --template <name>   template to import into the new ip
+    --var <key=value>   set a value for a template variable
+    --list              view available templates
+    --refresh           fetch remote templates again before listing
+    --dry-run           preview the files to create without writing
+    --file              create a single file within an existing ip
+    --from <path>       the file to copy into the new file
+    --vcs <kind>        initialize git or none (default: git)
+    --commit            create an initial git commit
     --ip                create an ip (default: true)
 
 Use 'orbit help new' to read more about the command.
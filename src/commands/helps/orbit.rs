@@ -9,6 +9,7 @@ Commands:
     init            initialize an ip from an existing project
     show            print information about an ip
     read            navigate hdl design unit source code
+    status          report changes since the last release
     get             fetch an entity
     tree            view the dependency graph
     plan, p         generate a blueprint file
@@ -17,16 +18,38 @@ Commands:
     search          browse the ip catalog 
     download        request packages from the internet
     install         store an immutable reference to an ip
+    fetch           restore dependencies from the lock file
+    vendor          copy locked dependencies into the project
+    clone           check out an ip for development
     env             print orbit environment information
     config          modify configuration values
     uninstall       remove an ip from the catalog
+    explain         describe an error code
+    self            manage the orbit installation
+    lsp             run a language server for editors
+    ci              generate a continuous integration pipeline
+    test            run the testbenches of an ip
+    graph           export the ip dependency graph
+    hash            compute the checksum of an ip
+    stats           summarize the disk usage of orbit
+    mv              rename a design unit across an ip
 
 Options:
-    --version       print version information and exit
-    --upgrade       check for the latest orbit binary
-    --force         bypass interactive prompts
-    --color <when>  coloring: auto, always, never
-    --help, -h      print help information
+    --version           print version information and exit
+    --upgrade           check for the latest orbit binary
+    --list              view all commands, plugins, and templates available
+    --force             bypass interactive prompts
+    --yes, -y           answer yes to prompts and never wait for input
+    --quiet, -q         suppress informational messages
+    --verbose, -v       display more details (repeat for debugging)
+    --color <when>      coloring: auto, always, never
+    --profile <name>    apply a configuration profile
+    --format <fmt>      display results as: text, json
+    --progress <fmt>    report progress of long operations as: text, json
+    --timings           report how long each phase of the command took
+    --trace <file>      write a trace of the timed phases to a json file
+    --help, -h          print help information
 
 Use 'orbit help <command>' for more information about a command.
+Any other command runs an 'orbit-<command>' executable found on the PATH.
 "#;
\ No newline at end of file
@@ -15,6 +15,8 @@ Options:
     --lock-only             create the lockfile and exit
     --all                   include all found HDL files
     --force                 skip reading from the lock file
+    --dev                   use dependencies in development
+    --plan-profile <name>   apply the options of a profile in the manifest
 
 Use 'orbit help plan' to read more about the command.
 "#;
\ No newline at end of file
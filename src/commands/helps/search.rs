@@ -13,6 +13,7 @@ Options:
     --keyword <term>... special word to filter out packages
     --limit <num>       maximum number of results to return
     --match             only return results with each filter passed
+    --refresh           fetch the vendor index before searching
 
 Use 'orbit help search' to read more about the command.
 "#;
\ No newline at end of file
@@ -0,0 +1,17 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Manage the orbit installation.
+
+Usage:
+    orbit self <command> [options]
+
+Commands:
+    install                 install the running executable
+    uninstall               remove the running executable
+
+Options:
+    --path <dir>            directory to install the executable into
+    --no-modify-path        do not add the directory to the PATH
+    --purge                 also remove all configuration, downloads, and ip
+
+Use 'orbit help self' to read more about the command.
+"#;
\ No newline at end of file
@@ -8,6 +8,8 @@ Options:
     <ip>                        the spec of the ip to query       
     --versions                  display the list of possible versions
     --units                     display primary design units within an ip
+    --files                     display the files within an ip with their hashes
+    --dev                       prefer the ip in development
 
 Use 'orbit help show' to read more about the command.
 "#;
\ No newline at end of file
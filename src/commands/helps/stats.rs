@@ -0,0 +1,11 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Summarize the disk usage of orbit.
+
+Usage:
+    orbit stats [options]
+
+Options:
+    --home              report the size and health of orbit's home directory
+
+Use 'orbit help stats' to read more about the command.
+"#;
\ No newline at end of file
@@ -0,0 +1,8 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Report changes since the last release.
+
+Usage:
+    orbit status [options]
+
+Use 'orbit help status' to read more about the command.
+"#;
\ No newline at end of file
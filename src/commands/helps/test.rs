@@ -0,0 +1,16 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Run the testbenches of an ip.
+
+Usage:
+    orbit test [options] [--] [args]...
+
+Options:
+    --plugin <name>     plugin to simulate each testbench
+    --bench <tb>...     only run these testbenches
+    --build-dir <dir>   the relative directory to place each testbench's build
+    --list              view the testbenches and exit
+    --fail-fast         skip the remaining testbenches after a failure
+    args                arguments to pass to the plugin
+
+Use 'orbit help test' to read more about the command.
+"#;
\ No newline at end of file
@@ -11,6 +11,7 @@ Options:
     --format <fmt>      select how to display unit nodes: 'long' or 'short'
     --ascii             restrict tree chars to the original 128 ascii set
     --ip                view the dependency graph at the ip level
+    --why <unit>        list the paths from the root to a unit
 
 Use 'orbit help tree' to read more about the command.
 "#;
\ No newline at end of file
@@ -0,0 +1,18 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Copy locked dependencies into the project.
+
+Usage:
+    orbit vendor [options] [<ip>]
+
+Args:
+    <ip>                upstream ip to mirror instead of the dependencies
+
+Options:
+    --dir <path>        directory to place the dependencies (default: vendor)
+    --mirror <path>     publish the dependencies as archives into a vendor index
+    --url <url>         fetch the named ip from a url before mirroring it
+    --all               vendor all dependencies including development
+    --refresh           fetch the configured vendor index again
+
+Use 'orbit help vendor' to read more about the command.
+"#;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use super::new::New;
 use crate::commands::orbit::AnyResult;
 use crate::core::context::Context;
-use crate::core::manifest::{Manifest, IP_MANIFEST_FILE};
+use crate::core::manifest::{Manifest, Vcs, IP_MANIFEST_FILE};
 use crate::core::pkgid::PkgPart;
 use crate::util::anyerror::AnyError;
 use crate::util::filesystem;
@@ -79,7 +79,7 @@ impl Init {
         };
 
         let mut manifest = std::fs::File::create(&manifest_path)?;
-        manifest.write_all(Manifest::write_empty_manifest(&ip).as_bytes())?;
+        manifest.write_all(Manifest::write_empty_manifest(&ip, &Vcs::default()).as_bytes())?;
         Ok(())
     }
 }
\ No newline at end of file
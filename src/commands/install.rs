@@ -20,10 +20,14 @@
 use super::plan::Plan;
 use crate::commands::download::Download;
 use crate::commands::plan;
+use crate::commands::vendor;
 use crate::core::algo;
 use crate::core::catalog::CacheSlot;
 use crate::core::catalog::Catalog;
 use crate::core::context::Context;
+use crate::core::hook::Event;
+use crate::core::hook::Hook;
+use crate::core::hook::InstallHook;
 use crate::core::ip::Ip;
 use crate::core::ip::PartialIpSpec;
 use crate::core::lockfile::LockEntry;
@@ -34,8 +38,11 @@ use crate::core::protocol::Protocol;
 use crate::core::source::Source;
 use crate::core::variable::VariableTable;
 use crate::core::version;
+use crate::core::version::AnyVersion;
+use crate::core::vendor::VendorIndex;
 use crate::util::anyerror::Fault;
 use crate::util::environment::Environment;
+use crate::util::filelock::FileLock;
 use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
 use crate::OrbitResult;
@@ -46,6 +53,9 @@ use clif::Error as CliError;
 use std::env;
 use std::fs;
 use crate::util::anyerror::AnyError;
+use crate::util::logger;
+use crate::util::progress::Progress;
+use crate::util::timing;
 use std::path::PathBuf;
 use crate::commands::helps::install;
 
@@ -180,12 +190,75 @@ impl Command<Context> for Install {
 
         // update the downloads
         catalog = catalog.downloads(c.get_downloads_path())?;
+
+        // copy an ip found nowhere else from the vendor index into the downloads
+        if self.path.is_none() && self.url.is_none() {
+            if let Some(spec) = &self.ip {
+                let found = catalog
+                    .inner()
+                    .get(spec.get_name())
+                    .and_then(|lvl| lvl.get(true, spec.get_version()))
+                    .is_some();
+                if found == false {
+                    if let Some((index, _)) = vendor::sync_index(c, false)? {
+                        if Self::copy_from_vendor_index(spec, &index, c.get_downloads_path())? == true {
+                            catalog = catalog.downloads(c.get_downloads_path())?;
+                        }
+                    }
+                }
+            }
+        }
+
+        // a forced reinstall never trusts the installed files, so fetch the archive again
+        if self.force == true && self.path.is_none() {
+            if let Some(spec) = &self.ip {
+                let suspect = catalog
+                    .inner()
+                    .get(spec.get_name())
+                    .filter(|lvl| lvl.get_download(spec.get_version()).is_none())
+                    .and_then(|lvl| lvl.get_install(spec.get_version()))
+                    .map(|ip| (ip.get_man().get_ip().into_ip_spec(), ip.get_man().get_ip().get_source().cloned()));
+                if let Some((installed, source)) = suspect {
+                    match source {
+                        Some(src) => {
+                            logger::info(format!("IP {} has no downloaded archive; fetching it from its source ...", installed));
+                            let env = Environment::new().from_config(c.get_config())?;
+                            let mut vtable = VariableTable::new()
+                                .load_environment(&env)?
+                                .load_config(c.get_config())?;
+                            Download::download(
+                                &mut vtable,
+                                &installed,
+                                &src,
+                                None,
+                                c.get_downloads_path(),
+                                &c.get_config().get_protocols(),
+                                &c.get_config().get_credentials(),
+                                &c.get_config().get_rewrites(),
+                                c.get_config().get_init_submodules(),
+                                false,
+                                true,
+                            )?;
+                            catalog = catalog.downloads(c.get_downloads_path())?;
+                        }
+                        None => {
+                            return Err(AnyError(format!("IP {} has no downloaded archive or source to reinstall from\n\nTry reinstalling the ip with `--path` or `--url`", installed)))?
+                        }
+                    }
+                }
+            }
+        }
         
         // use the catalog (if no path is provided)
         let target = if self.path.is_none() {
             if let Some(spec) = &self.ip {
                 if let Some(lvl) = catalog.inner().get(spec.get_name()) {
-                    if let Some(slot) = lvl.get(true, spec.get_version()) {
+                    // a forced reinstall extracts from the archive rather than the suspect installation
+                    let slot = match self.force {
+                        true => lvl.get_download(spec.get_version()),
+                        false => lvl.get(true, spec.get_version()),
+                    };
+                    if let Some(slot) = slot {
                         if let Some(bytes) = slot.get_mapping().as_bytes() {
                             // place the dependency into a temporary directory
                             let dir = tempfile::tempdir()?.into_path();
@@ -227,7 +300,7 @@ impl Command<Context> for Install {
 
         // move the IP to the downloads folder if not already there
         if catalog.is_downloaded_slot(&LockEntry::from((&target, true)).to_download_slot_key()) == false {
-            Download::move_to_download_dir(&target.get_root(), c.get_downloads_path(), &target.get_man().get_ip().into_ip_spec())?;
+            Download::move_to_download_dir(&target.get_root(), c.get_downloads_path(), &target.get_man().get_ip().into_ip_spec(), None, false)?;
         }
 
         // if target is not in downloads, download it
@@ -238,7 +311,10 @@ impl Command<Context> for Install {
             let env = Environment::new()
                 // read config.toml for setting any env variables
                 .from_config(c.get_config())?;
-            let vtable = VariableTable::new().load_environment(&env)?;
+            let vtable = VariableTable::new()
+                .load_environment(&env)?
+                // read config.toml for user-defined variables
+                .load_config(c.get_config())?;
 
             let le = LockEntry::from((&target, true));
 
@@ -250,11 +326,19 @@ impl Command<Context> for Install {
                 &le,
                 &catalog,
                 &c.get_config().get_protocols(),
+                &c.get_config().get_credentials(),
+                &c.get_config().get_rewrites(),
+                c.get_config().get_init_submodules(),
             )?;
             // recollect the queued items to update the catalog
             catalog = catalog.downloads(c.get_downloads_path())?;
 
-            plan::install_missing_deps(&lf, &le, &catalog)?;
+            plan::install_missing_deps(
+                &lf,
+                &le,
+                &catalog,
+                &c.get_config().get_allowed_install_hooks(),
+            )?;
             // recollect the installations and queued items to update the catalog
             catalog = catalog.installations(c.get_cache_path())?;
         }
@@ -265,11 +349,39 @@ impl Command<Context> for Install {
             Plan::write_lockfile(&target, &ip_graph, true)?;
         }
         // install the top-level target
-        self.run(&target, &catalog)
+        Hook::dispatch(&c.get_config().get_hooks(Event::PreInstall), Event::PreInstall, &target)?;
+        self.run(&target, &catalog, &c.get_config().get_allowed_install_hooks())?;
+        Hook::dispatch(&c.get_config().get_hooks(Event::PostInstall), Event::PostInstall, &target)
     }
 }
 
 impl Install {
+    /// Copies the archive of the ip `spec` from the vendor index at `index` into
+    /// the `downloads` directory.
+    ///
+    /// Returns `false` if the vendor index does not have the ip.
+    fn copy_from_vendor_index(spec: &PartialIpSpec, index: &PathBuf, downloads: &PathBuf) -> Result<bool, Fault> {
+        let vendored = Catalog::new().available(index)?;
+        let ip = match vendored
+            .inner()
+            .get(spec.get_name())
+            .and_then(|lvl| lvl.get_available(spec.get_version()))
+        {
+            Some(ip) => ip,
+            None => return Ok(false),
+        };
+        let man = ip.get_man().get_ip();
+        let records = VendorIndex::load(index)?;
+        let entry = match records.find(&man.get_name().to_string(), &man.get_version().to_string()) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        logger::info(format!("Copying ip {} from the vendor index ...", man.into_ip_spec()));
+        fs::create_dir_all(downloads)?;
+        fs::copy(index.join(entry.get_archive()), downloads.join(entry.get_archive()))?;
+        Ok(true)
+    }
+
     fn download_target_from_url(&self, c: &Context, url: &str) -> Result<(), Fault> {
         // verify a whole spec is provided
         let spec = match &self.ip {
@@ -287,7 +399,10 @@ impl Install {
         let env = Environment::new()
             // read config.toml for setting any env variables
             .from_config(c.get_config())?;
-        let mut vtable = VariableTable::new().load_environment(&env)?;
+        let mut vtable = VariableTable::new()
+            .load_environment(&env)?
+            // read config.toml for user-defined variables
+            .load_config(c.get_config())?;
         env.initialize();
 
         let protocols: ProtocolMap = c.get_config().get_protocols();
@@ -302,6 +417,9 @@ impl Install {
             None,
             c.get_downloads_path(),
             &protocols,
+            &c.get_config().get_credentials(),
+            &c.get_config().get_rewrites(),
+            c.get_config().get_init_submodules(),
             self.verbose,
             self.force,
         )?;
@@ -324,8 +442,20 @@ impl Install {
     /// Installs the `ip` with particular partial `version` to the `cache_root`.
     /// It will reinstall if it finds the original installation has a mismatching checksum.
     ///
+    /// Setting `force` wipes an existing installation in the slot regardless of its
+    /// checksum. The new installation is verified against its checksum afterward,
+    /// and a partial or mismatching installation is removed from the cache.
+    ///
+    /// A post-install hook declared by the ip runs within the verified cache slot,
+    /// and only if the ip is in `allowed_hooks`.
+    ///
     /// Returns `true` if the IP was successfully installed and `false` if it already existed.
-    pub fn install(src: &Ip, cache_root: &std::path::PathBuf, force: bool) -> Result<bool, Fault> {
+    pub fn install(
+        src: &Ip,
+        cache_root: &std::path::PathBuf,
+        force: bool,
+        allowed_hooks: &[&str],
+    ) -> Result<bool, Fault> {
         // temporary destination to move files for processing and manipulation
         let dest = tempfile::tempdir()?.into_path();
         filesystem::copy(src.get_root(), &dest, true, Some(src.get_files_to_keep()))?;
@@ -337,17 +467,14 @@ impl Install {
         // @note: ability to link various index directories (essentially vendors)
         // @note: also want to store zipped archives of installs in the "vault" for quicker retrieval
 
-        // @todo: listing all units
-
-        // @todo: store a LUT for unit names to the correct file to read when computing "get" command
-
         // @todo: getting the size of the entire directory
 
         // access the name and version
         let version = src.get_man().get_ip().get_version();
         let target = src.get_man().get_ip().get_name();
         let ip_spec = src.get_man().get_ip().into_ip_spec();
-        println!("info: Installing IP {} ...", &ip_spec);
+        let _span = timing::span(format!("install {}", &ip_spec));
+        logger::info(format!("Installing IP {} ...", &ip_spec));
 
         // perform sha256 on the temporary cloned directory
         let checksum = Ip::compute_checksum(&dest);
@@ -355,11 +482,16 @@ impl Install {
 
         // use checksum to create new directory slot
         let cache_slot_name = CacheSlot::new(target, &version, &checksum);
-        let cache_slot = cache_root.join(&cache_slot_name.to_string());
+        let cache_slot = cache_slot_name.to_path(cache_root, src.get_uuid());
+        // slots are sharded into directories by the ip's uuid
+        std::fs::create_dir_all(cache_slot.parent().unwrap())?;
+        // prevent another orbit process from writing to the same slot
+        let _lock = FileLock::acquire(&cache_slot)?;
         // check if the slot is occupied in the cache
         if cache_slot.exists() == true {
             // check if we should proceed with force regardless if the installation is valid
             if force == true {
+                logger::info(format!("Reinstalling IP {} ...", ip_spec));
                 std::fs::remove_dir_all(&cache_slot)?;
             } else {
                 // ip is already installed
@@ -368,7 +500,7 @@ impl Install {
                     fs::remove_dir_all(dest)?;
                     return Ok(false);
                 } else {
-                    println!("info: Reinstalling IP {} due to bad checksum ...", ip_spec);
+                    logger::info(format!("Reinstalling IP {} due to bad checksum ...", ip_spec));
 
                     // blow directory up for re-install
                     std::fs::remove_dir_all(&cache_slot)?;
@@ -376,7 +508,14 @@ impl Install {
             }
         }
         // copy contents into cache slot from temporary destination
-        crate::util::filesystem::copy(&dest, &cache_slot, false, None)?;
+        if let Err(e) = crate::util::filesystem::copy(&dest, &cache_slot, false, None) {
+            // never leave a partial installation behind
+            fs::remove_dir_all(&dest)?;
+            if cache_slot.exists() == true {
+                fs::remove_dir_all(&cache_slot)?;
+            }
+            return Err(e);
+        }
 
         // clean up the temporary directory ourself
         fs::remove_dir_all(dest)?;
@@ -387,17 +526,83 @@ impl Install {
             checksum.to_string().as_bytes(),
         )?;
 
+        // verify the installed files match the files that were hashed
+        if Self::is_checksum_good(&cache_slot) == false {
+            fs::remove_dir_all(&cache_slot)?;
+            return Err(AnyError(format!(
+                "Failed to verify the checksum of the installation for IP {}",
+                ip_spec
+            )))?;
+        }
+
+        // generate any files within the installation, keeping them out of its checksum
+        if let Err(e) = Self::run_install_hook(src, &cache_slot, allowed_hooks) {
+            fs::remove_dir_all(&cache_slot)?;
+            return Err(e);
+        }
+
+        // record the primary design units so reading them never parses the source code again
+        match Ip::collect_units(true, &cache_slot) {
+            Ok(units) => Ip::write_units_to_metadata(&cache_slot, &units)?,
+            Err(e) => logger::warn(format!(
+                "unable to record the design units of IP {}: {}",
+                ip_spec, e
+            )),
+        }
+
         Ok(true)
     }
 
-    fn run(&self, target: &Ip, catalog: &Catalog) -> Result<(), Fault> {
-        let result = Self::install(&target, &catalog.get_cache_path(), self.force)?;
+    /// Runs the post-install hook of the ip `src` within its installation at
+    /// `cache_slot` and records the files it created.
+    ///
+    /// The hook may only add files, so the installation must still match its
+    /// checksum afterward.
+    fn run_install_hook(src: &Ip, cache_slot: &PathBuf, allowed_hooks: &[&str]) -> Result<(), Fault> {
+        let before = filesystem::gather_current_files(cache_slot, true);
+        InstallHook::run(src, cache_slot, InstallHook::is_allowed(src, allowed_hooks))?;
+        let generated: Vec<String> = filesystem::gather_current_files(cache_slot, true)
+            .into_iter()
+            .filter(|f| before.contains(f) == false)
+            .collect();
+        if generated.is_empty() == false {
+            Ip::write_generated_files(cache_slot, &generated)?;
+        }
+        if Self::is_checksum_good(cache_slot) == false {
+            return Err(AnyError(format!(
+                "post-install hook of ip {} modified the installed files",
+                src.get_man().get_ip().into_ip_spec()
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn run(&self, target: &Ip, catalog: &Catalog, allowed_hooks: &[&str]) -> Result<(), Fault> {
+        // warn when the same version is already installed with different contents
+        let version = AnyVersion::from(target.get_man().get_ip().get_version());
+        if let Some(existing) = catalog
+            .inner()
+            .get(target.get_man().get_ip().get_name())
+            .and_then(|lvl| lvl.get_install(&version))
+            .filter(|ip| ip.get_uuid() == target.get_uuid())
+        {
+            if target.is_modified_since(existing) == true {
+                logger::warn(format!(
+                    "IP {} is already installed with different files; consider increasing its version",
+                    target.get_man().get_ip().into_ip_spec()
+                ));
+            }
+        }
+
+        let mut progress = Progress::start("install", Some(1));
+        let result = Self::install(&target, &catalog.get_cache_path(), self.force, allowed_hooks)?;
+        progress.advance(target.get_man().get_ip().into_ip_spec().to_string());
 
         if result == false {
-            println!(
-                "info: IP {} is already installed",
+            logger::info(format!(
+                "IP {} is already installed",
                 target.get_man().get_ip().into_ip_spec()
-            );
+            ));
         }
 
         Ok(())
@@ -1,10 +1,32 @@
+use crate::commands::hash::latest_release;
+use crate::commands::helps::launch;
+use crate::commands::install::Install;
+use crate::core::catalog::Catalog;
 use crate::core::context::Context;
-use crate::core::version::Version;
+use crate::core::credential;
+use crate::core::credential::Credential;
+use crate::core::forge::{Asset, Release};
+use crate::core::hook::{Event, Hook};
+use crate::core::ip::Ip;
+use crate::core::lang::vhdl::token::Identifier;
+use crate::core::lockfile::LockEntry;
+use crate::core::manifest::{Vcs, IP_MANIFEST_FILE};
+use crate::core::version::{AnyVersion, Version};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::compress;
+use crate::util::diagnostic::Diagnostic;
+use crate::util::logger;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
+use colored::Colorize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::Output;
+use std::str::FromStr;
 
 #[derive(Debug, PartialEq)]
 enum VersionField {
@@ -14,7 +36,7 @@ enum VersionField {
     Version(Version),
 }
 
-impl std::str::FromStr for VersionField {
+impl FromStr for VersionField {
     type Err = crate::core::version::VersionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -27,19 +49,39 @@ impl std::str::FromStr for VersionField {
     }
 }
 
+impl VersionField {
+    /// Computes the version that follows the `current` version.
+    fn apply(&self, current: &Version) -> Version {
+        let mut next = current.clone();
+        match self {
+            Self::Major => next.inc_major(),
+            Self::Minor => next.inc_minor(),
+            Self::Patch => next.inc_patch(),
+            Self::Version(v) => next = v.clone(),
+        }
+        next
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Launch {
     next: Option<VersionField>,
     ready: bool,
     install: bool,
+    force: bool,
+    changelog: bool,
+    sign: bool,
 }
 
 impl FromCli for Launch {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
-        cli.check_help(clif::Help::new().quick_text(HELP).ref_usage(2..4))?;
+        cli.check_help(clif::Help::new().quick_text(launch::HELP).ref_usage(2..4))?;
         let command = Ok(Launch {
             ready: cli.check_flag(Flag::new("ready"))?,
             install: cli.check_flag(Flag::new("install"))?,
+            force: cli.check_flag(Flag::new("force"))?,
+            changelog: cli.check_flag(Flag::new("changelog"))?,
+            sign: cli.check_flag(Flag::new("sign"))?,
             next: cli.check_option(Optional::new("next").value("version"))?,
         });
         command
@@ -49,26 +91,1017 @@ impl FromCli for Launch {
 impl Command<Context> for Launch {
     type Status = OrbitResult;
 
-    fn exec(&self, _c: &Context) -> Self::Status {
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+
+        let current = ip.get_man().get_ip().get_version();
+        let version = match &self.next {
+            Some(field) => field.apply(current),
+            None => current.clone(),
+        };
+
+        let target = Target {
+            ip: &ip,
+            version: &version,
+            context: c,
+        };
+        self.run(&target)
+    }
+}
+
+impl Launch {
+    fn run(&self, target: &Target) -> Result<(), Fault> {
+        let name = target.ip.get_man().get_ip().get_name();
+        logger::info(format!(
+            "Running pre-flight checks for ip {} {} ...",
+            name, target.version
+        ));
         // by default, do not make any changes to the codebase/project (only print out diagnostics)
-        todo!("verify the ip manifest is valid");
-        // todo!("verify the lock file is generated and up to date");
-        // todo!("verify there is no other ip with this name (and different uuid)");
-        // todo!("verify the HDL graph can be generated without errors");
-        // warn if there are no HDL units in the project
+        let mut failures = Vec::new();
+        let release = target.ip.get_man().get_ip().get_release().is_some();
+        for check in self.pipeline(release) {
+            let outcome = check.run(target);
+            println!("{}", outcome.fmt_line(check.name()));
+            if let Outcome::Fail(why) = outcome {
+                failures.push((check.name().to_string(), why));
+            }
+        }
+        if failures.is_empty() == false {
+            match self.force {
+                true => logger::warn(format!(
+                    "continuing with {} failed check(s) due to `--force`",
+                    failures.len()
+                )),
+                false => return Err(LaunchError::ChecksFailed(failures))?,
+            }
+        }
+
+        if self.ready == false {
+            logger::info(format!(
+                "Ip {} {} is ready to launch; use `--ready` to proceed",
+                name, target.version
+            ));
+            return Ok(());
+        }
+
+        // write the next version to the manifest
+        if target.version != target.ip.get_man().get_ip().get_version() {
+            Self::write_version(target.ip.get_root(), target.version)?;
+        }
+        let ip = Ip::load(target.ip.get_root().clone())?;
+
+        if self.changelog == true {
+            Self::write_changelog(&ip, target.version)?;
+        }
+
+        // record the edits before they are tagged, pushed, or archived
+        Self::commit_edits(&ip, target.version)?;
+
+        if self.sign == true {
+            Self::write_tag(&ip, target.version)?;
+        }
+
+        // the ip is published when it is installed to the cache or released to its channel
+        let config = target.context.get_config();
+        let release = ip.get_man().get_ip().get_release();
+        let publishing = self.install == true || release.is_some();
+        if publishing == true {
+            Hook::dispatch(&config.get_hooks(Event::PrePublish), Event::PrePublish, &ip)?;
+        }
+
+        if self.install == true {
+            Install::install(
+                &ip,
+                target.context.get_cache_path(),
+                false,
+                &config.get_allowed_install_hooks(),
+            )?;
+        }
+
+        if let Some(release) = release {
+            Self::publish_release(&ip, release, target.context)?;
+        }
+
+        if publishing == true {
+            Hook::dispatch(&config.get_hooks(Event::PostPublish), Event::PostPublish, &ip)?;
+        }
+
+        Hook::dispatch(
+            &target.context.get_config().get_hooks(Event::PostLaunch),
+            Event::PostLaunch,
+            &ip,
+        )?;
+        logger::info(format!("Launched ip {} {}", name, target.version));
+        Ok(())
+    }
+
+    /// Updates the `version` field of the manifest at `root` while preserving
+    /// the remaining contents of the file.
+    fn write_version(root: &PathBuf, version: &Version) -> Result<(), Fault> {
+        let path = root.join(IP_MANIFEST_FILE);
+        let mut doc = std::fs::read_to_string(&path)?.parse::<toml_edit::Document>()?;
+        doc["ip"]["version"] = toml_edit::value(version.to_string());
+        std::fs::write(&path, doc.to_string())?;
+        Ok(())
+    }
+
+    /// Writes a stub entry for the `version` to the changelog at `root` from the
+    /// git log since the last tag, unless an entry already exists.
+    fn write_changelog(ip: &Ip, version: &Version) -> Result<(), Fault> {
+        let path = ip.get_root().join(CHANGELOG_FILE);
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        if has_entry(&text, version) == true {
+            return Ok(());
+        }
+        let stub = fmt_stub(version, &commits_since_tag(ip));
+        std::fs::write(&path, insert_stub(&text, &stub))?;
+        logger::info(format!(
+            "Wrote a changelog entry for version {} to {:?}; review it before publishing",
+            version, path
+        ));
+        Ok(())
+    }
+
+    /// Commits the manifest and changelog changes made by the launch.
+    ///
+    /// Nothing is committed when the ip is not within a git repository.
+    fn commit_edits(ip: &Ip, version: &Version) -> Result<(), Fault> {
+        if git(ip, &["status"]).is_none() == true {
+            return Ok(());
+        }
+        let message = fmt_release_message(ip, version);
+        let files: Vec<&str> = [IP_MANIFEST_FILE, CHANGELOG_FILE]
+            .into_iter()
+            .filter(|f| ip.get_root().join(f).exists() == true)
+            .collect();
+        let mut args = vec!["status", "--porcelain", "--"];
+        args.extend(&files);
+        if git_ok(ip, &args)?.stdout.is_empty() == false {
+            let mut args = vec!["add", "--"];
+            args.extend(&files);
+            git_ok(ip, &args)?;
+            let mut args = vec!["commit", "--quiet", "-m", message.as_str(), "--"];
+            args.extend(&files);
+            git_ok(ip, &args)?;
+        }
+        Ok(())
+    }
+
+    /// Creates a signed annotated tag for the `version` at the current commit.
+    fn write_tag(ip: &Ip, version: &Version) -> Result<(), Fault> {
+        let tag = version.to_string();
+        let message = fmt_release_message(ip, version);
+        git_ok(ip, &["tag", "--sign", "-m", &message, &tag])?;
+        // the annotated tag records who created it
+        let tagger = git_ok(
+            ip,
+            &[
+                "for-each-ref",
+                "--format=%(taggername) %(taggeremail)",
+                &format!("refs/tags/{}", tag),
+            ],
+        )?;
+        let remote = match Upstream::detect(ip, None) {
+            Ok(Some(up)) => up.remote,
+            _ => String::from("<remote>"),
+        };
+        logger::info(format!(
+            "Created signed tag {} by {}; push it with `git push {} {}`",
+            tag,
+            String::from_utf8_lossy(&tagger.stdout).trim(),
+            remote,
+            tag
+        ));
+        Ok(())
+    }
+
+    /// Returns the checks to perform in the order they run.
+    ///
+    /// The changelog is only verified when `--changelog` is set, and a missing
+    /// entry is allowed when one will be generated during the launch. Access to
+    /// the forge is only verified when a `release` is configured for the ip.
+    fn pipeline(&self, release: bool) -> Vec<Box<dyn Check>> {
+        let mut checks: Vec<Box<dyn Check>> = vec![
+            Box::new(CleanTree),
+            Box::new(NewVersion),
+            Box::new(FilesChanged),
+            Box::new(LockConsistent),
+            Box::new(UnitsParse),
+            Box::new(NameCollision),
+            Box::new(RemoteDeps),
+        ];
+        if self.changelog == true {
+            checks.push(Box::new(ChangelogEntry {
+                generate: self.ready,
+            }));
+        }
+        if self.sign == true {
+            checks.push(Box::new(SigningKey));
+        }
+        if release == true {
+            checks.push(Box::new(ReleaseAccess { sign: self.sign }));
+            checks.push(Box::new(UpstreamBranch));
+        }
+        checks.push(Box::new(LaunchHooks));
+        checks
+    }
+
+    /// Uploads a zip archive of the `ip` and its checksum file as a release on
+    /// the ip's forge.
+    fn publish_release(ip: &Ip, release: &Release, c: &Context) -> Result<(), Fault> {
+        let token = match find_token(release, c) {
+            Some(t) => t,
+            None => return Err(AnyError(fmt_missing_token(release)))?,
+        };
+        let spec = ip.get_man().get_ip().into_ip_spec();
+        let version = spec.get_version().to_string();
+
+        let dir = tempfile::tempdir()?;
+        let zip = dir.path().join(format!("{}.zip", spec.get_name()));
+        compress::write_zip_dir(ip.get_root(), &zip)?;
+        let assets = Asset::from_archive(&spec, std::fs::read(&zip)?);
+
+        if release.requires_signed_tag() == true {
+            git_ok(ip, &["tag", "--verify", &version])?;
+        }
+        // the forge must have the released commit and its tag
+        if let Some(up) = Upstream::detect(ip, release.get_remote()).map_err(AnyError)? {
+            let mut refs = vec![format!("HEAD:refs/heads/{}", up.branch)];
+            if git_ok(ip, &["tag", "--list", &version])?.stdout.is_empty() == false {
+                refs.push(format!("refs/tags/{}", version));
+            }
+            let mut args = vec!["push", "--quiet", up.remote.as_str()];
+            args.extend(refs.iter().map(|r| r.as_str()));
+            logger::info(format!("Pushing to {}/{} ...", up.remote, up.branch));
+            git_ok(ip, &args)?;
+        }
+        let commit = git(ip, &["rev-parse", "HEAD"])
+            .filter(|out| out.status.success() == true)
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string());
+        let notes = entry_notes(
+            &std::fs::read_to_string(ip.get_root().join(CHANGELOG_FILE)).unwrap_or_default(),
+            spec.get_version(),
+        );
+
+        logger::info(format!("Publishing release {} to {} ...", version, release.get_host_url()));
+        let urls = release.publish(&spec, &version, commit.as_deref(), &notes, &assets, &token)?;
+        for (asset, url) in assets.iter().zip(urls.iter()) {
+            logger::info(format!("Uploaded {} to {}", asset.get_name(), url));
+        }
+        if let Some(url) = urls.iter().find(|u| u.ends_with(".zip") == true) {
+            logger::info(format!(
+                "Set the ip's source to \"{}\" to let others install this release over https",
+                url
+            ));
+        }
+        Ok(())
     }
 }
 
-const HELP: &str = "\
-Run a series of checks to verify the ip is ready to be released.
+/// Creates the message for the commit and tag that release the `ip` at the `version`.
+fn fmt_release_message(ip: &Ip, version: &Version) -> String {
+    format!("Release {} {}", ip.get_man().get_ip().get_name(), version)
+}
 
-Usage:
-    orbit launch [options]
+/// Reads the access token of the credential for the `release`'s forge.
+fn find_token(release: &Release, c: &Context) -> Option<String> {
+    Credential::find(&c.get_config().get_credentials(), &release.get_host_url())?.get_token()
+}
 
-Options:
-    --ready                 proceed with the launch process
-    --next <version>        semver version or 'major', 'minor', or 'patch'
-    --install               install the newly launched version
+fn fmt_missing_token(release: &Release) -> String {
+    format!(
+        "no access token is available for {}; add a credential with `token-env` for its host to the configuration",
+        release.get_host_url()
+    )
+}
+
+/// Extracts the body of the changelog `text`'s entry for the `version`, which
+/// is used as the notes of a release.
+fn entry_notes(text: &str, version: &Version) -> String {
+    let mut lines = text.lines().skip_while(|line| {
+        line.starts_with('#') == false || has_entry(line, version) == false
+    });
+    // skip the entry's heading
+    lines.next();
+    lines
+        .take_while(|line| line.starts_with("## ") == false && line.starts_with("# ") == false)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// The file that holds the release notes for each version of an ip.
+const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Checks if the changelog `text` has a heading for the `version`, such as
+/// `## 1.2.0` or `## [1.2.0] - 2024-01-01`.
+fn has_entry(text: &str, version: &Version) -> bool {
+    let ver = version.to_string();
+    text.lines()
+        .filter(|line| line.starts_with('#') == true)
+        .any(|line| {
+            line.split(|c: char| c.is_whitespace() || c == '[' || c == ']')
+                .any(|word| word == ver || word.strip_prefix('v') == Some(&ver))
+        })
+}
+
+/// Creates a changelog entry for the `version` listing the `commits`.
+fn fmt_stub(version: &Version, commits: &[String]) -> String {
+    let mut stub = format!("## {} - unreleased\n\n", version);
+    match commits.is_empty() {
+        true => stub.push_str("- \n"),
+        false => commits
+            .iter()
+            .for_each(|c| stub.push_str(&format!("- {}\n", c))),
+    }
+    stub
+}
+
+/// Places the `stub` before the latest entry of the changelog `text`, or after
+/// its title if there are no entries yet.
+fn insert_stub(text: &str, stub: &str) -> String {
+    if text.trim().is_empty() == true {
+        return format!("# Changelog\n\n{}", stub);
+    }
+    match text.find("\n## ") {
+        Some(i) => format!("{}\n{}\n{}", &text[..i], stub, &text[i + 1..]),
+        None => format!("{}\n\n{}", text.trim_end(), stub),
+    }
+}
+
+/// Collects the subject lines of the commits made since the latest git tag, or
+/// of all commits if there are no tags.
+fn commits_since_tag(ip: &Ip) -> Vec<String> {
+    let range = git(ip, &["describe", "--tags", "--abbrev=0"])
+        .filter(|out| out.status.success() == true)
+        .map(|out| format!("{}..HEAD", String::from_utf8_lossy(&out.stdout).trim()));
+    let mut args = vec!["log", "--pretty=format:%s"];
+    if let Some(range) = &range {
+        args.push(range);
+    }
+    match git(ip, &args) {
+        Some(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|l| l.to_string())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// The ip and version under inspection before a launch.
+struct Target<'a> {
+    ip: &'a Ip,
+    version: &'a Version,
+    context: &'a Context,
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Pass,
+    /// The check could not be performed, such as when the ip is not within a
+    /// git repository.
+    Skip(String),
+    /// The check found a problem that does not stop the launch.
+    Warn(String),
+    Fail(String),
+}
+
+impl Outcome {
+    fn fmt_line(&self, name: &str) -> String {
+        match self {
+            Self::Pass => format!("  {:<36}{}", name, "ok".green()),
+            Self::Skip(why) => format!("  {:<36}{} ({})", name, "skipped".yellow(), why),
+            Self::Warn(why) => format!("  {:<36}{}\n      {}", name, "warning".yellow(), why),
+            Self::Fail(why) => format!("  {:<36}{}\n      {}", name, "FAILED".red(), why),
+        }
+    }
+}
+
+/// A single verification performed before an ip is launched.
+trait Check {
+    fn name(&self) -> &str;
+
+    fn run(&self, target: &Target) -> Outcome;
+}
+
+/// Runs `git` with the `args` from the `ip`'s root directory.
+///
+/// Returns `None` if the ip does not use git as its vcs, git is not available,
+/// or the ip is not within a git repository.
+fn git(ip: &Ip, args: &[&str]) -> Option<Output> {
+    if ip.get_man().get_ip().get_vcs() != Vcs::Git {
+        return None;
+    }
+    let dir = ip.get_root();
+    let inside = std::process::Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if inside.status.success() == false {
+        return None;
+    }
+    std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()
+}
+
+/// The remote branch that receives the ip's commits.
+#[derive(Debug, PartialEq)]
+struct Upstream {
+    remote: String,
+    branch: String,
+}
+
+impl Upstream {
+    /// Detects the upstream of the current branch, where the `remote` replaces
+    /// the remote configured for the branch when given.
+    ///
+    /// Returns `None` if the ip is not within a git repository.
+    fn detect(ip: &Ip, remote: Option<&str>) -> Result<Option<Self>, String> {
+        let read = |args: &[&str]| -> Option<String> {
+            git(ip, args)
+                .filter(|out| out.status.success() == true)
+                .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+                .filter(|s| s.is_empty() == false)
+        };
+        if git(ip, &["rev-parse", "HEAD"]).is_none() == true {
+            return Ok(None);
+        }
+        let branch = match read(&["symbolic-ref", "--quiet", "--short", "HEAD"]) {
+            Some(b) => b,
+            None => {
+                return Err(String::from(
+                    "HEAD is detached; check out the branch to launch from",
+                ))
+            }
+        };
+        if let Some(r) = remote {
+            if read(&["remote", "get-url", r]).is_none() == true {
+                return Err(format!(
+                    "git remote '{}' does not exist; try `git remote add {} <url>`",
+                    r, r
+                ));
+            }
+        }
+        Self::resolve(
+            &branch,
+            remote,
+            read(&["config", "--get", &format!("branch.{}.remote", branch)]),
+            read(&["config", "--get", &format!("branch.{}.merge", branch)]),
+        )
+        .map(Some)
+    }
+
+    /// Determines the upstream of the local `branch` from its configured remote
+    /// and merge ref, where the `remote` takes precedence when given.
+    fn resolve(
+        branch: &str,
+        remote: Option<&str>,
+        config_remote: Option<String>,
+        config_merge: Option<String>,
+    ) -> Result<Self, String> {
+        let remote = match (remote, config_remote) {
+            (Some(r), _) => r.to_string(),
+            (None, Some(r)) => r,
+            (None, None) => {
+                return Err(format!(
+                    "branch '{}' has no upstream; try `git push --set-upstream <remote> {}` or set the `remote` of the ip's release",
+                    branch, branch
+                ))
+            }
+        };
+        let branch = match config_merge {
+            Some(m) => m.trim_start_matches("refs/heads/").to_string(),
+            None => branch.to_string(),
+        };
+        Ok(Self { remote, branch })
+    }
+}
+
+/// Runs `git` like [git] but errors when git is unavailable or the command
+/// fails.
+fn git_ok(ip: &Ip, args: &[&str]) -> Result<Output, Fault> {
+    match git(ip, args) {
+        Some(out) if out.status.success() == true => Ok(out),
+        Some(out) => Err(AnyError(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )))?,
+        None => Err(AnyError(format!(
+            "git {} failed because the ip is not within a git repository",
+            args.join(" ")
+        )))?,
+    }
+}
+
+/// Verifies the git working tree has no uncommitted changes.
+struct CleanTree;
+
+impl Check for CleanTree {
+    fn name(&self) -> &str {
+        "clean working tree"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        match git(target.ip, &["status", "--porcelain"]) {
+            Some(out) => match String::from_utf8_lossy(&out.stdout).lines().count() {
+                0 => Outcome::Pass,
+                n => Outcome::Fail(format!("{} file(s) have uncommitted changes", n)),
+            },
+            None => Outcome::Skip(String::from("not a git repository")),
+        }
+    }
+}
+
+/// Verifies the version is not already tagged or published to the catalog.
+struct NewVersion;
+
+impl Check for NewVersion {
+    fn name(&self) -> &str {
+        "version not yet released"
+    }
 
-Use 'orbit help launch' to learn more about the command.
-";
+    fn run(&self, target: &Target) -> Outcome {
+        let ver = target.version.to_string();
+        // check the git tags
+        if let Some(out) = git(target.ip, &["tag", "--list", &ver, &format!("v{}", ver)]) {
+            if let Some(tag) = String::from_utf8_lossy(&out.stdout).lines().next() {
+                return Outcome::Fail(format!("git tag '{}' already exists", tag));
+            }
+        }
+        // check the catalog
+        let c = target.context;
+        let catalog = match Catalog::new()
+            .installations(c.get_cache_path())
+            .and_then(|cat| cat.downloads(c.get_downloads_path()))
+        {
+            Ok(cat) => cat,
+            Err(e) => return Outcome::Skip(format!("failed to read catalog: {}", e)),
+        };
+        let any = AnyVersion::from(target.version);
+        if let Some(lvl) = catalog.inner().get(target.ip.get_man().get_ip().get_name()) {
+            if let Some(found) = lvl.get_install(&any).or(lvl.get_download(&any)) {
+                if found.get_uuid() == target.ip.get_uuid() {
+                    return Outcome::Fail(format!(
+                        "version {} is already in the catalog",
+                        target.version
+                    ));
+                }
+            }
+        }
+        Outcome::Pass
+    }
+}
+
+/// Verifies the ip's files changed since its last release, so a launch does not
+/// publish the same contents under a new version.
+struct FilesChanged;
+
+impl Check for FilesChanged {
+    fn name(&self) -> &str {
+        "files changed since release"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let catalog = match Catalog::new().installations(target.context.get_cache_path()) {
+            Ok(cat) => cat,
+            Err(e) => return Outcome::Skip(format!("failed to read catalog: {}", e)),
+        };
+        match latest_release(target.ip, &catalog) {
+            Some(rel) => match target.ip.is_modified_since(rel) {
+                true => Outcome::Pass,
+                false => Outcome::Warn(format!(
+                    "files are unchanged since version {}; see `orbit hash`",
+                    rel.get_man().get_ip().get_version()
+                )),
+            },
+            None => Outcome::Skip(String::from("no release is installed")),
+        }
+    }
+}
+
+/// Verifies the lock file exists and agrees with the manifest.
+struct LockConsistent;
+
+impl Check for LockConsistent {
+    fn name(&self) -> &str {
+        "lock file matches manifest"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        if target.ip.lock_exists() == false {
+            Outcome::Fail(String::from("missing lock file; try `orbit plan --lock-only`"))
+        } else if target.ip.can_use_lock() == false {
+            Outcome::Fail(String::from("lock file is outdated; try `orbit plan --lock-only`"))
+        } else {
+            Outcome::Pass
+        }
+    }
+}
+
+/// Verifies every hdl file is read into design units without errors.
+struct UnitsParse;
+
+impl Check for UnitsParse {
+    fn name(&self) -> &str {
+        "design units parse"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        match Ip::collect_units(true, target.ip.get_root()) {
+            Ok(units) => {
+                if units.is_empty() == true {
+                    logger::warn("no hdl design units were found in the ip");
+                }
+                Outcome::Pass
+            }
+            Err(e) => Outcome::Fail(e.to_string()),
+        }
+    }
+}
+
+/// Verifies the ip's name and its primary design units do not clash with a
+/// different ip in the catalog.
+struct NameCollision;
+
+impl Check for NameCollision {
+    fn name(&self) -> &str {
+        "name is unique in catalog"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let c = target.context;
+        let catalog = match Catalog::new()
+            .installations(c.get_cache_path())
+            .and_then(|cat| cat.downloads(c.get_downloads_path()))
+        {
+            Ok(cat) => cat,
+            Err(e) => return Outcome::Skip(format!("failed to read catalog: {}", e)),
+        };
+        let ip = target.ip.get_man().get_ip();
+        let library = library_of(target.ip);
+        let units: HashSet<String> = match Ip::collect_units(true, target.ip.get_root()) {
+            Ok(units) => units.keys().map(|k| k.to_string().to_lowercase()).collect(),
+            Err(_) => HashSet::new(),
+        };
+
+        let mut clashes = Vec::new();
+        for (name, lvl) in catalog.inner() {
+            // compare against the latest version of every other ip
+            let other = match lvl.get(true, &AnyVersion::Latest) {
+                Some(o) if o.get_uuid() != target.ip.get_uuid() => o,
+                _ => continue,
+            };
+            // names are compared without case
+            if name == ip.get_name() {
+                clashes.push(format!("name '{}' is already used by ip {}", ip.get_name(), name));
+            }
+            // only installations have their units available
+            let installed = match lvl.get_install(&AnyVersion::Latest) {
+                Some(i) if i.get_uuid() == other.get_uuid() => i,
+                _ => continue,
+            };
+            if library_of(installed) != library {
+                continue;
+            }
+            if let Ok(theirs) = Ip::collect_units(false, installed.get_root()) {
+                let theirs = theirs.keys().map(|k| k.to_string().to_lowercase()).collect();
+                let shared = shared_units(&units, &theirs);
+                if shared.is_empty() == false {
+                    clashes.push(format!(
+                        "units {} are also defined by ip {} in library '{}'",
+                        shared.join(", "),
+                        installed.get_man().get_ip().into_ip_spec(),
+                        library
+                    ));
+                }
+            }
+        }
+        if clashes.is_empty() == true {
+            return Outcome::Pass;
+        }
+        let deny = c
+            .get_config()
+            .get_general()
+            .map(|g| g.is_collision_denied())
+            .unwrap_or(false);
+        match deny {
+            true => Outcome::Fail(clashes.join("\n      ")),
+            false => Outcome::Warn(clashes.join("\n      ")),
+        }
+    }
+}
+
+/// Returns the hdl library the `ip`'s units are compiled into, in lowercase.
+fn library_of(ip: &Ip) -> String {
+    match ip.get_man().get_ip().get_library() {
+        Some(lib) => lib.to_string(),
+        None => Identifier::new_working().to_string(),
+    }
+    .to_lowercase()
+}
+
+/// Lists the unit names within both `ours` and `theirs` in alphabetical order.
+fn shared_units(ours: &HashSet<String>, theirs: &HashSet<String>) -> Vec<String> {
+    let mut shared: Vec<String> = ours.intersection(theirs).cloned().collect();
+    shared.sort();
+    shared
+}
+
+/// Verifies every dependency can be fetched by others from a remote source.
+struct RemoteDeps;
+
+impl Check for RemoteDeps {
+    fn name(&self) -> &str {
+        "dependencies have remote sources"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let le = LockEntry::from((target.ip, true));
+        let lf = target.ip.get_lock().keep_dev_dep_entries(target.ip, false);
+        let local: Vec<String> = lf
+            .inner()
+            .iter()
+            .filter(|e| e.matches_target(&le) == false)
+            .filter(|e| match e.get_source() {
+                Some(src) => credential::host_of(src.get_url()).is_none(),
+                None => true,
+            })
+            .map(|e| e.to_ip_spec().to_string())
+            .collect();
+        match local.is_empty() {
+            true => Outcome::Pass,
+            false => Outcome::Fail(format!(
+                "dependencies without a remote source: {}",
+                local.join(", ")
+            )),
+        }
+    }
+}
+
+/// Verifies the changelog has an entry for the version being launched.
+struct ChangelogEntry {
+    generate: bool,
+}
+
+impl Check for ChangelogEntry {
+    fn name(&self) -> &str {
+        "changelog has entry"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let text = std::fs::read_to_string(target.ip.get_root().join(CHANGELOG_FILE))
+            .unwrap_or_default();
+        if has_entry(&text, target.version) == true {
+            Outcome::Pass
+        } else if self.generate == true {
+            Outcome::Skip(String::from("a stub will be written from the git log"))
+        } else {
+            Outcome::Fail(format!(
+                "{} has no entry for version {}; add `--ready` to write a stub from the git log",
+                CHANGELOG_FILE, target.version
+            ))
+        }
+    }
+}
+
+/// Verifies a secret key is available for git to sign tags.
+struct SigningKey;
+
+impl Check for SigningKey {
+    fn name(&self) -> &str {
+        "tag signing key available"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        // git reads the program used for signing from its configuration
+        let program = match git(target.ip, &["config", "--get", "gpg.program"]) {
+            Some(out) => match String::from_utf8_lossy(&out.stdout).trim() {
+                "" => String::from("gpg"),
+                p => p.to_string(),
+            },
+            None => return Outcome::Fail(String::from("signed tags require a git repository")),
+        };
+        match std::process::Command::new(&program)
+            .args(["--list-secret-keys", "--with-colons"])
+            .output()
+        {
+            Ok(out) => match String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .any(|l| l.starts_with("sec:"))
+            {
+                true => Outcome::Pass,
+                false => Outcome::Fail(format!("no secret keys were found by {}", program)),
+            },
+            Err(e) => Outcome::Fail(format!("failed to run {}: {}", program, e)),
+        }
+    }
+}
+
+/// Verifies an access token is available to publish a release to the forge.
+struct ReleaseAccess {
+    sign: bool,
+}
+
+impl Check for ReleaseAccess {
+    fn name(&self) -> &str {
+        "forge access token available"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let release = match target.ip.get_man().get_ip().get_release() {
+            Some(r) => r,
+            None => return Outcome::Skip(String::from("no release configured")),
+        };
+        if release.requires_signed_tag() == true && self.sign == false {
+            return Outcome::Fail(String::from(
+                "the release requires a signed tag; add `--sign` to create one",
+            ));
+        }
+        match find_token(release, target.context) {
+            Some(_) => Outcome::Pass,
+            None => Outcome::Fail(fmt_missing_token(release)),
+        }
+    }
+}
+
+/// Verifies the current branch has a remote branch to push the release to.
+struct UpstreamBranch;
+
+impl Check for UpstreamBranch {
+    fn name(&self) -> &str {
+        "upstream branch configured"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let remote = target
+            .ip
+            .get_man()
+            .get_ip()
+            .get_release()
+            .and_then(|r| r.get_remote());
+        match Upstream::detect(target.ip, remote) {
+            Ok(Some(_)) => Outcome::Pass,
+            Ok(None) => Outcome::Skip(String::from("not a git repository")),
+            Err(e) => Outcome::Fail(e),
+        }
+    }
+}
+
+/// Runs the user-configured hooks bound to the `pre-launch` event.
+struct LaunchHooks;
+
+impl Check for LaunchHooks {
+    fn name(&self) -> &str {
+        "pre-launch hooks"
+    }
+
+    fn run(&self, target: &Target) -> Outcome {
+        let hooks = target.context.get_config().get_hooks(Event::PreLaunch);
+        if hooks.is_empty() == true {
+            return Outcome::Skip(String::from("no hooks configured"));
+        }
+        match Hook::dispatch(&hooks, Event::PreLaunch, target.ip) {
+            Ok(()) => Outcome::Pass,
+            Err(e) => Outcome::Fail(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LaunchError {
+    /// The name of each failed check along with the reason it failed.
+    ChecksFailed(Vec<(String, String)>),
+}
+
+impl Error for LaunchError {}
+
+impl LaunchError {
+    /// Reports each failed check as its own `Diagnostic`, followed by a summary.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Self::ChecksFailed(failures) => failures
+                .iter()
+                .map(|(name, why)| {
+                    Diagnostic::error(&format!("pre-flight check '{}' failed: {}", name, why))
+                })
+                .chain(std::iter::once(
+                    Diagnostic::error(&format!(
+                        "{} pre-flight check(s) failed",
+                        failures.len()
+                    ))
+                    .help("fix the failed checks or add `--force` to launch anyway"),
+                ))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChecksFailed(failures) => write!(
+                f,
+                "{} pre-flight check(s) failed\n\nFix the failed checks or add `--force` to launch anyway",
+                failures.len()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn next_version() {
+        let current = Version::from_str("1.2.3").unwrap();
+        assert_eq!(VersionField::Major.apply(&current).to_string(), "2.0.0");
+        assert_eq!(VersionField::Minor.apply(&current).to_string(), "1.3.0");
+        assert_eq!(VersionField::Patch.apply(&current).to_string(), "1.2.4");
+        assert_eq!(
+            VersionField::from_str("3.0.1").unwrap().apply(&current).to_string(),
+            "3.0.1"
+        );
+    }
+
+    #[test]
+    fn changelog_entry() {
+        let text = "# Changelog\n\n## [1.1.0] - 2024-01-01\n\n- fix\n\n## v1.0.0\n";
+        assert_eq!(has_entry(text, &Version::from_str("1.1.0").unwrap()), true);
+        assert_eq!(has_entry(text, &Version::from_str("1.0.0").unwrap()), true);
+        assert_eq!(has_entry(text, &Version::from_str("1.2.0").unwrap()), false);
+        // versions mentioned outside of headings do not count
+        assert_eq!(has_entry("- bump to 1.2.0\n", &Version::from_str("1.2.0").unwrap()), false);
+    }
+
+    #[test]
+    fn changelog_stub() {
+        let stub = fmt_stub(
+            &Version::from_str("1.2.0").unwrap(),
+            &[String::from("add fifo"), String::from("fix reset")],
+        );
+        assert_eq!(stub, "## 1.2.0 - unreleased\n\n- add fifo\n- fix reset\n");
+        assert_eq!(
+            insert_stub("# Changelog\n\n## 1.1.0\n\n- fix\n", &stub),
+            "# Changelog\n\n## 1.2.0 - unreleased\n\n- add fifo\n- fix reset\n\n## 1.1.0\n\n- fix\n"
+        );
+        assert_eq!(
+            insert_stub("", &stub),
+            "# Changelog\n\n## 1.2.0 - unreleased\n\n- add fifo\n- fix reset\n"
+        );
+    }
+
+    #[test]
+    fn upstream() {
+        assert_eq!(
+            Upstream::resolve(
+                "dev",
+                None,
+                Some(String::from("origin")),
+                Some(String::from("refs/heads/main"))
+            ),
+            Ok(Upstream {
+                remote: String::from("origin"),
+                branch: String::from("main")
+            })
+        );
+        // the configured remote replaces the branch's remote
+        assert_eq!(
+            Upstream::resolve("main", Some("upstream"), Some(String::from("origin")), None),
+            Ok(Upstream {
+                remote: String::from("upstream"),
+                branch: String::from("main")
+            })
+        );
+        assert!(Upstream::resolve("main", None, None, None).is_err());
+    }
+
+    #[test]
+    fn unit_clashes() {
+        let ours: HashSet<String> = ["and_gate", "or_gate"].iter().map(|s| s.to_string()).collect();
+        let theirs: HashSet<String> =
+            ["xor_gate", "or_gate", "and_gate"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(shared_units(&ours, &theirs), vec!["and_gate", "or_gate"]);
+        assert_eq!(shared_units(&ours, &HashSet::new()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn release_notes() {
+        let text = "# Changelog\n\n## 1.1.0\n\n- add fifo\n- fix reset\n\n## 1.0.0\n\n- initial\n";
+        assert_eq!(
+            entry_notes(text, &Version::from_str("1.1.0").unwrap()),
+            "- add fifo\n- fix reset"
+        );
+        assert_eq!(entry_notes(text, &Version::from_str("1.0.0").unwrap()), "- initial");
+        assert_eq!(entry_notes(text, &Version::from_str("2.0.0").unwrap()), "");
+    }
+}
@@ -0,0 +1,89 @@
+//! Runs a language server so text editors can navigate the design units known
+//! to orbit.
+
+use crate::commands::helps::lsp;
+use crate::core::algo;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lsp::{Server, UnitIndex};
+use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use crate::util::logger;
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Lsp {}
+
+impl FromCli for Lsp {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(lsp::HELP).ref_usage(2..4))?;
+        // stdio is the only transport, but editors often request it explicitly
+        cli.check_flag(Flag::new("stdio"))?;
+        let command = Ok(Lsp {});
+        command
+    }
+}
+
+impl Command<Context> for Lsp {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // the server still answers requests about open documents outside of an ip
+        let files = match c.get_ip_path() {
+            Some(_) => {
+                c.goto_ip_path()?;
+                Self::gather_files(c)?
+            }
+            None => Vec::new(),
+        };
+        let index = UnitIndex::from_files(&files);
+        logger::info(format!("language server indexed {} files", files.len()));
+
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        Server::new(index).run(&mut stdin.lock(), &mut stdout.lock())
+    }
+}
+
+impl Lsp {
+    /// Collects the absolute paths of every file in the current ip and its
+    /// dependencies.
+    ///
+    /// Falls back to only the files of the current ip if the dependencies cannot
+    /// be resolved, such as when some are not installed.
+    fn gather_files(c: &Context) -> Result<Vec<PathBuf>, Fault> {
+        let root = c.get_ip_path().unwrap();
+        let target = Ip::load(root.clone())?;
+        let catalog = Catalog::new().installations(c.get_cache_path())?;
+        let files: Vec<PathBuf> = match algo::compute_final_ip_graph(&target, &catalog) {
+            Ok(ip_graph) => algo::build_ip_file_list(&ip_graph, &target, c.get_config().get_ignore_file())
+                .into_iter()
+                .map(|f| PathBuf::from(f.get_file()))
+                .collect(),
+            Err(e) => {
+                logger::warn(format!(
+                    "indexing only the current ip because its dependencies could not be resolved: {}",
+                    e
+                ));
+                filesystem::gather_working_files(root, false, c.get_config().get_ignore_file())
+                    .into_iter()
+                    .map(|f| PathBuf::from(f))
+                    .collect()
+            }
+        };
+        // editors refer to documents by absolute paths
+        Ok(files
+            .into_iter()
+            .map(|f: PathBuf| match f.is_relative() {
+                true => root.join(f),
+                false => f,
+            })
+            .collect())
+    }
+}
@@ -18,9 +18,10 @@ DESCRIPTION
     specified during planning.
     
     If '--list' is used, then it will display a list of the available plugins to
-    the user. Using '--list' in combination with a plugin from '--plugin' will
-    display any detailed help information the plugin has documented in its 
-    definition.
+    the user along with each plugin's command and summary. Using '--list' in 
+    combination with a plugin from '--plugin' will display the plugin's command, 
+    declared filesets, the configuration file where it is defined, and any 
+    detailed help information the plugin has documented in its definition.
     
     As a refresher, a backend workflow typically performs three tasks:  
        1. Parse the blueprint file  
@@ -28,11 +29,21 @@ DESCRIPTION
        3. Generate an output product 
     
     Any command-line arguments entered after the terminating flag '--' will be
-    passed in the received order as arguments to the subprocess's command. If a plugin already
-    has defined arguments, the additional arguments passed from the command-line
-    will follow the previously defined arguments.
+    passed verbatim in the received order as arguments to the subprocess's 
+    command. If a plugin already has defined arguments with 'args' in its 
+    configuration, the additional arguments passed from the command-line will 
+    follow the previously defined arguments. If the plugin declares its supported
+    'options', then every option passed from the command-line must be one of them.
     
     The subprocess will spawn from the current working ip's root directory.
+    
+    To help diagnose a failing workflow, '--dump-env' saves the subprocess's 
+    command, arguments, working directory, environment variables, and standard 
+    input to a file before running it. Using '--replay' with that file later runs
+    the exact same subprocess again under only the captured environment, without
+    requiring an ip or blueprint. The saved environment may contain secrets such
+    as access tokens, so on unix the file is readable only by its owner and it
+    should not be shared or committed.
 
 OPTIONS
     --plugin <name>
@@ -50,11 +61,20 @@ OPTIONS
     --verbose
         Display the command being executed
 
+    --dump-env <file>
+        Save the subprocess's invocation to a file
+
+    --replay <file>
+        Run a previously saved invocation
+
     args
         Arguments to pass to the plugin or command
 
 EXAMPLES
     orbit build --plugin xsim -- --elab
+    orbit build --plugin modelsim -- --gui -do wave.do
     orbit build --command python -- synth.py
     orbit build --verbose
+    orbit build --plugin xsim --dump-env xsim.toml
+    orbit build --replay xsim.toml
 "#;
\ No newline at end of file
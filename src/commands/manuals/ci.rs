@@ -0,0 +1,45 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    ci - generate a continuous integration pipeline
+
+SYNOPSIS
+    orbit ci [options]
+
+DESCRIPTION
+    This command writes a pipeline for a continuous integration service into the
+    current ip. Select the service with either '--github', which writes
+    '.github/workflows/orbit.yml', or '--gitlab', which writes '.gitlab-ci.yml'.
+    The ip's root directory is expected to be the repository's root directory.
+    
+    The pipeline installs the version of orbit that generated it, restores the
+    dependencies with 'orbit fetch', verifies the dependency graph with 'orbit
+    tree', and then runs 'orbit plan' and 'orbit build' once for each plugin. The
+    contents of 'ORBIT_HOME' are cached between runs and the cache is keyed on the
+    ip's lock file.
+    
+    By default, the pipeline runs every plugin known to the current configuration.
+    Use '--plugin' to select specific plugins instead. Plugins defined outside of
+    the ip are not available to the pipeline unless they are also defined in the
+    ip's local configuration, and a warning is issued for each one. Any tools
+    required by a plugin must be installed by editing the generated pipeline.
+    
+    An existing pipeline is not overwritten unless '--force' is used.
+
+OPTIONS
+    --github
+        Write a GitHub Actions workflow
+
+    --gitlab
+        Write a GitLab CI/CD pipeline
+
+    --plugin <name>...
+        Plugin to build in the pipeline
+
+    --force
+        Overwrite an existing pipeline
+
+EXAMPLES
+    orbit ci --github
+    orbit ci --gitlab --plugin ghdl
+    orbit ci --github --plugin ghdl --plugin vivado --force
+"#;
\ No newline at end of file
@@ -0,0 +1,38 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    clone - check out an ip for development
+
+SYNOPSIS
+    orbit clone [options] <ip> [<dir>]
+
+DESCRIPTION
+    This command places a copy of an ip from the catalog into a new directory for
+    development. The ip must already be known to the catalog as an installation or
+    a download. By default, the directory is named after the ip and is created in
+    the current working directory.
+    
+    If the ip's source is a git repository, the repository is cloned and the tag
+    for the requested version is checked out. A tag given by '--tag' must exist in
+    the repository. Otherwise, the tag is the source's tag, then the version itself
+    with or without a leading "v", and if none of these tags exist, the default
+    branch is left checked out. Any credentials configured for the repository's
+    host are used for the clone.
+    
+    If the source is not a git repository, the files of the requested version are
+    copied from the catalog instead.
+
+OPTIONS
+    <ip>
+        Ip specification to clone
+
+    <dir>
+        The new directory to clone into
+
+    --tag <tag>
+        The git tag to check out
+
+EXAMPLES
+    orbit clone gates
+    orbit clone gates:1.0.0 ./projects/gates
+    orbit clone uart --tag release-2
+"#;
\ No newline at end of file
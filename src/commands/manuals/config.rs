@@ -6,7 +6,7 @@ SYNOPSIS
     orbit config [options]
 
 DESCRIPTION
-    This command will alter configuration entries in Orbit's settings file named
+    This command will alter configuration entries in Orbit's settings file named 
     'config.toml'. By default, it will modify the user's config file found at
     the path read from the environment variable '$ORBIT_HOME'.
       
@@ -16,6 +16,17 @@ DESCRIPTION
     The command modifies the document in three independent stages. The first stage
     modifies the settings by iterating through all defined '--append' values. Then, 
     it will insert all '--set' values. Lastly, it will remove all '--unset' entries.
+    
+    To read the effective value of an entry after all configuration files are 
+    combined, use '--get'. To view every effective entry along with the file
+    that defines it, use '--list'. Entries within an array of tables, such as
+    plugins, are accessed by their name ('plugin.<name>'). Neither option modifies
+    any configuration file.
+    
+    To open the configuration file in a text editor, use '--edit'. The editor is
+    read from 'general.editor' in the configuration, otherwise from the 'EDITOR'
+    environment variable. If the configuration file is missing or empty, it is
+    first written with commented default values.
 
 OPTIONS
     --global
@@ -33,7 +44,19 @@ OPTIONS
     --unset <key>...
         Delete the key's entry
 
+    --get <key>
+        Print the effective value of the key's entry
+
+    --list
+        Print every effective entry and the file that defines it
+
+    --edit
+        Open the configuration file in a text editor
+
 EXAMPLES
     orbit config --append include="~/.orbit/profiles/ks-tech"
     orbit config --unset env.VIVADO_PATH --global
+    orbit config --get general.build-dir
+    orbit config --list
+    orbit config --edit --local
 "#;
\ No newline at end of file
@@ -0,0 +1,36 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    explain - describe an error code
+
+SYNOPSIS
+    orbit explain [options] [<code>]
+
+DESCRIPTION
+    This command prints a detailed explanation of an error code. Major errors are
+    reported with a short code, such as 'E0301', that stays the same across
+    releases.
+    
+    The first digits of a code identify its class of failure, which also
+    determines the exit code of the program:
+    
+        E01xx    usage error        exit code 2
+        E02xx    parse error        exit code 3
+        E03xx    resolution error   exit code 4
+        E04xx    network error      exit code 5
+        E05xx    integrity error    exit code 6
+    
+    Errors without a code exit with code 101.
+    
+    Use '--list' to view every error code with a brief summary.
+
+OPTIONS
+    <code>
+        Error code to explain
+
+    --list
+        View all error codes and exit
+
+EXAMPLES
+    orbit explain E0301
+    orbit explain --list
+"#;
\ No newline at end of file
@@ -0,0 +1,37 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    fetch - restore dependencies from the lock file
+
+SYNOPSIS
+    orbit fetch [options]
+
+DESCRIPTION
+    This command reads the current ip's lock file and places every dependency it
+    lists into the cache. Dependencies missing from both the cache and the
+    downloads are downloaded from their source first. It is the typical first step
+    after checking out a project on a new machine or in a continuous integration
+    job.
+    
+    By default, dependencies required only for development are omitted. To also
+    fetch these dependencies, use '--all'.
+    
+    Every installation is verified against the checksum recorded in the lock file.
+    A mismatch is reported as an error.
+    
+    Use '--offline' to never access the network. In this mode, the command fails if
+    any dependency is not already downloaded or installed.
+    
+    A summary of the dependencies that were downloaded and installed is printed when
+    the command completes.
+
+OPTIONS
+    --all
+        Fetch all dependencies (including development)
+
+    --offline
+        Only use dependencies already downloaded or installed
+
+EXAMPLES
+    orbit fetch
+    orbit fetch --all --offline
+"#;
\ No newline at end of file
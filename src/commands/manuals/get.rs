@@ -16,7 +16,35 @@ DESCRIPTION
     working ip for the requested HDL entity.
     
     If the '--instance' flag is used without the '--component' flag, then it will
-    display the direct instantiation style code for VHDL (VHDL-93 feature).
+    display the direct instantiation style code for VHDL (VHDL-93 feature). The
+    '--style' option selects the instantiation syntax regardless of the
+    '--component' flag.
+    
+    The generated code follows the '[vhdl-format]' section of the configuration.
+    The options '--association', '--style', '--signal-prefix', '--signal-suffix',
+    '--std', and '--indent' override their configured values for a single call.
+    Positional association lists the connections in the order of the entity's
+    declarations. The signal prefix and suffix are applied to the declared signals
+    and the signals connected to the instance's ports. Following the VHDL-2008
+    conventions states the 'component' keyword in component instantiations and ends
+    a testbench's simulation with 'std.env.finish'.
+    
+    The casing of the generated VHDL code follows the '[codegen]' section of the
+    configuration, which writes keywords and identifiers in lowercase or uppercase
+    to match a project's style checks. Extended identifiers, literals, and comments
+    keep their case.
+    
+    The '--lang verilog' option translates the entity's interface into Verilog to
+    instantiate the VHDL entity from a Verilog module. The '--signals' flag
+    displays the parameter and wire declarations and the '--instance' flag displays
+    the module instantiation. Ports with a subtype that has no Verilog equivalent
+    are declared as single-bit wires with the VHDL subtype left in a comment.
+    
+    The '--testbench' flag displays a complete VHDL testbench for the entity instead.
+    The testbench declares a signal for each port, instantiates the entity as the
+    design under test, and includes processes to drive any detected clock and reset
+    ports along with a stimulus process to fill in. Redirect the output to a file
+    to save it into the ip.
     
     It is important to note that any units referenced from ip outside of the
     current working ip are not automatically tracked as a dependency. In order to
@@ -49,9 +77,36 @@ OPTIONS
     --name <identifier>
         Set the instance's identifier
 
+    --testbench
+        Display a testbench for the unit
+
+    --association <kind>
+        Connect by 'named' or 'positional' association
+
+    --style <kind>
+        Instantiate as an 'entity' or 'component'
+
+    --signal-prefix <str>
+        Prepend text to the names of connected signals
+
+    --signal-suffix <str>
+        Append text to the names of connected signals
+
+    --std <edition>
+        Follow the conventions of VHDL '93' or '2008'
+
+    --indent <n>
+        Set the number of spaces per indentation
+
+    --lang <lang>
+        Generate the code in 'vhdl' or 'verilog'
+
 EXAMPLES
     orbit get and_gate --ip gates:1.0.0 --component
     orbit get ram --ip mem:2.0.3 -csi
     orbit get uart -si --name u0
     orbit get or_gate --ip gates --json
+    orbit get fifo --testbench > sim/fifo_tb.vhd
+    orbit get fifo -si --association positional --signal-prefix w_
+    orbit get uart -si --lang verilog
 "#;
\ No newline at end of file
@@ -0,0 +1,36 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    graph - export the ip dependency graph
+
+SYNOPSIS
+    orbit graph [options]
+
+DESCRIPTION
+    This command writes the resolved dependency graph of the current ip for use
+    outside of orbit, such as for architecture reviews and license audits. The
+    graph is read from the ip's lock file, so the lock file must exist and be up to
+    date with the manifest.
+    
+    The '--ip' flag selects the graph at the ip level, which is currently the only
+    level that can be exported. Each node is an ip labeled as '<name>@<version>',
+    and each edge points from an ip to the exact version of one of its
+    dependencies. Each edge is labeled with the version required by the
+    dependent's manifest, or with the locked version when the dependent is not
+    installed. The current ip is drawn as a box.
+    
+    By default, the graph is written in the DOT language, which can be rendered by
+    tools such as graphviz. Use the '--format json' option of orbit to write the
+    nodes and edges as JSON instead, where the version required by an edge is
+    its 'requirement' field.
+    
+    To view the graph of design units, see 'orbit tree'.
+
+OPTIONS
+    --ip
+        Export the graph at the ip level
+
+EXAMPLES
+    orbit graph --ip
+    orbit graph --ip | dot -Tsvg -o deps.svg
+    orbit --format json graph --ip
+"#;
\ No newline at end of file
@@ -0,0 +1,25 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    hash - compute the checksum of an ip
+
+SYNOPSIS
+    orbit hash [options]
+
+DESCRIPTION
+    This command computes the checksum of the current ip's files and compares it
+    against the checksum recorded for its latest installation in the cache, which is
+    treated as its last release. Use it to answer whether anything actually changed
+    since the last release.
+    
+    Only the files kept by an installation are hashed, such as hdl source files, the
+    manifest, and the readme. The result is therefore the same checksum that
+    'orbit install' records for the ip's current files, and edits to files that are
+    not installed do not count as changes.
+    
+    If no version of the ip is installed, only the checksum is reported. Use the
+    '--format json' option of orbit to write the report as JSON.
+
+EXAMPLES
+    orbit hash
+    orbit --format json hash
+"#;
\ No newline at end of file
@@ -18,7 +18,25 @@ DESCRIPTION
     
     If a protocol is recognized using '--protocol', then an optional tag can also 
     be supplied to help the protocol with providing any additional information it
-    may require.
+    may require. 
+    
+    While installing, the primary design units of the ip are recorded along with the
+    files that define them. Later commands that read the units of an installed ip,
+    such as 'orbit show --units' and 'orbit get', use this record instead of parsing
+    every source file again.
+    
+    If the same version of the ip is already installed with different files, a
+    warning is reported, as the version should likely be increased. The files are
+    compared by checksum, as computed by 'orbit hash'.
+    
+    An existing installation is kept when its files still match its recorded
+    checksum, and is otherwise reinstalled. To reinstall an ip whose installation is
+    suspect regardless of its checksum, use '--force'. The installation is wiped and
+    its files are extracted again from the ip's downloaded archive, which is fetched
+    again from the ip's source when it is missing, or copied again from the source
+    given by '--path' or '--url'. Every installation is verified
+    against its checksum afterward, and a partial or mismatching installation is
+    removed from the cache instead of being left behind.
 
 OPTIONS
     <ip>
@@ -37,7 +55,7 @@ OPTIONS
         Unique tag to provide to the protocol
 
     --force
-        Install the ip regardless of the cache slot occupancy
+        Wipe and reinstall the ip regardless of the cache slot occupancy
 
     --list
         View available protocols and exit
@@ -1,44 +1,98 @@
-// This manual page was automatically generated from the rsmangen.py tool.
-pub const MANUAL: &str = "\
-NAME
-    launch - release an ip's next version 
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    launch - verify an upcoming release
 
 SYNOPSIS
     orbit launch [options]
 
 DESCRIPTION
-    This command will perform a series of checks against the current ip to 
-    verify it is able to release a new version and the tag the latest git 
-    commit with the next version number. 
-     
-    By default, it will only perform a dry run of the launch process to verify 
-    the procedure will run with no errors. To proceed with a launch to tag the
-    latest commit, include the '--ready' flag.
-     
-    The next version it will release is the one defined in the Orbit.toml 
-    manifest file. You can also set the next version on the command-line by 
-    using the '--next <version>' option. If this option is used, then a new git
-    commit will be created by Orbit to save the version change it makes to the 
-    Orbit.toml. To write a custom message for this commit, include the 
-    '--message <message>' option.
-     
-    The '--next <version>' option will go off of the previous version defined
-    in the Orbit.toml manifest to determine the next increment. 
+    This command runs a series of pre-flight checks on the current ip before it is
+    released. Each check reports whether it passed, failed, or was skipped. If any
+    check fails, the launch is aborted unless '--force' is given.
+    
+    The following checks are performed in order:
+    - the git working tree has no uncommitted changes
+    - the version is not already a git tag or in the catalog
+    - the files changed since the latest installed version, as computed by
+      'orbit hash' (reported as a warning)
+    - the lock file exists and agrees with the manifest
+    - every hdl file parses into design units
+    - the name and primary design units do not clash with a different ip in the
+      catalog
+    - every dependency in the lock file has a remote source
+    - the hooks configured for the 'pre-launch' event succeed
+    
+    Checks that cannot be performed, such as when the ip is not within a git
+    repository or its manifest sets 'vcs = "none"', are skipped.
+    
+    A clash with another ip's name, compared without case, or with the primary
+    design units of another installed ip in the same library is reported as a
+    warning. Set 'deny-collisions = true' in the '[general]' section of the
+    configuration to treat these clashes as failures.
+    
+    By default, the command only reports the results of the checks. To make changes,
+    use '--ready'. When '--next' is given with '--ready', the manifest's version is
+    updated to the next version. Use '--install' to also place the ip into the cache.
+    The hooks configured for the 'pre-publish' and 'post-publish' events run before
+    and after the ip is published, either by installing it with '--install' or
+    by uploading its release, such as to run a regression suite before the
+    release is stored or to sync a mirror afterward. The hooks
+    configured for the 'post-launch' event run once the launch completes.
+    
+    The value for '--next' can be one of the version fields "major", "minor", or
+    "patch", or it can be an explicit version.
+    
+    To keep the release notes in sync with the version, use '--changelog'. This adds
+    a check that the CHANGELOG.md file at the ip's root directory has a heading for
+    the next version. If the entry is missing when launching with '--ready', a stub
+    entry is written to the changelog listing the subjects of the git commits made
+    since the latest tag. When the ip is within a git repository, the version and
+    changelog changes made while launching with '--ready' are committed before
+    anything is tagged, pushed, or archived.
+    
+    When the manifest configures a 'release' for the ip, launching with '--ready'
+    also publishes a release to the ip's GitHub or GitLab repository through the
+    forge's api. A zip archive of the ip's files and a file holding its sha256
+    checksum are attached to the release, so others can install the ip over https
+    with the default protocol instead of cloning its repository. The release is
+    tagged with the version and its notes are read from the version's changelog
+    entry. An access token is read from the credential configured for the forge's
+    host, and a check verifies the token is available before launching. Before the
+    release is created, the current commit and the version's tag, if one exists,
+    are pushed to the upstream of the current branch. The release's 'remote' can
+    name a different git remote to push to. A check verifies the upstream can be
+    determined before launching.
+    
+    To sign the release, use '--sign'. This adds a check that git's signing program
+    has a secret key. When launching with '--ready', a GPG-signed annotated tag is
+    created for the version at the launch's commit, which records the tagger and a
+    release message.
+    If the ip's 'release' sets 'signed-tag = true', the release requires '--sign' and
+    the tag's signature is verified before it is published.
 
 OPTIONS
-    --ready  
-          perform a real run through the launch process
-     
-    --next <version>  
-          declare the next version or 'major', 'minor', or 'patch' increment
-     
-    --message, -m <message>  
-          override the default Orbit commit message when using '--next'
-     
-    --no-install  
-          skip installing the newly launched version to the cache
+    --ready
+        Perform the launch after the checks pass
+
+    --next <version>
+        Version field to increment or an explicit version
+
+    --install
+        Install the ip into the cache after launching
+
+    --force
+        Continue the launch when checks fail
+
+    --changelog
+        Verify or generate the changelog entry for the version
+
+    --sign
+        Commit the launch's changes and create a signed tag for the version
 
 EXAMPLES
-    orbit launch --next 1.0.0
-    orbit launch --next major --ready
-";
+    orbit launch
+    orbit launch --next minor
+    orbit launch --next 1.0.0 --ready --install
+    orbit launch --next patch --changelog --ready
+    orbit launch --next minor --changelog --sign --ready
+"#;
\ No newline at end of file
@@ -0,0 +1,35 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    lsp - run a language server for editors
+
+SYNOPSIS
+    orbit lsp [options]
+
+DESCRIPTION
+    This command starts a language server that communicates with a text editor
+    using the Language Server Protocol over stdin and stdout. Configure an editor to
+    launch 'orbit lsp' for VHDL files to navigate design units.
+    
+    When run within an ip, the server indexes the primary design units of the
+    current ip and every dependency resolved from its lock file. If the dependencies
+    cannot be resolved, only the units within the current ip are indexed. Documents
+    opened in the editor are indexed as they change, even when they are unsaved.
+    
+    The server provides the following features:
+    
+    - document symbols for each design unit within a file
+    - go-to-definition for references to entities, packages, contexts, and
+    configurations
+    - diagnostics for errors found while reading the source code
+    
+    Messages about the indexing are written to stderr so they do not interfere with
+    the protocol.
+
+OPTIONS
+    --stdio
+        Communicate over stdin and stdout (default)
+
+EXAMPLES
+    orbit lsp
+    orbit lsp --stdio
+"#;
\ No newline at end of file
@@ -14,4 +14,17 @@ pub mod search;
 pub mod tree;
 pub mod uninstall;
 pub mod download;
-pub mod show;
\ No newline at end of file
+pub mod show;
+pub mod explain;
+pub mod clone;
+pub mod fetch;
+pub mod vendor;
+pub mod status;
+pub mod selfcmd;
+pub mod lsp;
+pub mod ci;
+pub mod test;
+pub mod graph;
+pub mod hash;
+pub mod stats;
+pub mod mv;
\ No newline at end of file
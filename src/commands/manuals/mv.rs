@@ -0,0 +1,39 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    mv - rename a design unit across an ip
+
+SYNOPSIS
+    orbit mv [options] <old> <new>
+
+DESCRIPTION
+    This command renames an entity or package throughout the current ip. Every
+    VHDL file of the ip is rewritten so that the unit's declaration, its closing
+    'end', the 'of' clause of its architectures, its component declarations, its
+    instantiations, and the use clauses selecting it all refer to '<new>'.
+    
+    Only references that name the unit are replaced. A signal or port that
+    happens to share the unit's name is left unchanged. Selected names are replaced
+    when they go through the 'work' library or the library set for the ip in its
+    manifest.
+    
+    Every replaced location is reported as 'file:line:col'. Use '--dry-run' to
+    report the locations without changing any files. The new name must not already
+    be used by another primary design unit of the ip. Files of other ip that depend
+    on the unit are not changed.
+    
+    Use the '--format json' option of orbit to write the report as JSON.
+
+OPTIONS
+    <old>
+        The name of the entity or package to rename
+
+    <new>
+        The new name for the unit
+
+    --dry-run
+        Report the locations without changing any files
+
+EXAMPLES
+    orbit mv adder full_adder
+    orbit mv --dry-run pkg_util util_pkg
+"#;
\ No newline at end of file
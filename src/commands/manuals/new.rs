@@ -13,6 +13,41 @@ DESCRIPTION
     If no name is supplied, then the ip's name defaults to the final path component
     of the path argument. Use the name option to provide a custom name.
     
+    A template can be imported into the new ip with '--template'. Templates are
+    defined in the configuration with the '[[template]]' array. If the template's
+    directory has a 'Template.toml' file, every variable it declares must have a
+    value before any files are written. Values can be supplied with '--var', and
+    any remaining variables are prompted for, falling back to their default value
+    when the response is empty.
+    
+    Use '--list' to view the available templates. Combining '--list' with a
+    template from '--template' displays that template's definition.
+    
+    Templates registered by a 'url' are remote, and their files are fetched into
+    the 'templates/' directory within orbit's home directory. The listing shows when
+    each remote template was last fetched. Use '--refresh' with '--list' to fetch
+    the remote templates again before they are listed, or only the template given by
+    '--template'.
+    
+    Use '--dry-run' to preview the files that would be created, along with their
+    contents after variable substitution, without writing anything.
+    
+    Use '--file' to create a single file at '<path>' within an existing ip instead
+    of a new ip. The file's contents are copied from '--from' with variable
+    substitution. When a template is given, the '--from' path is relative to the
+    template's directory, and '--list' displays the template's available files.
+    Without '--from', a blank file is created.
+    
+    By default, a git repository is initialized for the new ip along with a
+    '.gitignore' file that ignores the build directory. The lock file is not
+    ignored so that others can reproduce the same dependencies. A '.gitignore' file
+    provided by the template is kept as is. No repository is initialized when the
+    new ip is already inside an existing git work tree. Use '--commit' to also
+    create an initial commit with the new ip's files after any hooks have ran. For projects managed by
+    other version control systems, such as Perforce or SVN, use '--vcs none' to skip
+    git entirely. This choice is recorded in the manifest's 'vcs' field so that
+    commands such as 'launch' skip their git-specific steps.
+    
     This command fails if the path already exists. See the 'init' command for
     initializing an already existing project into an ip.
 
@@ -23,7 +58,42 @@ OPTIONS
     --name <name>
         The ip name to create
 
+    --template <name>
+        Template to import into the new ip
+
+    --var <key=value>...
+        Set a value for a template variable
+
+    --list
+        View available templates
+
+    --refresh
+        Fetch remote templates again before listing them
+
+    --dry-run
+        Preview the files to create without writing anything
+
+    --file
+        Create a single file within an existing ip
+
+    --from <path>
+        The file to copy into the new file
+
+    --vcs <kind>
+        Version control system to use: git or none (default: git)
+
+    --commit
+        Create an initial git commit with the new files
+
 EXAMPLES
     orbit new gates
     orbit new ./projects/dir7 --name adder
+    orbit new fifo --template base --var orbit.author=kepler
+    orbit new --list
+    orbit new --list --refresh
+    orbit new fifo --template base --dry-run
+    orbit new --file sim/fifo_tb.vhd --template base --from extra/tb.vhd
+    orbit new --file --template base --list
+    orbit new uart --commit
+    orbit new spi --vcs none
 "#;
\ No newline at end of file
@@ -7,14 +7,19 @@ SYNOPSIS
 
 DESCRIPTION
     This command carries out the "planning phase". This phase involves reading
-    and writing to the lockfile and collecting all necessary files according to 
-    their defined fileset into a blueprint file for future build processes. This 
+    and writing to the lockfile and collecting all necessary files according to
+    their defined fileset into a blueprint file for future build processes. This
     command acts upon the current working ip.
     
     By default, the top level unit and testbench are auto-detected according to
     the current design heirarchy. If there are multiple candidates for a potential
     top level or testbench, it will exit and ask the user to explicitly select
-    a candidate. To include all top levels and testbenches, use '-all'.
+    a candidate. To include all top levels and testbenches, use '-all'. Packages
+    and contexts are never candidates for the top level.
+    
+    When neither '--top' nor '--bench' is provided, the 'top' and 'bench' fields of
+    the manifest select the default units instead. A default top is trusted even
+    when it has no ports, such as a wrapper around an internal test harness.
     
     The top level unit and testbench will be stored in a '.env' file within the
     build directory. This '.env' file is read during the build command to set
@@ -23,15 +28,39 @@ DESCRIPTION
     then it will also be stored in the '.env' file to be recalled during the
     build phase.
     
-    User-defined filesets are only collected within the current working ip's 
+    When the top level is an entity, its ports are listed in a 'ports.csv' and a
+    'ports.json' file within the build directory. Each port records its name,
+    direction, width, and type for cross-checking constraint files and schematics.
+    A width that depends on a generic is kept as an expression, such as 'WIDTH'.
+    
+    Every dependency used in the build is listed in a 'dependencies.json' file within
+    the build directory. Each entry records the ip's name, version, uuid, checksum,
+    and the path its files were read from, matching the data stored in the lockfile.
+    An ip in development used with '--dev' is listed without a checksum.
+    Plugins can find the file through the 'ORBIT_DEPENDENCIES' environment variable
+    to stamp bitstreams and reports with the exact versions that were used.
+    
+    When '--plugin' is not provided, the plugin set by 'default-plugin' in the
+    '[general]' section of the configuration is used, if one exists. A plugin may
+    also be referred to by any short name mapped in 'plugin-alias'.
+    
+    User-defined filesets are only collected within the current working ip's
     path. Plugins may have custom filesets defined in their configuration. When
-    specifying a known plugin with '--plugin', it will collect the filesets 
+    specifying a known plugin with '--plugin', it will collect the filesets
     defined for that plugin. Use '--fileset' as many times as needed to define
     additional filesets.
     
+    A fileset's glob may contain multiple patterns separated by '|', and braces
+    expand into one pattern per comma-separated item. For example, '*.{sv,svh}'
+    and '*.sv|*.svh' collect the same files.
+    
+    Filesets defined in the '[fileset]' table of an ip's manifest are always
+    collected for that ip, including when the ip is a dependency of the current
+    working ip.
+    
     During the planning phase, a lockfile is produced outlining the exact ip
     dependencies required, how to get them, and how to verify them. The lockfile
-    should be checked into version control and should not manually edited by the 
+    should be checked into version control and should not manually edited by the
     user.
     
     If the current working ip's manifest data matches its data stored in its
@@ -39,9 +68,13 @@ DESCRIPTION
     dependency graph. To force Orbit to build the ip dependency graph from
     scratch, use '--force'.
       
-    If only needing to update the lockfile, use '--lock-only'. This flag does 
+    Frequently used options can be recorded as a named profile in a '[plan.<name>]'
+    table of the manifest and then selected with '--plan-profile <name>'. Options
+    given on the command-line take priority over the profile's options.
+    
+    If only needing to update the lockfile, use '--lock-only'. This flag does
     not require a toplevel or testbench to be determined. Using '--lock-only' with
-    '--force' will overwrite the lockfile regardless if it is already in sync 
+    '--force' will overwrite the lockfile regardless if it is already in sync
     with the current working ip's manifest data.
     
     When updating the lockfile, this command will download and install any new
@@ -49,8 +82,13 @@ DESCRIPTION
     To only install an ip, see the 'install' command.
     
     If an installed dependency's computed checksum does not match the checksum
-    stored in the lockfile, it assumes the installation to be corrupt and will 
+    stored in the lockfile, it assumes the installation to be corrupt and will
     re-install the dependency to the cache.
+    
+    To build against a dependency's copy in development rather than its
+    installation, use '--dev'. The copies are found within the 'workspaces' listed in
+    the configuration's '[general]' section, and a dependency without one falls back
+    to its installation. The lockfile is left unchanged when using '--dev'.
 
 OPTIONS
     --top <unit>
@@ -63,7 +101,8 @@ OPTIONS
         A plugin to refer to gather its declared filesets
 
     --build-dir <dir>
-        The relative directory to place the blueprint.tsv file
+        The relative directory to place the blueprint.tsv file, which may use
+        `{{ orbit.plugin }}`
 
     --fileset <key=glob>...
         A glob-style pattern identified by a name to add into the blueprint
@@ -83,9 +122,17 @@ OPTIONS
     --all
         Include all locally found HDL files
 
+    --dev
+        Resolve dependencies to their copies in development
+
+    --plan-profile <name>
+        Apply the options recorded in the manifest's profile
+
 EXAMPLES
     orbit plan --bench my_tb
     orbit plan --top and_gate --fileset PIN-PLAN="*.board"
     orbit plan --plugin vivado --clean --bench ram_tb
     orbit plan --lock-only
+    orbit plan --plan-profile impl
+    orbit plan --dev
 "#;
\ No newline at end of file
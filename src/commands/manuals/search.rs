@@ -14,6 +14,16 @@ DESCRIPTION
     available. An ip does not exist in the catalog if it is not found at any one
     of the three defined levels.
     
+    An ip found within one of the 'workspaces' listed in the configuration's
+    '[general]' section is in development. An ip that is only in development is
+    listed with the "Development" status.
+    
+    An ip published in the vendor index set by the configuration's 'vendor-index'
+    field is available and is listed with the "Available" status. The index is
+    fetched once and reused until it is older than 'vendor-index-ttl' hours, after
+    which the next search fetches it again. Use '--refresh' to fetch the index
+    immediately. The age of the fetched index is displayed below the results.
+    
     When a package name is provided for '<ip>', it will begin to partially match 
     the name with the names of the known ip. If an ip's name begins with '<ip>', it
     is included in the filtered resultes. To strictly match the argument against an
@@ -38,8 +48,12 @@ OPTIONS
     --match
         Return results that only pass each filter
 
+    --refresh
+        Fetch the vendor index again before searching
+
 EXAMPLES
     orbit search axi
     orbit search --keyword memory --keyword ecc
     orbit search --keyword RF --limit 20
+    orbit search --refresh
 "#;
\ No newline at end of file
@@ -0,0 +1,60 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    self - manage the orbit installation
+
+SYNOPSIS
+    orbit self <command> [options]
+
+DESCRIPTION
+    This command manages the installation of the orbit executable itself.
+    
+    The 'install' subcommand copies the running executable into a directory and
+    adds that directory to the user's PATH. By default, the directory is
+    '~/.local/bin' on unix systems and '%LOCALAPPDATA%\Programs\orbit\bin' on
+    windows. Use '--path' to choose a different directory, which is created if it
+    does not exist.
+    
+    The executable is first copied under a temporary name and its checksum is
+    compared against the running executable before it replaces any existing
+    executable at the destination. If any step fails, every change made during the
+    installation is reverted.
+    
+    On windows, the directory is appended to the user's PATH in the registry.
+    Elsewhere, a line that extends the PATH is appended to the profile of the
+    user's shell as detected by the 'SHELL' environment variable: '.zshrc' for zsh,
+    '.bashrc' for bash ('.bash_profile' on macos), 'config.fish' for fish, and
+    '.profile' for others. The PATH is left unchanged if it already lists the
+    directory or if '--no-modify-path' is used. Open a new terminal afterward to
+    call 'orbit' from the command-line.
+    
+    The 'uninstall' subcommand removes the running executable and the lines that
+    'install' added to the PATH. Use '--purge' to also remove all of orbit's data,
+    including its configuration, downloads, and installed ip, which requires an
+    extra confirmation.
+    
+    The first time a new version of orbit runs, it upgrades any data written by an
+    older version, such as renaming a configuration entry or recording checksums for
+    older installations, and reports each change it made. The version that last ran
+    is stored in the 'orbit-version' file within 'ORBIT_HOME'.
+
+OPTIONS
+    install
+        Install the running executable
+
+    uninstall
+        Remove the running executable
+
+    --path <dir>
+        Directory to install the executable into
+
+    --no-modify-path
+        Do not add the directory to the PATH
+
+    --purge
+        Also remove all configuration, downloads, and installed ip
+
+EXAMPLES
+    orbit self install
+    orbit self install --path /usr/local/bin --no-modify-path
+    orbit self uninstall --purge
+"#;
\ No newline at end of file
@@ -13,7 +13,13 @@ DESCRIPTION
     It will first attempt to return the information from a possible installation. If
     one does not exist, then it searches the downloads location for the ip.
     
+    An ip in development within one of the 'workspaces' from the configuration's
+    '[general]' section is only used when it is not installed or downloaded. Use
+    '--dev' to prefer the copy in development instead.
+    
     If '--units' is specified, then a list of the ip's HDL units are displayed.
+    For an ip that is only downloaded, the units are read directly from its archive
+    without installing or extracting it.
     
     If '--versions' is specified, then a list of the ip's already available versions
     are displayed.
@@ -31,8 +37,12 @@ OPTIONS
     --units
         Display the list of HDL primary design units associated with this ip
 
+    --dev
+        Prefer the ip in development over an installation
+
 EXAMPLES
     orbit show --units
     orbit show gates:1.0.0 --units
     orbit show gates --versions
+    orbit show gates --dev --units
 "#;
\ No newline at end of file
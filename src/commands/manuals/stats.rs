@@ -0,0 +1,32 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    stats - summarize the disk usage of orbit
+
+SYNOPSIS
+    orbit stats [options]
+
+DESCRIPTION
+    This command summarizes the disk usage of orbit's home directory to guide
+    cleanup decisions before uninstalling ip. Use '--home' to report on the home
+    directory, as set by ORBIT_HOME.
+    
+    The report lists the number and total size of the slots in the cache and of the
+    archives in the downloads, the combined size of each ip across both locations,
+    and the largest individual items.
+    
+    A cache slot is orphaned when its ip has no archive in the downloads and no lock
+    file references it. Lock files are read from every installation in the cache and
+    from the current ip, if any. Orphaned slots are likely safe to remove with
+    'orbit uninstall'.
+    
+    Use the '--format json' option of orbit to write the report as JSON, where sizes
+    are given in bytes.
+
+OPTIONS
+    --home
+        Summarize the usage of orbit's home directory
+
+EXAMPLES
+    orbit stats --home
+    orbit --format json stats --home
+"#;
\ No newline at end of file
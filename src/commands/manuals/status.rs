@@ -0,0 +1,30 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    status - report changes since the last release
+
+SYNOPSIS
+    orbit status [options]
+
+DESCRIPTION
+    This command reports the state of the current ip compared to its latest
+    installation in the cache, which is treated as its last release.
+    
+    The report includes the manifest's version and the latest installed version,
+    whether the ip's files differ from the checksum of that installation, and
+    whether the lock file is missing or out of date with the manifest.
+    
+    Each primary design unit that was added, removed, or modified since the
+    installation is also listed. A unit is modified when the contents of the file
+    defining it changed.
+    
+    Only the files kept by an installation are compared against the checksum, so
+    edits to other files are not reported as modifications. See 'orbit hash' to
+    print the checksums themselves.
+    
+    If no version of the ip is installed, only the version and lock file are
+    reported.
+
+EXAMPLES
+    orbit status
+    orbit --format json status
+"#;
\ No newline at end of file
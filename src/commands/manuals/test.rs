@@ -0,0 +1,52 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    test - run the testbenches of an ip
+
+SYNOPSIS
+    orbit test [options] [--] [args]...
+
+DESCRIPTION
+    This command runs each testbench of the current ip through a plugin and reports
+    which ones passed. Every entity without ports is a testbench, unless the
+    manifest lists the testbenches with the 'testbenches' field. A listed entity
+    that has ports is planned as the top rather than the bench, which supports
+    testbenches written outside of hdl, such as with cocotb.
+    
+    For each testbench, this command runs 'orbit plan' and then 'orbit build' with
+    the plugin. Each testbench is planned into its own directory named after the
+    testbench within the build directory. A testbench fails if either step fails,
+    such as when the plugin exits with a non-zero exit code.
+    
+    The plugin is selected with '--plugin', otherwise the default plugin from the
+    configuration is used. Any arguments after the options are passed to the plugin
+    for every testbench.
+    
+    After every testbench runs, a table summarizes the result and duration of each
+    one. Use '--fail-fast' to skip the remaining testbenches after the first
+    failure. This command exits with an error if any testbench failed.
+
+OPTIONS
+    --plugin <name>
+        Plugin to simulate each testbench
+
+    --bench <tb>...
+        Only run these testbenches
+
+    --build-dir <dir>
+        The relative directory to place each testbench's build
+
+    --list
+        View the testbenches and exit
+
+    --fail-fast
+        Skip the remaining testbenches after a failure
+
+    args
+        Arguments to pass to the plugin
+
+EXAMPLES
+    orbit test --plugin ghdl
+    orbit test --bench adder_tb --bench uart_tb
+    orbit test --list
+    orbit test --plugin ghdl --fail-fast -- --std=08
+"#;
\ No newline at end of file
@@ -21,6 +21,11 @@ DESCRIPTION
     hdl source code file.
     
     To view the dependency tree at the ip-level, use '--ip'.
+    
+    To explain why a unit appears in the blueprint, use '--why <unit>'. Every path
+    of instantiations from the root down to the unit is listed on its own line. The
+    roots are selected the same as when viewing the tree, so combine '--why' with
+    '--root' or '--all' when the root cannot be detected.
 
 OPTIONS
     --root <unit>
@@ -41,8 +46,12 @@ OPTIONS
     --ip
         View the dependency graph at the ip level
 
+    --why <unit>
+        List every path of instantiations from the root to a unit
+
 EXAMPLES
     orbit tree --ip
     orbit tree --root top --format long
     orbit tree --ascii --all
+    orbit tree --why fifo
 "#;
\ No newline at end of file
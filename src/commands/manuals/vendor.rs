@@ -0,0 +1,67 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    vendor - copy locked dependencies into the project
+
+SYNOPSIS
+    orbit vendor [options] [<ip>]
+
+DESCRIPTION
+    This command copies the installation of every dependency in the current ip's
+    lock file into a directory within the ip, creating a self-contained snapshot
+    for archival and audits. By default, the directory is 'vendor/' at the ip's root
+    directory. Use '--dir' to choose a different directory.
+    
+    Every dependency must already be installed. Use 'orbit fetch' beforehand to
+    install any missing dependencies. By default, dependencies required only for
+    development are omitted. To also vendor these dependencies, use '--all'.
+    
+    The directory is added to the ip's '.orbitignore' file so the vendored files are
+    not treated as the ip's own source files. The directory is laid out as a cache,
+    so setting the ORBIT_CACHE environment variable to its path resolves the
+    dependencies from the snapshot instead of the usual cache. The exact setting is
+    printed when the command completes.
+    
+    To republish the dependencies through an approved internal channel instead,
+    use '--mirror'. Each dependency is written as an archive into the given
+    directory and its provenance (name, version, uuid, checksum, original source
+    url, and date) is recorded in the directory's 'index.toml' file. The directory
+    is laid out as a downloads directory, so setting the ORBIT_DOWNLOADS
+    environment variable to its path installs the dependencies only from the
+    mirrored archives. Mirroring an ip again replaces its archive and provenance.
+    
+    To mirror a single upstream ip that is not a dependency of the current ip,
+    name it with '<ip>' along with '--mirror'. The ip is taken from the catalog,
+    preferring its downloaded archive over its installation. Use '--url' to fetch
+    the ip from its source before it is mirrored, which requires a complete ip
+    specification. Naming an ip does not require the command to run within an ip.
+    
+    To fetch the vendor index set by the configuration's 'vendor-index' field
+    before its 'vendor-index-ttl' expires, use '--refresh'. The command then only
+    fetches the index and does not vendor any dependencies.
+
+OPTIONS
+    --dir <path>
+        Directory within the ip to copy the dependencies into
+
+    --mirror <path>
+        Directory to publish the dependencies into as archives
+
+    --url <url>
+        URL to fetch the named ip from before it is mirrored
+
+    --all
+        Vendor all dependencies (including development)
+
+    --refresh
+        Fetch the configured vendor index again
+
+    <ip>
+        Upstream ip specification to mirror instead of the dependencies
+
+EXAMPLES
+    orbit vendor
+    orbit vendor --dir third_party --all
+    orbit vendor --mirror /shared/orbit/vendor
+    orbit vendor --refresh
+    orbit vendor --mirror /shared/orbit/vendor --url https://example.com/uart.zip uart:1.0.0
+"#;
\ No newline at end of file
@@ -1,23 +1,42 @@
 // entry program
 pub mod orbit;
+pub mod external;
+pub mod suggest;
 
 // commands
 mod build;
+mod ci;
+mod clone;
 mod download;
 mod get;
+mod graph;
+mod hash;
 mod init;
 mod install;
 mod launch;
+mod lsp;
+mod mv;
 mod new;
 mod plan;
 mod show;
+mod stats;
+mod status;
+mod test;
 mod tree;
 mod config;
 mod env;
 mod help;
 mod read;
 mod search;
+mod selfcmd;
 mod uninstall;
+mod vendor;
+mod explain;
+mod fetch;
+mod overview;
+
+// error reporting
+pub mod errcode;
 
 // informational content for help about commands
 mod manuals;
@@ -0,0 +1,295 @@
+//! Renames an entity or package throughout the current ip's source code.
+
+use crate::commands::helps::mv;
+use crate::core::context::Context;
+use crate::core::fileset;
+use crate::core::ip::Ip;
+use crate::core::lang::lexer::Position;
+use crate::core::lang::vhdl::primaryunit::PrimaryUnit;
+use crate::core::lang::vhdl::token::{Delimiter, Identifier, Keyword, VHDLToken, VHDLTokenizer};
+use crate::util::anyerror::AnyError;
+use crate::util::filesystem;
+use crate::util::logger;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::arg::{Flag, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use serde_derive::Serialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub struct Mv {
+    old: Identifier,
+    new: Identifier,
+    dry_run: bool,
+}
+
+impl FromCli for Mv {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(mv::HELP).ref_usage(2..4))?;
+        let command = Ok(Mv {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            old: cli.require_positional(Positional::new("old"))?,
+            new: cli.require_positional(Positional::new("new"))?,
+        });
+        command
+    }
+}
+
+/// A location in the source code where the unit's name was replaced.
+#[derive(Debug, PartialEq, Serialize)]
+struct Location {
+    file: String,
+    line: usize,
+    col: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Report {
+    old: String,
+    new: String,
+    /// Set when the files were left unchanged.
+    dry_run: bool,
+    locations: Vec<Location>,
+}
+
+impl Command<Context> for Mv {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+        let units = Ip::collect_units(true, ip.get_root())?;
+        match units.get(&self.old) {
+            Some(PrimaryUnit::Entity(_)) | Some(PrimaryUnit::Package(_)) => (),
+            Some(_) => {
+                return Err(AnyError(format!(
+                    "Only an entity or package can be renamed, but '{}' is a {}",
+                    self.old,
+                    units.get(&self.old).unwrap()
+                )))?
+            }
+            None => {
+                return Err(AnyError(format!(
+                    "No entity or package named '{}' in the current ip",
+                    self.old
+                )))?
+            }
+        }
+        if units.contains_key(&self.new) == true {
+            return Err(AnyError(format!(
+                "A primary design unit named '{}' already exists in the current ip",
+                self.new
+            )))?;
+        }
+
+        // the unit may be selected through the working library or the ip's own library
+        let mut libraries = vec![Identifier::new_working()];
+        if let Some(lib) = ip.get_man().get_ip().get_library() {
+            libraries.push(Identifier::from(lib));
+        }
+
+        let mut locations = Vec::new();
+        for file in filesystem::gather_current_files(ip.get_root(), false) {
+            if fileset::is_vhdl(&file) == false {
+                continue;
+            }
+            let text = std::fs::read_to_string(&file)?;
+            let (renamed, positions) = rename_unit(&text, &self.old, &self.new, &libraries);
+            if positions.is_empty() == true {
+                continue;
+            }
+            if self.dry_run == false {
+                std::fs::write(&file, renamed)?;
+            }
+            let rel = filesystem::into_std_str(filesystem::remove_base(
+                ip.get_root(),
+                &PathBuf::from(&file),
+            ));
+            locations.extend(positions.into_iter().map(|p| Location {
+                file: rel.clone(),
+                line: p.line(),
+                col: p.col(),
+            }));
+        }
+
+        let report = Report {
+            old: self.old.to_string(),
+            new: self.new.to_string(),
+            dry_run: self.dry_run,
+            locations: locations,
+        };
+        match c.get_output_format() {
+            OutputFormat::Text => {
+                print!("{}", fmt_locations(&report.locations));
+                let count = report.locations.len();
+                match self.dry_run {
+                    true => logger::info(format!(
+                        "Would rename '{}' to '{}' at {} location(s)",
+                        self.old, self.new, count
+                    )),
+                    false => logger::info(format!(
+                        "Renamed '{}' to '{}' at {} location(s)",
+                        self.old, self.new, count
+                    )),
+                }
+            }
+            OutputFormat::Json => println!("{}", output::to_json("mv", &report)?),
+        }
+        Ok(())
+    }
+}
+
+/// Checks if the identifier at index `i` of the `tokens` refers to a primary
+/// design unit, where `libraries` are the names the unit can be selected from.
+///
+/// A reference is a unit's declaration or closing, the `of` clause of an
+/// architecture, a component declaration or instantiation, or a selected name
+/// such as `work.unit` in a use clause or entity instantiation.
+fn is_unit_reference(tokens: &[VHDLToken], i: usize, libraries: &[Identifier]) -> bool {
+    let prev = match i.checked_sub(1).and_then(|j| tokens.get(j)) {
+        Some(t) => t,
+        None => return false,
+    };
+    let next = tokens.get(i + 1);
+    match prev {
+        VHDLToken::Keyword(kw) => match kw {
+            Keyword::Entity
+            | Keyword::Package
+            | Keyword::Body
+            | Keyword::Component
+            | Keyword::Of
+            | Keyword::End => true,
+            _ => false,
+        },
+        // a selected name through one of the unit's libraries
+        VHDLToken::Delimiter(Delimiter::Dot) => match i.checked_sub(2).and_then(|j| tokens.get(j)) {
+            Some(VHDLToken::Identifier(lib)) => libraries.contains(lib),
+            _ => false,
+        },
+        // an instantiation or configuration of a component without its keyword
+        VHDLToken::Delimiter(Delimiter::Colon) => match next {
+            Some(t) => {
+                t.check_keyword(&Keyword::Port)
+                    || t.check_keyword(&Keyword::Generic)
+                    || t.check_keyword(&Keyword::Use)
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Replaces every reference to the unit `old` in the VHDL source code `text`
+/// with `new`.
+///
+/// Returns the updated text and the position of each replaced reference.
+fn rename_unit(
+    text: &str,
+    old: &Identifier,
+    new: &Identifier,
+    libraries: &[Identifier],
+) -> (String, Vec<Position>) {
+    let tokens = VHDLTokenizer::from_source_code(text).into_tokens();
+    let types: Vec<VHDLToken> = tokens.iter().map(|t| t.as_type().clone()).collect();
+
+    let mut edits: Vec<(Position, usize)> = Vec::new();
+    for (i, t) in tokens.iter().enumerate() {
+        if let VHDLToken::Identifier(id) = t.as_type() {
+            if id == old && is_unit_reference(&types, i, libraries) == true {
+                edits.push((t.locate().clone(), id.len()));
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut positions = Vec::new();
+    for (n, line) in text.split_inclusive('\n').enumerate() {
+        let mut chars: Vec<char> = line.chars().collect();
+        // replace from the end of the line so earlier columns stay valid
+        for (pos, len) in edits.iter().rev().filter(|(p, _)| p.line() == n + 1) {
+            let start = pos.col() - 1;
+            let end = start + len;
+            // only replace the characters that spell the old name
+            let found = chars
+                .get(start..end)
+                .map(|c| c.iter().collect::<String>())
+                .and_then(|s| Identifier::from_str(&s).ok());
+            if found.as_ref() == Some(old) {
+                chars.splice(start..end, new.to_string().chars());
+                positions.push(pos.clone());
+            }
+        }
+        result.extend(chars);
+    }
+    positions.sort();
+    (result, positions)
+}
+
+/// Formats each location as `file:line:col` on its own line.
+fn fmt_locations(locations: &Vec<Location>) -> String {
+    locations
+        .iter()
+        .map(|l| format!("{}:{}:{}\n", l.file, l.line, l.col))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rename_unit_references() {
+        let code = "\
+library ieee;
+use work.adder.all;
+
+entity Adder is
+end entity adder;
+
+architecture rtl of adder is
+    component adder is
+    end component adder;
+    signal adder_sum : std_logic;
+begin
+    u0 : adder port map (a => adder_sum);
+    u1 : entity work.adder(rtl);
+    u2 : entity lib.adder(rtl);
+end architecture;
+";
+        let (text, positions) = rename_unit(
+            code,
+            &Identifier::from_str("adder").unwrap(),
+            &Identifier::from_str("full_adder").unwrap(),
+            &[Identifier::new_working()],
+        );
+        assert_eq!(
+            text,
+            "\
+library ieee;
+use work.full_adder.all;
+
+entity full_adder is
+end entity full_adder;
+
+architecture rtl of full_adder is
+    component full_adder is
+    end component full_adder;
+    signal adder_sum : std_logic;
+begin
+    u0 : full_adder port map (a => adder_sum);
+    u1 : entity work.full_adder(rtl);
+    u2 : entity lib.adder(rtl);
+end architecture;
+"
+        );
+        assert_eq!(positions.len(), 8);
+        assert_eq!(positions[0], Position::place(2, 10));
+    }
+}
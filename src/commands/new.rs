@@ -1,8 +1,23 @@
 use crate::core::context::Context;
+use crate::core::hook::Event;
+use crate::core::hook::Hook;
 use crate::core::manifest::Manifest;
+use crate::core::manifest::Vcs;
+use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::core::pkgid::PkgPart;
+use crate::core::ip::Ip;
+use crate::core::lockfile::IP_LOCK_FILE;
+use crate::core::template;
+use crate::core::template::Template;
+use crate::core::template::TemplateError;
+use crate::core::template::TemplateFile;
+use crate::core::variable::Assignment;
+use crate::core::variable::VariableTable;
 use crate::util::anyerror::AnyError;
+use crate::util::environment::Environment;
+use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
+use crate::util::logger;
 use crate::commands::helps::new;
 use crate::commands::orbit::AnyResult;
 use crate::OrbitResult;
@@ -24,6 +39,24 @@ pub struct New {
     name: Option<PkgPart>,
     /// Create an ip directory with an `Orbit.toml` manifest file.
     is_ip: bool,
+    /// The template to import into the new ip.
+    template: Option<String>,
+    /// Values to assign to template variables.
+    vars: Vec<Assignment>,
+    /// Display the available templates.
+    list: bool,
+    /// Fetch the remote templates again before displaying them.
+    refresh: bool,
+    /// Display the files to be created without writing anything.
+    dry_run: bool,
+    /// Create a single file within an existing ip.
+    is_file: bool,
+    /// The file to copy into the new file.
+    from: Option<PathBuf>,
+    /// The version control system to initialize for the new ip.
+    vcs: Vcs,
+    /// Create an initial commit with the new ip's files.
+    commit: bool,
     // /// Overwrite any existing manifest at the given directory and do not error if the directory exists.
     // force: bool,
 }
@@ -31,11 +64,29 @@ pub struct New {
 impl FromCli for New {
     fn from_cli(cli: &mut Cli) -> Result<Self, CliError> {
         cli.check_help(clif::Help::new().quick_text(new::HELP).ref_usage(2..4))?;
-
+        let list = cli.check_flag(Flag::new("list"))?;
         let command = Ok(Self {
             is_ip: cli.check_flag(Flag::new("ip"))?,
+            list: list,
+            refresh: cli.check_flag(Flag::new("refresh"))?,
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            is_file: cli.check_flag(Flag::new("file"))?,
+            from: cli.check_option(Optional::new("from").value("path"))?,
+            vcs: cli
+                .check_option(Optional::new("vcs").value("kind"))?
+                .unwrap_or_default(),
+            commit: cli.check_flag(Flag::new("commit"))?,
             name: cli.check_option(Optional::new("name"))?,
-            path: cli.require_positional(Positional::new("path"))?,
+            template: cli.check_option(Optional::new("template").value("name"))?,
+            vars: cli
+                .check_option_all(Optional::new("var").value("key=value"))?
+                .unwrap_or(Vec::new()),
+            path: match list {
+                true => cli
+                    .check_positional(Positional::new("path"))?
+                    .unwrap_or(PathBuf::new()),
+                false => cli.require_positional(Positional::new("path"))?,
+            },
         });
 
         command
@@ -68,10 +119,69 @@ impl New {
     }
 }
 
-impl Command<()> for New {
+impl Command<Context> for New {
     type Status = OrbitResult;
 
-    fn exec(&self, _: &()) -> Self::Status {
+    fn exec(&self, c: &Context) -> Self::Status {
+        // locate the template
+        let template = match &self.template {
+            Some(name) => match c.get_config().get_templates().get(name.as_str()) {
+                Some(&t) => Some(t),
+                None => return Err(TemplateError::Missing(name.to_string()))?,
+            },
+            None => None,
+        };
+
+        if self.refresh == true && self.list == false {
+            return Err(AnyError(format!(
+                "flag '--refresh' can only be used with '--list'"
+            )))?;
+        }
+
+        // display template list and exit
+        if self.list == true {
+            if self.refresh == true {
+                // fetch only the requested template, otherwise every remote template
+                let mut remotes: Vec<&Template> = match template {
+                    Some(t) => vec![t],
+                    None => c.get_config().get_templates().into_values().collect(),
+                };
+                remotes.retain(|t| t.is_remote() == true);
+                remotes.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+                for t in remotes {
+                    logger::info(format!(
+                        "Fetching template {} from {} ...",
+                        t.get_name(),
+                        t.get_url().unwrap()
+                    ));
+                    t.refresh()?;
+                }
+            }
+            match template {
+                // display the files available within the particular template
+                Some(t) if self.is_file == true => print!("{}", t.list_files()?),
+                // display entire contents about the particular template
+                Some(t) => println!("{}", t),
+                // display quick overview of all templates
+                None => println!(
+                    "{}",
+                    Template::list_templates(
+                        &mut c
+                            .get_config()
+                            .get_templates()
+                            .values()
+                            .into_iter()
+                            .collect::<Vec<&&Template>>()
+                    )
+                ),
+            }
+            return Ok(());
+        }
+
+        if self.is_file == true {
+            return self.new_file(c, template);
+        }
+
         // verify we are not already in an ip directory
         {
             // resolve any relative path
@@ -94,31 +204,274 @@ impl Command<()> for New {
             )
         }
 
+        if self.commit == true && self.vcs != Vcs::Git {
+            return Err(AnyError(String::from(
+                "an initial commit requires git as the vcs\n\nTry removing `--vcs none` or `--commit`",
+            )))?;
+        }
+
         let ip_name = Self::extract_name(self.name.as_ref(), &self.path)?;
 
-        self.create_ip(&ip_name)
+        // fill in the variables before touching the filesystem to fail early
+        let env = Environment::new()
+            // read config.toml for setting any env variables
+            .from_config(c.get_config())?;
+        let mut vtable = VariableTable::new()
+            .load_environment(&env)?
+            // read config.toml for user-defined variables
+            .load_config(c.get_config())?;
+        vtable.add("orbit.name", &ip_name.to_string());
+        vtable.add("orbit.ip.name", &ip_name.to_string());
+        vtable.add("orbit.vcs", &self.vcs.to_string());
+        for var in &self.vars {
+            vtable.add(var.get_key(), var.get_value());
+        }
+        if let Some(t) = template {
+            t.load_manifest()?.resolve(&mut vtable, &self.vars, true)?;
+        }
+
+        // display what would be created and exit
+        if self.dry_run == true {
+            if let Some(t) = template {
+                println!("{}", t.preview(&vtable)?);
+            }
+            println!(
+                "--- {} ---\n{}",
+                IP_MANIFEST_FILE,
+                Manifest::write_empty_manifest(&ip_name, &self.vcs)
+            );
+            if self.vcs == Vcs::Git {
+                println!("--- {} ---\n{}", GIT_IGNORE_FILE, Self::write_gitignore(&c.get_build_dir_for(None)));
+            }
+            return Ok(());
+        }
+
+        self.create_ip(&ip_name, template, &vtable)?;
+        if self.vcs == Vcs::Git {
+            self.init_git(&c.get_build_dir_for(None))?;
+        }
+
+        // run any hooks on the newly created ip
+        let ip = Ip::load(PathBuf::standardize(self.path.clone()))?;
+        Hook::dispatch(&c.get_config().get_hooks(Event::PostNew), Event::PostNew, &ip)?;
+
+        // commit after the hooks to include any files they modified
+        // only the new ip's files are committed when inside an existing repository
+        if self.commit == true {
+            Self::git(&self.path, &["add", "--all", "--", "."])?;
+            Self::git(&self.path, &["commit", "--quiet", "-m", "Initial commit", "--", "."])?;
+        }
+        Ok(())
     }
 }
 
 impl New {
+    /// Creates a single file at `path` within an existing ip.
+    ///
+    /// The file's contents are copied from `from`, which is relative to the template's
+    /// directory when a template is given. Without a source, a blank file is created.
+    fn new_file(&self, c: &Context, template: Option<&Template>) -> AnyResult<()> {
+        if self.path.exists() == true {
+            return Err(AnyError(format!(
+                "destination \"{}\" already exists",
+                PathBuf::standardize(self.path.clone()).display()
+            )))?;
+        }
+        // verify the file will be within an ip
+        let dest = std::env::current_dir()?.join(&self.path);
+        let ip = match Context::find_ip_path(&dest) {
+            Some(root) => Ip::load(root)?,
+            None => {
+                return Err(AnyError(String::from(
+                    "a new file must be created within an ip\n\nTry `orbit new <path>` to create a new ip",
+                )))?
+            }
+        };
+
+        // locate the source file
+        let src = match (template, &self.from) {
+            (Some(t), Some(f)) => Some(t.get_file(f)?),
+            (Some(t), None) => {
+                return Err(AnyError(format!(
+                    "missing file to import from template '{0}' with option '--from'\n\nTry `orbit new --file --template {0} --list` to see available files",
+                    t.get_name()
+                )))?
+            }
+            (None, Some(f)) => match f.is_file() {
+                true => Some(f.clone()),
+                false => {
+                    return Err(AnyError(format!(
+                        "source file \"{}\" does not exist",
+                        PathBuf::standardize(f.clone()).display()
+                    )))?
+                }
+            },
+            (None, None) => None,
+        };
+
+        // fill in the variables
+        let env = Environment::new()
+            // read config.toml for setting any env variables
+            .from_config(c.get_config())?;
+        let mut vtable = VariableTable::new()
+            .load_environment(&env)?
+            // read config.toml for user-defined variables
+            .load_config(c.get_config())?;
+        vtable.add(
+            "orbit.name",
+            &self
+                .path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        );
+        vtable.add("orbit.ip.name", &ip.get_man().get_ip().get_name().to_string());
+        for var in &self.vars {
+            vtable.add(var.get_key(), var.get_value());
+        }
+        // files matching the template's `copy-only` patterns skip substitution
+        let mut copy_only = false;
+        if let Some(t) = template {
+            let manifest = t.load_manifest()?;
+            manifest.resolve(&mut vtable, &self.vars, true)?;
+            if let Some(from) = &self.from {
+                copy_only = manifest.is_copy_only(
+                    filesystem::into_std_str(from.clone()).trim_start_matches("./"),
+                );
+            }
+        }
+
+        // display what would be created and exit
+        if self.dry_run == true {
+            if let Some(s) = &src {
+                println!(
+                    "--- {} ---\n{}",
+                    filesystem::into_std_str(self.path.clone()),
+                    match template::read_text(s) {
+                        Some(contents) if copy_only == false => {
+                            template::substitute(contents, &vtable)?
+                        }
+                        Some(_) => String::from("(copied verbatim)"),
+                        None => String::from("(binary file)"),
+                    }
+                );
+            }
+            return Ok(());
+        }
+
+        // create all missing directories on destination side
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(&parent)?;
+        }
+        match src {
+            Some(s) => {
+                std::fs::copy(&s, &dest)?;
+                if copy_only == false {
+                    TemplateFile::new(&dest).substitute(&vtable)?;
+                }
+            }
+            None => {
+                std::fs::File::create(&dest)?;
+            }
+        }
+        Hook::dispatch(&c.get_config().get_hooks(Event::PostNew), Event::PostNew, &ip)
+    }
+
     /// Creates a new directory at the given `dest` with a new manifest file.
-    fn create_ip(&self, ip: &PkgPart) -> AnyResult<()> {
+    ///
+    /// The files from `template` are imported before the manifest is written.
+    fn create_ip(&self, ip: &PkgPart, template: Option<&Template>, vtable: &VariableTable) -> AnyResult<()> {
         // create the directory
         std::fs::create_dir_all(&self.path)?;
 
+        // import the template's files
+        if let Some(t) = template {
+            if let Err(e) = t.import(&self.path, vtable) {
+                std::fs::remove_dir_all(&self.path)?;
+                return Err(e);
+            }
+        }
+
         // create the file directly nested within the destination path
         let manifest_path = {
             let mut p = self.path.clone();
-            p.push("Orbit.toml");
+            p.push(IP_MANIFEST_FILE);
             p
         };
 
         let mut manifest = std::fs::File::create(&manifest_path)?;
-        manifest.write_all(Manifest::write_empty_manifest(&ip).as_bytes())?;
+        manifest.write_all(Manifest::write_empty_manifest(&ip, &self.vcs).as_bytes())?;
         Ok(())
     }
+
+    /// Initializes a git repository for the new ip and writes a `.gitignore` file
+    /// if the template did not provide one.
+    ///
+    /// A missing git installation is not an error, since the ip is still usable.
+    /// No repository is created when the ip is already inside a git work tree.
+    fn init_git(&self, build_dir: &str) -> AnyResult<()> {
+        let ignore = self.path.join(GIT_IGNORE_FILE);
+        if ignore.exists() == false {
+            std::fs::write(&ignore, Self::write_gitignore(build_dir))?;
+        }
+        // the ip is already tracked by the repository that contains it
+        if Self::is_inside_work_tree(&self.path) == true {
+            return Ok(());
+        }
+        if let Err(e) = Self::git(&self.path, &["init", "--quiet"]) {
+            logger::warn(format!("skipping git initialization: {}", e));
+        }
+        Ok(())
+    }
+
+    /// Checks if the `dir` directory is within an existing git work tree.
+    fn is_inside_work_tree(dir: &PathBuf) -> bool {
+        std::process::Command::new("git")
+            .args(["rev-parse", "--is-inside-work-tree"])
+            .current_dir(dir)
+            .output()
+            .map(|out| out.status.success() && out.stdout.starts_with(b"true"))
+            .unwrap_or(false)
+    }
+
+    /// Runs git with the `args` from the `dir` directory.
+    fn git(dir: &PathBuf, args: &[&str]) -> AnyResult<()> {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .map_err(|e| AnyError(format!("failed to run git: {}", e)))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(AnyError(format!(
+                "git {} exited with {}",
+                args.first().unwrap_or(&""),
+                status
+            )))?,
+        }
+    }
+
+    /// Composes the contents of a `.gitignore` file for a new ip.
+    ///
+    /// The lock file is kept under version control so the exact dependencies
+    /// are reproducible by others.
+    fn write_gitignore(build_dir: &str) -> String {
+        format!(
+            "# Outputs from orbit plan and orbit build
+/{}/
+
+# {} is tracked to reproduce the same dependencies
+",
+            build_dir.trim_matches('/'),
+            IP_LOCK_FILE
+        )
+    }
 }
 
+/// The file that lists the paths for git to ignore.
+const GIT_IGNORE_FILE: &str = ".gitignore";
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -164,64 +517,26 @@ mod test {
             &PkgPart::from_str("sha256").unwrap()
         );
     }
-}
 
-// /// Creates a new file.
-// ///
-// /// If pulling from a template, a source filepath must be defined with --from.
-// /// If not using a template, then it will copy from the actually provided filepath from --from.
-// /// If there is no source and no template, then it will create a new blank file at `dest`.
-// fn new_file(&self, template: Option<&Template>, lut: &VariableTable, dest: &PathBuf) -> Result<(), Fault> {
-//     // check if we are pulling from a template
-//     if let Some(tplate) = template {
-//         match &self.from {
-//             Some(p) => {
-//                 // verify path exists in template
-//                 let src = PathBuf::from(tplate.path()).join(&p);
-
-//                 if src.exists() == false {
-//                     return Err(AnyError(format!("relative file path '{0}' does not exist in template '{1}'\n\nTry `orbit new --file --template {1} --list` to see available files", filesystem::normalize_path(p.to_path_buf()).display(), template.unwrap().alias())))?
-//                 }
-//                 // create all missing directories on destination side
-//                 if let Some(parent) = dest.parent() {
-//                     std::fs::create_dir_all(&parent)?;
-//                 }
-//                 // copy the file using import
-//                 std::fs::copy(&src, &dest)?;
-
-//                 // create template file
-//                 let tfile = TemplateFile::new(&dest);
-//                 // perform variable substitution
-//                 tfile.substitute(&lut)?;
-//                 return Ok(())
-//             }
-//              // issue error if no 'from' specified but 'template' was specified
-//             None => {
-//                 // print error with help message to view available files
-//                 return Err(AnyError(format!("missing file to import from template '{1}' with option '{0}'\n\nTry `orbit new --file --template {1} --list` to see available files", "--from".yellow(), template.unwrap().alias())))?
-//             }
-//         }
-//     }
-//     // use from as a copy from relative path without a template
-//     match &self.from {
-//         // copy from file
-//         Some(src) => {
-//             std::fs::copy(&src, &dest)?;
-//             // create template file to perform variable substitution
-//             let tfile = TemplateFile::new(&dest);
-//             tfile.substitute(&lut)?;
-//         }
-//         // create a new blank file
-//         None => {
-//             std::fs::OpenOptions::new()
-//                 .write(true)
-//                 .truncate(true)
-//                 .create(true)
-//                 .open(&dest)?;
-//         }
-//     }
-//     Ok(())
-// }
+    #[test]
+    fn ut_write_gitignore() {
+        assert_eq!(
+            New::write_gitignore("target/"),
+            "# Outputs from orbit plan and orbit build\n/target/\n\n# Orbit.lock is tracked to reproduce the same dependencies\n"
+        );
+    }
+
+    #[test]
+    fn ut_is_inside_work_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let ip = root.join("gates");
+        std::fs::create_dir(&ip).unwrap();
+        assert_eq!(New::is_inside_work_tree(&ip), false);
+        New::git(&root, &["init", "--quiet"]).unwrap();
+        assert_eq!(New::is_inside_work_tree(&ip), true);
+    }
+}
 
 // fn run(&self, root: &std::path::PathBuf, template: Option<&Template>, lut: &mut VariableTable) -> Result<(), Fault> {
 //     // create ip stemming from DEV_PATH with default /VENDOR/LIBRARY/NAME
@@ -1,9 +1,16 @@
 use crate::core::config;
 use crate::core::context::Context;
+use crate::core::migrate;
+use crate::util::anyerror::AnyError;
 use crate::core::lang::vhdl::highlight::ColorMode;
 use crate::util::environment;
+use crate::util::logger;
+use crate::util::logger::Level;
+use crate::util::output::OutputFormat;
+use crate::util::progress;
 use crate::util::prompt;
 use crate::util::sha256::Sha256Hash;
+use crate::util::timing;
 use clif::arg::Optional;
 use clif::arg::{Flag, Positional};
 use clif::cmd::Command;
@@ -11,7 +18,9 @@ use clif::cmd::FromCli;
 use clif::Cli;
 use clif::Error as CliError;
 use std::env;
+use std::path::PathBuf;
 use crate::commands::helps::orbit;
+use crate::commands::overview;
 
 pub type AnyResult<T> = Result<T, Box<dyn std::error::Error>>;
 pub type OrbitResult = AnyResult<()>;
@@ -22,6 +31,15 @@ pub struct Orbit {
     upgrade: bool,
     version: bool,
     force: bool,
+    yes: bool,
+    quiet: bool,
+    verbose: usize,
+    list: bool,
+    timings: bool,
+    trace: Option<PathBuf>,
+    profile: Option<String>,
+    format: Option<OutputFormat>,
+    progress: Option<OutputFormat>,
     command: Option<OrbitSubcommand>,
 }
 
@@ -35,28 +53,53 @@ impl Command<()> for Orbit {
 
 impl Orbit {
     fn run(&self, _: &()) -> OrbitResult {
+        // set the level of detail for logged messages
+        logger::set_level(match (self.quiet, self.verbose) {
+            (true, _) => Level::Quiet,
+            (false, 0) => Level::Normal,
+            (false, 1) => Level::Verbose,
+            (false, _) => Level::Debug,
+        });
+        // select how the progress of long operations is reported
+        if let Some(fmt) = self.progress {
+            progress::set_format(fmt);
+        }
+        // answer prompts automatically for automated environments
+        if self.yes == true || Self::is_no_interactive() == true {
+            prompt::set_interactive(false);
+            env::set_var(environment::ORBIT_NO_INTERACTIVE, "1");
+        }
         // prioritize version information
         if self.version == true {
             println!("orbit {}", VERSION);
             Ok(())
         // prioritize upgrade information
         } else if self.upgrade == true {
-            println!("info: checking for latest orbit binary...");
+            logger::info("checking for latest orbit binary...");
             let info = self.upgrade()?;
-            println!("info: {}", info);
+            logger::info(info);
             Ok(())
         // run the specified command
         } else if let Some(c) = &self.command {
-            // set up the context (ignores the context passed in)
-            let context = Context::new()
-                .home(environment::ORBIT_HOME)?
-                .cache(environment::ORBIT_CACHE)?
-                .downloads(environment::ORBIT_DOWNLOADS)?
-                .current_ip_dir(environment::ORBIT_IP_PATH)? // must come before .settings() call
-                .settings(config::CONFIG_FILE)?
-                .build_dir(environment::ORBIT_BUILD_DIR)?;
-            // pass the context to the given command
-            c.exec(&context)
+            if self.format == Some(OutputFormat::Json) && c.supports_json() == false {
+                return Err(AnyError(format!(
+                    "the command does not support the 'json' output format"
+                )))?;
+            }
+            // measure the phases of the command when requested
+            if self.timings == true || self.trace.is_some() == true {
+                timing::enable();
+            }
+            let result = {
+                let _span = timing::span("total");
+                // pass the context to the given command
+                c.exec(&self.setup()?)
+            };
+            timing::report(self.trace.as_ref())?;
+            result
+        // display an overview of everything available to run
+        } else if self.list == true {
+            overview::run(&self.setup()?)
         // if no command is given then print default help
         } else {
             Ok(println!("{}", orbit::HELP))
@@ -64,6 +107,60 @@ impl Orbit {
     }
 }
 
+impl Orbit {
+    /// Applies the global options that affect a command and sets up its context
+    /// (ignores the context passed in).
+    fn setup(&self) -> AnyResult<Context> {
+        // select the profile before the configuration is loaded
+        if let Some(p) = &self.profile {
+            env::set_var(environment::ORBIT_PROFILE, p);
+        }
+        // keep escape codes out of machine-readable output
+        if self.format == Some(OutputFormat::Json) {
+            colored::control::set_override(false);
+        }
+        Ok(Self::context()?.output_format(self.get_format()))
+    }
+
+    /// Returns the format selected to display results and errors.
+    pub fn get_format(&self) -> OutputFormat {
+        self.format.unwrap_or(OutputFormat::Text)
+    }
+
+    /// Returns the name of the requested command, or "orbit" if no command is
+    /// given.
+    pub fn get_command_name(&self) -> &str {
+        match &self.command {
+            Some(c) => c.name(),
+            None => "orbit",
+        }
+    }
+
+    /// Checks if the ORBIT_NO_INTERACTIVE environment variable is set to a non-empty value.
+    fn is_no_interactive() -> bool {
+        env::var(environment::ORBIT_NO_INTERACTIVE)
+            .map(|v| v.is_empty() == false)
+            .unwrap_or(false)
+    }
+
+    /// Sets up the runtime context and its corresponding environment variables.
+    pub fn context() -> AnyResult<Context> {
+        let context = Context::new()
+            .home(environment::ORBIT_HOME)?
+            .config_home(environment::ORBIT_CONFIG_HOME)?
+            .cache(environment::ORBIT_CACHE)?
+            .downloads(environment::ORBIT_DOWNLOADS)?;
+        // upgrade any data left by an older version before it is read
+        if let Err(e) = migrate::run(&context) {
+            logger::warn(format!("failed to upgrade data from a previous version: {}", e));
+        }
+        Ok(context
+            .current_ip_dir(environment::ORBIT_IP_PATH)? // must come before .settings() call
+            .settings(config::CONFIG_FILE)?
+            .build_dir(environment::ORBIT_BUILD_DIR)?)
+    }
+}
+
 impl FromCli for Orbit {
     fn from_cli(cli: &mut Cli) -> Result<Self, CliError> {
         cli.check_help(clif::Help::new().quick_text(orbit::HELP).ref_usage(2..4))?;
@@ -71,22 +168,30 @@ impl FromCli for Orbit {
         match cli
             .check_option(Optional::new("color").value("when"))?
             .unwrap_or(ColorMode::Auto)
+            .apply()
         {
-            ColorMode::Always => {
+            Some(true) => {
                 cli.use_color();
-                colored::control::set_override(true);
             }
-            ColorMode::Never => {
+            Some(false) => {
                 cli.disable_color();
-                colored::control::set_override(false);
             }
-            ColorMode::Auto => (),
+            None => (),
         }
         let orbit = Ok(Orbit {
             help: cli.check_flag(Flag::new("help").switch('h'))?,
             upgrade: cli.check_flag(Flag::new("upgrade"))?,
             version: cli.check_flag(Flag::new("version"))?,
             force: cli.check_flag(Flag::new("force"))?,
+            yes: cli.check_flag(Flag::new("yes").switch('y'))?,
+            quiet: cli.check_flag(Flag::new("quiet").switch('q'))?,
+            verbose: cli.check_flag_all(Flag::new("verbose").switch('v'))? as usize,
+            list: cli.check_flag(Flag::new("list"))?,
+            timings: cli.check_flag(Flag::new("timings"))?,
+            trace: cli.check_option(Optional::new("trace").value("file"))?,
+            profile: cli.check_option(Optional::new("profile").value("name"))?,
+            format: cli.check_option(Optional::new("format").value("fmt"))?,
+            progress: cli.check_option(Optional::new("progress").value("fmt"))?,
             command: cli.check_command(Positional::new("command"))?,
         });
         // verify there are zero unhandled arguments
@@ -96,21 +201,69 @@ impl FromCli for Orbit {
 }
 
 use crate::commands::build::Build;
+use crate::commands::ci::Ci;
+use crate::commands::clone::CloneIp;
 use crate::commands::config::Config;
 use crate::commands::download::Download;
 use crate::commands::env::Env;
+use crate::commands::explain::Explain;
+use crate::commands::fetch::Fetch;
 use crate::commands::get::Get;
+use crate::commands::graph::Graph;
+use crate::commands::hash::Hash;
 use crate::commands::help::Help;
 use crate::commands::init::Init;
 use crate::commands::install::Install;
 use crate::commands::launch::Launch;
+use crate::commands::lsp::Lsp;
 use crate::commands::new::New;
 use crate::commands::plan::Plan;
+use crate::commands::mv::Mv;
 use crate::commands::read::Read;
 use crate::commands::search::Search;
+use crate::commands::selfcmd::SelfCmd;
 use crate::commands::show::Show;
+use crate::commands::stats::Stats;
+use crate::commands::status::Status;
+use crate::commands::test::Test;
 use crate::commands::tree::Tree;
 use crate::commands::uninstall::Uninstall;
+use crate::commands::vendor::Vendor;
+
+/// The names of every builtin subcommand, including aliases.
+pub const SUBCOMMANDS: [&str; 31] = [
+    "help",
+    "new",
+    "search",
+    "plan",
+    "p",
+    "build",
+    "launch",
+    "download",
+    "install",
+    "get",
+    "init",
+    "tree",
+    "show",
+    "b",
+    "env",
+    "config",
+    "uninstall",
+    "read",
+    "explain",
+    "clone",
+    "fetch",
+    "vendor",
+    "status",
+    "self",
+    "lsp",
+    "ci",
+    "test",
+    "graph",
+    "hash",
+    "stats",
+    "mv",
+];
 
 #[derive(Debug, PartialEq)]
 enum OrbitSubcommand {
@@ -130,31 +283,25 @@ enum OrbitSubcommand {
     Uninstall(Uninstall),
     Read(Read),
     Download(Download),
+    Explain(Explain),
+    Clone(CloneIp),
+    Fetch(Fetch),
+    Vendor(Vendor),
+    Status(Status),
+    SelfCmd(SelfCmd),
+    Lsp(Lsp),
+    Ci(Ci),
+    Test(Test),
+    Graph(Graph),
+    Hash(Hash),
+    Stats(Stats),
+    Mv(Mv),
 }
 
 impl FromCli for OrbitSubcommand {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
         match cli
-            .match_command(&[
-                "help",
-                "new",
-                "search",
-                "plan",
-                "p",
-                "build",
-                "launch",
-                "download",
-                "install",
-                "get",
-                "init",
-                "tree",
-                "show",
-                "b",
-                "env",
-                "config",
-                "uninstall",
-                "read",
-            ])?
+            .match_command(&SUBCOMMANDS)?
             .as_ref()
         {
             "get" => Ok(OrbitSubcommand::Get(Get::from_cli(cli)?)),
@@ -173,15 +320,81 @@ impl FromCli for OrbitSubcommand {
             "config" => Ok(OrbitSubcommand::Config(Config::from_cli(cli)?)),
             "uninstall" => Ok(OrbitSubcommand::Uninstall(Uninstall::from_cli(cli)?)),
             "read" => Ok(OrbitSubcommand::Read(Read::from_cli(cli)?)),
+            "explain" => Ok(OrbitSubcommand::Explain(Explain::from_cli(cli)?)),
+            "clone" => Ok(OrbitSubcommand::Clone(CloneIp::from_cli(cli)?)),
+            "fetch" => Ok(OrbitSubcommand::Fetch(Fetch::from_cli(cli)?)),
+            "vendor" => Ok(OrbitSubcommand::Vendor(Vendor::from_cli(cli)?)),
+            "status" => Ok(OrbitSubcommand::Status(Status::from_cli(cli)?)),
+            "self" => Ok(OrbitSubcommand::SelfCmd(SelfCmd::from_cli(cli)?)),
+            "lsp" => Ok(OrbitSubcommand::Lsp(Lsp::from_cli(cli)?)),
+            "ci" => Ok(OrbitSubcommand::Ci(Ci::from_cli(cli)?)),
+            "test" => Ok(OrbitSubcommand::Test(Test::from_cli(cli)?)),
+            "graph" => Ok(OrbitSubcommand::Graph(Graph::from_cli(cli)?)),
+            "hash" => Ok(OrbitSubcommand::Hash(Hash::from_cli(cli)?)),
+            "stats" => Ok(OrbitSubcommand::Stats(Stats::from_cli(cli)?)),
+            "mv" => Ok(OrbitSubcommand::Mv(Mv::from_cli(cli)?)),
             _ => panic!("an unimplemented command was passed through!"),
         }
     }
 }
 
 impl OrbitSubcommand {
+    /// Returns the name of the command as written on the command-line.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Help(_) => "help",
+            Self::New(_) => "new",
+            Self::Search(_) => "search",
+            Self::Plan(_) => "plan",
+            Self::Build(_) => "build",
+            Self::Launch(_) => "launch",
+            Self::Install(_) => "install",
+            Self::Tree(_) => "tree",
+            Self::Get(_) => "get",
+            Self::Init(_) => "init",
+            Self::Show(_) => "show",
+            Self::Env(_) => "env",
+            Self::Config(_) => "config",
+            Self::Uninstall(_) => "uninstall",
+            Self::Read(_) => "read",
+            Self::Download(_) => "download",
+            Self::Explain(_) => "explain",
+            Self::Clone(_) => "clone",
+            Self::Fetch(_) => "fetch",
+            Self::Vendor(_) => "vendor",
+            Self::Status(_) => "status",
+            Self::SelfCmd(_) => "self",
+            Self::Lsp(_) => "lsp",
+            Self::Ci(_) => "ci",
+            Self::Test(_) => "test",
+            Self::Graph(_) => "graph",
+            Self::Hash(_) => "hash",
+            Self::Stats(_) => "stats",
+            Self::Mv(_) => "mv",
+        }
+    }
+
+    /// Checks if the command is able to report its information as JSON.
+    fn supports_json(&self) -> bool {
+        match self {
+            Self::Search(_) => true,
+            Self::Plan(_) => true,
+            Self::Tree(_) => true,
+            Self::Show(_) => true,
+            Self::Status(_) => true,
+            Self::Test(_) => true,
+            Self::Graph(_) => true,
+            Self::Hash(_) => true,
+            Self::Stats(_) => true,
+            Self::Mv(_) => true,
+            _ => false,
+        }
+    }
+
     fn bypass_check(&self) -> bool {
         match self {
             Self::Config(_) => true,
+            Self::SelfCmd(_) => true,
             _ => false,
         }
     }
@@ -190,7 +403,7 @@ impl OrbitSubcommand {
 impl Command<Context> for OrbitSubcommand {
     type Status = OrbitResult;
 
-    fn exec(&self, context: &Context) -> Self::Status {
+    fn exec(&self, context: &Context) -> OrbitResult {
         match self {
             OrbitSubcommand::Get(c) => c.exec(context),
             OrbitSubcommand::Search(c) => c.exec(context),
@@ -198,7 +411,7 @@ impl Command<Context> for OrbitSubcommand {
             OrbitSubcommand::Build(c) => c.exec(context),
             OrbitSubcommand::Install(c) => c.exec(context),
             OrbitSubcommand::Help(c) => c.exec(&()),
-            OrbitSubcommand::New(c) => c.exec(&()),
+            OrbitSubcommand::New(c) => c.exec(context),
             OrbitSubcommand::Launch(c) => c.exec(context),
             OrbitSubcommand::Tree(c) => c.exec(context),
             OrbitSubcommand::Init(c) => c.exec(context),
@@ -208,6 +421,19 @@ impl Command<Context> for OrbitSubcommand {
             OrbitSubcommand::Uninstall(c) => c.exec(context),
             OrbitSubcommand::Read(c) => c.exec(context),
             OrbitSubcommand::Download(c) => c.exec(context),
+            OrbitSubcommand::Explain(c) => c.exec(&()),
+            OrbitSubcommand::Clone(c) => c.exec(context),
+            OrbitSubcommand::Fetch(c) => c.exec(context),
+            OrbitSubcommand::Vendor(c) => c.exec(context),
+            OrbitSubcommand::Status(c) => c.exec(context),
+            OrbitSubcommand::SelfCmd(c) => c.exec(context),
+            OrbitSubcommand::Lsp(c) => c.exec(context),
+            OrbitSubcommand::Ci(c) => c.exec(context),
+            OrbitSubcommand::Test(c) => c.exec(context),
+            OrbitSubcommand::Graph(c) => c.exec(context),
+            OrbitSubcommand::Hash(c) => c.exec(context),
+            OrbitSubcommand::Stats(c) => c.exec(context),
+            OrbitSubcommand::Mv(c) => c.exec(context),
         }
     }
 }
@@ -239,6 +465,22 @@ impl Orbit {
         format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
     }
 
+    /// Checks if the file at `path` is an old executable left behind by a previous
+    /// upgrade, named `orbit-<version>`.
+    ///
+    /// External commands named `orbit-<command>` are never considered stale.
+    fn is_stale_binary(path: &PathBuf) -> bool {
+        let name = match path.file_name().and_then(|s| s.to_str()) {
+            Some(s) => s,
+            None => return false,
+        };
+        let name = name.strip_suffix(".exe").unwrap_or(name);
+        match name.strip_prefix("orbit-") {
+            Some(v) => Version::from_str(v).is_ok(),
+            None => false,
+        }
+    }
+
     /// Runs a process to check for an updated version of Orbit on GitHub to install.
     ///
     /// Steps it follows:  
@@ -256,18 +498,10 @@ impl Orbit {
         // find any old versions existing in executable's current folder
         let paths = fs::read_dir(&current_exe_dir)?;
         for path in paths {
-            if path
-                .as_ref()
-                .unwrap()
-                .path()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .starts_with("orbit-")
-            {
+            let path = path?.path();
+            if Self::is_stale_binary(&path) == true {
                 // remove stale binaries
-                fs::remove_file(path.as_ref().unwrap().path())?;
+                fs::remove_file(&path)?;
             }
         }
 
@@ -335,7 +569,7 @@ impl Orbit {
         let base_url: &str = "https://github.com/c-rus/orbit/releases";
 
         // download the list of checksums
-        println!("info: downloading update...");
+        logger::info("downloading update...");
         let sum_url = format!(
             "{0}/download/{1}/orbit-{1}-checksums.txt",
             &base_url, &latest
@@ -391,7 +625,9 @@ impl Orbit {
         // }
         // let body_bytes = res.bytes().await?;
 
-        let mut body_bytes = Vec::new();
+        // write the zip pkg to a temporary file while computing its checksum
+        let mut temp_file = tempfile::tempfile()?;
+        let mut hasher = sha256::Sha256::new();
         {
             let mut easy = Easy::new();
             easy.url(&pkg_url).unwrap();
@@ -400,8 +636,12 @@ impl Orbit {
                 let mut transfer = easy.transfer();
                 transfer
                     .write_function(|data| {
-                        body_bytes.extend_from_slice(data);
-                        Ok(data.len())
+                        hasher.update(data);
+                        match temp_file.write_all(data) {
+                            Ok(()) => Ok(data.len()),
+                            // reporting fewer bytes than given aborts the transfer
+                            Err(_) => Ok(0),
+                        }
                     })
                     .unwrap();
 
@@ -413,18 +653,15 @@ impl Orbit {
             }
         }
 
-        // compute the checksum on the downloaded zip file
-        let sum = sha256::compute_sha256(&body_bytes);
-        // verify the checksums match
+        // verify the checksum of the downloaded zip file matches
+        let sum = hasher.finalize();
         match sum == cert {
-            true => println!("info: verified download"),
+            true => logger::info("verified download"),
             false => return Err(Box::new(UpgradeError::BadChecksum(sum, cert)))?,
         };
 
-        // unzip the bytes and put file in temporary file
-        println!("info: installing update...");
-        let mut temp_file = tempfile::tempfile()?;
-        temp_file.write_all(&body_bytes)?;
+        // unzip the temporary file
+        logger::info("installing update...");
         let mut zip_archive = ZipArchive::new(temp_file)?;
 
         // decompress zip file to a temporary directory
@@ -496,3 +733,18 @@ impl std::fmt::Display for UpgradeError {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stale_binaries() {
+        assert_eq!(Orbit::is_stale_binary(&PathBuf::from("/bin/orbit-0.9.2")), true);
+        assert_eq!(Orbit::is_stale_binary(&PathBuf::from("/bin/orbit-0.9.2.exe")), true);
+        // external commands must never be removed
+        assert_eq!(Orbit::is_stale_binary(&PathBuf::from("/bin/orbit-fmt")), false);
+        assert_eq!(Orbit::is_stale_binary(&PathBuf::from("/bin/orbit-lint.exe")), false);
+        assert_eq!(Orbit::is_stale_binary(&PathBuf::from("/bin/orbit")), false);
+    }
+}
@@ -0,0 +1,155 @@
+//! Lists everything that can be run with orbit on the current machine: the
+//! builtin subcommands, the configured plugins and templates, and the external
+//! subcommands found on the PATH.
+
+use crate::commands::external::External;
+use crate::commands::helps::orbit;
+use crate::core::context::Context;
+use crate::util::anyerror::Fault;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use serde_derive::Serialize;
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Builtin,
+    Plugin,
+    Template,
+    External,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Builtin => write!(f, "builtin"),
+            Self::Plugin => write!(f, "plugin"),
+            Self::Template => write!(f, "template"),
+            Self::External => write!(f, "external"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Entry {
+    name: String,
+    kind: Kind,
+    summary: String,
+}
+
+impl Entry {
+    fn new(name: &str, kind: Kind, summary: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            summary: summary.to_string(),
+        }
+    }
+}
+
+/// Displays the overview in the format selected for the `context`.
+pub fn run(context: &Context) -> Result<(), Fault> {
+    let entries = collect(context);
+    match context.get_output_format() {
+        OutputFormat::Text => println!("{}", fmt_table(&entries)),
+        OutputFormat::Json => println!("{}", output::to_json("list", &entries)?),
+    }
+    Ok(())
+}
+
+/// Gathers every command, plugin, and template available in the `context`.
+fn collect(context: &Context) -> Vec<Entry> {
+    let mut entries = builtins(orbit::HELP);
+
+    let mut plugins: Vec<Entry> = context
+        .get_config()
+        .get_plugins()
+        .values()
+        .map(|p| {
+            Entry::new(
+                p.get_alias(),
+                Kind::Plugin,
+                p.get_summary().map(|s| s.as_str()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.append(&mut plugins);
+
+    let mut templates: Vec<Entry> = context
+        .get_config()
+        .get_templates()
+        .values()
+        .map(|t| {
+            Entry::new(
+                t.get_name(),
+                Kind::Template,
+                t.get_summary().map(|s| s.as_str()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.append(&mut templates);
+
+    External::find_all().into_iter().for_each(|(name, path)| {
+        entries.push(Entry::new(
+            &name,
+            Kind::External,
+            &path.display().to_string(),
+        ))
+    });
+    entries
+}
+
+/// Reads the builtin subcommands and their summaries from the "Commands:"
+/// section of the `help` text.
+fn builtins(help: &str) -> Vec<Entry> {
+    help.lines()
+        .skip_while(|line| line.starts_with("Commands:") == false)
+        .skip(1)
+        .take_while(|line| line.trim().is_empty() == false)
+        .filter_map(|line| {
+            // the name and its aliases are separated from the summary by 2+ spaces
+            let (name, summary) = line.trim().split_once("  ")?;
+            Some(Entry::new(name.trim(), Kind::Builtin, summary.trim()))
+        })
+        .collect()
+}
+
+fn fmt_table(entries: &[Entry]) -> String {
+    let header = format!(
+        "\
+{:<20}{:<10}{:<9}
+{3:->20}{3:->10}{3:->40}\n",
+        "Name", "Kind", "Description", " "
+    );
+    let mut body = String::new();
+    for entry in entries {
+        body.push_str(&format!(
+            "{:<20}{:<10}{}\n",
+            entry.name,
+            entry.kind.to_string(),
+            entry.summary
+        ));
+    }
+    header + &body
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builtin_commands() {
+        let help = "Usage:\n    orbit\n\nCommands:\n    new             create a new ip\n    plan, p         generate a blueprint file\n\nOptions:\n    --list    a\n";
+        assert_eq!(
+            builtins(help),
+            vec![
+                Entry::new("new", Kind::Builtin, "create a new ip"),
+                Entry::new("plan, p", Kind::Builtin, "generate a blueprint file"),
+            ]
+        );
+        // every builtin in orbit's help text is listed
+        assert_eq!(builtins(orbit::HELP).len(), 28);
+    }
+}
@@ -3,7 +3,12 @@ use colored::Colorize;
 use clif::cmd::{Command, FromCli};
 
 use crate::commands::download::Download;
+use crate::commands::errcode::ErrorCode;
+use crate::commands::suggest;
+use crate::core::context;
 use crate::core::context::Context;
+use crate::core::credential::Credential;
+use crate::core::rewrite::Rewrite;
 use crate::core::fileset::Fileset;
 use crate::core::iparchive::IpArchive;
 use crate::core::lang::vhdl::subunit::SubUnit;
@@ -16,11 +21,20 @@ use crate::core::variable;
 use crate::core::variable::VariableTable;
 use crate::core::version::AnyVersion;
 use crate::util::anyerror::Fault;
+use crate::util::diagnostic::Diagnostic;
 use crate::util::environment;
 use crate::util::environment::EnvVar;
 use crate::util::environment::Environment;
 use crate::util::filesystem;
+use crate::util::graph::EdgeStatus;
 use crate::util::graphmap::GraphMap;
+use crate::util::logger;
+use crate::util::progress::Progress;
+use crate::util::seqalin;
+use crate::util::timing;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use serde_derive::Serialize;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::Cli;
@@ -31,21 +45,30 @@ use std::fs::File;
 use std::hash::Hash;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use crate::commands::install::Install;
 use crate::core::algo;
+use crate::core::algo::BlackBox;
 use crate::core::algo::IpFileNode;
 use crate::core::algo::IpNode;
 use crate::core::catalog::Catalog;
 use crate::core::ip::Ip;
 use crate::core::ip::IpSpec;
+use crate::core::pkgid::PkgPart;
 use crate::core::lockfile::LockEntry;
 use crate::core::lockfile::LockFile;
+use crate::core::manifest::PlanProfile;
+use crate::core::depreport::{DependencyReport, DEPENDENCY_REPORT_FILE};
+use crate::core::portreport::{PortReport, PORT_REPORT_CSV};
+use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::commands::helps::plan;
 use crate::util::graphmap::Node;
 
 pub const BLUEPRINT_FILE: &str = "blueprint.tsv";
 pub const BLUEPRINT_DELIMITER: &str = "\t";
+/// The fileset in the blueprint listing the files of manifest-declared blackboxes.
+pub const BLACKBOX_FILESET: &str = "BLACKBOX";
 
 #[derive(Debug, PartialEq)]
 pub struct Plan {
@@ -59,6 +82,8 @@ pub struct Plan {
     filesets: Option<Vec<Fileset>>,
     only_lock: bool,
     force: bool,
+    dev: bool,
+    profile: Option<String>,
 }
 
 impl FromCli for Plan {
@@ -68,6 +93,7 @@ impl FromCli for Plan {
             // flags
             force: cli.check_flag(Flag::new("force"))?,
             only_lock: cli.check_flag(Flag::new("lock-only"))?,
+            dev: cli.check_flag(Flag::new("dev"))?,
             all: cli.check_flag(Flag::new("all"))?,
             clean: cli.check_flag(Flag::new("clean"))?,
             list: cli.check_flag(Flag::new("list"))?,
@@ -77,6 +103,7 @@ impl FromCli for Plan {
             plugin: cli.check_option(Optional::new("plugin").value("name"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
             filesets: cli.check_option_all(Optional::new("fileset").value("key=glob"))?,
+            profile: cli.check_option(Optional::new("plan-profile").value("name"))?,
         });
         command
     }
@@ -86,11 +113,111 @@ impl Command<Context> for Plan {
     type Status = OrbitResult;
 
     fn exec(&self, c: &Context) -> Self::Status {
+        self.execute(c, c.get_output_format())
+    }
+}
+
+impl Plan {
+    /// Creates a plan for a single `bench` or `top` that writes its blueprint
+    /// into `build_dir`.
+    pub fn with_unit(
+        plugin: Option<String>,
+        bench: Option<Identifier>,
+        top: Option<Identifier>,
+        build_dir: String,
+    ) -> Self {
+        Self {
+            plugin,
+            bench,
+            top,
+            clean: false,
+            list: false,
+            all: false,
+            build_dir: Some(build_dir),
+            filesets: None,
+            only_lock: false,
+            force: false,
+            dev: false,
+            profile: None,
+        }
+    }
+
+    /// Fills in the options missing from the command-line with the options
+    /// recorded in the `profile`.
+    ///
+    /// A `--top` or `--bench` from the command-line replaces both units of the
+    /// profile, and a fileset from the command-line replaces the profile's
+    /// fileset of the same name.
+    fn with_profile(&self, profile: &PlanProfile) -> Result<Self, Fault> {
+        let (top, bench) = match self.top.is_some() || self.bench.is_some() {
+            true => (self.top.clone(), self.bench.clone()),
+            false => (
+                match profile.get_top() {
+                    Some(t) => Some(Identifier::from_str(t)?),
+                    None => None,
+                },
+                match profile.get_bench() {
+                    Some(b) => Some(Identifier::from_str(b)?),
+                    None => None,
+                },
+            ),
+        };
+        let cli_filesets = self.filesets.as_ref();
+        let mut filesets = Vec::new();
+        for entry in profile.get_filesets() {
+            let fset = Fileset::from_str(&entry)?;
+            let overridden = cli_filesets
+                .map(|fs| fs.iter().any(|f| f.get_name() == fset.get_name()))
+                .unwrap_or(false);
+            if overridden == false {
+                filesets.push(fset);
+            }
+        }
+        if let Some(fs) = cli_filesets {
+            filesets.extend(fs.iter().cloned());
+        }
+        Ok(Self {
+            plugin: self.plugin.clone().or(profile.get_plugin().cloned()),
+            bench,
+            top,
+            clean: self.clean,
+            list: self.list,
+            all: self.all || profile.is_all(),
+            build_dir: self.build_dir.clone().or(profile.get_build_dir().cloned()),
+            filesets: match filesets.is_empty() {
+                true => None,
+                false => Some(filesets),
+            },
+            only_lock: self.only_lock,
+            force: self.force,
+            dev: self.dev,
+            profile: None,
+        })
+    }
+
+    /// Generates the blueprint and reports the outcome using `fmt`.
+    pub fn execute(&self, c: &Context, fmt: OutputFormat) -> OrbitResult {
+        // apply the options recorded in the manifest's profile
+        if let Some(name) = &self.profile {
+            c.goto_ip_path()?;
+            let target = Ip::load(c.get_ip_path().unwrap().clone())?;
+            let profile = match target.get_man().get_plan_profile(name) {
+                Some(p) => p,
+                None => {
+                    return Err(AnyError(format!(
+                        "no plan profile named '{}' in the {} file\n\nTry adding a [plan.{}] table to the manifest",
+                        name, IP_MANIFEST_FILE, name
+                    )))?
+                }
+            };
+            return self.with_profile(profile)?.execute(c, fmt);
+        }
+
         // locate the plugin
         let plugin = match &self.plugin {
             // verify the plugin alias matches
-            Some(alias) => match c.get_config().get_plugins().get(alias.as_str()) {
-                Some(&p) => Some(p),
+            Some(alias) => match c.get_config().find_plugin(alias.as_str()) {
+                Some(p) => Some(p),
                 None => return Err(PluginError::Missing(alias.to_string()))?,
             },
             None => None,
@@ -117,16 +244,41 @@ impl Command<Context> for Plan {
             return Ok(());
         }
 
+        // fall back to the configured default plugin
+        let plugin = match (plugin, c.get_config().get_default_plugin()) {
+            (None, Some(name)) => match c.get_config().find_plugin(name) {
+                Some(p) => Some(p),
+                None => return Err(PluginError::Missing(name.to_string()))?,
+            },
+            (p, _) => p,
+        };
+
         // check that user is in an IP directory
         c.goto_ip_path()?;
 
         // create the ip manifest
         let target = Ip::load(c.get_ip_path().unwrap().clone())?;
 
+        // verify the plugin's requirements are met before planning
+        if let Some(plg) = plugin {
+            let env = Environment::new()
+                .from_config(c.get_config())?
+                .from_ip(&target)?
+                .from_dotenv(target.get_root())?;
+            plg.validate_env(&env)?;
+        }
+
         // gather the catalog
+        let span = timing::span("gather catalog");
         let mut catalog = Catalog::new()
             .installations(c.get_cache_path())?
             .downloads(c.get_downloads_path())?;
+        if self.dev == true {
+            catalog = catalog
+                .development(c.get_config().get_workspaces())?
+                .prefer_development(true);
+        }
+        drop(span);
 
         // @todo: recreate the ip graph from the lockfile, then read each installation
         // see Install::install_from_lock_file
@@ -139,40 +291,76 @@ impl Command<Context> for Plan {
             let env = Environment::new()
                 // read config.toml for setting any env variables
                 .from_config(c.get_config())?;
-            let vtable = VariableTable::new().load_environment(&env)?;
-
-            download_missing_deps(vtable, &lf, &le, &catalog, &c.get_config().get_protocols())?;
+            let vtable = VariableTable::new()
+                .load_environment(&env)?
+                // read config.toml for user-defined variables
+                .load_config(c.get_config())?;
+
+            download_missing_deps(
+                vtable,
+                &lf,
+                &le,
+                &catalog,
+                &c.get_config().get_protocols(),
+                &c.get_config().get_credentials(),
+                &c.get_config().get_rewrites(),
+                c.get_config().get_init_submodules(),
+            )?;
             // recollect the downloaded items to update the catalog for installations
             catalog = catalog.downloads(c.get_downloads_path())?;
 
-            install_missing_deps(&lf, &le, &catalog)?;
+            install_missing_deps(
+                &lf,
+                &le,
+                &catalog,
+                &c.get_config().get_allowed_install_hooks(),
+            )?;
             // recollect the installations to update the catalog for dependency graphing
             catalog = catalog.installations(c.get_cache_path())?;
         }
 
         // determine the build directory (command-line arg overrides configuration setting)
-        let default_build_dir = c.get_build_dir();
-        let b_dir = match &self.build_dir {
-            Some(dir) => dir,
-            None => &default_build_dir,
-        };
+        let b_dir = context::resolve_build_dir(
+            self.build_dir.as_ref().unwrap_or(&c.get_build_dir()),
+            plugin.map(|p| p.get_alias()),
+        );
+
+        // read config.toml and the ip's .env files for variables to substitute into filesets
+        let vtable = VariableTable::new()
+            .load_environment(&Environment::new().from_dotenv(target.get_root())?)?
+            .load_config(c.get_config())?;
 
-        self.run(target, b_dir, plugin, catalog)
+        let ignore_file = c.get_config().get_ignore_file();
+        self.run(target, &b_dir, plugin, catalog, vtable, fmt, ignore_file)
     }
 }
 
+/// Downloads every entry of the lock file that is neither downloaded nor
+/// installed with a valid checksum.
+///
+/// Returns the ip that were downloaded.
 pub fn download_missing_deps(
     vtable: VariableTable,
     lf: &LockFile,
     le: &LockEntry,
     catalog: &Catalog,
     protocols: &ProtocolMap,
-) -> Result<(), Fault> {
+    creds: &[&Credential],
+    rewrites: &[&Rewrite],
+    submodules: bool,
+) -> Result<Vec<IpSpec>, Fault> {
     let mut vtable = vtable;
+    let mut fetched = Vec::new();
+    let mut progress = Progress::start("fetch", Some(count_deps(lf, le)));
     // fetch all non-downloaded packages
     for entry in lf.inner() {
-        // skip the current project's IP entry or any IP already in the downloads/
-        if entry.matches_target(le) == true || catalog.is_downloaded_slot(&entry.to_download_slot_key()) == true {
+        // skip the current project's IP entry
+        if entry.matches_target(le) == true {
+            continue;
+        }
+        progress.advance(entry.to_ip_spec().to_string());
+        // skip any IP already in the downloads/
+        if catalog.is_downloaded_slot(&entry.to_download_slot_key()) == true {
             continue;
         }
 
@@ -186,10 +374,10 @@ pub fn download_missing_deps(
                     Some(dep) => {
                         // verify the checksum
                         if Install::is_checksum_good(&dep.get_root()) == false {
-                            println!(
-                                "info: Redownloading IP {} due to bad checksum ...",
+                            logger::info(format!(
+                                "Redownloading IP {} due to bad checksum ...",
                                 dep.get_man().get_ip().into_ip_spec()
-                            );
+                            ));
                             require_download = true;
                         }
                     }
@@ -222,9 +410,13 @@ pub fn download_missing_deps(
                         None,
                         catalog.get_downloads_path(),
                         &protocols,
+                        creds,
+                        rewrites,
+                        submodules,
                         false,
                         true,
                     )?;
+                    fetched.push(entry.to_ip_spec());
                 }
                 None => {
                     return Err(AnyError(format!(
@@ -235,16 +427,28 @@ pub fn download_missing_deps(
             }
         }
     }
-    Ok(())
+    Ok(fetched)
 }
 
-pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) -> Result<(), Fault> {
+/// Installs every entry of the lock file from the downloads that is not
+/// already installed with a valid checksum.
+///
+/// Returns the ip that were installed.
+pub fn install_missing_deps(
+    lf: &LockFile,
+    le: &LockEntry,
+    catalog: &Catalog,
+    allowed_hooks: &[&str],
+) -> Result<Vec<IpSpec>, Fault> {
+    let mut installed = Vec::new();
+    let mut progress = Progress::start("install", Some(count_deps(lf, le)));
     // fill in the catalog with missing modules according the lock file if available
     for entry in lf.inner() {
         // skip the current project's IP entry
         if entry.matches_target(&le) {
             continue;
         }
+        progress.advance(entry.to_ip_spec().to_string());
 
         let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
 
@@ -259,12 +463,13 @@ pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) ->
                         if Install::is_checksum_good(&dep.get_root()) == false {
                             match status.get_download(&ver) {
                                 Some(dep) => {
-                                    println!(
-                                        "info: Reinstalling IP {} due to bad checksum ...",
+                                    logger::info(format!(
+                                        "Reinstalling IP {} due to bad checksum ...",
                                         dep.get_man().get_ip().into_ip_spec()
-                                    );
+                                    ));
                                     // perform extra work if the Ip is virtual (from downloads)
-                                    install_ip_from_downloads(&dep, &catalog, true)?
+                                    install_ip_from_downloads(&dep, &catalog, true, allowed_hooks)?;
+                                    installed.push(entry.to_ip_spec());
                                 }
                                 None => {
                                     // failed to get the install from the queue
@@ -281,7 +486,8 @@ pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) ->
                         match status.get_download(&ver) {
                             Some(dep) => {
                                 // perform extra work if the Ip is virtual (from downloads)
-                                install_ip_from_downloads(&dep, &catalog, false)?
+                                install_ip_from_downloads(&dep, &catalog, false, allowed_hooks)?;
+                                installed.push(entry.to_ip_spec());
                             }
                             None => {
                                 panic!("entry is not queued for installation")
@@ -295,10 +501,23 @@ pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) ->
             }
         }
     }
-    Ok(())
+    Ok(installed)
+}
+
+/// Counts the entries of the lock file besides the current project's entry `le`.
+fn count_deps(lf: &LockFile, le: &LockEntry) -> usize {
+    lf.inner()
+        .iter()
+        .filter(|entry| entry.matches_target(le) == false)
+        .count()
 }
 
-fn install_ip_from_downloads(dep: &Ip, catalog: &Catalog, force: bool) -> Result<(), Fault> {
+fn install_ip_from_downloads(
+    dep: &Ip,
+    catalog: &Catalog,
+    force: bool,
+    allowed_hooks: &[&str],
+) -> Result<(), Fault> {
     // perform extra work if the Ip is virtual (from downloads)
     if let Some(bytes) = dep.get_mapping().as_bytes() {
         // place the dependency into a temporary directory
@@ -316,14 +535,14 @@ fn install_ip_from_downloads(dep: &Ip, catalog: &Catalog, force: bool) -> Result
             }
         };
         // install from the unzipp ip
-        match Install::install(&unzipped_dep, catalog.get_cache_path(), force) {
+        match Install::install(&unzipped_dep, catalog.get_cache_path(), force, allowed_hooks) {
             Ok(_) => {}
             Err(e) => {
                 fs::remove_dir_all(dir)?;
                 return Err(e);
             }
         }
-        fs::remove_dir_all(unzipped_dep.get_root())?;
+        fs::remove_dir_all(filesystem::to_extended(unzipped_dep.get_root()))?;
     } else {
         panic!("trying to download from a physical path")
     }
@@ -340,8 +559,15 @@ use crate::core::lang::node::SubUnitNode;
 
 impl Plan {
     /// Builds a graph of design units. Used for planning.
+    ///
+    /// Entities found in `bindings` only link the architectures they are bound
+    /// to; all other entities link every one of their architectures. Units in
+    /// `blackboxes` are added as nodes without files so their instantiations
+    /// are resolved.
     fn build_full_graph<'a>(
         files: &'a Vec<IpFileNode>,
+        bindings: &HashMap<CompoundIdentifier, HashSet<Identifier>>,
+        blackboxes: &Vec<BlackBox>,
     ) -> GraphMap<CompoundIdentifier, HdlNode<'a>, ()> {
         let mut graph_map: GraphMap<CompoundIdentifier, HdlNode, ()> = GraphMap::new();
 
@@ -349,14 +575,31 @@ impl Plan {
         let mut bodies: Vec<(Identifier, PackageBody)> = Vec::new();
         // store the (suffix, prefix) for all entities
         let mut component_pairs: HashMap<Identifier, Identifier> = HashMap::new();
+        let mut progress = Progress::start(
+            "parse",
+            Some(
+                files
+                    .iter()
+                    .filter(|f| fileset::is_vhdl(&f.get_file()) == true)
+                    .count(),
+            ),
+        );
         // read all files
         for source_file in files {
             if fileset::is_vhdl(&source_file.get_file()) == true {
-                let contents = fs::read_to_string(&source_file.get_file()).unwrap();
-                let symbols = VHDLParser::read(&contents).into_symbols();
+                progress.advance(source_file.get_file());
+                let symbols = {
+                    let _span = timing::span("parse");
+                    let contents = fs::read_to_string(&source_file.get_file()).unwrap();
+                    VHDLParser::read(&contents).into_symbols()
+                };
 
                 let lib = source_file.get_library();
-                // println!("{} {}", source_file.get_file(), source_file.get_library());
+                logger::debug(format!(
+                    "reading {} into library {}",
+                    source_file.get_file(),
+                    source_file.get_library()
+                ));
 
                 // add all entities to a graph and store architectures for later analysis
                 let mut iter = symbols.into_iter().filter_map(|f| {
@@ -417,11 +660,36 @@ impl Plan {
             }
         }
 
+        // add the externally-provided units that were not found in source code
+        for bb in blackboxes {
+            let key = CompoundIdentifier::new(bb.get_library().clone(), bb.get_name().clone());
+            if graph_map.has_node_by_key(&key) == true {
+                continue;
+            }
+            component_pairs
+                .entry(bb.get_name().clone())
+                .or_insert(bb.get_library().clone());
+            graph_map.add_node(
+                key,
+                HdlNode::external(VHDLSymbol::from(Entity::black_box(bb.get_name().clone()))),
+            );
+        }
+
         // go through all architectures and make the connections
         let mut sub_nodes_iter = sub_nodes.into_iter();
         while let Some((lib, node)) = sub_nodes_iter.next() {
             let node_name = CompoundIdentifier::new(lib, node.get_sub().get_entity().clone());
 
+            // skip architectures that are not bound to the entity by a configuration
+            if let (Some(arch), Some(bound)) =
+                (node.get_sub().get_arch_name(), bindings.get(&node_name))
+            {
+                if bound.contains(arch) == false {
+                    logger::debug(format!("skipping architecture {} of {}", arch, node_name));
+                    continue;
+                }
+            }
+
             // link to the owner and add architecture's source file
             let entity_node = match graph_map.get_node_by_key_mut(&node_name) {
                 Some(en) => en,
@@ -429,6 +697,8 @@ impl Plan {
                 None => continue,
             };
             entity_node.as_ref_mut().add_file(node.get_file());
+            // remember the references to units that are not found
+            let mut unresolved = Vec::new();
             // create edges
             for dep in node.get_sub().get_edges() {
                 // need to locate the key with a suffix matching `dep` if it was a component instantiation
@@ -440,14 +710,26 @@ impl Plan {
                             (),
                         );
                     }
-                } else {
-                    graph_map.add_edge_by_key(dep, &node_name, ());
+                } else if graph_map.add_edge_by_key(dep, &node_name, ()) == EdgeStatus::MissingSource {
+                    unresolved.push(dep.clone());
                 };
             }
             // add edges for reference calls
             for dep in node.get_sub().get_refs() {
                 // note: verify the dependency exists (occurs within function)
-                graph_map.add_edge_by_key(dep, &node_name, ());
+                if graph_map.add_edge_by_key(dep, &node_name, ()) == EdgeStatus::MissingSource {
+                    unresolved.push(dep.clone());
+                }
+            }
+            let entity_node = graph_map.get_node_by_key_mut(&node_name).unwrap().as_ref_mut();
+            unresolved.into_iter().for_each(|dep| entity_node.add_unresolved(dep));
+            // keep configurations with their entity for selecting bindings later
+            if let SubUnit::Configuration(cfg) = node.into_sub() {
+                graph_map
+                    .get_node_by_key_mut(&node_name)
+                    .unwrap()
+                    .as_ref_mut()
+                    .add_config(cfg);
             }
         }
 
@@ -481,10 +763,15 @@ impl Plan {
                 } else {
                     dep
                 };
-                // println!("{} {} ... {}", iden, dep, dep_adjusted);
+                logger::debug(format!("{} depends on {}", iden, dep_adjusted));
                 // verify the dep exists
-                let _stat = graph_map.add_edge_by_key(dep_adjusted, &iden, ());
-                // println!("{:?}", stat);
+                if graph_map.add_edge_by_key(dep_adjusted, &iden, ()) == EdgeStatus::MissingSource {
+                    graph_map
+                        .get_node_by_key_mut(&iden)
+                        .unwrap()
+                        .as_ref_mut()
+                        .add_unresolved(dep_adjusted.clone());
+                }
             }
         }
         graph_map
@@ -492,11 +779,13 @@ impl Plan {
 
     /// Writes the lockfile according to the constructed `ip_graph`. Only writes if the lockfile is
     /// out of date or `force` is `true`.
+    ///
+    /// Returns the lockfile that is on disk afterward.
     pub fn write_lockfile(
         target: &Ip,
         ip_graph: &GraphMap<IpSpec, IpNode, ()>,
         force: bool,
-    ) -> Result<(), Fault> {
+    ) -> Result<LockFile, Fault> {
         // only modify the lockfile if it is out-of-date
         if target.can_use_lock() == false || force == true {
             // create build list
@@ -507,11 +796,12 @@ impl Plan {
                 .collect();
             let lock = LockFile::from_build_list(&mut build_list, target);
             lock.save_to_disk(target.get_root())?;
-            println!("info: Updated lockfile");
+            logger::info("Updated lockfile");
+            Ok(lock)
         } else {
-            println!("info: Lockfile is already up to date");
+            logger::info("Lockfile is already up to date");
+            Ok(target.get_lock().clone())
         }
-        Ok(())
     }
 
     /// Maps the local index to the global index between two different maps.
@@ -527,13 +817,182 @@ impl Plan {
             .unwrap()
     }
 
+    /// Collects the architecture bindings from the configuration declared for
+    /// each unit in `roots`.
+    ///
+    /// Each entity maps to the set of architectures its instances are bound to,
+    /// since instances of the same entity may be bound to different
+    /// architectures. A unit with more than one configuration is skipped because
+    /// the choice is ambiguous.
+    fn collect_bindings(
+        graph: &GraphMap<CompoundIdentifier, HdlNode, ()>,
+        roots: &[Option<&CompoundIdentifier>],
+    ) -> HashMap<CompoundIdentifier, HashSet<Identifier>> {
+        let working = Identifier::new_working();
+        let mut bindings: HashMap<CompoundIdentifier, HashSet<Identifier>> = HashMap::new();
+        for &key in roots.iter().flatten() {
+            let node = match graph.get_node_by_key(key) {
+                Some(n) => n,
+                None => continue,
+            };
+            let cfg = match node.as_ref().get_configs().as_slice() {
+                [] => continue,
+                [cfg] => cfg,
+                _ => {
+                    logger::warn(format!(
+                        "ignoring configurations for {} because more than one is declared",
+                        key
+                    ));
+                    continue;
+                }
+            };
+            let lib = key.get_prefix().unwrap_or(&working);
+            logger::info(format!("using configuration {} for {}", cfg.name(), key));
+            if let Some(arch) = cfg.architecture() {
+                bindings.entry(key.clone()).or_default().insert(arch.clone());
+            }
+            for b in cfg.bindings() {
+                let arch = match b.architecture() {
+                    Some(a) => a,
+                    None => continue,
+                };
+                // resolve the entity's library relative to the configuration's library
+                let prefix = match b.entity().get_prefix() {
+                    Some(p) if p != &working => p,
+                    _ => lib,
+                };
+                let entity = CompoundIdentifier::new(prefix.clone(), b.entity().get_suffix().clone());
+                bindings.entry(entity).or_default().insert(arch.clone());
+            }
+        }
+        bindings
+    }
+
+    /// Warns about the dependencies of the `target` that none of its units
+    /// reference, and about the libraries its units reference that are not
+    /// provided by any dependency.
+    ///
+    /// A missing library is only reported when an ip in the `catalog` declares
+    /// it, so the ip can be suggested as the dependency to add.
+    fn check_dependencies(
+        target: &Ip,
+        global_graph: &GraphMap<CompoundIdentifier, HdlNode, ()>,
+        local_graph: &GraphMap<&CompoundIdentifier, &HdlNode, &()>,
+        blackboxes: &Vec<BlackBox>,
+        catalog: &Catalog,
+    ) -> () {
+        let working = Identifier::new_working();
+        // libraries already provided by the ip graph
+        let known: HashSet<&Identifier> = global_graph
+            .get_map()
+            .keys()
+            .filter_map(|k| k.get_prefix())
+            .chain([&working])
+            .collect();
+
+        let mut used: HashSet<&PkgPart> = HashSet::new();
+        let mut missing: Vec<(&Identifier, &CompoundIdentifier)> = Vec::new();
+        for (key, node) in local_graph.get_map() {
+            // find the ip of each unit this unit depends on
+            for (dep_key, dep_node, _) in global_graph.predecessors_by_key(key).unwrap() {
+                let ip = match dep_node.is_external() {
+                    true => blackboxes
+                        .iter()
+                        .find(|bb| {
+                            Some(bb.get_library()) == dep_key.get_prefix()
+                                && bb.get_name() == dep_key.get_suffix()
+                        })
+                        .map(|bb| bb.get_ip()),
+                    false => dep_node.get_associated_files().first().map(|f| f.get_ip()),
+                };
+                if let Some(ip) = ip {
+                    used.insert(ip.get_man().get_ip().get_name());
+                }
+            }
+            // find the libraries referenced by this unit that do not exist
+            for dep in node.as_ref().get_unresolved() {
+                if let Some(lib) = dep.get_prefix() {
+                    if known.contains(lib) == false && missing.iter().any(|(l, _)| l == &lib) == false {
+                        missing.push((lib, *key));
+                    }
+                }
+            }
+        }
+
+        let man = target.get_man();
+        for (table, deps) in [("dependencies", man.get_deps()), ("dev-dependencies", man.get_dev_deps())] {
+            let mut names: Vec<&PkgPart> = deps.keys().collect();
+            names.sort();
+            for name in names {
+                if used.contains(name) == false {
+                    logger::warn(format!(
+                        "dependency {} is never referenced by a design unit; consider removing it from [{}]",
+                        name, table
+                    ));
+                }
+            }
+        }
+
+        for (lib, unit) in missing {
+            // find an ip that declares the library
+            let provider = catalog.inner().values().find_map(|lvl| {
+                let ip = lvl
+                    .get(true, &AnyVersion::Latest)
+                    .or(lvl.get_available(&AnyVersion::Latest))?;
+                match ip.get_man().get_ip().get_library() {
+                    Some(l) if &Identifier::from(l) == lib => Some(ip),
+                    _ => None,
+                }
+            });
+            if let Some(ip) = provider {
+                let spec = ip.get_man().get_ip();
+                logger::warn(format!(
+                    "library {} referenced by {} is not provided by a dependency; try `orbit install {}` and add `{} = \"{}\"` to [dependencies]",
+                    lib,
+                    unit,
+                    spec.get_name(),
+                    spec.get_name(),
+                    spec.get_version()
+                ));
+            }
+        }
+    }
+
+    /// Determines the `top` and `bench` requested for the plan.
+    ///
+    /// The units from the command-line take priority. When neither is given,
+    /// the defaults from the manifest's `top` and `bench` fields are used.
+    /// Returns `true` as the last element when the top is a manifest default,
+    /// which is trusted to be the top even if it has no ports.
+    fn requested_units(
+        &self,
+        target: &Ip,
+    ) -> Result<(Option<Identifier>, Option<Identifier>, bool), Fault> {
+        if self.top.is_some() || self.bench.is_some() {
+            return Ok((self.top.clone(), self.bench.clone(), false));
+        }
+        let ip = target.get_man().get_ip();
+        let top = match ip.get_top() {
+            Some(t) => Some(Identifier::from_str(t)?),
+            None => None,
+        };
+        let bench = match ip.get_bench() {
+            Some(b) => Some(Identifier::from_str(b)?),
+            None => None,
+        };
+        let trusted = top.is_some();
+        Ok((top, bench, trusted))
+    }
+
     fn detect_bench(
         &self,
         _graph: &GraphMap<CompoundIdentifier, HdlNode, ()>,
         local: &GraphMap<&CompoundIdentifier, &HdlNode, &()>,
         working_lib: &Identifier,
+        top: Option<&Identifier>,
+        bench: Option<&Identifier>,
     ) -> Result<(Option<usize>, Option<usize>), PlanError> {
-        Ok(if let Some(t) = &self.bench {
+        Ok(if let Some(t) = bench {
             match local.get_node_by_key(&&CompoundIdentifier::new(working_lib.clone(), t.clone())) {
                 // verify the unit is an entity that is a testbench
                 Some(node) => {
@@ -547,11 +1006,25 @@ impl Plan {
                         return Err(PlanError::BadEntity(t.clone()))?;
                     }
                 }
-                None => return Err(PlanError::UnknownEntity(t.clone()))?,
+                None => return Err(PlanError::unknown_entity(t, local))?,
             }
         // try to find the naturally occurring top-level if user did not provide --bench and did not provide --top
-        } else if self.top.is_none() {
-            match local.find_root() {
+        } else if top.is_none() {
+            // packages and contexts are never the top-level, so only consider entities
+            let roots = match local.find_root() {
+                Ok(n) => Ok(n),
+                Err(e) => {
+                    let entities: Vec<&Node<&HdlNode>> = e
+                        .into_iter()
+                        .filter(|n| Node::as_ref(n).get_symbol().as_entity().is_some())
+                        .collect();
+                    match entities.len() {
+                        1 => Ok(entities[0]),
+                        _ => Err(entities),
+                    }
+                }
+            };
+            match roots {
                 // only detected a single root
                 Ok(n) => {
                     let n = local
@@ -598,16 +1071,19 @@ impl Plan {
         _graph: &GraphMap<CompoundIdentifier, HdlNode, ()>,
         local: &GraphMap<&CompoundIdentifier, &HdlNode, &()>,
         working_lib: &Identifier,
+        top: Option<&Identifier>,
+        trusted: bool,
         natural_top: Option<usize>,
         mut bench: Option<usize>,
     ) -> Result<(Option<usize>, Option<usize>), PlanError> {
         // determine the top-level node index
-        let top: Option<usize> = if let Some(t) = &self.top {
+        let top: Option<usize> = if let Some(t) = top {
             match local.get_node_by_key(&&CompoundIdentifier::new(working_lib.clone(), t.clone())) {
                 Some(node) => {
                     // verify the unit is an entity that is not a testbench
                     if let Some(e) = node.as_ref().get_symbol().as_entity() {
-                        if e.is_testbench() == true {
+                        // a trusted top may have no ports, such as a test harness wrapper
+                        if e.is_testbench() == true && trusted == false {
                             return Err(PlanError::BadTop(t.clone()))?;
                         }
                     } else {
@@ -651,7 +1127,7 @@ impl Plan {
                     // return the index from the local graph
                     Some(n)
                 }
-                None => return Err(PlanError::UnknownEntity(t.clone()))?,
+                None => return Err(PlanError::unknown_entity(t, local))?,
             }
         } else {
             match natural_top {
@@ -792,7 +1268,7 @@ impl Plan {
             }
         }
         // topologically sort and transform into list of the file nodes
-        file_graph.get_graph().topological_sort().into_iter().map(|i| { *file_graph.get_key_by_index(i).unwrap() } ).collect()
+        file_graph.topological_sort().into_iter().copied().collect()
     }
 
     /// Filters out the local nodes existing within the current IP from the `global_graph`.
@@ -809,6 +1285,8 @@ impl Plan {
                 Some(iden) => iden == working_lib,
                 None => false,
             })
+            // blackboxes have no source to belong to the current IP
+            .filter(|f| f.1.is_external() == false)
             // filter by checking if the node's ip is the same as target
             .filter(|f| {
                 let mut in_range: bool = true;
@@ -832,6 +1310,9 @@ impl Plan {
         build_dir: &str,
         plug: Option<&Plugin>,
         catalog: Catalog,
+        vtable: VariableTable,
+        fmt: OutputFormat,
+        ignore_file: Option<&PathBuf>,
     ) -> Result<(), Fault> {
         // create the build path to know where to begin storing files
         let mut build_path = target.get_root().clone();
@@ -843,16 +1324,31 @@ impl Plan {
         }
 
         // build entire ip graph and resolve with dynamic symbol transformation
-        let ip_graph = algo::compute_final_ip_graph(&target, &catalog)?;
+        let ip_graph = {
+            let _span = timing::span("resolve ip graph");
+            algo::compute_final_ip_graph(&target, &catalog)?
+        };
 
         // only write lockfile and exit if flag is raised
         if self.only_lock == true {
+            if self.dev == true {
+                return Err(AnyError(format!(
+                    "'--lock-only' cannot be used with '--dev' because the lockfile only records installations"
+                )))?;
+            }
             Self::write_lockfile(&target, &ip_graph, self.force)?;
             return Ok(());
         }
 
-        let files = algo::build_ip_file_list(&ip_graph);
-        let global_graph = Self::build_full_graph(&files);
+        let files = {
+            let _span = timing::span("gather files");
+            algo::build_ip_file_list(&ip_graph, &target, ignore_file)
+        };
+        let blackboxes = algo::build_blackbox_list(&ip_graph)?;
+        let global_graph = {
+            let _span = timing::span("build graph");
+            Self::build_full_graph(&files, &HashMap::new(), &blackboxes)
+        };
 
         let working_lib = Identifier::new_working();
 
@@ -860,7 +1356,15 @@ impl Plan {
         let local_graph: GraphMap<&CompoundIdentifier, &HdlNode, &()> =
             Self::compute_local_graph(&global_graph, &working_lib, &target);
 
-        let (top, bench) = match self.detect_bench(&global_graph, &local_graph, &working_lib) {
+        let (req_top, req_bench, trusted) = self.requested_units(&target)?;
+
+        let (top, bench) = match self.detect_bench(
+            &global_graph,
+            &local_graph,
+            &working_lib,
+            req_top.as_ref(),
+            req_bench.as_ref(),
+        ) {
             Ok(r) => r,
             Err(e) => match e {
                 PlanError::Ambiguous(_, _) => {
@@ -874,20 +1378,27 @@ impl Plan {
             },
         };
         // determine the top-level node index
-        let (top, bench) =
-            match self.detect_top(&global_graph, &local_graph, &working_lib, top, bench) {
-                Ok(r) => r,
-                Err(e) => match e {
-                    PlanError::Ambiguous(_, _) => {
-                        if self.all == true {
-                            (top, bench)
-                        } else {
-                            return Err(e)?;
-                        }
+        let (top, bench) = match self.detect_top(
+            &global_graph,
+            &local_graph,
+            &working_lib,
+            req_top.as_ref(),
+            trusted,
+            top,
+            bench,
+        ) {
+            Ok(r) => r,
+            Err(e) => match e {
+                PlanError::Ambiguous(_, _) => {
+                    if self.all == true {
+                        (top, bench)
+                    } else {
+                        return Err(e)?;
                     }
-                    _ => return Err(e)?,
-                },
-            };
+                }
+                _ => return Err(e)?,
+            },
+        };
 
         let top = match top {
             Some(i) => Some(Self::local_to_global(i, &global_graph, &local_graph).index()),
@@ -898,6 +1409,30 @@ impl Plan {
             Some(i) => Some(Self::local_to_global(i, &global_graph, &local_graph).index()),
             None => None,
         };
+
+        // rebuild the graph with only the architectures selected by configurations
+        let top_key = top.map(|i| global_graph.get_key_by_index(i).unwrap().clone());
+        let bench_key = bench.map(|i| global_graph.get_key_by_index(i).unwrap().clone());
+        let bindings =
+            Self::collect_bindings(&global_graph, &[bench_key.as_ref(), top_key.as_ref()]);
+        drop(local_graph);
+        let (global_graph, top, bench) = match bindings.is_empty() {
+            true => (global_graph, top, bench),
+            false => {
+                let graph = {
+                    let _span = timing::span("build graph");
+                    Self::build_full_graph(&files, &bindings, &blackboxes)
+                };
+                let top = top_key.map(|k| graph.get_node_by_key(&k).unwrap().index());
+                let bench = bench_key.map(|k| graph.get_node_by_key(&k).unwrap().index());
+                (graph, top, bench)
+            }
+        };
+        let local_graph: GraphMap<&CompoundIdentifier, &HdlNode, &()> =
+            Self::compute_local_graph(&global_graph, &working_lib, &target);
+
+        Self::check_dependencies(&target, &global_graph, &local_graph, &blackboxes, &catalog);
+
         // guarantees top exists if not using --all
 
         // error if the user-defined top is not instantiated in the testbench. Say this can be fixed by adding '--all'
@@ -915,7 +1450,14 @@ impl Plan {
         }
 
         // [!] write the lock file
-        Self::write_lockfile(&target, &ip_graph, true)?;
+        let lock = match self.dev {
+            // copies in development are not recorded because their contents can change
+            true => {
+                logger::info("Skipped updating the lockfile while using ip in development");
+                None
+            }
+            false => Some(Self::write_lockfile(&target, &ip_graph, true)?),
+        };
 
         // compute minimal topological ordering
         let min_order = match self.all {
@@ -960,6 +1502,13 @@ impl Plan {
         };
 
         // generate the file order while merging dependencies for common file path names together
+        // remember the blackboxes within the hierarchy to collect their files
+        let external_units: Vec<&CompoundIdentifier> = min_order
+            .iter()
+            .filter(|&&i| global_graph.get_node_by_index(i).unwrap().as_ref().is_external())
+            .map(|&i| global_graph.get_key_by_index(i).unwrap())
+            .collect();
+
         let file_order = Self::determine_file_order(&global_graph, min_order);
 
         // remove duplicate files from list while perserving order
@@ -985,12 +1534,12 @@ impl Plan {
 
         // print information (maybe also print the plugin saved to .env too?)
         match top_name.is_empty() {
-            false => println!("info: top-level set to {}", top_name.blue()),
-            true => println!("{} no top-level set", "warning:".yellow()),
+            false => logger::info(format!("top-level set to {}", top_name.blue())),
+            true => logger::warn("no top-level set"),
         }
         match bench_name.is_empty() {
-            false => println!("info: testbench set to {}", bench_name.blue()),
-            true => println!("{} no testbench set", "warning:".yellow()),
+            false => logger::info(format!("testbench set to {}", bench_name.blue())),
+            true => logger::warn("no testbench set"),
         }
 
         // store data in blueprint TSV format
@@ -999,9 +1548,9 @@ impl Plan {
         // [!] collect user-defined filesets
         {
             let current_files: Vec<String> =
-                filesystem::gather_current_files(&target.get_root(), false);
+                filesystem::gather_working_files(&target.get_root(), false, ignore_file);
 
-            let mut vtable = VariableTable::new();
+            let mut vtable = vtable;
             // variables could potentially store empty strings if units are not set
             vtable.add("orbit.bench", &bench_name);
             vtable.add("orbit.top", &top_name);
@@ -1029,7 +1578,7 @@ impl Plan {
                             let entry = cli_fset_map.remove(&proper_key);
                             (name, entry.unwrap().get_pattern())
                         }
-                        false => (name, pattern.inner()),
+                        false => (name, pattern),
                     };
                     // perform variable substitution
                     let fset = Fileset::new()
@@ -1060,6 +1609,61 @@ impl Plan {
                         blueprint_data += &fset.to_blueprint_string(&f);
                     });
             }
+
+            // collect the filesets defined in the manifest of every ip, which
+            // travel with an ip when it is used as a dependency
+            let target_spec = target.get_man().get_ip().into_ip_spec();
+            let mut ips: Vec<(&IpSpec, &Ip)> = ip_graph
+                .get_map()
+                .iter()
+                .map(|(spec, node)| (spec, node.as_ref().as_original_ip()))
+                .collect();
+            ips.sort_by(|a, b| a.0.to_string().cmp(&b.0.to_string()));
+            for (spec, ip) in ips {
+                let man_fsets = ip.get_man().get_filesets();
+                if man_fsets.is_empty() == true {
+                    continue;
+                }
+                let dep_files;
+                let ip_files = match spec == &target_spec {
+                    true => &current_files,
+                    false => {
+                        dep_files = filesystem::gather_current_files(ip.get_root(), false);
+                        &dep_files
+                    }
+                };
+                for (name, globs) in man_fsets {
+                    // perform variable substitution
+                    let globs: Vec<String> = globs
+                        .iter()
+                        .map(|g| variable::substitute(g.to_string(), &vtable))
+                        .collect();
+                    let fset = Fileset::new().name(name).patterns(&globs)?;
+                    // match files
+                    fset.collect_files(ip_files)
+                        .into_iter()
+                        .for_each(|f| {
+                            blueprint_data += &fset.to_blueprint_string(&f);
+                        });
+                }
+            }
+        }
+
+        // collect the files of the blackboxes used in the design
+        for key in external_units {
+            for bb in blackboxes.iter().filter(|bb| {
+                Some(bb.get_library()) == key.get_prefix() && bb.get_name() == key.get_suffix()
+            }) {
+                let fset = Fileset::new()
+                    .name(BLACKBOX_FILESET)
+                    .patterns(bb.get_patterns())?;
+                let ip_files = filesystem::gather_current_files(bb.get_ip().get_root(), false);
+                fset.collect_files(&ip_files)
+                    .into_iter()
+                    .for_each(|f| {
+                        blueprint_data += &fset.to_blueprint_string(&f);
+                    });
+            }
         }
 
         // collect in-order HDL file list
@@ -1079,6 +1683,7 @@ impl Plan {
         }
 
         // [!] create the blueprint file
+        let span = timing::span("write blueprint");
         let blueprint_path = build_path.join(BLUEPRINT_FILE);
         let mut blueprint_file =
             File::create(&blueprint_path).expect("could not create blueprint file");
@@ -1087,12 +1692,18 @@ impl Plan {
             .write_all(blueprint_data.as_bytes())
             .expect("failed to write data to blueprint");
 
-        // create environment variables to .env file
+        // create environment variables to .env file from the units verified in the graph
         let mut envs = Environment::from_vec(vec![
             EnvVar::new().key(environment::ORBIT_TOP).value(&top_name),
             EnvVar::new()
                 .key(environment::ORBIT_BENCH)
                 .value(&bench_name),
+            EnvVar::new()
+                .key(environment::ORBIT_BUILD_DIR)
+                .value(build_dir),
+            EnvVar::new()
+                .key(environment::ORBIT_DEPENDENCIES)
+                .value(DEPENDENCY_REPORT_FILE),
         ]);
         // conditionally set the plugin used to plan
         match plug {
@@ -1102,18 +1713,84 @@ impl Plan {
                         .key(environment::ORBIT_PLUGIN)
                         .value(&p.get_alias()),
                 );
-                ()
+                // record the plugin's own environment variables
+                p.get_env().into_iter().for_each(|e| {
+                    envs.insert(e);
+                });
             }
             None => (),
         };
+        // point to the installation of each dependency
+        let target_spec = target.get_man().get_ip().into_ip_spec();
+        let deps: Vec<(&IpSpec, &PathBuf)> = ip_graph
+            .get_map()
+            .iter()
+            .filter(|(spec, _)| *spec != &target_spec)
+            .map(|(spec, node)| (spec, node.as_ref().as_original_ip().get_root()))
+            .collect();
+        let envs = envs.from_dependencies(&deps);
         environment::save_environment(&envs, &build_path)?;
 
+        // record the exact version of each dependency used in the build
+        let build_list: Vec<&Ip> = ip_graph
+            .get_map()
+            .iter()
+            .filter(|(spec, _)| *spec != &target_spec)
+            .map(|(_, node)| node.as_ref().as_original_ip())
+            .collect();
+        DependencyReport::from_build_list(&build_list, lock.as_ref()).save(&build_path)?;
+
+        // list the top-level entity's ports for checking board-level designs
+        let ports = match top.and_then(|i| global_graph.get_node_by_index(i)) {
+            Some(node) => match node.as_ref().get_symbol().as_entity() {
+                Some(e) => {
+                    PortReport::from_entity(e).save(&build_path)?;
+                    Some(build_path.join(PORT_REPORT_CSV))
+                }
+                None => None,
+            },
+            None => None,
+        };
+        drop(span);
+
         // create a blueprint file
-        println!("info: Blueprint created at: {}", blueprint_path.display());
+        logger::info(format!("Blueprint created at: {}", blueprint_path.display()));
+        if let Some(path) = &ports {
+            logger::info(format!("Port report created at: {}", path.display()));
+        }
+        match fmt {
+            OutputFormat::Text => (),
+            OutputFormat::Json => {
+                let summary = PlanSummary {
+                    top: Some(top_name).filter(|s| s.is_empty() == false),
+                    bench: Some(bench_name).filter(|s| s.is_empty() == false),
+                    plugin: plug.map(|p| p.get_alias().to_string()),
+                    build_dir: build_dir.to_string(),
+                    blueprint: filesystem::into_std_str(blueprint_path),
+                    ports: ports.map(|p| filesystem::into_std_str(p)),
+                    dependencies: filesystem::into_std_str(build_path.join(DEPENDENCY_REPORT_FILE)),
+                };
+                println!("{}", output::to_json("plan", &summary)?);
+            }
+        }
         Ok(())
     }
 }
 
+/// The outcome of planning a design.
+#[derive(Debug, Serialize)]
+struct PlanSummary {
+    top: Option<String>,
+    bench: Option<String>,
+    plugin: Option<String>,
+    build_dir: String,
+    blueprint: String,
+    /// The port report of the top-level entity, if one was written.
+    ports: Option<String>,
+    /// The report of the exact version of each dependency.
+    dependencies: String,
+}
+
 #[derive(Debug)]
 pub enum PlanError {
     BadTestbench(Identifier),
@@ -1121,18 +1798,59 @@ pub enum PlanError {
     BadEntity(Identifier),
     TestbenchNoTest(Identifier),
     UnknownUnit(Identifier),
-    UnknownEntity(Identifier),
+    UnknownEntity(Identifier, Option<Identifier>),
     Ambiguous(String, Vec<Identifier>),
     Empty,
 }
 
 impl std::error::Error for PlanError {}
 
+impl PlanError {
+    /// Creates the error for the requested entity `name` that is not found in
+    /// the `local` graph, suggesting the entity with the closest spelling.
+    pub fn unknown_entity(name: &Identifier, local: &GraphMap<&CompoundIdentifier, &HdlNode, &()>) -> Self {
+        let entities: Vec<String> = local
+            .get_map()
+            .iter()
+            .filter(|(_, n)| n.as_ref().get_symbol().as_entity().is_some())
+            .map(|(k, _)| k.get_suffix().to_string())
+            .collect();
+        let suggestion = seqalin::sel_min_edit_str(&name.to_string(), &entities, suggest::THRESHOLD)
+            .and_then(|s| Identifier::from_str(s).ok());
+        Self::UnknownEntity(name.clone(), suggestion)
+    }
+
+    /// Reports the error as a `Diagnostic` with a suggestion for how to resolve it.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let suggestion;
+        let help = match self {
+            Self::TestbenchNoTest(_) => "select the unit under test with `--top <unit>`",
+            Self::UnknownEntity(_, Some(near)) => {
+                suggestion = format!("did you mean '{}'?", near);
+                suggestion.as_str()
+            }
+            Self::UnknownEntity(_, None) | Self::UnknownUnit(_) => {
+                "see the available units with `orbit tree`"
+            }
+            Self::Empty => "add a VHDL file declaring an entity to the current ip",
+            Self::BadEntity(_) => "only entities can be selected as top or bench",
+            Self::BadTestbench(_) => "select the entity with `--top <unit>`",
+            Self::BadTop(_) => "select the entity with `--bench <tb>`",
+            Self::Ambiguous(_, _) => {
+                "select the unit explicitly with `--top <unit>` or `--bench <tb>`"
+            }
+        };
+        Diagnostic::error(&self.to_string())
+            .code(&ErrorCode::E0303.to_string())
+            .help(help)
+    }
+}
+
 impl std::fmt::Display for PlanError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::TestbenchNoTest(id) => write!(f, "No entities are tested in testbench {}", id),
-            Self::UnknownEntity(id) => write!(f, "No entity named '{}' in the current IP", id),
+            Self::UnknownEntity(id, _) => write!(f, "No entity named '{}' in the current IP", id),
             Self::Empty => write!(f, "No entities found"),
             Self::BadEntity(id) => write!(f, "Primary design unit '{}' is not an entity", id),
             Self::BadTestbench(id) => write!(
@@ -1182,4 +1900,130 @@ mod test {
             vec![&9, &8, &7, &6, &5, &4]
         );
     }
+
+    #[test]
+    fn unknown_entity_suggestion() {
+        let mut global: GraphMap<CompoundIdentifier, HdlNode, ()> = GraphMap::new();
+        for sym in VHDLParser::read("entity and_gate is end entity;\nentity or_gate is end entity;")
+            .into_symbols()
+        {
+            let key = CompoundIdentifier::new(Identifier::new_working(), sym.as_iden().unwrap().clone());
+            global.add_node(key, HdlNode::black_box(sym));
+        }
+        let local: GraphMap<&CompoundIdentifier, &HdlNode, &()> = global.iter().collect();
+
+        let err = PlanError::unknown_entity(&Identifier::from_str("adn_gate").unwrap(), &local);
+        assert_eq!(err.to_string(), "No entity named 'adn_gate' in the current IP");
+        assert_eq!(
+            err.to_diagnostic().get_help(),
+            Some(&String::from("did you mean 'and_gate'?"))
+        );
+
+        let err = PlanError::unknown_entity(&Identifier::from_str("multiplier").unwrap(), &local);
+        assert_eq!(
+            err.to_diagnostic().get_help(),
+            Some(&String::from("see the available units with `orbit tree`"))
+        );
+    }
+
+    #[test]
+    fn bindings_per_instance() {
+        let code = r#"
+entity top is end entity;
+
+configuration top_cfg of top is
+    for rtl
+        for u0 : leaf use entity work.leaf(fast); end for;
+        for u1 : leaf use entity work.leaf(small); end for;
+    end for;
+end configuration;
+"#;
+        let mut global: GraphMap<CompoundIdentifier, HdlNode, ()> = GraphMap::new();
+        let mut configs = Vec::new();
+        for sym in VHDLParser::read(code).into_symbols() {
+            match sym {
+                VHDLSymbol::Configuration(cfg) => configs.push(cfg),
+                _ => {
+                    let key = CompoundIdentifier::new(Identifier::new_working(), sym.as_iden().unwrap().clone());
+                    global.add_node(key, HdlNode::black_box(sym));
+                }
+            }
+        }
+        let top = CompoundIdentifier::new(Identifier::new_working(), Identifier::from_str("top").unwrap());
+        let node = global.get_node_by_key_mut(&top).unwrap().as_ref_mut();
+        configs.into_iter().for_each(|cfg| node.add_config(cfg));
+
+        let bindings = Plan::collect_bindings(&global, &[None, Some(&top)]);
+        let leaf = CompoundIdentifier::new(Identifier::new_working(), Identifier::from_str("leaf").unwrap());
+        // each instance keeps the architecture it is bound to
+        let mut archs: Vec<String> = bindings.get(&leaf).unwrap().iter().map(|a| a.to_string()).collect();
+        archs.sort();
+        assert_eq!(archs, vec!["fast", "small"]);
+        assert_eq!(
+            bindings.get(&top),
+            Some(&HashSet::from([Identifier::from_str("rtl").unwrap()]))
+        );
+    }
+
+    #[test]
+    fn profile_options() {
+        let man = crate::core::manifest::Manifest::from_str(
+            r#"[ip]
+name = "soc"
+version = "1.0.0"
+
+[plan.impl]
+plugin = "vivado"
+top = "soc_top"
+build-dir = "impl"
+fileset = { XDC = "constr/*.xdc", TCL = "scripts/*.tcl" }
+"#,
+        )
+        .unwrap();
+        let profile = man.get_plan_profile("impl").unwrap();
+
+        // the profile fills in every missing option
+        let plan = Plan::with_unit(None, None, None, String::from("build"));
+        let plan = Plan {
+            build_dir: None,
+            ..plan
+        }
+        .with_profile(profile)
+        .unwrap();
+        assert_eq!(plan.plugin, Some(String::from("vivado")));
+        assert_eq!(plan.top, Some(Identifier::from_str("soc_top").unwrap()));
+        assert_eq!(plan.bench, None);
+        assert_eq!(plan.build_dir, Some(String::from("impl")));
+        assert_eq!(
+            plan.filesets,
+            Some(vec![
+                Fileset::from_str("TCL=scripts/*.tcl").unwrap(),
+                Fileset::from_str("XDC=constr/*.xdc").unwrap(),
+            ])
+        );
+
+        // the command-line takes priority over the profile
+        let plan = Plan {
+            filesets: Some(vec![Fileset::from_str("xdc=board/*.xdc").unwrap()]),
+            ..Plan::with_unit(
+                Some(String::from("yosys")),
+                Some(Identifier::from_str("soc_tb").unwrap()),
+                None,
+                String::from("build"),
+            )
+        }
+        .with_profile(profile)
+        .unwrap();
+        assert_eq!(plan.plugin, Some(String::from("yosys")));
+        assert_eq!(plan.top, None);
+        assert_eq!(plan.bench, Some(Identifier::from_str("soc_tb").unwrap()));
+        assert_eq!(plan.build_dir, Some(String::from("build")));
+        assert_eq!(
+            plan.filesets,
+            Some(vec![
+                Fileset::from_str("TCL=scripts/*.tcl").unwrap(),
+                Fileset::from_str("XDC=board/*.xdc").unwrap(),
+            ])
+        );
+    }
 }
@@ -359,7 +359,7 @@ impl Read {
         let (source, position) = match units.get_key_value(unit) {
             Some((_, unit)) => (
                 unit.get_unit().get_source_code_file(),
-                unit.get_unit().get_symbol().unwrap().get_position().clone(),
+                unit.get_unit().get_position().clone(),
             ),
             None => {
                 return Err(GetError::SuggestShow(
@@ -1,7 +1,10 @@
 use crate::core::context::Context;
-use crate::core::ip::Mapping;
 use crate::core::pkgid::PkgPart;
 use crate::util::anyerror::Fault;
+use crate::util::date;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use serde_derive::Serialize;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional, Positional};
 use clif::cmd::{Command, FromCli};
@@ -11,8 +14,10 @@ use std::collections::BTreeMap;
 
 use crate::core::catalog::Catalog;
 use crate::core::catalog::IpLevel;
+use crate::core::catalog::IpState;
 use crate::core::version::AnyVersion;
 use crate::commands::helps::search;
+use crate::commands::vendor;
 
 #[derive(Debug, PartialEq)]
 pub struct Search {
@@ -22,6 +27,7 @@ pub struct Search {
     keywords: Vec<String>,
     limit: Option<usize>,
     hard_match: bool,
+    refresh: bool,
 }
 
 impl FromCli for Search {
@@ -31,6 +37,7 @@ impl FromCli for Search {
             downloaded: cli.check_flag(Flag::new("download").switch('d'))?,
             cached: cli.check_flag(Flag::new("install").switch('i'))?,
             hard_match: cli.check_flag(Flag::new("match"))?,
+            refresh: cli.check_flag(Flag::new("refresh"))?,
             limit: cli.check_option(Optional::new("limit").value("num"))?,
             keywords: cli
                 .check_option_all(Optional::new("keyword").value("term"))?
@@ -51,15 +58,22 @@ impl Command<Context> for Search {
         catalog = catalog.installations(c.get_cache_path())?;
         // collect downloaded IP
         catalog = catalog.downloads(c.get_downloads_path())?;
-        // collect available IP
-        // @todo
+        // collect IP in development
+        catalog = catalog.development(c.get_config().get_workspaces())?;
+        // collect IP available from the vendor index
+        let index = vendor::sync_index(c, self.refresh)?;
+        if let Some((path, _)) = &index {
+            catalog = catalog.available(path)?;
+        }
 
-        self.run(&catalog)
+        self.run(&catalog, c.get_output_format(), index.map(|(_, age)| age))
     }
 }
 
 impl Search {
-    fn run(&self, catalog: &Catalog) -> Result<(), Fault> {
+    /// Displays the ip in the `catalog`, noting the `age` in seconds of the
+    /// vendor index when one was searched.
+    fn run(&self, catalog: &Catalog, fmt: OutputFormat, age: Option<u64>) -> Result<(), Fault> {
         // transform into a BTreeMap for alphabetical ordering
         let mut tree = BTreeMap::new();
         catalog
@@ -67,7 +81,11 @@ impl Search {
             .into_iter()
             // filter by name if user entered a pkgid to search
             .filter(|(key, iplvl)| { 
-                if let Some(prj) = iplvl.get(true, &AnyVersion::Latest) {
+                if let Some(prj) = iplvl
+                    .get(true, &AnyVersion::Latest)
+                    .or_else(|| iplvl.get_development(&AnyVersion::Latest))
+                    .or_else(|| iplvl.get_available(&AnyVersion::Latest))
+                {
                     match self.hard_match {
                         true => {
                             let name_match = match &self.ip {
@@ -125,7 +143,18 @@ impl Search {
                 tree.insert(key, status);
             });
 
-        println!("{}", Self::fmt_table(tree, self.limit, self.cached, self.downloaded));
+        match fmt {
+            OutputFormat::Text => {
+                println!("{}", Self::fmt_table(tree, self.limit, self.cached, self.downloaded));
+                if let Some(age) = age {
+                    println!("Vendor index fetched {}", date::fmt_age(age));
+                }
+            }
+            OutputFormat::Json => println!(
+                "{}",
+                output::to_json("search", &Self::collect_entries(tree, self.limit, self.cached, self.downloaded))?
+            ),
+        }
         Ok(())
     }
 
@@ -137,6 +166,21 @@ impl Search {
             "Package", "Latest", "Status", " "
         );
         let mut body = String::new();
+        for entry in Self::collect_entries(catalog, limit, cached, downloaded) {
+            body.push_str(&format!(
+                "{:<28}{:<10}{:<9}\n",
+                entry.name,
+                entry.version.clone() + { if entry.update == true { "*" } else { "" } },
+                entry.status,
+            ));
+        }
+        header + &body
+    }
+
+    /// Collects the ip to display from the `catalog`, choosing the latest version
+    /// of each ip according to the `cached` and `downloaded` filters.
+    fn collect_entries(catalog: BTreeMap<&PkgPart, &IpLevel>, limit: Option<usize>, cached: bool, downloaded: bool) -> Vec<SearchEntry> {
+        let mut entries = Vec::new();
         let mut index = 0;
 
         let default = !(cached || downloaded);
@@ -156,7 +200,9 @@ impl Search {
                     // always return the installation version if one is possible
                     if default == true || cached == true { ins } else { dld }
                 } else if dld.is_none() {
-                    ins
+                    // an ip only in development or a vendor index is listed by that copy
+                    ins.or_else(|| status.get_development(&AnyVersion::Latest))
+                        .or_else(|| status.get_available(&AnyVersion::Latest))
                 } else {
                     dld
                 }
@@ -176,33 +222,42 @@ impl Search {
             }
 
             // determine if to skip this IP based on settings
-            let cleared = default == true || match ip.get_mapping() {
-                Mapping::Physical => cached == true,
-                Mapping::Virtual(_) => downloaded == true,
+            let state = status.get_state(ip);
+            let cleared = default == true || match state {
+                IpState::Installation => cached == true,
+                IpState::Downloaded => downloaded == true,
+                _ => false,
             };
             if cleared == false {
                 continue;
             }
 
-            body.push_str(&format!(
-                "{:<28}{:<10}{:<9}\n",
-                    name.to_string(),
-                    ip
-                    .get_man()
-                    .get_ip()
-                    .get_version().to_string() + { if is_update_available == true { "*" } else { "" } },
-                    match ip.get_mapping() {
-                        Mapping::Physical => "Installed",
-                        Mapping::Virtual(_) => "Downloaded",
-                        // Mapping::Imaginary => "Available",
-                        // _ => ""
-                    },
-            ));
+            entries.push(SearchEntry {
+                name: name.to_string(),
+                version: ip.get_man().get_ip().get_version().to_string(),
+                update: is_update_available,
+                status: match state {
+                    IpState::Development => "Development",
+                    IpState::Downloaded => "Downloaded",
+                    IpState::Available => "Available",
+                    _ => "Installed",
+                },
+            });
         }
-        header + &body
+        entries
     }
 }
 
+/// An ip found in the catalog.
+#[derive(Debug, PartialEq, Serialize)]
+struct SearchEntry {
+    name: String,
+    version: String,
+    /// A higher version is downloaded but not yet installed.
+    update: bool,
+    status: &'static str,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,617 @@
+//! Manages the installation of the orbit executable itself.
+//!
+//! Installing copies the running executable into a directory and adds that
+//! directory to the user's PATH, so a downloaded binary can install itself
+//! without a separate installer program. Every change is recorded so a failure
+//! midway through restores the machine to how it was found. Uninstalling
+//! reverses the installation.
+
+use crate::commands::helps::selfcmd;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::logger;
+use crate::util::prompt;
+use crate::util::sha256;
+use crate::OrbitResult;
+use clif::arg::{Flag, Optional, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::path::{Path, PathBuf};
+use std::process::Command as Process;
+
+#[cfg(target_os = "windows")]
+const EXE_NAME: &str = "orbit.exe";
+#[cfg(not(target_os = "windows"))]
+const EXE_NAME: &str = "orbit";
+
+/// The comment written above the line that extends the PATH in a shell profile.
+const PROFILE_MARKER: &str = "# added by `orbit self install`";
+
+/// The shell profiles, relative to the home directory, that installing may edit.
+const PROFILES: [&str; 5] = [
+    ".zshrc",
+    ".bashrc",
+    ".bash_profile",
+    ".config/fish/config.fish",
+    ".profile",
+];
+
+const SELF_COMMANDS: [&str; 2] = ["install", "uninstall"];
+
+#[derive(Debug, PartialEq)]
+pub struct SelfCmd {
+    action: Option<SelfAction>,
+}
+
+#[derive(Debug, PartialEq)]
+enum SelfAction {
+    Install(SelfInstall),
+    Uninstall(SelfUninstall),
+}
+
+#[derive(Debug, PartialEq)]
+struct SelfInstall {
+    path: Option<PathBuf>,
+    no_modify_path: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct SelfUninstall {
+    purge: bool,
+}
+
+impl FromCli for SelfCmd {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(selfcmd::HELP).ref_usage(2..4))?;
+        let command = Ok(SelfCmd {
+            action: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl FromCli for SelfAction {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        match cli.match_command(&SELF_COMMANDS)?.as_ref() {
+            "install" => Ok(SelfAction::Install(SelfInstall {
+                path: cli.check_option(Optional::new("path").value("dir"))?,
+                no_modify_path: cli.check_flag(Flag::new("no-modify-path"))?,
+            })),
+            "uninstall" => Ok(SelfAction::Uninstall(SelfUninstall {
+                purge: cli.check_flag(Flag::new("purge"))?,
+            })),
+            _ => panic!("an unimplemented command was passed through!"),
+        }
+    }
+}
+
+impl Command<Context> for SelfCmd {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match &self.action {
+            Some(SelfAction::Install(i)) => i.run(),
+            Some(SelfAction::Uninstall(u)) => u.run(c),
+            None => Ok(println!("{}", selfcmd::HELP)),
+        }
+    }
+}
+
+/// A change made during installation and how to reverse it.
+#[derive(Debug, PartialEq)]
+enum Undo {
+    /// Remove a directory that did not exist before.
+    RemoveDir(PathBuf),
+    /// Remove a file that did not exist before.
+    RemoveFile(PathBuf),
+    /// Move the backup (first) of a replaced file back to its original path (second).
+    Restore(PathBuf, PathBuf),
+    /// Write back the previous contents of a file, removing it if it did not exist.
+    Rewrite(PathBuf, Option<String>),
+    /// Set the user's PATH in the windows registry back to its previous value.
+    UserPath(Option<String>),
+}
+
+impl Undo {
+    fn apply(&self) -> Result<(), Fault> {
+        match self {
+            Self::RemoveDir(dir) => std::fs::remove_dir_all(dir)?,
+            Self::RemoveFile(file) => std::fs::remove_file(file)?,
+            Self::Restore(backup, file) => std::fs::rename(backup, file)?,
+            Self::Rewrite(file, Some(text)) => std::fs::write(file, text)?,
+            Self::Rewrite(file, None) => std::fs::remove_file(file)?,
+            Self::UserPath(value) => write_user_path(value.as_deref().unwrap_or(""))?,
+        }
+        Ok(())
+    }
+}
+
+impl SelfInstall {
+    fn run(&self) -> OrbitResult {
+        let src = filesystem::get_exe_path()?;
+        let dir = match &self.path {
+            Some(p) => std::env::current_dir()?.join(p),
+            None => Self::default_dir()?,
+        };
+        let dest = dir.join(EXE_NAME);
+
+        if dest.exists() == true && std::fs::canonicalize(&dest)? == src {
+            logger::info(format!("orbit is already installed at {:?}", dest));
+        } else {
+            if dest.exists() == true
+                && prompt::prompt(&format!(
+                    "file {:?} already exists; is it okay to replace it",
+                    dest
+                ))? == false
+            {
+                println!("cancelled installation");
+                return Ok(());
+            }
+            let mut undos = Vec::new();
+            match self.install(&src, &dir, &dest, &mut undos) {
+                Ok(()) => Self::finish(undos),
+                Err(e) => {
+                    Self::rollback(undos);
+                    return Err(AnyError(format!(
+                        "failed to install orbit: {}\n\nAll changes made during the installation were reverted",
+                        e
+                    )))?;
+                }
+            }
+            logger::info(format!("installed orbit to {:?}", dest));
+        }
+        // the executable is in place, so the PATH is only extended as a convenience
+        if self.no_modify_path == false {
+            Self::add_to_path(&dir)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the executable at `src` to `dest` within `dir` and verifies the
+    /// copy's checksum, recording each change in `undos`.
+    fn install(&self, src: &Path, dir: &Path, dest: &Path, undos: &mut Vec<Undo>) -> Result<(), Fault> {
+        if dir.exists() == false {
+            // record the topmost directory that gets created
+            let mut top = dir.to_path_buf();
+            while let Some(parent) = top.parent() {
+                if parent.exists() == true {
+                    break;
+                }
+                top = parent.to_path_buf();
+            }
+            std::fs::create_dir_all(dir)?;
+            undos.push(Undo::RemoveDir(top));
+        }
+        // write to a temporary name so a partial copy never replaces a working executable
+        let staged = dir.join(format!("{}.part", EXE_NAME));
+        std::fs::copy(src, &staged)?;
+        undos.push(Undo::RemoveFile(staged.clone()));
+
+        let expected = sha256::compute_sha256_reader(std::fs::File::open(src)?)?;
+        let computed = sha256::compute_sha256_reader(std::fs::File::open(&staged)?)?;
+        if computed != expected {
+            return Err(AnyError(format!(
+                "checksum of the copied executable did not match\n\ncomputed: {}\nexpected: {}",
+                computed, expected
+            )))?;
+        }
+
+        if dest.exists() == true {
+            let backup = dir.join(format!("{}.old", EXE_NAME));
+            std::fs::rename(dest, &backup)?;
+            undos.push(Undo::Restore(backup, dest.to_path_buf()));
+        }
+        std::fs::rename(&staged, dest)?;
+        undos.retain(|u| u != &Undo::RemoveFile(staged.clone()));
+        undos.push(Undo::RemoveFile(dest.to_path_buf()));
+        Ok(())
+    }
+
+    /// Removes the backups kept to revert the `undos` once the installation
+    /// succeeds.
+    fn finish(undos: Vec<Undo>) -> () {
+        for undo in undos {
+            if let Undo::Restore(backup, _) = undo {
+                if let Err(e) = std::fs::remove_file(&backup) {
+                    logger::warn(format!("failed to remove backup {:?}: {}", backup, e));
+                }
+            }
+        }
+    }
+
+    /// Reverts the `undos` in the opposite order they were made.
+    fn rollback(undos: Vec<Undo>) -> () {
+        for undo in undos.into_iter().rev() {
+            if let Err(e) = undo.apply() {
+                logger::warn(format!("failed to revert change {:?}: {}", undo, e));
+            }
+        }
+    }
+
+    /// Returns the directory to install the executable into when no path is
+    /// given.
+    fn default_dir() -> Result<PathBuf, Fault> {
+        if cfg!(windows) {
+            match std::env::var("LOCALAPPDATA") {
+                Ok(v) => Ok(PathBuf::from(v).join("Programs").join("orbit").join("bin")),
+                Err(_) => Err(AnyError(String::from(
+                    "failed to find the LOCALAPPDATA directory\n\nTry `orbit self install --path <dir>` to choose the directory",
+                )))?,
+            }
+        } else {
+            match home::home_dir() {
+                Some(home) => Ok(home.join(".local").join("bin")),
+                None => Err(AnyError(String::from(
+                    "failed to find the home directory\n\nTry `orbit self install --path <dir>` to choose the directory",
+                )))?,
+            }
+        }
+    }
+
+    /// Adds the `dir` to the user's PATH if it is not already listed.
+    ///
+    /// Windows stores the user's PATH in the registry. Elsewhere, a line is
+    /// appended to the profile of the user's shell.
+    fn add_to_path(dir: &Path) -> Result<(), Fault> {
+        let current = std::env::var_os("PATH").unwrap_or_default();
+        if std::env::split_paths(&current).any(|p| p == dir) == true {
+            logger::info(format!("{:?} is already on the PATH", dir));
+            return Ok(());
+        }
+        let mut undos = Vec::new();
+        let result = match cfg!(windows) {
+            true => Self::add_to_user_path(dir, &mut undos),
+            false => Self::add_to_profile(dir, &mut undos),
+        };
+        if let Err(e) = result {
+            Self::rollback(undos);
+            return Err(AnyError(format!(
+                "installed orbit but failed to add {:?} to the PATH: {}\n\nAdd the directory to the PATH manually to call `orbit` from the command-line",
+                dir, e
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn add_to_user_path(dir: &Path, undos: &mut Vec<Undo>) -> Result<(), Fault> {
+        let prev = read_user_path()?;
+        let dir = dir.display().to_string();
+        if let Some(value) = &prev {
+            if value.split(';').any(|p| p.trim_end_matches('\\') == dir.trim_end_matches('\\')) == true {
+                return Ok(());
+            }
+        }
+        let next = match &prev {
+            Some(value) if value.is_empty() == false => format!("{};{}", value.trim_end_matches(';'), dir),
+            _ => dir.clone(),
+        };
+        undos.push(Undo::UserPath(prev));
+        write_user_path(&next)?;
+        logger::info(format!(
+            "added {} to the user PATH; open a new terminal to use `orbit`",
+            dir
+        ));
+        Ok(())
+    }
+
+    fn add_to_profile(dir: &Path, undos: &mut Vec<Undo>) -> Result<(), Fault> {
+        let home = match home::home_dir() {
+            Some(h) => h,
+            None => return Err(AnyError(String::from("failed to find the home directory")))?,
+        };
+        let shell = std::env::var("SHELL").unwrap_or_default();
+        let profile = home.join(find_profile(&shell, cfg!(target_os = "macos")));
+        let prev = std::fs::read_to_string(&profile).ok();
+        let line = profile_line(&shell, dir);
+        if let Some(text) = &prev {
+            if text.lines().any(|l| l.trim() == line) == true {
+                return Ok(());
+            }
+        }
+        let mut text = prev.clone().unwrap_or_default();
+        if text.is_empty() == false && text.ends_with('\n') == false {
+            text.push('\n');
+        }
+        text.push_str(&format!("\n{}\n{}\n", PROFILE_MARKER, line));
+        if let Some(parent) = profile.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        undos.push(Undo::Rewrite(profile.clone(), prev));
+        std::fs::write(&profile, text)?;
+        logger::info(format!(
+            "added {:?} to the PATH in {:?}; open a new terminal to use `orbit`",
+            dir, profile
+        ));
+        Ok(())
+    }
+}
+
+impl SelfUninstall {
+    fn run(&self, c: &Context) -> OrbitResult {
+        let exe = filesystem::get_exe_path()?;
+        let dir = exe.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        // collect the data directories, skipping any held within another one
+        let mut data: Vec<&PathBuf> = Vec::new();
+        if self.purge == true {
+            for path in [
+                c.get_home_path(),
+                c.get_config_home_path(),
+                c.get_cache_path(),
+                c.get_downloads_path(),
+            ] {
+                if path.exists() == true && data.iter().any(|d| path.starts_with(d)) == false {
+                    data.retain(|d| d.starts_with(path) == false);
+                    data.push(path);
+                }
+            }
+        }
+
+        if prompt::prompt(&format!("Uninstall orbit from {:?}", exe))? == false {
+            println!("cancelled uninstallation");
+            return Ok(());
+        }
+        if data.is_empty() == false
+            && prompt::prompt(&format!(
+                "Permanently remove all orbit data, including installed ip, in {}",
+                data.iter()
+                    .map(|d| format!("{:?}", d))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))? == false
+        {
+            println!("cancelled uninstallation");
+            return Ok(());
+        }
+
+        match cfg!(windows) {
+            true => Self::remove_from_user_path(&dir)?,
+            false => Self::remove_from_profiles(&dir)?,
+        }
+        for d in data {
+            std::fs::remove_dir_all(filesystem::to_extended(d))?;
+            logger::info(format!("removed {:?}", d));
+        }
+        Self::remove_exe(&exe)?;
+        logger::info(format!("uninstalled orbit from {:?}", exe));
+        Ok(())
+    }
+
+    fn remove_from_user_path(dir: &Path) -> Result<(), Fault> {
+        if let Some(value) = read_user_path()? {
+            if let Some(next) = remove_path_entry(&value, &dir.display().to_string()) {
+                write_user_path(&next)?;
+                logger::info(format!("removed {:?} from the user PATH", dir));
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_from_profiles(dir: &Path) -> Result<(), Fault> {
+        let home = match home::home_dir() {
+            Some(h) => h,
+            None => return Ok(()),
+        };
+        for profile in PROFILES.iter().map(|p| home.join(p)) {
+            let text = match std::fs::read_to_string(&profile) {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if let Some(next) = remove_profile_lines(&text, dir) {
+                std::fs::write(&profile, next)?;
+                logger::info(format!("removed {:?} from the PATH in {:?}", dir, profile));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the running executable at `exe`.
+    ///
+    /// Windows does not allow a running executable to be deleted, so a separate
+    /// process deletes it after orbit exits.
+    fn remove_exe(exe: &Path) -> Result<(), Fault> {
+        match cfg!(windows) {
+            true => {
+                Process::new("cmd")
+                    .args(["/C", "timeout", "/T", "2", "/NOBREAK", ">NUL", "&", "del", "/F", "/Q"])
+                    .arg(exe)
+                    .spawn()?;
+            }
+            false => std::fs::remove_file(exe)?,
+        }
+        Ok(())
+    }
+}
+
+/// Removes the lines that `orbit self install` added to a shell profile's
+/// `text` to place `dir` on the PATH.
+///
+/// Returns `None` if the profile does not contain the lines.
+fn remove_profile_lines(text: &str, dir: &Path) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut kept: Vec<&str> = Vec::new();
+    let mut changed = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let next = lines.get(i + 1).map(|l| l.trim()).unwrap_or("");
+        if lines[i].trim() == PROFILE_MARKER
+            && (next == profile_line("", dir) || next == profile_line("fish", dir))
+        {
+            // drop the blank line written before the marker
+            if kept.last().map(|l| l.trim().is_empty()) == Some(true) {
+                kept.pop();
+            }
+            changed = true;
+            i += 2;
+            continue;
+        }
+        kept.push(lines[i]);
+        i += 1;
+    }
+    match changed {
+        true => Some(kept.iter().map(|l| format!("{}\n", l)).collect()),
+        false => None,
+    }
+}
+
+/// Removes the `dir` from a `;`-separated windows PATH `value`.
+///
+/// Returns `None` if the `value` does not list the `dir`.
+fn remove_path_entry(value: &str, dir: &str) -> Option<String> {
+    let entries: Vec<&str> = value.split(';').collect();
+    let kept: Vec<&str> = entries
+        .iter()
+        .filter(|p| p.trim_end_matches('\\') != dir.trim_end_matches('\\'))
+        .map(|p| *p)
+        .collect();
+    match kept.len() == entries.len() {
+        true => None,
+        false => Some(kept.join(";")),
+    }
+}
+
+/// Selects the profile, relative to the home directory, that the `shell` reads
+/// when starting.
+fn find_profile(shell: &str, is_macos: bool) -> PathBuf {
+    match shell.rsplit('/').next().unwrap_or("") {
+        "zsh" => PathBuf::from(".zshrc"),
+        "bash" if is_macos == true => PathBuf::from(".bash_profile"),
+        "bash" => PathBuf::from(".bashrc"),
+        "fish" => PathBuf::from(".config/fish/config.fish"),
+        _ => PathBuf::from(".profile"),
+    }
+}
+
+/// Writes the line for the `shell`'s profile that places `dir` on the PATH.
+fn profile_line(shell: &str, dir: &Path) -> String {
+    match shell.rsplit('/').next().unwrap_or("") {
+        "fish" => format!("fish_add_path \"{}\"", dir.display()),
+        _ => format!("export PATH=\"{}:$PATH\"", dir.display()),
+    }
+}
+
+/// Reads the user's PATH from the windows registry, if it is set.
+fn read_user_path() -> Result<Option<String>, Fault> {
+    let output = Process::new("reg")
+        .args(["query", "HKCU\\Environment", "/v", "Path"])
+        .output()?;
+    match output.status.success() {
+        true => Ok(parse_reg_value(&String::from_utf8_lossy(&output.stdout))),
+        // the value does not exist yet
+        false => Ok(None),
+    }
+}
+
+/// Sets the user's PATH in the windows registry to `value`.
+fn write_user_path(value: &str) -> Result<(), Fault> {
+    let status = Process::new("reg")
+        .args([
+            "add",
+            "HKCU\\Environment",
+            "/v",
+            "Path",
+            "/t",
+            "REG_EXPAND_SZ",
+            "/d",
+            value,
+            "/f",
+        ])
+        .status()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(AnyError(String::from("failed to write the user PATH to the registry")))?,
+    }
+}
+
+/// Extracts the data from the output of `reg query`, which lists a value as
+/// `<name>    <type>    <data>`.
+fn parse_reg_value(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once("REG_")?;
+        let (_, data) = rest.split_once(char::is_whitespace)?;
+        Some(data.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shell_profiles() {
+        assert_eq!(find_profile("/bin/zsh", true), PathBuf::from(".zshrc"));
+        assert_eq!(find_profile("/usr/bin/bash", true), PathBuf::from(".bash_profile"));
+        assert_eq!(find_profile("/usr/bin/bash", false), PathBuf::from(".bashrc"));
+        assert_eq!(find_profile("", false), PathBuf::from(".profile"));
+        assert_eq!(
+            profile_line("/bin/bash", &PathBuf::from("/home/user/.local/bin")),
+            "export PATH=\"/home/user/.local/bin:$PATH\""
+        );
+        assert_eq!(
+            profile_line("/usr/bin/fish", &PathBuf::from("/home/user/.local/bin")),
+            "fish_add_path \"/home/user/.local/bin\""
+        );
+    }
+
+    #[test]
+    fn uninstall_profile() {
+        let dir = PathBuf::from("/home/user/.local/bin");
+        let text = format!(
+            "alias ll='ls -l'\n\n{}\n{}\n",
+            PROFILE_MARKER,
+            profile_line("/bin/bash", &dir)
+        );
+        assert_eq!(
+            remove_profile_lines(&text, &dir),
+            Some(String::from("alias ll='ls -l'\n"))
+        );
+        assert_eq!(remove_profile_lines("alias ll='ls -l'\n", &dir), None);
+        assert_eq!(
+            remove_path_entry("C:\\Tools;C:\\orbit\\bin\\;D:\\bin", "C:\\orbit\\bin"),
+            Some(String::from("C:\\Tools;D:\\bin"))
+        );
+        assert_eq!(remove_path_entry("C:\\Tools", "C:\\orbit\\bin"), None);
+    }
+
+    #[test]
+    fn registry_value() {
+        let output = "\r\nHKEY_CURRENT_USER\\Environment\r\n    Path    REG_EXPAND_SZ    C:\\Tools;%USERPROFILE%\\bin\r\n\r\n";
+        assert_eq!(
+            parse_reg_value(output),
+            Some(String::from("C:\\Tools;%USERPROFILE%\\bin"))
+        );
+        assert_eq!(parse_reg_value("ERROR: not found"), None);
+    }
+
+    #[test]
+    fn rollback_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let dest = root.join(EXE_NAME);
+        std::fs::write(&dest, "old").unwrap();
+        let src = root.join("new");
+        std::fs::write(&src, "new").unwrap();
+
+        let mut undos = Vec::new();
+        let cmd = SelfInstall {
+            path: None,
+            no_modify_path: true,
+        };
+        cmd.install(&src, &root.join("bin"), &root.join("bin").join(EXE_NAME), &mut undos)
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("bin").join(EXE_NAME)).unwrap(), "new");
+        // the staged copy is no longer tracked once moved into place
+        assert_eq!(undos.len(), 2);
+
+        // replacing an existing executable keeps a backup to restore
+        let mut replace = Vec::new();
+        cmd.install(&src, &root, &dest, &mut replace).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "new");
+
+        SelfInstall::rollback(replace);
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "old");
+        SelfInstall::rollback(undos);
+        assert_eq!(root.join("bin").exists(), false);
+    }
+}
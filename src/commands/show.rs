@@ -1,22 +1,32 @@
 use crate::core::catalog::Catalog;
+use crate::core::catalog::IpState;
 use crate::core::context::Context;
-use crate::core::ip::{Ip, PartialIpSpec};
-use crate::core::lang::vhdl::primaryunit::PrimaryUnit;
+use crate::core::ip::{Ip, Mapping, PartialIpSpec};
+use crate::core::iparchive::IpArchive;
 use crate::core::version;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::util::sha256;
 use crate::OrbitResult;
 use clif::arg::{Flag, Positional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
+use serde_derive::Serialize;
 use std::env::current_dir;
+use std::fs::File;
+use std::path::PathBuf;
 use crate::commands::helps::show;
 
 #[derive(Debug, PartialEq)]
 pub struct Show {
     tags: bool,
     units: bool,
+    files: bool,
+    dev: bool,
     ip: Option<PartialIpSpec>,
 }
 
@@ -26,12 +36,31 @@ impl FromCli for Show {
         let command = Ok(Show {
             tags: cli.check_flag(Flag::new("versions"))?,
             units: cli.check_flag(Flag::new("units"))?,
+            files: cli.check_flag(Flag::new("files"))?,
+            dev: cli.check_flag(Flag::new("dev"))?,
             ip: cli.check_positional(Positional::new("ip"))?,
         });
         command
     }
 }
 
+/// A primary design unit defined within the ip.
+#[derive(Debug, PartialEq, Serialize)]
+struct UnitEntry {
+    identifier: String,
+    #[serde(rename = "type")]
+    kind: String,
+    public: bool,
+}
+
+/// A file shipped with the ip along with its size in bytes and SHA-256 hash.
+#[derive(Debug, PartialEq, Serialize)]
+struct FileEntry {
+    file: String,
+    size: u64,
+    sha256: String,
+}
+
 impl Command<Context> for Show {
     type Status = OrbitResult;
 
@@ -39,7 +68,8 @@ impl Command<Context> for Show {
         // collect all manifests available (load catalog)
         let catalog = Catalog::new()
             .installations(c.get_cache_path())?
-            .downloads(c.get_downloads_path())?;
+            .downloads(c.get_downloads_path())?
+            .development(c.get_config().get_workspaces())?;
 
         let dev_ip: Option<Result<Ip, Fault>> = {
             match Context::find_ip_path(&current_dir().unwrap()) {
@@ -52,16 +82,16 @@ impl Command<Context> for Show {
         let ip: &Ip = if let Some(spec) = &self.ip {
             // find the path to the provided ip by searching through the catalog
             if let Some(lvl) = catalog.inner().get(spec.get_name()) {
-                // return the highest available version
-                if let Some(slot) = lvl.get_install(spec.get_version()) {
-                    slot
-                } else {
-                    // try to find from downloads
-                    if let Some(slot) = lvl.get_download(spec.get_version()) {
-                        slot
-                    } else {
-                        return Err(AnyError(format!("IP {} does not exist in the cache", spec)))?;
-                    }
+                // prefer the copy in development when requested
+                let dev = lvl.get_development(spec.get_version());
+                let found = match self.dev {
+                    true => dev.or_else(|| lvl.get(true, spec.get_version())),
+                    // return the highest available version, and then try the workspaces
+                    false => lvl.get(true, spec.get_version()).or(dev),
+                };
+                match found {
+                    Some(slot) => slot,
+                    None => return Err(AnyError(format!("IP {} does not exist in the cache", spec)))?,
                 }
             } else {
                 return Err(AnyError(format!("no ip found anywhere")))?;
@@ -80,17 +110,55 @@ impl Command<Context> for Show {
 
         // load the ip's manifest
         if self.units == true {
+            let units = match ip.get_mapping() {
+                // force computing the primary design units if a development version,
+                // otherwise use the units recorded at installation
+                Mapping::Physical => {
+                    let is_dev = self.ip.is_none()
+                        || catalog
+                            .inner()
+                            .get(ip.get_man().get_ip().get_name())
+                            .map(|lvl| lvl.get_state(ip) == IpState::Development)
+                            .unwrap_or(false);
+                    Ip::collect_units(is_dev, &ip.get_root())?
+                }
+                // read the units from the downloaded archive without extracting it
+                Mapping::Virtual(bytes) => IpArchive::read_units(bytes)?,
+            };
+            let mut units: Vec<UnitEntry> = units
+                .into_iter()
+                .map(|(_, unit)| UnitEntry {
+                    identifier: unit.get_iden().to_string(),
+                    kind: unit.to_string(),
+                    public: true,
+                })
+                .collect();
+            units.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+            match c.get_output_format() {
+                OutputFormat::Text => println!("{}", Self::format_units_table(&units)),
+                OutputFormat::Json => println!("{}", output::to_json("show", &units)?),
+            }
+
+            return Ok(());
+        }
+
+        // list every file shipped with the ip along with its hash
+        if self.files == true {
             if ip.get_mapping().is_physical() == true {
-                // force computing the primary design units if a development version
-                let units = Ip::collect_units(true, &ip.get_root())?;
-                println!(
-                    "{}",
-                    Self::format_units_table(units.into_iter().map(|(_, unit)| unit).collect())
-                );
+                let files = filesystem::gather_current_files(&ip.get_root(), true);
+                let files = Self::collect_files(&ip.get_root(), files)?;
+                match c.get_output_format() {
+                    OutputFormat::Text => println!("{}", Self::format_files_table(&files)),
+                    OutputFormat::Json => println!("{}", output::to_json("show", &files)?),
+                }
+            } else if c.get_output_format() == OutputFormat::Json {
+                return Err(AnyError(format!(
+                    "unable to display files from a downloaded IP; try again after installing"
+                )))?;
             } else {
                 println!(
                     "info: {}",
-                    "unable to display HDL units from a downloaded IP; try again after installing"
+                    "unable to display files from a downloaded IP; try again after installing"
                 );
             }
 
@@ -102,6 +170,18 @@ impl Command<Context> for Show {
             let specified_ver = self.ip.as_ref().unwrap().get_version().as_specific();
 
             return match catalog.get_possible_versions(ip.get_man().get_ip().get_name()) {
+                Some(vers) if c.get_output_format() == OutputFormat::Json => {
+                    let vers: Vec<String> = vers
+                        .iter()
+                        .filter(|p| {
+                            specified_ver.is_none()
+                                || version::is_compatible(specified_ver.unwrap(), &p) == true
+                        })
+                        .map(|v| v.to_string())
+                        .collect();
+                    println!("{}", output::to_json("show", &vers)?);
+                    Ok(())
+                }
                 Some(vers) => {
                     match vers.len() {
                         0 => {
@@ -127,8 +207,10 @@ impl Command<Context> for Show {
         }
 
         // print the manifest data "pretty"
-        let s = toml::to_string_pretty(ip.get_man())?;
-        println!("{}", s);
+        match c.get_output_format() {
+            OutputFormat::Text => println!("{}", toml::to_string_pretty(ip.get_man())?),
+            OutputFormat::Json => println!("{}", output::to_json("show", ip.get_man())?),
+        }
         Ok(())
     }
 }
@@ -139,7 +221,7 @@ impl Show {
     }
 
     /// Creates a string for to display the primary design units for the particular ip.
-    fn format_units_table(table: Vec<PrimaryUnit>) -> String {
+    fn format_units_table(table: &[UnitEntry]) -> String {
         let header = format!(
             "\
 {:<36}{:<14}{:<9}
@@ -147,15 +229,52 @@ impl Show {
             "Identifier", "Type", "Public", " "
         );
         let mut body = String::new();
-
-        let mut table = table;
-        table.sort_by(|a, b| a.get_iden().cmp(b.get_iden()));
         for unit in table {
             body.push_str(&format!(
                 "{:<36}{:<14}{:<2}\n",
-                unit.get_iden().to_string(),
-                unit.to_string(),
-                "y"
+                unit.identifier,
+                unit.kind,
+                match unit.public {
+                    true => "y",
+                    false => "n",
+                }
+            ));
+        }
+        header + &body
+    }
+
+    /// Collects the files within the ip at `root` with their sizes in bytes and
+    /// SHA-256 hashes.
+    fn collect_files(root: &PathBuf, files: Vec<String>) -> Result<Vec<FileEntry>, Fault> {
+        let mut entries = Vec::new();
+        for file in files {
+            let path = root.join(&file);
+            entries.push(FileEntry {
+                size: std::fs::metadata(&path)?.len(),
+                sha256: sha256::compute_sha256_reader(File::open(&path)?)?.to_string(),
+                file: file,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Creates a string to display the files within the ip.
+    fn format_files_table(files: &[FileEntry]) -> String {
+        let width = files.iter().map(|f| f.file.len()).max().unwrap_or(0).max(36) + 2;
+        let header = format!(
+            "\
+{:<width$}{:>12}  {:<64}
+{:->width$}{3:->12}  {3:->64}\n",
+            "File", "Size", "SHA-256", " ", width = width
+        );
+        let mut body = String::new();
+        for entry in files {
+            body.push_str(&format!(
+                "{:<width$}{:>12}  {}\n",
+                entry.file,
+                entry.size,
+                entry.sha256,
+                width = width
             ));
         }
         header + &body
@@ -0,0 +1,377 @@
+//! Summarizes the disk usage of orbit's home directory to guide cleanup
+//! decisions.
+
+use crate::commands::helps::stats;
+use crate::core::catalog::Catalog;
+use crate::core::catalog::DownloadSlot;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lockfile::LockEntry;
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use crate::util::filesystem::{Standardize, Unit};
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The number of largest items to list.
+const LARGEST_COUNT: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    home: bool,
+}
+
+impl FromCli for Stats {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(stats::HELP).ref_usage(2..4))?;
+        let command = Ok(Stats {
+            home: cli.check_flag(Flag::new("home"))?,
+        });
+        command
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Clone)]
+struct Item {
+    name: String,
+    version: String,
+    /// Where the item is stored: `cache` or `downloads`.
+    kind: &'static str,
+    /// The size in bytes.
+    size: u64,
+    path: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct IpUsage {
+    name: String,
+    slots: usize,
+    archives: usize,
+    /// The combined size in bytes of the ip's slots and archives.
+    size: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Report {
+    home: String,
+    cache_slots: usize,
+    /// The combined size in bytes of every cache slot.
+    cache_size: u64,
+    download_archives: usize,
+    /// The combined size in bytes of every downloaded archive.
+    downloads_size: u64,
+    ips: Vec<IpUsage>,
+    /// Cache slots without an archive that no lock file references.
+    orphans: Vec<Item>,
+    largest: Vec<Item>,
+}
+
+impl Command<Context> for Stats {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        if self.home == false {
+            return Err(AnyError(format!(
+                "Missing a location to report on\n\nTry `orbit stats --home` to summarize orbit's home directory"
+            )))?;
+        }
+
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .downloads(c.get_downloads_path())?;
+
+        // the lock file of the current ip also keeps its dependencies in use
+        let current = match c.get_ip_path() {
+            Some(path) => Ip::load(path.clone()).ok(),
+            None => None,
+        };
+
+        let report = Self::report(c, &catalog, current.as_ref())?;
+        match c.get_output_format() {
+            OutputFormat::Text => print!("{}", fmt_report(&report)),
+            OutputFormat::Json => println!("{}", output::to_json("stats", &report)?),
+        }
+        Ok(())
+    }
+}
+
+impl Stats {
+    /// Collects the usage of every cache slot and downloaded archive in the
+    /// `catalog`.
+    fn report(c: &Context, catalog: &Catalog, current: Option<&Ip>) -> Result<Report, Fault> {
+        let installs: Vec<&Ip> = catalog
+            .inner()
+            .values()
+            .flat_map(|lvl| lvl.get_installations())
+            .collect();
+
+        let orphans = Self::find_orphans(&installs, current, c.get_downloads_path());
+
+        let mut slots = Vec::new();
+        let mut orphaned = Vec::new();
+        for ip in &installs {
+            let item = Self::measure(ip, ip.get_root().clone(), "cache")?;
+            if orphans.iter().any(|o| std::ptr::eq(*o, *ip)) == true {
+                orphaned.push(item.clone());
+            }
+            slots.push(item);
+        }
+
+        let mut archives = Vec::new();
+        for ip in catalog.inner().values().flat_map(|lvl| lvl.get_downloads()) {
+            let path = c.get_downloads_path().join(Self::to_archive(ip).as_ref());
+            archives.push(Self::measure(ip, path, "downloads")?);
+        }
+
+        Ok(Self::summarize(
+            filesystem::into_std_str(c.get_home_path().clone()),
+            slots,
+            archives,
+            orphaned,
+        ))
+    }
+
+    /// Finds the `installs` without an archive in the `downloads` that no lock
+    /// file of another installation or the `current` ip references.
+    fn find_orphans<'b>(installs: &[&'b Ip], current: Option<&Ip>, downloads: &PathBuf) -> Vec<&'b Ip> {
+        // every installation referenced by a lock file, skipping each lock file's own ip
+        let referenced: Vec<&LockEntry> = installs
+            .iter()
+            .map(|ip| *ip)
+            .chain(current)
+            .flat_map(|ip| {
+                let target = LockEntry::from((ip, true));
+                ip.get_lock()
+                    .inner()
+                    .iter()
+                    .filter(move |e| e.matches_target(&target) == false)
+            })
+            .collect();
+
+        installs
+            .iter()
+            .filter(|ip| {
+                let has_archive = downloads.join(Self::to_archive(ip).as_ref()).exists();
+                let is_locked = referenced.iter().any(|e| {
+                    e.get_name() == ip.get_man().get_ip().get_name()
+                        && e.get_version() == ip.get_man().get_ip().get_version()
+                        && e.get_uuid() == ip.get_uuid()
+                });
+                has_archive == false && is_locked == false
+            })
+            .map(|ip| *ip)
+            .collect()
+    }
+
+    /// Determines the name of the archive for the `ip` in the downloads.
+    fn to_archive(ip: &Ip) -> DownloadSlot {
+        DownloadSlot::new(
+            ip.get_man().get_ip().get_name(),
+            ip.get_man().get_ip().get_version(),
+            ip.get_uuid(),
+        )
+    }
+
+    /// Computes the size of the `ip` stored at `path`.
+    fn measure(ip: &Ip, path: PathBuf, kind: &'static str) -> Result<Item, Fault> {
+        Ok(Item {
+            name: ip.get_man().get_ip().get_name().to_string(),
+            version: ip.get_man().get_ip().get_version().to_string(),
+            kind: kind,
+            size: filesystem::compute_size(&path, Unit::Bytes)? as u64,
+            path: filesystem::into_std_str(PathBuf::standardize(path)),
+        })
+    }
+
+    /// Totals the `slots` and `archives` by ip and finds the largest items.
+    fn summarize(
+        home: String,
+        slots: Vec<Item>,
+        archives: Vec<Item>,
+        mut orphans: Vec<Item>,
+    ) -> Report {
+        let mut ips: BTreeMap<String, IpUsage> = BTreeMap::new();
+        for item in slots.iter().chain(archives.iter()) {
+            let usage = ips.entry(item.name.clone()).or_insert(IpUsage {
+                name: item.name.clone(),
+                slots: 0,
+                archives: 0,
+                size: 0,
+            });
+            match item.kind {
+                "cache" => usage.slots += 1,
+                _ => usage.archives += 1,
+            }
+            usage.size += item.size;
+        }
+        let mut ips: Vec<IpUsage> = ips.into_values().collect();
+        ips.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+
+        let mut largest: Vec<Item> = slots.iter().chain(archives.iter()).cloned().collect();
+        largest.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+        largest.truncate(LARGEST_COUNT);
+
+        orphans.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+
+        Report {
+            home: home,
+            cache_slots: slots.len(),
+            cache_size: slots.iter().map(|i| i.size).sum(),
+            download_archives: archives.len(),
+            downloads_size: archives.iter().map(|i| i.size).sum(),
+            ips: ips,
+            orphans: orphans,
+            largest: largest,
+        }
+    }
+}
+
+/// Formats a number of `bytes` in megabytes.
+fn fmt_size(bytes: u64) -> String {
+    format!("{:.2} MB", bytes as f32 / 1000000.0)
+}
+
+fn fmt_report(report: &Report) -> String {
+    let mut text = format!("{:<12}{}\n", "Home:", report.home);
+    text.push_str(&format!(
+        "{:<12}{} slots, {}\n",
+        "Cache:",
+        report.cache_slots,
+        fmt_size(report.cache_size)
+    ));
+    text.push_str(&format!(
+        "{:<12}{} archives, {}\n",
+        "Downloads:",
+        report.download_archives,
+        fmt_size(report.downloads_size)
+    ));
+
+    if report.ips.is_empty() == false {
+        text.push_str("\nSize by ip:\n");
+        for ip in &report.ips {
+            text.push_str(&format!(
+                "    {:<24}{:>3} slots {:>3} archives {:>12}\n",
+                ip.name,
+                ip.slots,
+                ip.archives,
+                fmt_size(ip.size)
+            ));
+        }
+    }
+
+    let list = |title: &str, items: &Vec<Item>| -> String {
+        let mut text = format!("\n{}:\n", title);
+        for item in items {
+            text.push_str(&format!(
+                "    {:<24}{:<10}{:>12}  {}\n",
+                format!("{}:{}", item.name, item.version),
+                item.kind,
+                fmt_size(item.size),
+                item.path
+            ));
+        }
+        text
+    };
+    if report.largest.is_empty() == false {
+        text.push_str(&list("Largest items", &report.largest));
+    }
+    if report.orphans.is_empty() == false {
+        text.push_str(&list("Orphaned slots", &report.orphans));
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(name: &str, version: &str, kind: &'static str, size: u64) -> Item {
+        Item {
+            name: name.to_string(),
+            version: version.to_string(),
+            kind: kind,
+            size: size,
+            path: format!("/home/{}/{}-{}", kind, name, version),
+        }
+    }
+
+    #[test]
+    fn summarize_by_ip() {
+        let slots = vec![
+            item("gates", "1.0.0", "cache", 2000000),
+            item("gates", "1.1.0", "cache", 3000000),
+            item("alu", "0.1.0", "cache", 500000),
+        ];
+        let archives = vec![item("gates", "1.0.0", "downloads", 1000000)];
+        let orphans = vec![slots[2].clone()];
+        let report = Stats::summarize(String::from("/home"), slots, archives, orphans);
+
+        assert_eq!(report.cache_slots, 3);
+        assert_eq!(report.cache_size, 5500000);
+        assert_eq!(report.downloads_size, 1000000);
+        assert_eq!(
+            report.ips,
+            vec![
+                IpUsage {
+                    name: String::from("gates"),
+                    slots: 2,
+                    archives: 1,
+                    size: 6000000
+                },
+                IpUsage {
+                    name: String::from("alu"),
+                    slots: 1,
+                    archives: 0,
+                    size: 500000
+                },
+            ]
+        );
+        assert_eq!(report.largest[0].version, "1.1.0");
+        assert_eq!(report.largest.len(), 4);
+
+        let text = fmt_report(&report);
+        assert_eq!(
+            text.lines().take(3).collect::<Vec<&str>>(),
+            vec![
+                "Home:       /home",
+                "Cache:      3 slots, 5.50 MB",
+                "Downloads:  1 archives, 1.00 MB"
+            ]
+        );
+        assert_eq!(text.contains("\nOrphaned slots:\n    alu:0.1.0"), true);
+    }
+
+    #[test]
+    fn orphans_exclude_own_lock_entry() {
+        let a = Ip::load(PathBuf::from("./tests/env/projects/ip-a")).unwrap();
+        let b = Ip::load(PathBuf::from("./tests/env/projects/ip-b")).unwrap();
+        let c = Ip::load(PathBuf::from("./tests/env/projects/ip-c")).unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+        let downloads = downloads.path().to_path_buf();
+        let installs = vec![&a, &b];
+
+        // ip-b is locked by ip-a, but nothing else locks ip-a
+        assert_eq!(Stats::find_orphans(&installs, None, &downloads), vec![&a]);
+        // the current ip locks ip-a
+        assert_eq!(
+            Stats::find_orphans(&installs, Some(&c), &downloads),
+            Vec::<&Ip>::new()
+        );
+        // an archive can reinstall ip-a
+        std::fs::write(downloads.join(Stats::to_archive(&a).as_ref()), "").unwrap();
+        assert_eq!(
+            Stats::find_orphans(&installs, None, &downloads),
+            Vec::<&Ip>::new()
+        );
+    }
+}
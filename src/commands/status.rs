@@ -0,0 +1,248 @@
+use crate::commands::hash::latest_release;
+use crate::commands::helps::status;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lang::vhdl::primaryunit::PrimaryUnitStore;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use colored::Colorize;
+use serde_derive::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub struct Status {}
+
+impl FromCli for Status {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(status::HELP).ref_usage(2..4))?;
+        let command = Ok(Status {});
+        command
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Change {
+    fn as_symbol(&self) -> colored::ColoredString {
+        match self {
+            Self::Added => "+".green(),
+            Self::Removed => "-".red(),
+            Self::Modified => "~".yellow(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct UnitChange {
+    name: String,
+    change: Change,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum LockState {
+    Missing,
+    Outdated,
+    UpToDate,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Report {
+    name: String,
+    version: String,
+    /// The latest version of the ip found in the cache.
+    released: Option<String>,
+    /// Whether the files differ from the released version's checksum.
+    modified: Option<bool>,
+    lock: LockState,
+    units: Vec<UnitChange>,
+}
+
+impl Command<Context> for Status {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+        let catalog = Catalog::new().installations(c.get_cache_path())?;
+
+        // find the latest installation of this same ip
+        let released = latest_release(&ip, &catalog);
+
+        let current_units = Ip::collect_units(true, ip.get_root())?;
+        let (modified, units) = match released {
+            Some(rel) => {
+                let released_units = Ip::collect_units(true, rel.get_root())?;
+                (
+                    Some(ip.is_modified_since(rel)),
+                    diff_units(&read_sources(&current_units), &read_sources(&released_units)),
+                )
+            }
+            None => (None, Vec::new()),
+        };
+
+        let report = Report {
+            name: ip.get_man().get_ip().get_name().to_string(),
+            version: ip.get_man().get_ip().get_version().to_string(),
+            released: released.map(|r| r.get_man().get_ip().get_version().to_string()),
+            modified,
+            lock: match (ip.lock_exists(), ip.can_use_lock()) {
+                (false, _) => LockState::Missing,
+                (true, false) => LockState::Outdated,
+                (true, true) => LockState::UpToDate,
+            },
+            units,
+        };
+
+        match c.get_output_format() {
+            OutputFormat::Text => print!("{}", fmt_report(&report)),
+            OutputFormat::Json => println!("{}", output::to_json("status", &report)?),
+        }
+        Ok(())
+    }
+}
+
+/// Maps each unit's name to the contents of the file that defines it.
+fn read_sources(units: &PrimaryUnitStore) -> HashMap<String, String> {
+    units
+        .iter()
+        .map(|(name, unit)| {
+            (
+                format!("{} {}", unit, name),
+                std::fs::read_to_string(unit.get_unit().get_source_code_file())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// Compares the `current` units against the `released` units, where each unit's
+/// name maps to its source code.
+fn diff_units(
+    current: &HashMap<String, String>,
+    released: &HashMap<String, String>,
+) -> Vec<UnitChange> {
+    let mut changes: Vec<UnitChange> = current
+        .iter()
+        .filter_map(|(name, src)| {
+            let change = match released.get(name) {
+                None => Change::Added,
+                Some(prev) if prev != src => Change::Modified,
+                Some(_) => return None,
+            };
+            Some(UnitChange {
+                name: name.clone(),
+                change,
+            })
+        })
+        .chain(
+            released
+                .keys()
+                .filter(|name| current.contains_key(*name) == false)
+                .map(|name| UnitChange {
+                    name: name.clone(),
+                    change: Change::Removed,
+                }),
+        )
+        .collect();
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
+
+fn fmt_report(report: &Report) -> String {
+    let mut text = format!("{:<12}{} {}\n", "Ip:", report.name, report.version);
+    text.push_str(&format!(
+        "{:<12}{}\n",
+        "Released:",
+        match &report.released {
+            Some(v) if v == &report.version => format!("{} (same as manifest)", v),
+            Some(v) => v.clone(),
+            None => String::from("none installed"),
+        }
+    ));
+    if let Some(modified) = report.modified {
+        text.push_str(&format!(
+            "{:<12}{}\n",
+            "Files:",
+            match modified {
+                true => "modified since release".yellow().to_string(),
+                false => "unchanged since release".to_string(),
+            }
+        ));
+    }
+    text.push_str(&format!(
+        "{:<12}{}\n",
+        "Lock file:",
+        match report.lock {
+            LockState::Missing => "missing; try `orbit plan --lock-only`".red().to_string(),
+            LockState::Outdated => "out of date with the manifest; try `orbit plan --lock-only`"
+                .yellow()
+                .to_string(),
+            LockState::UpToDate => String::from("up to date"),
+        }
+    ));
+    if report.units.is_empty() == false {
+        text.push_str("Units:\n");
+        for unit in &report.units {
+            text.push_str(&format!("    {} {}\n", unit.change.as_symbol(), unit.name));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn units(list: &[(&str, &str)]) -> HashMap<String, String> {
+        list.iter()
+            .map(|(n, s)| (n.to_string(), s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn unit_changes() {
+        let current = units(&[
+            ("entity and_gate", "a"),
+            ("entity nand_gate", "b"),
+            ("package gates_pkg", "c2"),
+        ]);
+        let released = units(&[
+            ("entity and_gate", "a"),
+            ("entity or_gate", "d"),
+            ("package gates_pkg", "c"),
+        ]);
+        assert_eq!(
+            diff_units(&current, &released),
+            vec![
+                UnitChange {
+                    name: String::from("entity nand_gate"),
+                    change: Change::Added
+                },
+                UnitChange {
+                    name: String::from("entity or_gate"),
+                    change: Change::Removed
+                },
+                UnitChange {
+                    name: String::from("package gates_pkg"),
+                    change: Change::Modified
+                },
+            ]
+        );
+        assert_eq!(diff_units(&current, &current), vec![]);
+    }
+}
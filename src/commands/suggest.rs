@@ -0,0 +1,157 @@
+//! Detects misspelled subcommands and options on the command-line to suggest
+//! the closest known word.
+
+use crate::commands::helps;
+use crate::commands::orbit::SUBCOMMANDS;
+use crate::util::anyerror::AnyError;
+use crate::util::seqalin;
+
+/// The edit distance a word must be below to be suggested.
+pub const THRESHOLD: usize = 3;
+
+/// The global options that take a value.
+const GLOBAL_VALUE_OPTIONS: [&str; 5] = [
+    "--color",
+    "--format",
+    "--profile",
+    "--progress",
+    "--trace",
+];
+
+/// Returns the help text listing the options for the subcommand `name`.
+fn get_help(name: &str) -> Option<&'static str> {
+    match name {
+        "new" => Some(helps::new::HELP),
+        "init" => Some(helps::init::HELP),
+        "show" => Some(helps::show::HELP),
+        "read" => Some(helps::read::HELP),
+        "get" => Some(helps::get::HELP),
+        "tree" => Some(helps::tree::HELP),
+        "p" | "plan" => Some(helps::plan::HELP),
+        "b" | "build" => Some(helps::build::HELP),
+        "launch" => Some(helps::launch::HELP),
+        "search" => Some(helps::search::HELP),
+        "download" => Some(helps::download::HELP),
+        "install" => Some(helps::install::HELP),
+        "env" => Some(helps::env::HELP),
+        "config" => Some(helps::config::HELP),
+        "explain" => Some(helps::explain::HELP),
+        "clone" => Some(helps::clone::HELP),
+        "fetch" => Some(helps::fetch::HELP),
+        "vendor" => Some(helps::vendor::HELP),
+        "status" => Some(helps::status::HELP),
+        "self" => Some(helps::selfcmd::HELP),
+        "lsp" => Some(helps::lsp::HELP),
+        "ci" => Some(helps::ci::HELP),
+        "test" => Some(helps::test::HELP),
+        "graph" => Some(helps::graph::HELP),
+        "hash" => Some(helps::hash::HELP),
+        "stats" => Some(helps::stats::HELP),
+        "mv" => Some(helps::mv::HELP),
+        _ => None,
+    }
+}
+
+/// Collects the names of the long options (`--<name>`) listed in a `help` text.
+fn options_from_help(help: &str) -> Vec<&str> {
+    help.lines()
+        .map(|line| line.trim_start())
+        .filter(|line| line.starts_with("--") == true)
+        .filter_map(|line| line.split(|c: char| c.is_whitespace() || c == ',').next())
+        .collect()
+}
+
+/// Checks the option `arg` against the `known` options for a misspelling.
+fn check_option(arg: &str, known: &[&str]) -> Result<(), AnyError> {
+    let name = arg.split_once('=').map(|(n, _)| n).unwrap_or(arg);
+    if known.contains(&name) == true {
+        return Ok(());
+    }
+    match seqalin::sel_min_edit_str(name, known, THRESHOLD) {
+        Some(word) => Err(AnyError(format!(
+            "Unknown option '{}'\n\nDid you mean '{}'?",
+            name, word
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Scans the command-line `args` for an unknown subcommand or option that closely
+/// matches a known one.
+///
+/// Unknown words without a close match are left for the command-line parser to report.
+pub fn check(args: &[String]) -> Result<(), AnyError> {
+    let globals = options_from_help(helps::orbit::HELP);
+    let mut iter = args.iter().skip(1);
+    // check the global options until the subcommand is found
+    let command = loop {
+        let arg = match iter.next() {
+            Some(a) => a,
+            None => return Ok(()),
+        };
+        if arg.starts_with("--") == true {
+            check_option(arg, &globals)?;
+            if GLOBAL_VALUE_OPTIONS.contains(&arg.as_str()) == true {
+                iter.next();
+            }
+        } else if arg.starts_with('-') == false {
+            break arg;
+        }
+    };
+    if SUBCOMMANDS.contains(&command.as_str()) == false {
+        return match seqalin::sel_min_edit_str(command, &SUBCOMMANDS, THRESHOLD) {
+            Some(word) => Err(AnyError(format!(
+                "Unknown subcommand '{}'\n\nDid you mean `orbit {}`?",
+                command, word
+            ))),
+            None => Ok(()),
+        };
+    }
+    // check the subcommand's options up until the argument terminator
+    if let Some(help) = get_help(command) {
+        let mut known = options_from_help(help);
+        known.push("--help");
+        for arg in iter.take_while(|a| a.as_str() != "--") {
+            if arg.starts_with("--") == true {
+                check_option(arg, &known)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn help_options() {
+        let help = "Options:\n    --top <unit>      a\n    --help, -h    b\n    args    c\n";
+        assert_eq!(options_from_help(help), vec!["--top", "--help"]);
+    }
+
+    #[test]
+    fn suggest_subcommand() {
+        let err = check(&to_args(&["orbit", "--color", "never", "plna"])).unwrap_err();
+        assert_eq!(err.0, "Unknown subcommand 'plna'\n\nDid you mean `orbit plan`?");
+        // no close match is left for the parser
+        assert!(check(&to_args(&["orbit", "zzzzzzzz"])).is_ok());
+        // the values of global options are never mistaken for the subcommand
+        assert!(check(&to_args(&["orbit", "--progress", "text", "plan"])).is_ok());
+    }
+
+    #[test]
+    fn suggest_option() {
+        let err = check(&to_args(&["orbit", "plan", "--fielset", "A=*.txt"])).unwrap_err();
+        assert_eq!(err.0, "Unknown option '--fielset'\n\nDid you mean '--fileset'?");
+        let err = check(&to_args(&["orbit", "--profle=ci", "plan"])).unwrap_err();
+        assert_eq!(err.0, "Unknown option '--profle'\n\nDid you mean '--profile'?");
+        // known options and arguments after the terminator are accepted
+        assert!(check(&to_args(&["orbit", "plan", "--fileset", "A=*.txt", "--all"])).is_ok());
+        assert!(check(&to_args(&["orbit", "build", "--", "--fielset"])).is_ok());
+    }
+}
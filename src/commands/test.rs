@@ -0,0 +1,430 @@
+//! Runs each testbench of the current ip through a plugin and reports which
+//! ones passed.
+
+use crate::commands::build::Build;
+use crate::commands::helps;
+use crate::commands::plan::Plan;
+use crate::core::context;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lang::vhdl::primaryunit::PrimaryUnit;
+use crate::core::lang::vhdl::token::Identifier;
+use crate::core::plugin::PluginError;
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use crate::util::logger;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::OrbitResult;
+use clif::arg::{Flag, Optional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use colored::Colorize;
+use serde_derive::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Instant;
+
+#[derive(Debug, PartialEq)]
+pub struct Test {
+    plugin: Option<String>,
+    benches: Vec<Identifier>,
+    build_dir: Option<String>,
+    list: bool,
+    fail_fast: bool,
+    args: Vec<String>,
+}
+
+impl FromCli for Test {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(
+            clif::Help::new()
+                .quick_text(helps::test::HELP)
+                .ref_usage(2..4),
+        )?;
+        let command = Ok(Test {
+            // Flags
+            list: cli.check_flag(Flag::new("list"))?,
+            fail_fast: cli.check_flag(Flag::new("fail-fast"))?,
+            // Options
+            plugin: cli.check_option(Optional::new("plugin").value("name"))?,
+            benches: cli
+                .check_option_all(Optional::new("bench").value("tb"))?
+                .unwrap_or(Vec::new()),
+            build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
+            // Remaining args
+            args: cli.check_remainder()?,
+        });
+        command
+    }
+}
+
+/// A design unit to simulate as a testbench.
+#[derive(Debug, PartialEq)]
+struct Testbench {
+    name: Identifier,
+    /// Units with ports are planned as the top rather than the bench, such as
+    /// the top-level of a cocotb testbench.
+    has_ports: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+struct TestResult {
+    bench: String,
+    outcome: Outcome,
+    /// The number of seconds spent planning and building the testbench.
+    seconds: Option<f64>,
+    error: Option<String>,
+}
+
+impl Command<Context> for Test {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let ip = Ip::load(c.get_ip_path().unwrap().clone())?;
+        let units = Ip::collect_units(true, ip.get_root())?;
+        let mut benches = discover_benches(&units, ip.get_man().get_ip().get_testbenches())?;
+
+        // run only the requested testbenches
+        if self.benches.is_empty() == false {
+            for name in &self.benches {
+                if benches.iter().find(|b| &b.name == name).is_none() {
+                    return Err(AnyError(format!(
+                        "Unit '{}' is not a testbench of the current ip",
+                        name
+                    )))?;
+                }
+            }
+            benches.retain(|b| self.benches.contains(&b.name));
+        }
+
+        // display testbench list and exit
+        if self.list == true {
+            for bench in &benches {
+                println!("{}", bench.name);
+            }
+            return Ok(());
+        }
+
+        if benches.is_empty() == true {
+            logger::warn("no testbenches were found in the current ip");
+            return Ok(());
+        }
+
+        // locate the plugin, falling back to the configured default plugin
+        let plugin = match self
+            .plugin
+            .as_ref()
+            .map(|p| p.as_str())
+            .or(c.get_config().get_default_plugin())
+        {
+            Some(name) => match c.get_config().find_plugin(name) {
+                Some(p) => p.get_alias().to_string(),
+                None => return Err(PluginError::Missing(name.to_string()))?,
+            },
+            None => {
+                return Err(AnyError(format!("Testing requires a plugin to simulate each testbench\n\nTry `orbit test --plugin <name>` or set a default plugin in the configuration")))?
+            }
+        };
+
+        // determine the build directory based on cli priority
+        let b_dir = context::resolve_build_dir(
+            self.build_dir.as_ref().unwrap_or(&c.get_build_dir()),
+            Some(plugin.as_str()),
+        );
+
+        let mut results = Vec::with_capacity(benches.len());
+        let mut stop = false;
+        for bench in &benches {
+            if stop == true {
+                results.push(TestResult {
+                    bench: bench.name.to_string(),
+                    outcome: Outcome::Skipped,
+                    seconds: None,
+                    error: None,
+                });
+                continue;
+            }
+            logger::info(format!("testing {}", bench.name));
+            // give each testbench its own blueprint
+            let dir = format!("{}/{}", b_dir, bench.name);
+            let start = Instant::now();
+            let result = self.run_bench(c, &plugin, bench, &dir);
+            let seconds = Some(start.elapsed().as_secs_f64());
+            results.push(match result {
+                Ok(()) => TestResult {
+                    bench: bench.name.to_string(),
+                    outcome: Outcome::Passed,
+                    seconds,
+                    error: None,
+                },
+                Err(e) => {
+                    logger::warn(format!("testbench {} failed: {}", bench.name, e));
+                    stop = self.fail_fast;
+                    TestResult {
+                        bench: bench.name.to_string(),
+                        outcome: Outcome::Failed,
+                        seconds,
+                        error: Some(e.to_string()),
+                    }
+                }
+            });
+        }
+
+        match c.get_output_format() {
+            OutputFormat::Text => print!("{}", fmt_table(&results)),
+            OutputFormat::Json => println!("{}", output::to_json("test", &results)?),
+        }
+
+        let failed = results
+            .iter()
+            .filter(|r| r.outcome == Outcome::Failed)
+            .count();
+        match failed {
+            0 => Ok(()),
+            _ => Err(AnyError(format!(
+                "{} of {} testbenches failed",
+                failed,
+                results.len()
+            )))?,
+        }
+    }
+}
+
+impl Test {
+    /// Plans the `bench` into the build directory `dir` and then builds it with
+    /// the `plugin`.
+    fn run_bench(
+        &self,
+        c: &Context,
+        plugin: &str,
+        bench: &Testbench,
+        dir: &str,
+    ) -> Result<(), Fault> {
+        let (tb, top) = match bench.has_ports {
+            false => (Some(bench.name.clone()), None),
+            true => (None, Some(bench.name.clone())),
+        };
+        // the summary is the only report written for the testbenches
+        Plan::with_unit(Some(plugin.to_string()), tb, top, dir.to_string())
+            .execute(c, OutputFormat::Text)?;
+        Build::with_plugin(plugin.to_string(), dir.to_string(), self.args.clone()).exec(c)
+    }
+}
+
+/// Determines the testbenches among the ip's primary design `units`.
+///
+/// The units `tagged` in the manifest are used when there are any, otherwise
+/// every entity without ports is a testbench. The testbenches are sorted by name.
+fn discover_benches(
+    units: &HashMap<Identifier, PrimaryUnit>,
+    tagged: &[String],
+) -> Result<Vec<Testbench>, Fault> {
+    let mut benches = Vec::new();
+    match tagged.is_empty() {
+        true => {
+            for (name, unit) in units {
+                if let PrimaryUnit::Entity(u) = unit {
+                    if let Some(e) = u.get_symbol().and_then(|s| s.as_entity()) {
+                        if e.is_testbench() == true {
+                            benches.push(Testbench {
+                                name: name.clone(),
+                                has_ports: false,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        false => {
+            for tag in tagged {
+                let name = Identifier::from_str(tag)?;
+                match units.get(&name) {
+                    Some(PrimaryUnit::Entity(u)) => benches.push(Testbench {
+                        has_ports: u
+                            .get_symbol()
+                            .and_then(|s| s.as_entity())
+                            .map(|e| e.is_testbench() == false)
+                            .unwrap_or(false),
+                        name,
+                    }),
+                    Some(_) => {
+                        return Err(AnyError(format!(
+                            "testbench '{}' in the manifest is not an entity",
+                            tag
+                        )))?
+                    }
+                    None => {
+                        return Err(AnyError(format!(
+                            "testbench '{}' in the manifest is not a unit of the current ip",
+                            tag
+                        )))?
+                    }
+                }
+            }
+        }
+    }
+    benches.sort_by(|a, b| a.name.to_string().cmp(&b.name.to_string()));
+    Ok(benches)
+}
+
+/// Creates a table of each testbench's outcome followed by a summary line.
+fn fmt_table(results: &[TestResult]) -> String {
+    let header = format!(
+        "\
+{:<28}{:<10}{:<9}
+{3:->28}{3:->10}{3:->10}\n",
+        "Testbench", "Result", "Time", " "
+    );
+    let mut body = String::new();
+    for result in results {
+        let outcome = format!(
+            "{:<10}",
+            match result.outcome {
+                Outcome::Passed => "pass",
+                Outcome::Failed => "fail",
+                Outcome::Skipped => "skip",
+            }
+        );
+        body.push_str(&format!(
+            "{:<28}{}{}\n",
+            result.bench,
+            match result.outcome {
+                Outcome::Passed => outcome.green(),
+                Outcome::Failed => outcome.red(),
+                Outcome::Skipped => outcome.yellow(),
+            },
+            match result.seconds {
+                Some(s) => format!("{:.2}s", s),
+                None => String::from("-"),
+            }
+        ));
+    }
+    let count = |o: Outcome| results.iter().filter(|r| r.outcome == o).count();
+    body.push_str(&format!(
+        "\n{} passed; {} failed; {} skipped\n",
+        count(Outcome::Passed),
+        count(Outcome::Failed),
+        count(Outcome::Skipped)
+    ));
+    header + &body
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::lang::vhdl::primaryunit;
+
+    const DESIGN: &str = "\
+entity and_gate is
+    port (a, b : in bit; y : out bit);
+end entity;
+
+entity and_gate_tb is
+end entity;
+
+entity zero_tb is
+end entity;
+
+package gates_pkg is
+end package;
+";
+
+    fn units() -> HashMap<Identifier, PrimaryUnit> {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("gates.vhd");
+        std::fs::write(&file, DESIGN).unwrap();
+        primaryunit::collect_units(&vec![file.display().to_string()]).unwrap()
+    }
+
+    #[test]
+    fn discover_entities_without_ports() {
+        let benches = discover_benches(&units(), &Vec::new()).unwrap();
+        assert_eq!(
+            benches,
+            vec![
+                Testbench {
+                    name: Identifier::from_str("and_gate_tb").unwrap(),
+                    has_ports: false,
+                },
+                Testbench {
+                    name: Identifier::from_str("zero_tb").unwrap(),
+                    has_ports: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_tagged_units() {
+        let units = units();
+        let benches = discover_benches(
+            &units,
+            &vec![String::from("and_gate"), String::from("zero_tb")],
+        )
+        .unwrap();
+        assert_eq!(
+            benches,
+            vec![
+                Testbench {
+                    name: Identifier::from_str("and_gate").unwrap(),
+                    has_ports: true,
+                },
+                Testbench {
+                    name: Identifier::from_str("zero_tb").unwrap(),
+                    has_ports: false,
+                },
+            ]
+        );
+        // only entities can be testbenches
+        assert_eq!(
+            discover_benches(&units, &vec![String::from("gates_pkg")]).is_err(),
+            true
+        );
+        assert_eq!(
+            discover_benches(&units, &vec![String::from("missing_tb")]).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn summary_table() {
+        colored::control::set_override(false);
+        let results = vec![
+            TestResult {
+                bench: String::from("and_gate_tb"),
+                outcome: Outcome::Passed,
+                seconds: Some(1.5),
+                error: None,
+            },
+            TestResult {
+                bench: String::from("zero_tb"),
+                outcome: Outcome::Skipped,
+                seconds: None,
+                error: None,
+            },
+        ];
+        let table = fmt_table(&results);
+        assert_eq!(
+            table.contains("and_gate_tb                 pass      1.50s\n"),
+            true
+        );
+        assert_eq!(
+            table.contains("zero_tb                     skip      -\n"),
+            true
+        );
+        assert_eq!(table.ends_with("\n1 passed; 0 failed; 1 skipped\n"), true);
+    }
+}
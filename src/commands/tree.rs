@@ -1,5 +1,6 @@
 use crate::commands::plan::Plan;
 use crate::core::algo;
+use crate::core::algo::BlackBox;
 use crate::core::algo::IpFileNode;
 use crate::core::catalog::Catalog;
 use crate::core::context::Context;
@@ -10,7 +11,9 @@ use crate::core::lang::vhdl::subunit::SubUnit;
 use crate::core::lang::vhdl::symbol::CompoundIdentifier;
 use crate::core::lang::vhdl::symbol::Entity;
 use crate::core::lang::vhdl::token::Identifier;
+use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
+use crate::util::logger;
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
@@ -18,11 +21,17 @@ use clif::Cli;
 use clif::Error as CliError;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use crate::core::fileset;
 use crate::core::lang::node::SubUnitNode;
 use crate::core::lang::vhdl::symbol::{VHDLParser, VHDLSymbol};
 use crate::util::graph::EdgeStatus;
+use crate::util::graph::Twig;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use crate::util::output::TreeNode;
 use crate::util::graphmap::GraphMap;
+use serde_derive::Serialize;
 use std::fs;
 use crate::commands::helps::tree;
 use super::plan::PlanError;
@@ -35,6 +44,7 @@ pub struct Tree {
     ascii: bool,
     ip: bool,
     all: bool,
+    why: Option<Identifier>,
 }
 
 impl FromCli for Tree {
@@ -47,6 +57,7 @@ impl FromCli for Tree {
             all: cli.check_flag(Flag::new("all"))?,
             root: cli.check_option(Optional::new("root").value("unit"))?,
             format: cli.check_option(Optional::new("format").value("fmt"))?,
+            why: cli.check_option(Optional::new("why").value("unit"))?,
         });
         command
     }
@@ -69,33 +80,170 @@ impl Command<Context> for Tree {
         // gather the catalog
         let catalog = Catalog::new().installations(c.get_cache_path())?;
 
-        self.run(ip, catalog)
+        self.run(ip, catalog, c.get_output_format(), c.get_config().get_ignore_file())
     }
 }
 
 impl Tree {
-    fn run(&self, target: Ip, catalog: Catalog) -> Result<(), Fault> {
-        match &self.ip {
-            true => self.run_ip_graph(target, catalog),
-            false => self.run_hdl_graph(target, catalog),
+    fn run(
+        &self,
+        target: Ip,
+        catalog: Catalog,
+        fmt: OutputFormat,
+        ignore_file: Option<&PathBuf>,
+    ) -> Result<(), Fault> {
+        if let Some(unit) = &self.why {
+            if self.ip == true {
+                return Err(AnyError(format!(
+                    "Cannot explain a design unit at the ip level"
+                )))?;
+            }
+            return self.run_why(target, catalog, unit, fmt, ignore_file);
+        }
+        let twigs = match &self.ip {
+            true => self.run_ip_graph(target, catalog)?,
+            false => self.run_hdl_graph(target, catalog, ignore_file)?,
+        };
+        match fmt {
+            OutputFormat::Text => {
+                for (twig, name) in &twigs {
+                    let branch_str = match self.ascii {
+                        true => Self::to_ascii(&twig.to_string()),
+                        false => twig.to_string(),
+                    };
+                    println!("{}{}", branch_str, name);
+                }
+            }
+            OutputFormat::Json => {
+                let nodes = twigs
+                    .into_iter()
+                    .map(|(twig, name)| (twig.depth(), name))
+                    .collect();
+                println!("{}", output::to_json("tree", &TreeNode::from_depths(nodes))?);
+            }
         }
+        Ok(())
     }
 
-    /// Construct and print the graph at an HDL-entity level.
-    fn run_hdl_graph(&self, target: Ip, catalog: Catalog) -> Result<(), Fault> {
-        let working_lib = Identifier::new_working();
-
+    /// Construct the graph at an HDL-entity level and collect the tree of each root.
+    fn run_hdl_graph(
+        &self,
+        target: Ip,
+        catalog: Catalog,
+        ignore_file: Option<&PathBuf>,
+    ) -> Result<Vec<(Twig, String)>, Fault> {
         // build graph again but with entire set of all files available from all depdendencies
         let ip_graph = algo::compute_final_ip_graph(&target, &catalog)?;
-        let files = algo::build_ip_file_list(&ip_graph);
+        let files = algo::build_ip_file_list(&ip_graph, &target, ignore_file);
+        let blackboxes = algo::build_blackbox_list(&ip_graph)?;
 
         // build the complete graph (using entities as the nodes)
-        let global_graph = Self::build_graph(&files);
+        let global_graph = Self::build_graph(&files, &blackboxes);
+
+        let roots = self.find_roots(&global_graph, &target)?;
+
+        // collect each root's tree
+        Ok(roots
+            .into_iter()
+            .flat_map(|n| global_graph.get_graph().treeview(n))
+            .map(|(twig, i)| {
+                let name = global_graph
+                    .get_node_by_index(i)
+                    .unwrap()
+                    .as_ref()
+                    .display(self.format.as_ref().unwrap_or(&IdentifierFormat::Short));
+                (twig, name)
+            })
+            .collect())
+    }
+
+    /// Explains why the design `unit` is in the hierarchy by reporting every
+    /// path of instantiations from the roots down to it.
+    fn run_why(
+        &self,
+        target: Ip,
+        catalog: Catalog,
+        unit: &Identifier,
+        fmt: OutputFormat,
+        ignore_file: Option<&PathBuf>,
+    ) -> Result<(), Fault> {
+        let ip_graph = algo::compute_final_ip_graph(&target, &catalog)?;
+        let files = algo::build_ip_file_list(&ip_graph, &target, ignore_file);
+        let blackboxes = algo::build_blackbox_list(&ip_graph)?;
+
+        let global_graph = Self::build_graph(&files, &blackboxes);
+
+        // the unit may be declared in more than one library
+        let targets: Vec<usize> = global_graph
+            .get_map()
+            .iter()
+            .filter(|(k, _)| k.get_suffix() == unit)
+            .map(|(_, n)| n.index())
+            .collect();
+        if targets.is_empty() == true {
+            return Err(AnyError(format!(
+                "No design unit named '{}' in the current ip or its dependencies",
+                unit
+            )))?;
+        }
+
+        let fmt_name = |i: usize| {
+            global_graph
+                .get_node_by_index(i)
+                .unwrap()
+                .as_ref()
+                .display(self.format.as_ref().unwrap_or(&IdentifierFormat::Short))
+        };
+        let roots = self.find_roots(&global_graph, &target)?;
+        let paths: Vec<Vec<String>> = roots
+            .iter()
+            .flat_map(|r| targets.iter().map(move |t| (*r, *t)))
+            .flat_map(|(r, t)| global_graph.get_graph().find_paths(r, t))
+            .map(|path| path.into_iter().map(|i| fmt_name(i)).collect())
+            .collect();
+
+        match fmt {
+            OutputFormat::Text => {
+                if paths.is_empty() == true {
+                    logger::info(format!(
+                        "{} is not instantiated under {}",
+                        unit,
+                        roots
+                            .iter()
+                            .map(|r| fmt_name(*r))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ));
+                }
+                print!("{}", fmt_paths(&paths));
+            }
+            OutputFormat::Json => {
+                let report = WhyReport {
+                    unit: unit.to_string(),
+                    paths: paths,
+                };
+                println!("{}", output::to_json("tree", &report)?);
+            }
+        }
+        Ok(())
+    }
 
-        if self.all == false {
+    /// Determines the indices of the units in the `global_graph` to start
+    /// viewing the hierarchy from.
+    ///
+    /// Only units of the current `target` ip are candidates: the `--root` unit
+    /// when given, otherwise the detected root, or every root with `--all`.
+    fn find_roots(
+        &self,
+        global_graph: &GraphMap<CompoundIdentifier, HdlNode, ()>,
+        target: &Ip,
+    ) -> Result<Vec<usize>, Fault> {
+        let working_lib = Identifier::new_working();
+
+        Ok(if self.all == false {
             let n = {
                 // restrict graph to units only found within the current IP
-                let local_graph = Plan::compute_local_graph(&global_graph, &working_lib, &target);
+                let local_graph = Plan::compute_local_graph(global_graph, &working_lib, target);
 
                 let root_index = if let Some(ent) = &self.root {
                     // check if the identifier exists in the entity graph
@@ -103,16 +251,16 @@ impl Tree {
                         .get_node_by_key(&&CompoundIdentifier::new(working_lib, ent.clone()))
                     {
                         Some(id) => id.index(),
-                        None => return Err(PlanError::UnknownEntity(ent.clone()))?,
+                        None => return Err(PlanError::unknown_entity(ent, &local_graph))?,
                     };
-                    Plan::local_to_global(i, &global_graph, &local_graph).index()
+                    Plan::local_to_global(i, global_graph, &local_graph).index()
                 // auto-detect the root if possible
                 } else {
                     // check if --all is applied
                     // traverse subset of graph by filtering only for working library entities
                     match local_graph.find_root() {
                         Ok(i) => {
-                            Plan::local_to_global(i.index(), &global_graph, &local_graph).index()
+                            Plan::local_to_global(i.index(), global_graph, &local_graph).index()
                         }
                         Err(e) => match e.len() {
                             0 => return Err(PlanError::Empty)?,
@@ -137,85 +285,50 @@ impl Tree {
                 root_index
             };
 
-            // display the root's tree to the console
-            let tree = global_graph.get_graph().treeview(n);
-            for twig in &tree {
-                let branch_str = match self.ascii {
-                    true => Self::to_ascii(&twig.0.to_string()),
-                    false => twig.0.to_string(),
-                };
-                println!(
-                    "{}{}",
-                    branch_str,
-                    global_graph
-                        .get_node_by_index(twig.1)
-                        .unwrap()
-                        .as_ref()
-                        .display(self.format.as_ref().unwrap_or(&IdentifierFormat::Short))
-                );
-            }
+            vec![n]
         } else {
             // restrict graph to units only found within the current IP
-            let local_graph = Plan::compute_local_graph(&global_graph, &working_lib, &target);
+            let local_graph = Plan::compute_local_graph(global_graph, &working_lib, target);
             // compile list of all roots
             let mut roots = Vec::new();
             match local_graph.find_root() {
                 Ok(i) => roots
-                    .push(Plan::local_to_global(i.index(), &global_graph, &local_graph).index()),
+                    .push(Plan::local_to_global(i.index(), global_graph, &local_graph).index()),
                 Err(e) => match e.len() {
                     0 => return Err(PlanError::Empty)?,
                     _ => e.into_iter().for_each(|f| {
                         roots.push(
-                            Plan::local_to_global(f.index(), &global_graph, &local_graph).index(),
+                            Plan::local_to_global(f.index(), global_graph, &local_graph).index(),
                         )
                     }),
                 },
             }
 
-            // display each root's tree to the console
-            roots.iter().for_each(|n| {
-                let tree = global_graph.get_graph().treeview(*n);
-                for twig in &tree {
-                    let branch_str = match self.ascii {
-                        true => Self::to_ascii(&twig.0.to_string()),
-                        false => twig.0.to_string(),
-                    };
-                    println!(
-                        "{}{}",
-                        branch_str,
-                        global_graph
-                            .get_node_by_index(twig.1)
-                            .unwrap()
-                            .as_ref()
-                            .display(self.format.as_ref().unwrap_or(&IdentifierFormat::Short))
-                    );
-                }
-            });
-        }
-
-        Ok(())
+            roots
+        })
     }
 
-    /// Construct and print the graph at an IP dependency level.
-    fn run_ip_graph(&self, target: Ip, catalog: Catalog) -> Result<(), Fault> {
+    /// Construct the graph at an IP dependency level and collect its tree.
+    fn run_ip_graph(&self, target: Ip, catalog: Catalog) -> Result<Vec<(Twig, String)>, Fault> {
         let ip_graph = algo::compute_final_ip_graph(&target, &catalog)?;
 
-        let tree = ip_graph.get_graph().treeview(0);
-        for twig in &tree {
-            println!(
-                "{}{}",
-                twig.0,
-                ip_graph
-                    .get_node_by_index(twig.1)
+        Ok(ip_graph
+            .get_graph()
+            .treeview(0)
+            .into_iter()
+            .map(|(twig, i)| {
+                let name = ip_graph
+                    .get_node_by_index(i)
                     .unwrap()
                     .as_ref()
                     .as_ip()
                     .get_man()
                     .get_ip()
                     .into_ip_spec()
-            );
-        }
-        Ok(())
+                    .to_string();
+                (twig, name)
+            })
+            .collect())
     }
 
     /// Converts the original treeview text from using extended ascii characters
@@ -236,8 +349,11 @@ impl Tree {
     }
 
     /// Constructs a graph of the design heirarchy with entity nodes.
+    ///
+    /// Units declared in `blackboxes` are resolved instead of being shown as missing.
     fn build_graph<'a>(
         files: &'a Vec<IpFileNode>,
+        blackboxes: &Vec<BlackBox>,
     ) -> GraphMap<CompoundIdentifier, HdlNode<'a>, ()> {
         // entity identifier, HashNode (hash-node holds entity structs)
         let mut graph = GraphMap::<CompoundIdentifier, HdlNode, ()>::new();
@@ -286,6 +402,21 @@ impl Tree {
             });
         }
 
+        // add the externally-provided units that were not found in source code
+        for bb in blackboxes {
+            let key = CompoundIdentifier::new(bb.get_library().clone(), bb.get_name().clone());
+            if graph.has_node_by_key(&key) == true {
+                continue;
+            }
+            component_pairs
+                .entry(bb.get_name().clone())
+                .or_insert(bb.get_library().clone());
+            graph.add_node(
+                key,
+                HdlNode::external(VHDLSymbol::from(Entity::black_box(bb.get_name().clone()))),
+            );
+        }
+
         // go through all subunits and make the connections
         let mut sub_nodes_iter = sub_nodes.into_iter();
         while let Some((lib, node)) = sub_nodes_iter.next() {
@@ -354,4 +485,19 @@ impl Tree {
         }
         graph
     }
-}
\ No newline at end of file
+}
+/// The paths of instantiations leading to a design unit.
+#[derive(Debug, Serialize)]
+struct WhyReport {
+    unit: String,
+    /// Each path lists the units from a root down to the unit.
+    paths: Vec<Vec<String>>,
+}
+
+/// Formats each path on its own line with its units separated by arrows.
+fn fmt_paths(paths: &Vec<Vec<String>>) -> String {
+    paths
+        .iter()
+        .map(|p| format!("{}\n", p.join(" -> ")))
+        .collect()
+}
@@ -1,7 +1,10 @@
-use crate::core::catalog::{Catalog, CacheSlot};
+use crate::core::catalog::{Catalog, CacheEntry, CacheSlot};
 use crate::core::context::Context;
 use crate::core::ip::{PartialIpSpec, Ip};
 use crate::util::anyerror::AnyError;
+use crate::util::filelock::FileLock;
+use crate::util::filesystem;
+use crate::util::logger;
 use crate::OrbitResult;
 use clif::arg::{Flag, Positional};
 use clif::cmd::{Command, FromCli};
@@ -61,12 +64,21 @@ impl Command<Context> for Uninstall {
 
         let ip_spec = target.get_man().get_ip().into_ip_spec();
 
-        // delete the project
-        fs::remove_dir_all(target.get_root())?;
-        println!("info: Removed IP {} from the cache", ip_spec);
+        // delete the project once no other orbit process is writing to it
+        let _lock = FileLock::acquire(target.get_root())?;
+        fs::remove_dir_all(filesystem::to_extended(target.get_root()))?;
+        logger::info(format!("Removed IP {} from the cache", ip_spec));
 
-        // check for any "dynamics" under this target
-        for dir in fs::read_dir(c.get_cache_path())? {
+        // check for any "dynamics" under this target (including slots from before the cache was sharded)
+        let shard = c.get_cache_path().join(CacheEntry::from(target.get_uuid()).to_shard());
+        for dir in [shard, c.get_cache_path().clone()]
+            .iter()
+            .filter(|d| d.is_dir() == true)
+            .map(|d| fs::read_dir(d))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+        {
             // only check valid directory entries
             if let Ok(entry) = dir {
                 // println!("{:?}", entry.file_name());
@@ -84,8 +96,8 @@ impl Command<Context> for Uninstall {
                     }
                     // remove the slot if it is dynamic
                     if cached_ip.is_dynamic() == true {
-                        fs::remove_dir_all(entry.path())?;
-                        println!("info: Removed a dynamic variant of IP {} from the cache", ip_spec);
+                        fs::remove_dir_all(filesystem::to_extended(&entry.path()))?;
+                        logger::info(format!("Removed a dynamic variant of IP {} from the cache", ip_spec));
                     }
                 }
                 
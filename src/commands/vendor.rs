@@ -0,0 +1,361 @@
+use crate::commands::download::Download;
+use crate::commands::helps::vendor;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::credential::Credential;
+use crate::core::hook::{Event, Hook};
+use crate::core::ip::{Ip, PartialIpSpec};
+use crate::core::iparchive::IpArchive;
+use crate::core::lockfile::LockEntry;
+use crate::core::source::Source;
+use crate::core::variable::VariableTable;
+use crate::core::vendor as mirror;
+use crate::core::vendor::VendorCache;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment::Environment;
+use crate::util::environment::{ORBIT_CACHE, ORBIT_DOWNLOADS};
+use crate::util::filesystem;
+use crate::util::filesystem::{Standardize, ORBIT_IGNORE_FILE};
+use crate::util::logger;
+use crate::OrbitResult;
+use clif::arg::{Flag, Optional, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::path::PathBuf;
+
+/// The default directory within the ip to place the dependencies.
+const VENDOR_DIR: &str = "vendor";
+
+#[derive(Debug, PartialEq)]
+pub struct Vendor {
+    dir: Option<PathBuf>,
+    mirror: Option<PathBuf>,
+    url: Option<String>,
+    all: bool,
+    refresh: bool,
+    ip: Option<PartialIpSpec>,
+}
+
+impl FromCli for Vendor {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(vendor::HELP).ref_usage(2..4))?;
+        let command = Ok(Vendor {
+            all: cli.check_flag(Flag::new("all"))?,
+            refresh: cli.check_flag(Flag::new("refresh"))?,
+            dir: cli.check_option(Optional::new("dir").value("path"))?,
+            mirror: cli.check_option(Optional::new("mirror").value("path"))?,
+            url: cli.check_option(Optional::new("url"))?,
+            // Positionals
+            ip: cli.check_positional(Positional::new("ip"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Vendor {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // fetch the configured vendor index again without vendoring anything
+        if self.refresh == true {
+            if self.dir.is_some() || self.mirror.is_some() || self.url.is_some() || self.ip.is_some() {
+                return Err(AnyError(format!(
+                    "Cannot refresh the vendor index while vendoring dependencies"
+                )))?;
+            }
+            if let Some((index, _)) = sync_index(c, true)? {
+                println!("Fetched vendor index into {}", PathBuf::standardize(index).display());
+            }
+            return Ok(());
+        }
+
+        if self.dir.is_some() && self.mirror.is_some() {
+            return Err(AnyError(format!(
+                "Cannot copy dependencies into a directory while mirroring them"
+            )))?;
+        }
+
+        // resolve the mirror from the original working directory
+        let index = match &self.mirror {
+            Some(dir) => Some(std::env::current_dir()?.join(dir)),
+            None => None,
+        };
+
+        // publish a single upstream ip without reading the current ip
+        if let Some(spec) = &self.ip {
+            return match &index {
+                Some(dest) => self.mirror_upstream(c, spec, dest),
+                None => Err(AnyError(format!(
+                    "Naming an ip to vendor requires an index\n\nTry `orbit vendor --mirror <path> {}`",
+                    spec
+                )))?,
+            };
+        }
+        if self.url.is_some() {
+            return Err(AnyError(format!(
+                "Fetching from a url requires the ip to mirror\n\nTry `orbit vendor --mirror <path> --url <url> <ip>`"
+            )))?;
+        }
+
+        // verify running from an ip directory and enter ip's root directory
+        c.goto_ip_path()?;
+
+        let target = Ip::load(c.get_ip_path().unwrap().clone())?;
+
+        if target.lock_exists() == false || target.can_use_lock() == false {
+            return Err(AnyError(String::from(
+                "cannot vendor dependencies due to a missing or outdated lock file\n\nTry `orbit plan --lock-only` to update the lock file",
+            )))?;
+        }
+
+        let le = LockEntry::from((&target, true));
+        let lf = target.get_lock().keep_dev_dep_entries(&target, self.all);
+
+        let rel_dir = self.dir.clone().unwrap_or(PathBuf::from(VENDOR_DIR));
+        let dest = index
+            .clone()
+            .unwrap_or(target.get_root().join(&rel_dir));
+        std::fs::create_dir_all(&dest)?;
+
+        let mut count = 0;
+        for entry in lf.inner().iter().filter(|e| e.matches_target(&le) == false) {
+            // locate the exact installation recorded in the lock file
+            let slot = entry.to_cache_slot_key();
+            let src = match slot.locate(c.get_cache_path(), entry.get_uuid()) {
+                Some(src) => src,
+                None => return Err(AnyError(format!(
+                    "ip {} is not installed in the cache\n\nTry `orbit fetch{}` to install the locked dependencies",
+                    entry.to_ip_spec(),
+                    match self.all {
+                        true => " --all",
+                        false => "",
+                    }
+                )))?,
+            };
+            // republish the installation into the vendor index
+            if index.is_some() {
+                Self::publish(c, &Ip::load(src)?, entry.get_source(), &dest)?;
+                logger::info(format!("Mirrored ip {}", entry.to_ip_spec()));
+                count += 1;
+                continue;
+            }
+            // replace any previous snapshot of the same installation
+            let to = dest.join(slot.to_string());
+            if to.exists() == true {
+                std::fs::remove_dir_all(filesystem::to_extended(&to))?;
+            }
+            filesystem::copy(&src, &to, false, None)?;
+            logger::info(format!("Vendored ip {}", entry.to_ip_spec()));
+            count += 1;
+        }
+
+        if index.is_some() {
+            println!(
+                "{}",
+                Self::fmt_mirror_snippet(count, &PathBuf::standardize(&dest))
+            );
+            return Ok(());
+        }
+
+        // keep the vendored files out of the current ip's source files
+        if let Some(dir) = rel_dir.to_str() {
+            if rel_dir.is_relative() == true {
+                Self::ignore_dir(target.get_root(), dir)?;
+            }
+        }
+
+        println!(
+            "{}",
+            Self::fmt_snippet(count, &PathBuf::standardize(&dest))
+        );
+        Ok(())
+    }
+}
+
+impl Vendor {
+    /// Fetches the upstream ip `spec` and publishes it into the vendor index at
+    /// `dest`.
+    ///
+    /// The ip is fetched from the `--url` when given and is otherwise taken from
+    /// the catalog, preferring its downloaded archive over its installation.
+    fn mirror_upstream(&self, c: &Context, spec: &PartialIpSpec, dest: &PathBuf) -> Result<(), Fault> {
+        let fetched = match &self.url {
+            Some(url) => {
+                let full_spec = match spec.as_ip_spec() {
+                    Some(s) => s,
+                    None => {
+                        return Err(AnyError(format!(
+                            "A complete IP specification is required when providing a url"
+                        )))?
+                    }
+                };
+                let env = Environment::new().from_config(c.get_config())?;
+                let mut vtable = VariableTable::new()
+                    .load_environment(&env)?
+                    .load_config(c.get_config())?;
+                env.initialize();
+                let source = Source::new().url(url.to_string());
+                Download::download(
+                    &mut vtable,
+                    &full_spec,
+                    &source,
+                    None,
+                    c.get_downloads_path(),
+                    &c.get_config().get_protocols(),
+                    &c.get_config().get_credentials(),
+                    &c.get_config().get_rewrites(),
+                    c.get_config().get_init_submodules(),
+                    false,
+                    true,
+                )?;
+                Some(source)
+            }
+            None => None,
+        };
+
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .downloads(c.get_downloads_path())?;
+        let found = match catalog.inner().get(spec.get_name()).and_then(|lvl| {
+            lvl.get_download(spec.get_version())
+                .or(lvl.get_install(spec.get_version()))
+        }) {
+            Some(ip) => ip,
+            None => {
+                return Err(AnyError(format!(
+                    "IP {} does not exist in the catalog\n\nTry `--url <url>` to fetch it from its source",
+                    spec
+                )))?
+            }
+        };
+        // read the files of a downloaded archive from a temporary directory
+        let temp = tempfile::tempdir()?;
+        let ip = match found.get_mapping().as_bytes() {
+            Some(bytes) => {
+                let dir = temp.path().to_path_buf();
+                IpArchive::extract(&bytes, &dir)?;
+                Ip::load(dir)?
+            }
+            None => Ip::load(found.get_root().clone())?,
+        };
+        let source = fetched.or(found.get_man().get_ip().get_source().cloned());
+        Self::publish(c, &ip, source.as_ref(), dest)?;
+        logger::info(format!(
+            "Mirrored ip {}",
+            ip.get_man().get_ip().into_ip_spec()
+        ));
+        println!(
+            "{}",
+            Self::fmt_mirror_snippet(1, &PathBuf::standardize(dest))
+        );
+        Ok(())
+    }
+
+    /// Mirrors the `ip` into the vendor index at `dest`, running the hooks
+    /// configured for the `pre-publish` and `post-publish` events around it.
+    fn publish(c: &Context, ip: &Ip, source: Option<&Source>, dest: &PathBuf) -> Result<(), Fault> {
+        let config = c.get_config();
+        Hook::dispatch(&config.get_hooks(Event::PrePublish), Event::PrePublish, ip)?;
+        mirror::mirror(ip, source, dest)?;
+        Hook::dispatch(&config.get_hooks(Event::PostPublish), Event::PostPublish, ip)?;
+        Ok(())
+    }
+
+    /// Adds the `dir` to the `.orbitignore` file at `root` if it is not already
+    /// listed.
+    fn ignore_dir(root: &PathBuf, dir: &str) -> Result<(), std::io::Error> {
+        let path = root.join(ORBIT_IGNORE_FILE);
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        let pattern = format!("/{}/", dir.trim_start_matches("./").trim_matches('/'));
+        if text.lines().any(|l| l.trim() == pattern) == true {
+            return Ok(());
+        }
+        let mut contents = text;
+        if contents.is_empty() == false && contents.ends_with('\n') == false {
+            contents.push('\n');
+        }
+        contents.push_str(&pattern);
+        contents.push('\n');
+        std::fs::write(&path, contents)
+    }
+
+    /// Describes how to resolve dependencies from the vendor directory at `dir`.
+    fn fmt_snippet(count: usize, dir: &PathBuf) -> String {
+        format!(
+            "Vendored {} dependencies into {:?}
+
+To resolve dependencies from this directory instead of the cache, set:
+
+    {}=\"{}\"",
+            count,
+            dir,
+            ORBIT_CACHE,
+            filesystem::into_std_str(dir.clone())
+        )
+    }
+
+    /// Describes how to install dependencies from the vendor index at `dir`.
+    fn fmt_mirror_snippet(count: usize, dir: &PathBuf) -> String {
+        format!(
+            "Mirrored {} dependencies into {:?}
+
+To install dependencies only from this index, set:
+
+    {}=\"{}\"",
+            count,
+            dir,
+            ORBIT_DOWNLOADS,
+            filesystem::into_std_str(dir.clone())
+        )
+    }
+}
+
+/// Fetches the vendor index set by `vendor-index` when its copy is missing,
+/// older than its time-to-live, or `force` is set.
+///
+/// Returns the directory of the fetched index and its age in seconds, or `None`
+/// when no vendor index is configured.
+pub fn sync_index(c: &Context, force: bool) -> Result<Option<(PathBuf, u64)>, Fault> {
+    let url = match c.get_config().get_vendor_index() {
+        Some(url) => url,
+        None => match force {
+            true => {
+                return Err(AnyError(format!(
+                    "No vendor index is configured to refresh\n\nTry setting `vendor-index` in the [general] section of a config.toml file"
+                )))?
+            }
+            false => return Ok(None),
+        },
+    };
+    let cache = VendorCache::new(c.get_home_path());
+    if force == true || cache.is_stale(url, c.get_config().get_vendor_index_ttl()) == true {
+        logger::info(format!("Fetching vendor index from {} ...", url));
+        let auth = match Credential::find(&c.get_config().get_credentials(), url) {
+            Some(cred) => cred.get_http_auth()?,
+            None => None,
+        };
+        cache.refresh(url, auth.as_ref())?;
+    }
+    Ok(Some((cache.get_index_path(), cache.get_age(url).unwrap_or(0))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ignore_vendor_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join(ORBIT_IGNORE_FILE), "sim/").unwrap();
+
+        Vendor::ignore_dir(&root, "./vendor").unwrap();
+        // adding the same directory twice does not duplicate the entry
+        Vendor::ignore_dir(&root, "vendor/").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join(ORBIT_IGNORE_FILE)).unwrap(),
+            "sim/\n/vendor/\n"
+        );
+    }
+}
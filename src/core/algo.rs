@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::util::anyerror::{AnyError, Fault};
 use crate::util::graphmap::GraphMap;
 use std::hash::Hash;
+use std::str::FromStr;
 use tempfile::tempdir;
 
 use crate::core::lang::vhdl::dst;
@@ -73,7 +74,7 @@ fn graph_ip<'a>(
             match catalog.inner().get(pkgid) {
                 Some(status) => {
                     // find this IP to read its dependencies
-                    match status.get_install(&AnyVersion::from(version)) {
+                    match catalog.resolve(status, &AnyVersion::from(version)) {
                         Some(dep) => {
                             // check if node is already in graph ????
                             let s = if let Some(existing_node) =
@@ -94,11 +95,7 @@ fn graph_ip<'a>(
                                             PathBuf::from(
                                                 dupe.get_unit().get_source_code_file(),
                                             ),
-                                            dupe.get_unit()
-                                                .get_symbol()
-                                                .unwrap()
-                                                .get_position()
-                                                .clone(),
+                                            dupe.get_unit().get_position().clone(),
                                         ))?;
                                     }
                                     true
@@ -182,14 +179,11 @@ pub fn compute_final_ip_graph<'a>(
                 }
 
                 // grab neighbors and update their hashmaps
-                let index = rough_ip_graph.get_node_by_key(&key).unwrap().index();
-                let mut dependents = rough_ip_graph.get_graph().successors(index);
+                let mut dependents = rough_ip_graph.successors_by_key(&key).unwrap();
 
-                while let Some(i) = dependents.next() {
+                while let Some((neighbor_key, _, _)) = dependents.next() {
                     // remember units if true that a transform occurred on the direct conflict node
                     let lut = node.as_ref().as_ip().generate_dst_lut();
-                    // determine the neighboring node's ip spec
-                    let neighbor_key = rough_ip_graph.get_key_by_index(i).unwrap();
 
                     match transforms.get_mut(&neighbor_key) {
                         // update the hashmap for the key
@@ -224,12 +218,21 @@ pub fn compute_final_ip_graph<'a>(
 }
 
 /// Take the ip graph and create the entire space of VHDL files that could be used for the current design.
+///
+/// The patterns of the `global` ignore file only apply to the files of the `working` ip.
 pub fn build_ip_file_list<'a>(
     ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>,
+    working: &Ip,
+    global: Option<&PathBuf>,
 ) -> Vec<IpFileNode<'a>> {
     let mut files = Vec::new();
     ip_graph.get_map().iter().for_each(|(_, ip)| {
-        crate::util::filesystem::gather_current_files(&ip.as_ref().as_ip().get_root(), false)
+        let root = ip.as_ref().as_ip().get_root();
+        let global = match root == working.get_root() {
+            true => global,
+            false => None,
+        };
+        crate::util::filesystem::gather_working_files(&root, false, global)
             .into_iter()
             .filter(|f| crate::core::fileset::is_vhdl(f))
             .for_each(|f| {
@@ -243,6 +246,55 @@ pub fn build_ip_file_list<'a>(
     files
 }
 
+/// Take the ip graph and collect the externally-provided design units declared by
+/// each ip's manifest.
+pub fn build_blackbox_list<'a>(
+    ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>,
+) -> Result<Vec<BlackBox<'a>>, Fault> {
+    let mut boxes = Vec::new();
+    for (_, ip) in ip_graph.get_map().iter() {
+        for (name, patterns) in ip.as_ref().as_ip().get_man().get_blackboxes() {
+            boxes.push(BlackBox {
+                name: Identifier::from_str(name)?,
+                patterns: patterns,
+                ip: ip.as_ref().as_ip(),
+                library: ip.as_ref().get_library().clone(),
+            });
+        }
+    }
+    Ok(boxes)
+}
+
+/// A design unit without parsable source, such as a netlist or encrypted ip,
+/// that is declared in an ip's manifest.
+#[derive(Debug, PartialEq)]
+pub struct BlackBox<'a> {
+    name: Identifier,
+    patterns: &'a Vec<String>,
+    ip: &'a Ip,
+    library: Identifier,
+}
+
+impl<'a> BlackBox<'a> {
+    pub fn get_name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// References the glob patterns of the unit's files within its ip.
+    pub fn get_patterns(&self) -> &Vec<String> {
+        self.patterns
+    }
+
+    pub fn get_ip(&self) -> &Ip {
+        &self.ip
+    }
+
+    /// References the library identifier.
+    pub fn get_library(&self) -> &Identifier {
+        &self.library
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct IpNode<'a> {
     dyn_state: DynState,
@@ -359,7 +411,7 @@ fn install_dst(source_ip: &Ip, root: &std::path::PathBuf) -> Ip {
             source_ip.get_man().get_ip().get_version(),
             &sum,
         );
-        root.join(cache_slot.to_string())
+        cache_slot.to_path(root, source_ip.get_uuid())
     };
 
     // check if already exists and return early with manifest if exists
@@ -14,16 +14,19 @@ use super::{
 
 use crate::core::ip::Ip;
 use crate::core::iparchive::IpArchive;
+use crate::util::progress::Progress;
 
 #[derive(Debug)]
 pub struct Catalog<'a> {
     inner: HashMap<PkgPart, IpLevel>,
     cache: Option<&'a PathBuf>,
     downloads: Option<&'a PathBuf>,
+    prefer_dev: bool,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum IpState {
+    Development,
     Downloaded,
     Installation,
     Available,
@@ -33,6 +36,7 @@ pub enum IpState {
 impl std::fmt::Display for IpState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self {
+            Self::Development => write!(f, "development"),
             Self::Downloaded => write!(f, "downloaded"),
             Self::Installation => write!(f, "installation"),
             Self::Available => write!(f, "available"),
@@ -43,6 +47,7 @@ impl std::fmt::Display for IpState {
 
 #[derive(Debug)]
 pub struct IpLevel {
+    developments: Vec<Ip>,
     installs: Vec<Ip>,
     downloads: Vec<Ip>,
     available: Vec<Ip>,
@@ -51,6 +56,7 @@ pub struct IpLevel {
 impl IpLevel {
     pub fn new() -> Self {
         Self {
+            developments: Vec::new(),
             installs: Vec::new(),
             available: Vec::new(),
             downloads: Vec::new(),
@@ -64,6 +70,10 @@ impl IpLevel {
         }
     }
 
+    pub fn add_development(&mut self, m: Ip) -> () {
+        self.developments.push(m);
+    }
+
     pub fn add_download(&mut self, m: Ip) -> () {
         self.downloads.push(m);
     }
@@ -76,6 +86,10 @@ impl IpLevel {
         &self.installs
     }
 
+    pub fn get_developments(&self) -> &Vec<Ip> {
+        &self.developments
+    }
+
     pub fn get_downloads(&self) -> &Vec<Ip> {
         &self.downloads
     }
@@ -96,11 +110,20 @@ impl IpLevel {
         self.downloads.is_empty() == false
     }
 
+    pub fn is_developed(&self) -> bool {
+        self.developments.is_empty() == false
+    }
+
     /// Returns the manifest with the most compatible version fitting `version`.
     pub fn get_install(&self, version: &AnyVersion) -> Option<&Ip> {
         Self::get_target_version(version, self.get_installations())
     }
 
+    /// Returns the manifest with the most compatible version fitting `version`.
+    pub fn get_development(&self, version: &AnyVersion) -> Option<&Ip> {
+        Self::get_target_version(version, self.get_developments())
+    }
+
     /// Returns the manifest with the most compatible version fitting `version`.
     pub fn get_download(&self, version: &AnyVersion) -> Option<&Ip> {
         Self::get_target_version(version, self.get_downloads())
@@ -129,7 +152,9 @@ impl IpLevel {
 
     /// Tracks what level the `manifest` came from.
     pub fn get_state(&self, ip: &Ip) -> IpState {
-        if self.installs.iter().find(|f| f == &ip).is_some() {
+        if self.developments.iter().find(|f| f == &ip).is_some() {
+            IpState::Development
+        } else if self.installs.iter().find(|f| f == &ip).is_some() {
             IpState::Installation
         } else if self.available.iter().find(|f| f == &ip).is_some() {
             IpState::Available
@@ -179,12 +204,54 @@ impl<'a> Catalog<'a> {
             inner: HashMap::new(),
             cache: None,
             downloads: None,
+            prefer_dev: false,
         }
     }
 
-    /// Uses the cache slot name to check if the directory exists.
-    pub fn is_cached_slot(&self, slot: &CacheSlot) -> bool {
-        self.get_cache_path().join(slot.to_string()).is_dir()
+    /// Uses the cache slot name to check if the directory exists for the ip
+    /// with the `uuid`.
+    pub fn is_cached_slot(&self, slot: &CacheSlot, uuid: &Uuid) -> bool {
+        slot.locate(self.get_cache_path(), uuid).is_some()
+    }
+
+    /// Finds the ip with the `uuid` and the most compatible version fitting
+    /// `version`.
+    ///
+    /// Installations are searched first, and if not found then the downloads
+    /// are searched, similar to [IpLevel::get].
+    pub fn get_by_uuid(&self, uuid: &Uuid, version: &AnyVersion) -> Option<&Ip> {
+        self.find_by_uuid(IpLevel::get_installations, uuid, version)
+            .or_else(|| self.find_by_uuid(IpLevel::get_downloads, uuid, version))
+    }
+
+    /// Finds the ip with the `uuid` and the most compatible version fitting
+    /// `version` among the `space` of every level.
+    fn find_by_uuid(
+        &self,
+        space: fn(&IpLevel) -> &Vec<Ip>,
+        uuid: &Uuid,
+        version: &AnyVersion,
+    ) -> Option<&Ip> {
+        let mut latest: Option<&Ip> = None;
+        self.inner
+            .values()
+            .flat_map(|lvl| space(lvl).iter())
+            .filter(|ip| ip.get_uuid() == uuid)
+            .filter(|ip| match version {
+                AnyVersion::Specific(v) => {
+                    crate::core::version::is_compatible(v, ip.get_man().get_ip().get_version())
+                }
+                AnyVersion::Latest => true,
+            })
+            .for_each(|ip| {
+                if latest.is_none()
+                    || ip.get_man().get_ip().get_version()
+                        > latest.unwrap().get_man().get_ip().get_version()
+                {
+                    latest = Some(ip);
+                }
+            });
+        latest
     }
 
     /// Uses the download slot name to check if the file exists.
@@ -204,6 +271,48 @@ impl<'a> Catalog<'a> {
         self.detect(path, &IpLevel::add_download, IpState::Downloaded)
     }
 
+    /// Searches the vendor index at `path` for IP available to be installed.
+    ///
+    /// A vendor index that does not exist is skipped.
+    pub fn available(self, path: &PathBuf) -> Result<Self, Fault> {
+        match path.is_dir() {
+            true => self.detect(path, &IpLevel::add_available, IpState::Available),
+            false => Ok(self),
+        }
+    }
+
+    /// Searches each workspace in `paths` for IP in development.
+    ///
+    /// Workspaces that do not exist are skipped.
+    pub fn development(mut self, paths: Vec<&PathBuf>) -> Result<Self, Fault> {
+        for path in paths {
+            if path.is_dir() == true {
+                self = self.detect(path, &IpLevel::add_development, IpState::Development)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Sets whether dependencies resolve to the IP in development before their
+    /// installations.
+    pub fn prefer_development(mut self, enable: bool) -> Self {
+        self.prefer_dev = enable;
+        self
+    }
+
+    /// Finds the IP in `lvl` that a dependency of `version` resolves to.
+    ///
+    /// An IP in development is only chosen when it is preferred, and otherwise
+    /// only installations are considered.
+    pub fn resolve<'b>(&self, lvl: &'b IpLevel, version: &AnyVersion) -> Option<&'b Ip> {
+        match self.prefer_dev {
+            true => lvl
+                .get_development(version)
+                .or_else(|| lvl.get_install(version)),
+            false => lvl.get_install(version),
+        }
+    }
+
     pub fn inner(&self) -> &HashMap<PkgPart, IpLevel> {
         &self.inner
     }
@@ -238,22 +347,26 @@ impl<'a> Catalog<'a> {
 
     /// Finds all `Orbit.toml` manifest files (markings of an IP) within the provided `path`.
     ///
-    /// This function is generic enough to be used to catch ip at all 3 levels: dev, install, and available.
+    /// This function is generic enough to be used to catch ip at all levels: dev, install, download, and available.
     fn detect(
         mut self,
         path: &PathBuf,
         add: &dyn Fn(&mut IpLevel, Ip) -> (),
         lvl: IpState,
     ) -> Result<Self, Fault> {
+        let mut progress = Progress::start("scan", None);
         match lvl {
+            // a workspace may hold ip that are mid-edit
+            IpState::Development => Ip::detect_all_lenient(path),
             IpState::Installation => Ip::detect_all(path),
-            IpState::Available => todo!("only detect for available"),
+            IpState::Available => IpArchive::detect_all(path),
             IpState::Downloaded => IpArchive::detect_all(path),
             _ => panic!("Unknown catalog state to find"),
         }?
         .into_iter()
-        .for_each(
-            |ip| match self.inner.get_mut(&ip.get_man().get_ip().get_name()) {
+        .for_each(|ip| {
+            progress.advance(ip.get_man().get_ip().into_ip_spec().to_string());
+            match self.inner.get_mut(&ip.get_man().get_ip().get_name()) {
                 Some(lvl) => add(lvl, ip),
                 None => {
                     let pkgid = ip.get_man().get_ip().get_name().clone();
@@ -262,8 +375,8 @@ impl<'a> Catalog<'a> {
                     self.inner.insert(pkgid, lvl);
                     ()
                 }
-            },
-        );
+            }
+        });
         Ok(self)
     }
 
@@ -319,6 +432,15 @@ impl CacheEntry {
             .iter()
             .fold(String::new(), |acc, x| acc + &format!("{:02X}", x))
     }
+
+    /// The directory within the cache holding the slots of the ip, in the form
+    /// `<set>/<tag>`.
+    ///
+    /// Sharding the slots by the leading bytes of the [Uuid] keeps each directory
+    /// small as the number of installations grows.
+    pub fn to_shard(&self) -> PathBuf {
+        PathBuf::from(self.set()).join(self.tag())
+    }
 }
 
 #[cfg(test)]
@@ -342,6 +464,68 @@ mod test {
         let ce = CacheEntry::from(&Uuid::nil());
         assert_eq!("0000000000000000000000000000", ce.offset());
     }
+
+    #[test]
+    fn development_level() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_path_buf();
+        std::fs::create_dir(ws.join("gates")).unwrap();
+        std::fs::write(
+            ws.join("gates").join("Orbit.toml"),
+            "[ip]\nname = \"gates\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        // an ip being edited with a broken manifest is skipped
+        std::fs::create_dir(ws.join("broken")).unwrap();
+        std::fs::write(ws.join("broken").join("Orbit.toml"), "[ip]\nname = ").unwrap();
+        let missing = ws.join("missing");
+
+        let catalog = Catalog::new().development(vec![&ws, &missing]).unwrap();
+        assert_eq!(catalog.inner().len(), 1);
+        let name = PkgPart::from_str("gates").unwrap();
+        let lvl = catalog.inner().get(&name).unwrap();
+        let dev = lvl.get_development(&AnyVersion::Latest).unwrap();
+        assert_eq!(lvl.get_state(dev), IpState::Development);
+        // dependencies only resolve to a copy in development when preferred
+        assert_eq!(catalog.resolve(lvl, &AnyVersion::Latest), None);
+
+        let catalog = catalog.prefer_development(true);
+        let lvl = catalog.inner().get(&name).unwrap();
+        assert_eq!(
+            catalog
+                .resolve(lvl, &AnyVersion::Latest)
+                .unwrap()
+                .get_man()
+                .get_ip()
+                .get_version()
+                .to_string(),
+            "1.2.0"
+        );
+    }
+
+    #[test]
+    fn slot_path() {
+        let slot = CacheSlot::try_from_str("gates-1.0.0-0123456789").unwrap();
+        assert_eq!(
+            slot.to_path(&PathBuf::from("cache"), &Uuid::nil()),
+            PathBuf::from("cache/00/00/gates-1.0.0-0123456789")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        assert_eq!(slot.locate(&root, &Uuid::nil()), None);
+        // slots from before the cache was sharded are still found
+        std::fs::create_dir(root.join("gates-1.0.0-0123456789")).unwrap();
+        assert_eq!(
+            slot.locate(&root, &Uuid::nil()),
+            Some(root.join("gates-1.0.0-0123456789"))
+        );
+        std::fs::create_dir_all(root.join("00/00/gates-1.0.0-0123456789")).unwrap();
+        assert_eq!(
+            slot.locate(&root, &Uuid::nil()),
+            Some(root.join("00/00/gates-1.0.0-0123456789"))
+        );
+    }
 }
 
 type Remainder = String;
@@ -382,6 +566,28 @@ impl CacheSlot {
     pub fn get_version(&self) -> &Version {
         &self.1
     }
+
+    /// Returns the path of the slot for the ip with the `uuid` within the cache
+    /// at `cache_root`.
+    pub fn to_path(&self, cache_root: &PathBuf, uuid: &Uuid) -> PathBuf {
+        cache_root
+            .join(CacheEntry::from(uuid).to_shard())
+            .join(self.to_string())
+    }
+
+    /// Finds the existing directory of the slot for the ip with the `uuid`
+    /// within the cache at `cache_root`.
+    ///
+    /// Slots installed before the cache was sharded are found directly under
+    /// `cache_root`.
+    pub fn locate(&self, cache_root: &PathBuf, uuid: &Uuid) -> Option<PathBuf> {
+        [
+            self.to_path(cache_root, uuid),
+            cache_root.join(self.to_string()),
+        ]
+        .into_iter()
+        .find(|p| p.is_dir() == true)
+    }
 }
 
 use std::fmt::Display;
@@ -1,16 +1,23 @@
 use crate::core::manifest::FromFile;
+use crate::core::credential::{Credential, Credentials};
+use crate::core::rewrite::{Rewrite, Rewrites};
+use crate::core::hook::{Event, Hook, Hooks};
 use crate::core::plugin::{Plugin, Plugins};
 use crate::core::protocol::Protocol;
 use crate::core::protocol::Protocols;
+use crate::core::template::{Template, Templates};
+use crate::core::vendor::DEFAULT_VENDOR_INDEX_TTL;
 use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
 use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
+use crate::util::logger;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::path::PathBuf;
 use std::str::FromStr;
-use crate::core::lang::vhdl::format::VhdlFormat;
+use crate::core::lang::vhdl::format::{Codegen, VhdlFormat};
 
 use serde_derive::{Deserialize, Serialize};
 use toml_edit::Document;
@@ -33,7 +40,6 @@ impl FromStr for ConfigDocument {
 }
 
 const INCLUDE_KEY: &str = "include";
-use crate::util::anyerror::Fault;
 use toml_edit::Array;
 use toml_edit::Formatted;
 use toml_edit::Item;
@@ -148,11 +154,16 @@ impl FromFile for ConfigDocument {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Locality {
+    Environment,
     Global,
     Local,
     Other,
 }
 
+/// The tables that can have their values overridden by environment variables
+/// named `ORBIT_<TABLE>_<KEY>`.
+const ENV_OVERRIDE_TABLES: [&str; 3] = ["general", "vhdl-format", "codegen"];
+
 #[derive(Debug, PartialEq)]
 pub struct Configs {
     inner: Vec<(PathBuf, Config, Locality)>,
@@ -194,6 +205,133 @@ impl Configs {
         Ok(Self { inner: configs })
     }
 
+    /// Loads every plugin defined in a `.toml` file directly within the directory `dir`.
+    ///
+    /// Each file defines a single plugin with the same fields as an entry in the
+    /// `[[plugin]]` array. The plugins are collected into one configuration at the
+    /// given locality `lvl`.
+    pub fn load_plugins(self, dir: PathBuf, lvl: Locality) -> Result<Self, Box<dyn Error>> {
+        let mut configs = self.inner;
+        let mut plugs = Vec::new();
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().map(|ext| ext == "toml").unwrap_or(false))
+            .collect();
+        files.sort();
+        let base = PathBuf::standardize(dir.clone());
+        for file in files {
+            let contents = std::fs::read_to_string(&file)?;
+            match Plugin::from_str(&contents) {
+                Ok(mut p) => {
+                    p.set_root(base.clone());
+                    p.set_origin(PathBuf::standardize(file.clone()));
+                    plugs.push(p);
+                }
+                Err(e) => {
+                    return Err(AnyError(format!(
+                        "failed to parse \"{}\" file: {}",
+                        file.display(),
+                        e
+                    )))?
+                }
+            }
+        }
+        let mut cfg = Config::new();
+        cfg.plugin = Some(plugs);
+        configs.push((base, cfg, lvl));
+        Ok(Self { inner: configs })
+    }
+
+    /// Loads the configuration values set by environment variables within `vars`.
+    ///
+    /// A variable named `ORBIT_<TABLE>_<KEY>` sets the value for `KEY` under `TABLE`,
+    /// where both names are uppercase with '-' replaced by '_'. For example,
+    /// `ORBIT_GENERAL_BUILD_DIR` overrides `build-dir` in the `[general]` table.
+    ///
+    /// A variable naming an unknown field is skipped with a warning.
+    pub fn load_env<I: Iterator<Item = (String, String)>>(self, vars: I) -> Result<Self, Box<dyn Error>> {
+        let mut configs = self.inner;
+        let mut root = toml::Table::new();
+        for (key, value) in vars {
+            for table in ENV_OVERRIDE_TABLES {
+                let prefix = format!("ORBIT_{}_", table.to_ascii_uppercase().replace('-', "_"));
+                let field = match key.strip_prefix(&prefix) {
+                    Some(f) if f.is_empty() == false => f.to_ascii_lowercase().replace('_', "-"),
+                    _ => continue,
+                };
+                let value = match Self::coerce_env_value(table, &field, &value) {
+                    Ok(Some(v)) => v,
+                    Ok(None) => {
+                        logger::warn(format!(
+                            "skipping environment variable '{}' because '{}' is not a field of the [{}] configuration table",
+                            key, field, table
+                        ));
+                        continue;
+                    }
+                    Err(e) => {
+                        return Err(AnyError(format!(
+                            "failed to set configuration from environment variable '{}': {}",
+                            key, e
+                        )))?
+                    }
+                };
+                root.entry(table)
+                    .or_insert(toml::Value::Table(toml::Table::new()))
+                    .as_table_mut()
+                    .unwrap()
+                    .insert(field, value);
+            }
+        }
+        if root.is_empty() == false {
+            let cfg: Config = match toml::Value::Table(root).try_into() {
+                Ok(c) => c,
+                Err(e) => {
+                    return Err(AnyError(format!(
+                        "failed to set configuration from environment variables: {}",
+                        e
+                    )))?
+                }
+            };
+            configs.push((PathBuf::new(), cfg, Locality::Environment));
+        }
+        Ok(Self { inner: configs })
+    }
+
+    /// Converts the `text` of an environment variable into the type expected by
+    /// `field` within `table`.
+    ///
+    /// The text is first read as a toml value, such as `true` or `4`, and is
+    /// otherwise kept as a string, so a string field accepts any text. Returns
+    /// `None` if the table has no such field.
+    fn coerce_env_value(table: &str, field: &str, text: &str) -> Result<Option<toml::Value>, String> {
+        let check = |value: &toml::Value| {
+            let mut inner = toml::Table::new();
+            inner.insert(field.to_string(), value.clone());
+            let mut outer = toml::Table::new();
+            outer.insert(table.to_string(), toml::Value::Table(inner));
+            toml::Value::Table(outer)
+                .try_into::<Config>()
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        };
+        let mut candidates = Vec::new();
+        if let Ok(mut t) = toml::from_str::<toml::Table>(&format!("v = {}", text)) {
+            candidates.push(t.remove("v").unwrap());
+        }
+        candidates.push(toml::Value::String(text.to_string()));
+
+        let mut error = String::new();
+        for value in candidates {
+            match check(&value) {
+                Ok(()) => return Ok(Some(value)),
+                Err(e) if e.contains("unknown field") => return Ok(None),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+
     pub fn get_plugins(&self) -> HashMap<&str, &Plugin> {
         // iterate through all linked configs
         let mut map = HashMap::new();
@@ -212,6 +350,66 @@ impl Configs {
         map
     }
 
+    /// Applies the profile called `name` from every configuration that defines it.
+    ///
+    /// A profile's values take precedence over the values of the configuration
+    /// that defines it. Errors if no configuration defines the profile.
+    pub fn select_profile(self, name: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let name = match name {
+            Some(n) => n,
+            None => return Ok(self),
+        };
+        let mut configs = Vec::new();
+        let mut found = false;
+        for (path, mut cfg, lvl) in self.inner {
+            if let Some(prof) = cfg.profile.as_mut().and_then(|p| p.remove(name)) {
+                configs.push((path.clone(), prof, lvl.clone()));
+                found = true;
+            }
+            configs.push((path, cfg, lvl));
+        }
+        if found == false {
+            return Err(AnyError(format!(
+                "no profile named '{}' is defined in any configuration\n\nTry `orbit config --list` to see the defined profiles",
+                name
+            )))?;
+        }
+        Ok(Self { inner: configs })
+    }
+
+    /// References the configurations in their order of precedence (highest first).
+    pub fn get_precedence(&self) -> Vec<&(PathBuf, Config, Locality)> {
+        let rank = |lvl: &Locality| match lvl {
+            Locality::Environment => 0,
+            Locality::Local => 1,
+            Locality::Global => 2,
+            Locality::Other => 3,
+        };
+        let mut list: Vec<&(PathBuf, Config, Locality)> = self.inner.iter().collect();
+        // stable sort keeps configurations of the same locality in the order they were read
+        list.sort_by_key(|(_, _, lvl)| rank(lvl));
+        list
+    }
+
+    /// Collects every entry defined across the configurations as a flattened key
+    /// along with its value and the configuration that defines it.
+    ///
+    /// Only the entry with the highest precedence is kept for each key.
+    pub fn get_entries(&self) -> Result<Vec<(String, toml::Value, &PathBuf, &Locality)>, Fault> {
+        let mut entries: Vec<(String, toml::Value, &PathBuf, &Locality)> = Vec::new();
+        for (path, cfg, lvl) in self.get_precedence() {
+            let mut flat = Vec::new();
+            flatten_value(String::new(), toml::Value::try_from(cfg)?, &mut flat);
+            for (key, value) in flat {
+                if entries.iter().any(|e| e.0 == key) == false {
+                    entries.push((key, value, path, lvl));
+                }
+            }
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
     pub fn get_global(&self) -> (&PathBuf, &Config) {
         let cfg = &self
             .inner
@@ -223,13 +421,49 @@ impl Configs {
     }
 }
 
+/// Joins the table `prefix` and the `key` with a '.'.
+fn join_key(prefix: &str, key: &str) -> String {
+    match prefix.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", prefix, key),
+    }
+}
+
+/// Identifies an entry within an array of tables by its `name` field, otherwise
+/// by its position `i` in the array.
+fn array_entry_key(value: &toml::Value, i: usize) -> String {
+    match value.get("name").and_then(|n| n.as_str()) {
+        Some(name) => name.to_string(),
+        None => i.to_string(),
+    }
+}
+
+/// Flattens nested tables of `value` into a list of dotted keys and their values.
+fn flatten_value(prefix: String, value: toml::Value, list: &mut Vec<(String, toml::Value)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, val) in table {
+                flatten_value(join_key(&prefix, &key), val, list);
+            }
+        }
+        toml::Value::Array(arr) if arr.is_empty() == false && arr.iter().all(|v| v.is_table()) => {
+            for (i, val) in arr.into_iter().enumerate() {
+                let key = join_key(&prefix, &array_entry_key(&val, i));
+                list.push((key, val));
+            }
+        }
+        val => list.push((prefix, val)),
+    }
+}
+
 impl From<Configs> for Config {
     /// Transform the multi-layered configurations into a single level.
     /// 
     /// This function processes configurations in the following order:
-    /// 1. LOCAL
-    /// 2. GLOBAL
-    /// 3. INCLUDES (first to last)
+    /// 1. ENVIRONMENT
+    /// 2. LOCAL
+    /// 3. GLOBAL
+    /// 4. INCLUDES (first to last)
     /// 
     /// Once a value is set (not None), then it will not be overridden by any
     /// configuration file later in the processing order. The processing order is
@@ -237,9 +471,13 @@ impl From<Configs> for Config {
     fn from(value: Configs) -> Self {
         let mut single = Config::new();
         let mut value = value;
-        // process local file
-        let local = value.inner.iter().position(|p| p.2 == Locality::Local);
-        if let Some(i) = local {
+        // process environment variable overrides
+        let env = value.inner.iter().position(|p| p.2 == Locality::Environment);
+        if let Some(i) = env {
+            single.append(value.inner.remove(i).1);
+        }
+        // process local files in the order they were read
+        while let Some(i) = value.inner.iter().position(|p| p.2 == Locality::Local) {
             single.append(value.inner.remove(i).1);
         }
         // process global file
@@ -260,17 +498,128 @@ impl From<Configs> for Config {
 pub struct General {
     #[serde(rename = "build-dir")]
     build_dir: Option<String>,
+    #[serde(rename = "default-plugin")]
+    default_plugin: Option<String>,
+    #[serde(rename = "plugin-alias")]
+    plugin_alias: Option<HashMap<String, String>>,
+    editor: Option<String>,
+    #[serde(rename = "deny-collisions")]
+    deny_collisions: Option<bool>,
+    #[serde(rename = "ignore-file")]
+    ignore_file: Option<PathBuf>,
+    #[serde(rename = "allow-install-hooks")]
+    allow_install_hooks: Option<Vec<String>>,
+    workspaces: Option<Vec<PathBuf>>,
+    #[serde(rename = "vendor-index")]
+    vendor_index: Option<String>,
+    #[serde(rename = "vendor-index-ttl")]
+    vendor_index_ttl: Option<u64>,
+    #[serde(rename = "init-submodules")]
+    init_submodules: Option<bool>,
 }
 
 impl General {
     pub fn new() -> Self {
         Self {
-            build_dir: None
+            build_dir: None,
+            default_plugin: None,
+            plugin_alias: None,
+            editor: None,
+            deny_collisions: None,
+            ignore_file: None,
+            allow_install_hooks: None,
+            workspaces: None,
+            vendor_index: None,
+            vendor_index_ttl: None,
+            init_submodules: None,
         }
     }
 
     pub fn get_build_dir(&self) -> String {
-        self.build_dir.as_ref().unwrap_or(&String::from("build")).clone()
+        self.build_dir
+            .as_ref()
+            .unwrap_or(&String::from(DEFAULT_BUILD_DIR))
+            .clone()
+    }
+
+    /// References the plugin to use when one is not specified on the command-line.
+    pub fn get_default_plugin(&self) -> Option<&str> {
+        self.default_plugin.as_ref().map(|p| p.as_str())
+    }
+
+    /// References the text editor to open files with.
+    pub fn get_editor(&self) -> Option<&str> {
+        self.editor.as_ref().map(|e| e.as_str())
+    }
+
+    /// Checks if name collisions found before a launch are errors rather than
+    /// warnings.
+    pub fn is_collision_denied(&self) -> bool {
+        self.deny_collisions.unwrap_or(false)
+    }
+
+    /// References the names or uuids of the ip allowed to run the post-install hook
+    /// declared in their manifest.
+    pub fn get_allowed_install_hooks(&self) -> Vec<&str> {
+        match &self.allow_install_hooks {
+            Some(list) => list.iter().map(|n| n.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// References the file of ignore patterns applied to every ip, if any.
+    pub fn get_ignore_file(&self) -> Option<&PathBuf> {
+        self.ignore_file.as_ref()
+    }
+
+    /// References the directories to scan for ip in development.
+    pub fn get_workspaces(&self) -> Vec<&PathBuf> {
+        match &self.workspaces {
+            Some(list) => list.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// References the url of the zip archive of a vendor index to search and
+    /// install ip from, if any.
+    pub fn get_vendor_index(&self) -> Option<&str> {
+        self.vendor_index.as_ref().map(|u| u.as_str())
+    }
+
+    /// Returns the number of hours a fetched vendor index is used before it is
+    /// fetched again.
+    pub fn get_vendor_index_ttl(&self) -> u64 {
+        self.vendor_index_ttl.unwrap_or(DEFAULT_VENDOR_INDEX_TTL)
+    }
+
+    /// Checks if the git submodules of a downloaded or cloned ip are initialized.
+    pub fn get_init_submodules(&self) -> bool {
+        self.init_submodules.unwrap_or(false)
+    }
+
+    /// Resolves a relative ignore file and workspaces to be relative to the
+    /// directory `base` of the configuration file that defined them.
+    fn set_root(&mut self, base: &PathBuf) {
+        let resolve = |path: &PathBuf| match path.is_relative() == true && path.starts_with("~") == false {
+            true => PathBuf::standardize(base.join(path)),
+            false => PathBuf::standardize(path),
+        };
+        if let Some(file) = &self.ignore_file {
+            self.ignore_file = Some(resolve(file));
+        }
+        if let Some(list) = &self.workspaces {
+            self.workspaces = Some(list.iter().map(|p| resolve(p)).collect());
+        }
+    }
+
+    /// Resolves the plugin alias `name` into the plugin's full name.
+    ///
+    /// Returns `name` if it is not an alias.
+    pub fn resolve_plugin_alias<'a>(&'a self, name: &'a str) -> &'a str {
+        match self.plugin_alias.as_ref().and_then(|m| m.get(name)) {
+            Some(full) => full.as_str(),
+            None => name,
+        }
     }
 
     /// Merges any populated data from `rhs` into attributes that do not already
@@ -281,20 +630,103 @@ impl General {
             if self.build_dir.is_some() == false {
                 self.build_dir = rhs.build_dir
             }
+            if self.default_plugin.is_some() == false {
+                self.default_plugin = rhs.default_plugin
+            }
+            if self.editor.is_some() == false {
+                self.editor = rhs.editor
+            }
+            if self.deny_collisions.is_some() == false {
+                self.deny_collisions = rhs.deny_collisions
+            }
+            if self.ignore_file.is_some() == false {
+                self.ignore_file = rhs.ignore_file
+            }
+            if self.vendor_index.is_some() == false {
+                self.vendor_index = rhs.vendor_index
+            }
+            if self.vendor_index_ttl.is_some() == false {
+                self.vendor_index_ttl = rhs.vendor_index_ttl
+            }
+            if self.init_submodules.is_some() == false {
+                self.init_submodules = rhs.init_submodules
+            }
+            // combine the ip allowed to run post-install hooks
+            match &mut self.allow_install_hooks {
+                Some(v) => v.append(&mut rhs.allow_install_hooks.unwrap_or(Vec::new())),
+                None => self.allow_install_hooks = rhs.allow_install_hooks,
+            }
+            // combine the workspaces scanned for ip in development
+            match &mut self.workspaces {
+                Some(v) => v.append(&mut rhs.workspaces.unwrap_or(Vec::new())),
+                None => self.workspaces = rhs.workspaces,
+            }
+            // combine aliases, keeping any alias already defined
+            match &mut self.plugin_alias {
+                Some(v) => {
+                    for (key, val) in rhs.plugin_alias.unwrap_or(HashMap::new()) {
+                        if v.contains_key(&key) == false {
+                            v.insert(key, val);
+                        }
+                    }
+                }
+                None => self.plugin_alias = rhs.plugin_alias,
+            }
         }
     }
 }
 
 pub const CONFIG_FILE: &str = "config.toml";
 
+/// The build directory used when `build-dir` is not set, which separates the
+/// outputs of each plugin.
+pub const DEFAULT_BUILD_DIR: &str = "build/{{ orbit.plugin }}";
+
+/// The commented contents written to a new configuration file.
+pub const DEFAULT_CONFIG: &str = "\
+# Orbit configuration file
+# See https://c-rus.github.io/orbit/reference/configuration.html for all fields.
+
+# include = []
+
+# [general]
+# build-dir = \"build/{{ orbit.plugin }}\"
+# default-plugin = \"\"
+# editor = \"\"
+# ignore-file = \"\"
+# allow-install-hooks = []
+# workspaces = []
+# vendor-index = \"\"
+# vendor-index-ttl = 24
+# init-submodules = false
+
+# [env]
+# key = \"value\"
+
+# [variables]
+# org.author = \"\"
+
+# [[plugin]]
+# name = \"\"
+# command = \"\"
+# args = []
+";
+
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
 pub struct Config {
     include: Option<Vec<PathBuf>>,
     env: Option<HashMap<String, String>>,
+    variables: Option<toml::Table>,
     plugin: Option<Plugins>,
     protocol: Option<Protocols>,
+    template: Option<Templates>,
+    hook: Option<Hooks>,
+    credential: Option<Credentials>,
+    rewrite: Option<Rewrites>,
+    profile: Option<HashMap<String, Config>>,
     #[serde(rename="vhdl-format")]
     vhdl_format: Option<VhdlFormat>,
+    codegen: Option<Codegen>,
     general: Option<General>,
 }
 
@@ -303,9 +735,16 @@ impl Config {
         Self {
             include: None,
             env: None,
+            variables: None,
             plugin: None,
             protocol: None,
+            template: None,
+            hook: None,
+            credential: None,
+            rewrite: None,
+            profile: None,
             vhdl_format: None,
+            codegen: None,
             general: None,
         }
     }
@@ -347,11 +786,21 @@ impl Config {
             },
             None => self.env = rhs.env,
         }
+        // combine '[variables]' table
+        match &mut self.variables {
+            Some(v) => Self::merge_tables(v, rhs.variables.unwrap_or(toml::Table::new())),
+            None => self.variables = rhs.variables,
+        }
         // combine '[vhdl-format]' table
         match &mut self.vhdl_format {
             Some(v) => v.merge(rhs.vhdl_format),
             None => self.vhdl_format = rhs.vhdl_format
         }
+        // combine '[codegen]' table
+        match &mut self.codegen {
+            Some(v) => v.merge(rhs.codegen),
+            None => self.codegen = rhs.codegen,
+        }
         // combine '[[plugin]]' array
         match &mut self.plugin {
             Some(v) => v.append(&mut rhs.plugin.unwrap_or(Vec::new())),
@@ -362,6 +811,75 @@ impl Config {
             Some(v) => v.append(&mut rhs.protocol.unwrap_or(Vec::new())),
             None => self.protocol = rhs.protocol,
         }
+        // combine '[[template]]' array
+        match &mut self.template {
+            Some(v) => v.append(&mut rhs.template.unwrap_or(Vec::new())),
+            None => self.template = rhs.template,
+        }
+        // combine '[[hook]]' array
+        match &mut self.hook {
+            Some(v) => v.append(&mut rhs.hook.unwrap_or(Vec::new())),
+            None => self.hook = rhs.hook,
+        }
+        // combine '[[credential]]' array
+        match &mut self.credential {
+            Some(v) => v.append(&mut rhs.credential.unwrap_or(Vec::new())),
+            None => self.credential = rhs.credential,
+        }
+        // combine '[[rewrite]]' array
+        match &mut self.rewrite {
+            Some(v) => v.append(&mut rhs.rewrite.unwrap_or(Vec::new())),
+            None => self.rewrite = rhs.rewrite,
+        }
+        // combine '[profile]' table
+        match &mut self.profile {
+            Some(v) => {
+                for (key, val) in rhs.profile.unwrap_or(HashMap::new()) {
+                    if v.contains_key(&key) == false {
+                        v.insert(key, val);
+                    }
+                }
+            }
+            None => self.profile = rhs.profile,
+        }
+    }
+
+    /// Adds the entries from `rhs` that are not already defined in `lhs`, descending
+    /// into tables defined in both.
+    fn merge_tables(lhs: &mut toml::Table, rhs: toml::Table) {
+        for (key, val) in rhs {
+            match (lhs.get_mut(&key), val) {
+                (Some(toml::Value::Table(l)), toml::Value::Table(r)) => Self::merge_tables(l, r),
+                (Some(_), _) => (),
+                (None, r) => {
+                    lhs.insert(key, r);
+                }
+            }
+        }
+    }
+
+    /// Accesses the value at the dotted `key`, where each '.' delimits a table.
+    ///
+    /// Entries within an array of tables are accessed by their `name` field, such
+    /// as `plugin.ghdl`, or by their position in the array.
+    pub fn get_value(&self, key: &str) -> Result<Option<toml::Value>, Fault> {
+        let mut value = toml::Value::try_from(self)?;
+        for part in key.split('.') {
+            let next = match value {
+                toml::Value::Table(mut table) => table.remove(part),
+                toml::Value::Array(arr) => arr
+                    .into_iter()
+                    .enumerate()
+                    .find(|(i, v)| array_entry_key(v, *i) == part)
+                    .map(|(_, v)| v),
+                _ => None,
+            };
+            value = match next {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(value))
     }
 
     pub fn get_includes(&self) -> Vec<&PathBuf> {
@@ -386,10 +904,54 @@ impl Config {
         map
     }
 
+    /// Finds the plugin called `name`, which may be an alias defined under
+    /// `[general.plugin-alias]`.
+    pub fn find_plugin(&self, name: &str) -> Option<&Plugin> {
+        let name = match &self.general {
+            Some(g) => g.resolve_plugin_alias(name),
+            None => name,
+        };
+        self.get_plugins().get(name).map(|p| *p)
+    }
+
+    /// References the plugin to use when one is not specified on the command-line.
+    pub fn get_default_plugin(&self) -> Option<&str> {
+        self.general.as_ref().and_then(|g| g.get_default_plugin())
+    }
+
     pub fn get_env(&self) -> &Option<HashMap<String, String>> {
         &self.env
     }
 
+    /// Collects the user-defined variables from the `[variables]` table.
+    ///
+    /// Nested tables are flattened into keys joined by '.', such that
+    /// `[variables.org] author = "..."` is stored as `org.author`.
+    pub fn get_variables(&self) -> HashMap<String, String> {
+        fn flatten(prefix: &str, table: &toml::Table, map: &mut HashMap<String, String>) {
+            for (key, val) in table {
+                let key = match prefix.is_empty() {
+                    true => key.to_string(),
+                    false => format!("{}.{}", prefix, key),
+                };
+                match val {
+                    toml::Value::Table(t) => flatten(&key, t, map),
+                    toml::Value::String(s) => {
+                        map.insert(key, s.to_string());
+                    }
+                    v => {
+                        map.insert(key, v.to_string());
+                    }
+                }
+            }
+        }
+        let mut map = HashMap::new();
+        if let Some(vars) = &self.variables {
+            flatten("", vars, &mut map);
+        }
+        map
+    }
+
     pub fn get_protocols(&self) -> HashMap<&str, &Protocol> {
         let mut map = HashMap::new();
 
@@ -405,13 +967,142 @@ impl Config {
         map
     }
 
+    pub fn get_templates(&self) -> HashMap<&str, &Template> {
+        let mut map = HashMap::new();
+
+        if let Some(temps) = &self.template {
+            temps.iter().for_each(|t| match map.get(t.get_name()) {
+                Some(_) => (),
+                None => {
+                    map.insert(t.get_name(), t);
+                    ()
+                }
+            });
+        }
+        map
+    }
+
+    /// Sets the roots for the ignore file, plugins, protocols, templates, hooks, and
+    /// credentials, including those within profiles, to the parent directory of
+    /// the configuration `file`.
+    fn set_roots(&mut self, file: &PathBuf) {
+        let base = file.parent().unwrap().to_path_buf();
+        if let Some(general) = &mut self.general {
+            general.set_root(&base);
+        }
+        if let Some(protos) = &mut self.protocol {
+            protos.iter_mut().for_each(|p| {
+                p.set_root(base.clone());
+            });
+        }
+        if let Some(plugs) = &mut self.plugin {
+            plugs.iter_mut().for_each(|p| {
+                p.set_root(base.clone());
+                p.set_origin(file.clone());
+            });
+        }
+        if let Some(temps) = &mut self.template {
+            temps.iter_mut().for_each(|t| {
+                t.set_root(base.clone());
+            });
+        }
+        if let Some(hooks) = &mut self.hook {
+            hooks.iter_mut().for_each(|h| {
+                h.set_root(base.clone());
+            });
+        }
+        if let Some(creds) = &mut self.credential {
+            creds.iter_mut().for_each(|c| {
+                c.set_root(base.clone());
+            });
+        }
+        if let Some(profiles) = &mut self.profile {
+            profiles.values_mut().for_each(|p| {
+                p.set_roots(file);
+            });
+        }
+    }
+
+    /// Collects every hook bound to the `event` in the order they were defined.
+    pub fn get_hooks(&self, event: Event) -> Vec<&Hook> {
+        match &self.hook {
+            Some(hooks) => hooks.iter().filter(|h| h.get_event() == &event).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Collects every credential in order of precedence, where the first
+    /// credential for a host is the one used.
+    pub fn get_credentials(&self) -> Vec<&Credential> {
+        match &self.credential {
+            Some(creds) => creds.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Collects every url rewrite, where the rewrite with the longest matching
+    /// prefix is the one used.
+    pub fn get_rewrites(&self) -> Vec<&Rewrite> {
+        match &self.rewrite {
+            Some(rules) => rules.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn get_vhdl_formatting(&self) -> Option<&VhdlFormat> {
         self.vhdl_format.as_ref()
     }
 
+    pub fn get_codegen(&self) -> Option<&Codegen> {
+        self.codegen.as_ref()
+    }
+
     pub fn get_general(&self) -> Option<&General> {
         self.general.as_ref()
     }
+
+    /// Collects the names or uuids of the ip allowed to run their post-install hook.
+    pub fn get_allowed_install_hooks(&self) -> Vec<&str> {
+        match &self.general {
+            Some(g) => g.get_allowed_install_hooks(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Collects the directories to scan for ip in development.
+    pub fn get_workspaces(&self) -> Vec<&PathBuf> {
+        match &self.general {
+            Some(g) => g.get_workspaces(),
+            None => Vec::new(),
+        }
+    }
+
+    /// References the file of ignore patterns applied to the working ip, if any.
+    pub fn get_ignore_file(&self) -> Option<&PathBuf> {
+        self.general.as_ref().and_then(|g| g.get_ignore_file())
+    }
+
+    /// References the url of the vendor index to search and install ip from, if any.
+    pub fn get_vendor_index(&self) -> Option<&str> {
+        self.general.as_ref().and_then(|g| g.get_vendor_index())
+    }
+
+    /// Returns the number of hours a fetched vendor index is used before it is
+    /// fetched again.
+    pub fn get_vendor_index_ttl(&self) -> u64 {
+        match &self.general {
+            Some(g) => g.get_vendor_index_ttl(),
+            None => DEFAULT_VENDOR_INDEX_TTL,
+        }
+    }
+
+    /// Checks if the git submodules of a downloaded or cloned ip are initialized.
+    pub fn get_init_submodules(&self) -> bool {
+        match &self.general {
+            Some(g) => g.get_init_submodules(),
+            None => false,
+        }
+    }
 }
 
 impl FromStr for Config {
@@ -436,18 +1127,15 @@ impl FromFile for Config {
         // parse toml syntax
         match Self::from_str(&contents) {
             Ok(mut r) => {
-                // set roots for plugins and protocols
-                let base = PathBuf::standardize(path).parent().unwrap().to_path_buf();
-                if let Some(protos) = &mut r.protocol {
-                    protos.iter_mut().for_each(|p| {
-                        p.set_root(base.clone());
-                    });
-                }
-                if let Some(plugs) = &mut r.plugin {
-                    plugs.iter_mut().for_each(|p| {
-                        p.set_root(base.clone());
-                    });
+                // every template must be found at exactly one location
+                if let Some(e) = r.template.iter().flatten().find_map(|t| t.validate().err()) {
+                    return Err(AnyError(format!(
+                        "failed to load \"{}\" file: {}",
+                        path.display(),
+                        e
+                    )))?;
                 }
+                r.set_roots(&PathBuf::standardize(path));
                 Ok(r)
             }
             // enter a blank lock file if failed (do not exit)
@@ -496,6 +1184,11 @@ name = "kstp"
 command = "python"
 args = ["./download.py"]
 
+[[template]]
+name = "base"
+path = "./templates/base"
+summary = "standard project structure with rtl and sim folders"
+
 [vhdl-format]
 tab-size = 3
 "#;
@@ -528,4 +1221,181 @@ tab-size = 3
             .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
             .unwrap();
     }
+
+    #[test]
+    fn local_plugins() {
+        let cfg: Config = Configs::new()
+            .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
+            .unwrap()
+            .load_plugins(PathBuf::from("./tests/data/plugins"), Locality::Local)
+            .unwrap()
+            .into();
+        assert_eq!(cfg.get_plugins().contains_key("ghdl"), true);
+    }
+
+    #[test]
+    fn env_overrides() {
+        let vars = vec![
+            ("ORBIT_GENERAL_BUILD_DIR", "target"),
+            ("ORBIT_VHDL_FORMAT_TAB_SIZE", "4"),
+            ("ORBIT_ENV_FOO", "bar"),
+            ("ORBIT_HOME", "/tmp"),
+        ];
+        let cfg: Config = Configs::new()
+            .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
+            .unwrap()
+            .load_env(vars.into_iter().map(|(k, v)| (k.to_string(), v.to_string())))
+            .unwrap()
+            .into();
+        assert_eq!(cfg.get_general().unwrap().get_build_dir(), "target");
+        assert_eq!(cfg.get_vhdl_formatting().unwrap().get_tab_size(), 4);
+
+        // unknown keys are skipped rather than failing every command
+        let vars = vec![(String::from("ORBIT_GENERAL_BUILD_DIRECTORY"), String::from("out"))];
+        assert_eq!(Configs::new().load_env(vars.into_iter()).unwrap(), Configs::new());
+
+        // a string field keeps a value that looks like a number
+        let vars = vec![(String::from("ORBIT_GENERAL_BUILD_DIR"), String::from("2024"))];
+        let cfg: Config = Configs::new().load_env(vars.into_iter()).unwrap().into();
+        assert_eq!(cfg.get_general().unwrap().get_build_dir(), "2024");
+
+        // a value of the wrong type for a known field is still an error
+        let vars = vec![(String::from("ORBIT_VHDL_FORMAT_TAB_SIZE"), String::from("wide"))];
+        assert!(Configs::new().load_env(vars.into_iter()).is_err());
+    }
+
+    #[test]
+    fn get_entries() {
+        let cfgs = Configs::new()
+            .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
+            .unwrap()
+            .load_plugins(PathBuf::from("./tests/data/plugins"), Locality::Local)
+            .unwrap();
+        let entries = cfgs.get_entries().unwrap();
+        let (_, value, path, lvl) = entries.iter().find(|e| e.0 == "env.key").unwrap();
+        assert_eq!(value, &toml::Value::String(String::from("1")));
+        assert_eq!(path.file_name().unwrap(), "config1.toml");
+        assert_eq!(lvl, &&Locality::Global);
+        let (_, _, _, lvl) = entries.iter().find(|e| e.0 == "plugin.ghdl").unwrap();
+        assert_eq!(lvl, &&Locality::Local);
+
+        let cfg: Config = cfgs.into();
+        assert_eq!(
+            cfg.get_value("env.key").unwrap(),
+            Some(toml::Value::String(String::from("1")))
+        );
+        assert_eq!(
+            cfg.get_value("plugin.ghdl.command").unwrap(),
+            Some(toml::Value::String(String::from("python")))
+        );
+        assert_eq!(cfg.get_value("env.missing").unwrap(), None);
+    }
+
+    #[test]
+    fn select_profile() {
+        let cfgs = Configs {
+            inner: vec![(
+                PathBuf::from("./config.toml"),
+                Config::from_str(
+                    r#"
+[general]
+build-dir = "build"
+
+[profile.ci.general]
+build-dir = "target"
+
+[profile.ci.env]
+vivado-path = "/opt/vivado"
+"#,
+                )
+                .unwrap(),
+                Locality::Global,
+            )],
+        };
+        let cfg: Config = cfgs.select_profile(Some("ci")).unwrap().into();
+        assert_eq!(cfg.get_general().unwrap().get_build_dir(), "target");
+        assert_eq!(cfg.get_env().as_ref().unwrap().get("vivado-path").unwrap(), "/opt/vivado");
+
+        let cfgs = Configs::new()
+            .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
+            .unwrap();
+        assert!(cfgs.select_profile(Some("lab")).is_err());
+    }
+
+    #[test]
+    fn default_config() {
+        assert_eq!(Config::from_str(DEFAULT_CONFIG).unwrap(), Config::new());
+    }
+
+    #[test]
+    fn ignore_file_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(CONFIG_FILE);
+        std::fs::write(&file, "[general]\nignore-file = \"orbitignore\"\n").unwrap();
+        let cfg = Config::from_file(&file).unwrap();
+        // relative paths are relative to the configuration file
+        assert_eq!(
+            cfg.get_general().unwrap().get_ignore_file(),
+            Some(&PathBuf::standardize(dir.path().join("orbitignore")))
+        );
+    }
+
+    #[test]
+    fn vendor_index_ttl() {
+        let mut cfg = Config::from_str("[general]\nvendor-index = \"https://a.com/vendor.zip\"\n").unwrap();
+        assert_eq!(cfg.get_vendor_index_ttl(), DEFAULT_VENDOR_INDEX_TTL);
+        cfg.append(
+            Config::from_str("[general]\nvendor-index = \"https://b.com/vendor.zip\"\nvendor-index-ttl = 0\n")
+                .unwrap(),
+        );
+        // the first url is kept while the unset time-to-live is filled in
+        assert_eq!(cfg.get_vendor_index(), Some("https://a.com/vendor.zip"));
+        assert_eq!(cfg.get_vendor_index_ttl(), 0);
+    }
+
+    #[test]
+    fn plugin_alias() {
+        let mut cfg = Config::from_str(C_1).unwrap();
+        cfg.append(
+            Config::from_str(
+                r#"
+[general]
+default-plugin = "q"
+plugin-alias.q = "quartus"
+"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(cfg.get_default_plugin(), Some("q"));
+        assert_eq!(cfg.find_plugin("q").unwrap().get_alias(), "quartus");
+        assert_eq!(cfg.find_plugin("quartus").unwrap().get_alias(), "quartus");
+        assert_eq!(cfg.find_plugin("ghdl"), None);
+    }
+
+    #[test]
+    fn user_variables() {
+        let mut cfg = Config::from_str(
+            r#"
+[variables]
+org.author = "Jane Doe"
+year = 2024
+"#,
+        )
+        .unwrap();
+        cfg.append(
+            Config::from_str(
+                r#"
+[variables.org]
+author = "John Doe"
+copyright = "Copyright (c) Acme"
+"#,
+            )
+            .unwrap(),
+        );
+        let vars = cfg.get_variables();
+        assert_eq!(vars.get("org.author").unwrap(), "Jane Doe");
+        assert_eq!(vars.get("org.copyright").unwrap(), "Copyright (c) Acme");
+        assert_eq!(vars.get("year").unwrap(), "2024");
+        assert_eq!(vars.len(), 3);
+    }
 }
@@ -1,15 +1,20 @@
 use crate::core::config::{Config, Configs, Locality};
 use crate::core::plugin::Plugin;
+use crate::core::variable::{self, VariableTable};
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
+use crate::util::environment::ORBIT_LAYOUT;
+use crate::util::environment::ORBIT_PROFILE;
 use crate::util::environment::ORBIT_WIN_LITERAL_CMD;
 use crate::util::filesystem::Standardize;
+use crate::util::output::OutputFormat;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path;
 use crate::core::config::General;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 const CACHE_TAG_FILE: &str = "CACHEDIR.TAG";
 
@@ -19,10 +24,79 @@ Signature: 8a477f597d28d172789f06886806bc55
 # For information about cache directory tags see https://bford.info/cachedir/
 ";
 
+/// The arrangement of the directories that hold orbit's data.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Layout {
+    /// Every directory is placed within `ORBIT_HOME`.
+    Home,
+    /// Directories follow the platform's conventions: configuration under
+    /// `XDG_CONFIG_HOME` (`APPDATA` on windows) and the cache and downloads
+    /// under `XDG_CACHE_HOME` (`LOCALAPPDATA` on windows).
+    System,
+}
+
+impl FromStr for Layout {
+    type Err = ContextError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "home" => Ok(Self::Home),
+            "system" => Ok(Self::System),
+            _ => Err(ContextError(format!(
+                "unknown layout '{}' for {}; expected 'home' or 'system'",
+                s, ORBIT_LAYOUT
+            ))),
+        }
+    }
+}
+
+impl Layout {
+    /// Reads the layout from the ORBIT_LAYOUT environment variable, defaulting
+    /// to `Home` when it is not set.
+    pub fn from_env() -> Result<Self, ContextError> {
+        match env::var(ORBIT_LAYOUT) {
+            Ok(s) => Self::from_str(&s),
+            Err(_) => Ok(Self::Home),
+        }
+    }
+
+    /// Returns the platform's base directory for configuration files.
+    fn config_base() -> Option<PathBuf> {
+        if cfg!(target_os = "windows") {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            Self::xdg_base("XDG_CONFIG_HOME", ".config")
+        }
+    }
+
+    /// Returns the platform's base directory for non-essential cached data.
+    fn cache_base() -> Option<PathBuf> {
+        if cfg!(target_os = "windows") {
+            env::var_os("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            Self::xdg_base("XDG_CACHE_HOME", ".cache")
+        }
+    }
+
+    /// Reads the XDG variable `key`, falling back to `fallback` within the user's
+    /// home directory as defined by the XDG base directory specification.
+    fn xdg_base(key: &str, fallback: &str) -> Option<PathBuf> {
+        match env::var_os(key).map(PathBuf::from) {
+            // relative paths are invalid according to the specification
+            Some(p) if p.is_absolute() == true => Some(p),
+            _ => home::home_dir().map(|h| h.join(fallback)),
+        }
+    }
+}
+
 /// Shared attributes about the surrounding user run-time environment.
 pub struct Context {
     /// Path to a "hidden" directory for internal Orbit operations.
     home_path: PathBuf,
+    /// Directory holding the global configuration file.
+    config_path: PathBuf,
+    /// The arrangement of the directories outside of `home_path`.
+    layout: Layout,
     /// Directory holding installed immutable tags of git repositories.
     cache_path: PathBuf,
     /// Directory holding orbit IP downloaded
@@ -37,6 +111,8 @@ pub struct Context {
     all_configs: Configs,
     // @idea: optionally move hashmap out of context and create it from fn to allow dynamic loading
     plugins: HashMap<String, Plugin>,
+    /// The format to display the information reported by a command.
+    output_format: OutputFormat,
 }
 
 impl Context {
@@ -45,6 +121,8 @@ impl Context {
         let cache = home.join("cache");
         let downloads = home.join("downloads");
         Context {
+            config_path: home.clone(),
+            layout: Layout::Home,
             home_path: home,
             cache_path: cache,
             download_path: downloads,
@@ -53,12 +131,16 @@ impl Context {
             all_configs: Configs::new(),
             config: Config::new(),
             build_dir: String::new(),
+            output_format: OutputFormat::Text,
         }
     }
 
     /// Sets the home directory. By default this is `$HOME/.orbit`. If set by `var`,
     /// it must be an existing directory.
+    ///
+    /// The layout for the remaining directories is also read from ORBIT_LAYOUT.
     pub fn home(mut self, key: &str) -> Result<Context, ContextError> {
+        self.layout = Layout::from_env()?;
         self.home_path = if let Ok(s) = env::var(key) {
             std::path::PathBuf::from(s)
         } else {
@@ -138,27 +220,18 @@ impl Context {
     fn folder(&self, key: &str, folder: &str) -> Result<PathBuf, Fault> {
         // prioritize explicit variable setting
         let dir = if let Ok(s) = env::var(key) {
-            let ep = PathBuf::from(s);
-            // verify the path exists
-            if ep.exists() == false {
-                return Err(AnyError(format!(
-                    "directory {} does not exist for {}",
-                    ep.display(),
-                    key
-                )))?;
-            }
-            // verify the path is a directory
-            if ep.is_dir() == false {
-                return Err(AnyError(format!("{} must be a filesystem directory", key)))?;
-            }
-            ep
+            Self::existing_dir(key, s)?
         // proceed with default
         } else {
-            let ep = self.home_path.join(&folder);
+            let base = match self.layout {
+                Layout::Home => self.home_path.clone(),
+                Layout::System => Self::system_dir(Layout::cache_base())?,
+            };
+            let ep = base.join(&folder);
             // create the directory if does not exist
             if ep.exists() == false {
-                std::fs::create_dir(&ep)
-                    .expect(&format!("failed to create .orbit/{} directory", folder));
+                std::fs::create_dir_all(&ep)
+                    .expect(&format!("failed to create {} directory", ep.display()));
             }
             ep
         };
@@ -167,6 +240,58 @@ impl Context {
         Ok(dir)
     }
 
+    /// Sets the directory holding the global configuration file. If set by `key`,
+    /// it must be an existing directory. By default it is the home directory, or the
+    /// platform's configuration directory for the `System` layout.
+    pub fn config_home(mut self, key: &str) -> Result<Context, Fault> {
+        self.config_path = if let Ok(s) = env::var(key) {
+            Self::existing_dir(key, s)?
+        } else {
+            match self.layout {
+                Layout::Home => self.home_path.clone(),
+                Layout::System => {
+                    let ep = Self::system_dir(Layout::config_base())?;
+                    if ep.exists() == false {
+                        std::fs::create_dir_all(&ep)
+                            .expect(&format!("failed to create {} directory", ep.display()));
+                    }
+                    ep
+                }
+            }
+        };
+        env::set_var(key, &PathBuf::standardize(&self.config_path));
+        Ok(self)
+    }
+
+    /// Returns orbit's directory within the platform's `base` directory.
+    fn system_dir(base: Option<PathBuf>) -> Result<PathBuf, ContextError> {
+        match base {
+            Some(b) => Ok(b.join("orbit")),
+            None => Err(ContextError(format!(
+                "failed to detect the system directories; please unset the {} environment variable",
+                ORBIT_LAYOUT
+            ))),
+        }
+    }
+
+    /// Verifies the path `s` set for the variable `key` is an existing directory.
+    fn existing_dir(key: &str, s: String) -> Result<PathBuf, Fault> {
+        let ep = PathBuf::from(s);
+        // verify the path exists
+        if ep.exists() == false {
+            return Err(AnyError(format!(
+                "directory {} does not exist for {}",
+                ep.display(),
+                key
+            )))?;
+        }
+        // verify the path is a directory
+        if ep.is_dir() == false {
+            return Err(AnyError(format!("{} must be a filesystem directory", key)))?;
+        }
+        Ok(ep)
+    }
+
     /// References the cache directory.
     pub fn get_cache_path(&self) -> &PathBuf {
         &self.cache_path
@@ -180,14 +305,17 @@ impl Context {
     /// Configures and reads data from the settings object to return a `Settings` struct
     /// in the `Context`.
     ///
-    /// The settings file `name` must be directly under `$ORBIT_HOME`. It also
+    /// The settings file `name` must be directly under `$ORBIT_CONFIG_HOME`. It also
     /// checks for a local configuration as `name` under a .orbit/ directory if
     /// the command is invoked from within an ip directory.
     ///
     /// Note: the `self.ip_path` must already be determined before invocation.
     pub fn settings(mut self, name: &str) -> Result<Context, Fault> {
+        // check if a profile is selected
+        let profile = env::var(ORBIT_PROFILE).ok().filter(|p| p.is_empty() == false);
+
         // check if global file exists first
-        let global_file = self.home_path.join(name);
+        let global_file = self.config_path.join(name);
         if global_file.exists() == false {
             std::fs::write(&global_file, Vec::new())?;
         }
@@ -195,36 +323,45 @@ impl Context {
         // initialize and load the global configuration
         let cfg = Configs::new().load(global_file, Locality::Global)?;
         // if in ip, also look along current directory for a /.orbit/config.toml file to load (local configuration)
-        self.all_configs = if let Some(ip_dir) = self.get_ip_path() {
-            let local_path = ip_dir.join(".orbit").join(name);
-            if local_path.exists() == true {
-                cfg.load(local_path, Locality::Local)?
-            } else {
-                cfg
-            }
-        } else {
-            cfg
-        };
+        self.all_configs = self.load_local_settings(cfg, name)?
+            // apply the values from the selected profile
+            .select_profile(profile.as_deref())?
+            // override values with any ORBIT_<TABLE>_<KEY> environment variables
+            .load_env(env::vars())?;
 
         // @TODO: FIXME (clone?)
         // initialize and load the global configuration
-        let cfg = Configs::new().load(self.home_path.join(name), Locality::Global)?;
+        let cfg = Configs::new().load(self.config_path.join(name), Locality::Global)?;
         // if in ip, also look along current directory for a /.orbit/config.toml file to load (local configuration)
-        self.config = if let Some(ip_dir) = self.get_ip_path() {
-            let local_path = ip_dir.join(".orbit").join(name);
-            if local_path.exists() == true {
-                cfg.load(local_path, Locality::Local)?
-            } else {
-                cfg
-            }
-        } else {
-            cfg
-        }
-        .into();
-        // @todo: dynamically set from environment variables from configuration data
+        self.config = self
+            .load_local_settings(cfg, name)?
+            .select_profile(profile.as_deref())?
+            .load_env(env::vars())?
+            .into();
         Ok(self)
     }
 
+    /// Loads the local configuration `name` and any local plugins under the ip's
+    /// .orbit/ directory into `cfg`.
+    ///
+    /// Returns `cfg` unmodified if the command is not invoked from within an ip.
+    fn load_local_settings(&self, cfg: Configs, name: &str) -> Result<Configs, Fault> {
+        let ip_dir = match self.get_ip_path() {
+            Some(p) => p.join(".orbit"),
+            None => return Ok(cfg),
+        };
+        let local_path = ip_dir.join(name);
+        let cfg = match local_path.exists() {
+            true => cfg.load(local_path, Locality::Local)?,
+            false => cfg,
+        };
+        let plugins_path = ip_dir.join("plugins");
+        match plugins_path.is_dir() {
+            true => cfg.load_plugins(plugins_path, Locality::Local),
+            false => Ok(cfg),
+        }
+    }
+
     /// Access the configuration data.
     pub fn get_config(&self) -> &Config {
         &self.config
@@ -247,11 +384,31 @@ impl Context {
         }
     }
 
+    /// Access the build directory used with the plugin `alias`.
+    pub fn get_build_dir_for(&self, alias: Option<&str>) -> String {
+        resolve_build_dir(&self.get_build_dir(), alias)
+    }
+
+    /// Access the build directory used with the configured default plugin.
+    pub fn get_default_build_dir(&self) -> String {
+        let alias = self
+            .config
+            .get_default_plugin()
+            .and_then(|name| self.config.find_plugin(name))
+            .map(|p| p.get_alias());
+        self.get_build_dir_for(alias)
+    }
+
     /// Access the ip directory detected from the current working directory.
     pub fn get_ip_path(&self) -> Option<&path::PathBuf> {
         self.ip_path.as_ref()
     }
 
+    /// Access the directory holding the global configuration file.
+    pub fn get_config_home_path(&self) -> &path::PathBuf {
+        &self.config_path
+    }
+
     /// Access the home path.
     pub fn get_home_path(&self) -> &path::PathBuf {
         &self.home_path
@@ -335,13 +492,40 @@ impl Context {
         }
     }
 
+    /// Sets the format to display the information reported by a command.
+    pub fn output_format(mut self, fmt: OutputFormat) -> Self {
+        self.output_format = fmt;
+        self
+    }
+
+    pub fn get_output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
     /// Sets the IP's build directory and the corresponding environment variable.
     pub fn build_dir(self, s: &str) -> Result<Context, ContextError> {
-        env::set_var(s, &self.get_build_dir());
+        env::set_var(s, &self.get_build_dir_for(None));
         Ok(self)
     }
 }
 
+/// Replaces the `{{ orbit.plugin }}` variable in the build directory `dir` with
+/// the plugin `alias`.
+///
+/// Without a plugin, the variable is removed along with its empty path component
+/// so `build/{{ orbit.plugin }}` becomes `build`.
+pub fn resolve_build_dir(dir: &str, alias: Option<&str>) -> String {
+    let mut vtable = VariableTable::new();
+    vtable.add("orbit.plugin", alias.unwrap_or(""));
+    let dir = variable::substitute(dir.to_string(), &vtable);
+    // remove the empty components left from an unset plugin
+    let parts: Vec<&str> = dir.split('/').filter(|p| p.is_empty() == false).collect();
+    match dir.starts_with('/') {
+        true => format!("/{}", parts.join("/")),
+        false => parts.join("/"),
+    }
+}
+
 #[derive(Debug)]
 pub struct ContextError(String);
 
@@ -359,6 +543,23 @@ mod test {
 
     const HOME: &str = "./tests/env";
 
+    #[test]
+    fn resolve_build_dir_plugin() {
+        assert_eq!(resolve_build_dir("build/{{ orbit.plugin }}", Some("ghdl")), "build/ghdl");
+        assert_eq!(resolve_build_dir("build/{{ orbit.plugin }}", None), "build");
+        assert_eq!(resolve_build_dir("out/{{orbit.plugin}}/sim", None), "out/sim");
+        assert_eq!(resolve_build_dir("/tmp/{{ orbit.plugin }}", Some("vivado")), "/tmp/vivado");
+        assert_eq!(resolve_build_dir("target", Some("ghdl")), "target");
+    }
+
+    #[test]
+    fn layout_from_str() {
+        assert_eq!(Layout::from_str("home").unwrap(), Layout::Home);
+        assert_eq!(Layout::from_str("").unwrap(), Layout::Home);
+        assert_eq!(Layout::from_str("system").unwrap(), Layout::System);
+        assert!(Layout::from_str("xdg").is_err());
+    }
+
     #[test]
     fn find_target_path() {
         // existing path with target at root
@@ -0,0 +1,412 @@
+//! A credential describes how to authenticate with a particular host when
+//! downloading packages from private servers.
+//!
+//! Secrets are never stored in a configuration file. A token is read from an
+//! environment variable at the time of the request, and it is only given to
+//! protocols through environment variables of the running process.
+
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::logger;
+use curl::easy::{Auth, Easy, List};
+use serde_derive::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub type Credentials = Vec<Credential>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Credential {
+    host: String,
+    /// The name of the environment variable that holds the access token.
+    token_env: Option<String>,
+    /// The git credential helper to use for the host.
+    helper: Option<String>,
+    /// The private key to use for ssh connections to the host.
+    ssh_key: Option<PathBuf>,
+    /// Allows ssh urls for the host, otherwise they are accessed over https.
+    ssh: Option<bool>,
+    /// How the token is presented when downloading with the default protocol.
+    auth: Option<AuthScheme>,
+    /// The name of the environment variable that holds the user name or access key id.
+    user_env: Option<String>,
+    /// The name of the http header carrying the token for the `header` scheme.
+    header: Option<String>,
+    /// The region of the object store for the `aws-sigv4` scheme.
+    region: Option<String>,
+    #[serde(skip_serializing, skip_deserializing)]
+    root: Option<PathBuf>,
+}
+
+/// The ways a server may expect to receive a credential's secrets over https.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthScheme {
+    /// Sends the token in an `Authorization: Bearer` header.
+    Bearer,
+    /// Sends the user and token as http basic authentication, such as for
+    /// Artifactory or Nexus repositories.
+    Basic,
+    /// Sends the token as the value of a custom header, such as `X-JFrog-Art-Api`.
+    Header,
+    /// Signs the request with the access key id and secret key, such as for S3
+    /// buckets and compatible object stores.
+    AwsSigv4,
+}
+
+/// The secrets of a credential resolved from the environment to authenticate
+/// a single download.
+#[derive(Debug, PartialEq)]
+pub enum HttpAuth {
+    Bearer(String),
+    Basic { user: String, password: String },
+    Header { name: String, value: String },
+    AwsSigv4 { region: String, key: String, secret: String },
+}
+
+impl HttpAuth {
+    /// Configures the `easy` request to authenticate with the server.
+    pub fn apply(&self, easy: &mut Easy) -> Result<(), curl::Error> {
+        match self {
+            Self::Bearer(token) => {
+                let mut list = List::new();
+                list.append(&format!("Authorization: Bearer {}", token))?;
+                easy.http_headers(list)
+            }
+            Self::Basic { user, password } => {
+                easy.username(user)?;
+                easy.password(password)?;
+                easy.http_auth(Auth::new().basic(true))
+            }
+            Self::Header { name, value } => {
+                let mut list = List::new();
+                list.append(&format!("{}: {}", name, value))?;
+                easy.http_headers(list)
+            }
+            Self::AwsSigv4 { region, key, secret } => {
+                easy.username(key)?;
+                easy.password(secret)?;
+                easy.aws_sigv4(&format!("aws:amz:{}:s3", region))
+            }
+        }
+    }
+}
+
+impl FromStr for Credential {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl Credential {
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = Some(root);
+    }
+
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    /// Finds the first credential within `creds` for the host of the `url`.
+    pub fn find<'a>(creds: &[&'a Credential], url: &str) -> Option<&'a Credential> {
+        let host = host_of(url)?;
+        creds
+            .iter()
+            .find(|c| c.host.eq_ignore_ascii_case(host) == true)
+            .copied()
+    }
+
+    /// Checks if urls for the host may be accessed over ssh, which is allowed
+    /// unless `ssh` is set to `false`.
+    pub fn is_ssh_allowed(&self) -> bool {
+        self.ssh.unwrap_or(true)
+    }
+
+    /// Reads the access token from the environment variable named by `token-env`.
+    ///
+    /// Returns `None` if no variable is named or the variable is not set.
+    pub fn get_token(&self) -> Option<String> {
+        self.read_env(self.token_env.as_ref()?)
+    }
+
+    /// Reads the secret stored in the environment variable `key`, warning when
+    /// it is not set.
+    fn read_env(&self, key: &str) -> Option<String> {
+        match std::env::var(key) {
+            Ok(v) if v.is_empty() == false => Some(v),
+            _ => {
+                logger::warn(format!(
+                    "environment variable '{}' for the credential of host '{}' is not set",
+                    key, self.host
+                ));
+                None
+            }
+        }
+    }
+
+    /// Resolves the secrets to authenticate a download over https according to
+    /// the credential's `auth` scheme.
+    ///
+    /// Returns `None` if a secret's environment variable is not set, in which
+    /// case the download is attempted without authentication.
+    pub fn get_http_auth(&self) -> Result<Option<HttpAuth>, Fault> {
+        let scheme = self.auth.unwrap_or(AuthScheme::Bearer);
+        // verify the fields required by the scheme are configured
+        let missing = |field: &str| {
+            AnyError(format!(
+                "credential for host '{}' requires '{}' for the '{}' auth scheme",
+                self.host,
+                field,
+                match scheme {
+                    AuthScheme::Bearer => "bearer",
+                    AuthScheme::Basic => "basic",
+                    AuthScheme::Header => "header",
+                    AuthScheme::AwsSigv4 => "aws-sigv4",
+                }
+            ))
+        };
+        let user_env = match scheme {
+            AuthScheme::Basic | AuthScheme::AwsSigv4 => {
+                Some(self.user_env.as_ref().ok_or_else(|| missing("user-env"))?)
+            }
+            _ => None,
+        };
+        if scheme != AuthScheme::Bearer && self.token_env.is_none() == true {
+            return Err(missing("token-env"))?;
+        }
+        let header = match scheme {
+            AuthScheme::Header => Some(self.header.clone().ok_or_else(|| missing("header"))?),
+            _ => None,
+        };
+        let region = match scheme {
+            AuthScheme::AwsSigv4 => Some(self.region.clone().ok_or_else(|| missing("region"))?),
+            _ => None,
+        };
+        let token = match self.get_token() {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let user = match user_env {
+            Some(key) => match self.read_env(key) {
+                Some(u) => Some(u),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+        Ok(Some(match scheme {
+            AuthScheme::Bearer => HttpAuth::Bearer(token),
+            AuthScheme::Basic => HttpAuth::Basic {
+                user: user.unwrap(),
+                password: token,
+            },
+            AuthScheme::Header => HttpAuth::Header {
+                name: header.unwrap(),
+                value: token,
+            },
+            AuthScheme::AwsSigv4 => HttpAuth::AwsSigv4 {
+                region: region.unwrap(),
+                key: user.unwrap(),
+                secret: token,
+            },
+        }))
+    }
+
+    /// Returns the path to the ssh key with any relative path resolved against
+    /// the configuration's directory.
+    fn get_ssh_key(&self) -> Option<PathBuf> {
+        let key = self.ssh_key.as_ref()?;
+        Some(match &self.root {
+            Some(root) => filesystem::resolve_rel_path2(root, key),
+            None => key.clone(),
+        })
+    }
+
+    /// Creates the environment variables that configure git to authenticate with
+    /// the host, using git's `GIT_CONFIG_*` variables so that nothing is written
+    /// to a git configuration file.
+    ///
+    /// The `token` is scoped to https urls of the credential's host.
+    pub fn to_git_env(&self, token: Option<&str>) -> Vec<(String, String)> {
+        let mut config: Vec<(String, String)> = Vec::new();
+        if let Some(helper) = &self.helper {
+            config.push((String::from("credential.helper"), helper.clone()));
+        }
+        if let Some(token) = token {
+            // only send the token to the credential's host, never to submodules or redirects elsewhere
+            config.push((
+                format!("http.https://{}/.extraHeader", self.host),
+                format!("Authorization: Bearer {}", token),
+            ));
+        }
+        let mut vars = Vec::new();
+        if let Some(key) = self.get_ssh_key() {
+            vars.push((
+                String::from("GIT_SSH_COMMAND"),
+                format!(
+                    "ssh -i \"{}\" -o IdentitiesOnly=yes",
+                    filesystem::into_std_str(key)
+                ),
+            ));
+        }
+        if config.is_empty() == false {
+            vars.push((String::from("GIT_CONFIG_COUNT"), config.len().to_string()));
+            for (i, (key, value)) in config.into_iter().enumerate() {
+                vars.push((format!("GIT_CONFIG_KEY_{}", i), key));
+                vars.push((format!("GIT_CONFIG_VALUE_{}", i), value));
+            }
+        }
+        vars
+    }
+
+    /// Sets the git environment variables for this credential on the current
+    /// process, runs `f`, and then removes the variables.
+    pub fn with_git_env<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, Fault>,
+    ) -> Result<T, Fault> {
+        let vars = self.to_git_env(self.get_token().as_deref());
+        vars.iter().for_each(|(k, v)| std::env::set_var(k, v));
+        let result = f();
+        vars.iter().for_each(|(k, _)| std::env::remove_var(k));
+        result
+    }
+}
+
+/// Extracts the host name from a `url`, which may also be an scp-like git
+/// address such as `git@host:path/to/repo.git`.
+pub fn host_of(url: &str) -> Option<&str> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        // scp-like addresses require a ':' before any '/'
+        None => match url.find(':') {
+            Some(i) if url[..i].contains('/') == false => url,
+            _ => return None,
+        },
+    };
+    let authority = rest.split(['/', '?', '#']).next()?;
+    // remove the user information
+    let authority = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    // remove the port or scp-like path
+    let host = authority.split(':').next()?;
+    match host.is_empty() {
+        true => None,
+        false => Some(host),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_toml_string() {
+        let cred = Credential::from_str(
+            r#"
+host = "gitlab.example.com"
+token-env = "GITLAB_TOKEN"
+ssh-key = "/home/user/.ssh/id_work"
+"#,
+        )
+        .unwrap();
+        assert_eq!(cred.get_host(), "gitlab.example.com");
+        assert_eq!(cred.token_env, Some(String::from("GITLAB_TOKEN")));
+        assert_eq!(cred.ssh_key, Some(PathBuf::from("/home/user/.ssh/id_work")));
+        // secrets are not accepted in the configuration
+        assert!(Credential::from_str("host = \"a.com\"\ntoken = \"abc\"\n").is_err());
+    }
+
+    #[test]
+    fn url_host() {
+        assert_eq!(host_of("https://github.com/c-rus/orbit.git"), Some("github.com"));
+        assert_eq!(host_of("https://user@example.com:8443/a.zip"), Some("example.com"));
+        assert_eq!(host_of("ssh://git@gitlab.com/c-rus/gates.git"), Some("gitlab.com"));
+        assert_eq!(host_of("git@gitlab.com:c-rus/gates.git"), Some("gitlab.com"));
+        assert_eq!(host_of("./local/path"), None);
+    }
+
+    #[test]
+    fn git_env() {
+        let cred = Credential::from_str(
+            "host = \"gitlab.com\"\nhelper = \"store\"\nssh-key = \"/keys/id\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cred.to_git_env(Some("abc")),
+            vec![
+                (
+                    String::from("GIT_SSH_COMMAND"),
+                    String::from("ssh -i \"/keys/id\" -o IdentitiesOnly=yes")
+                ),
+                (String::from("GIT_CONFIG_COUNT"), String::from("2")),
+                (String::from("GIT_CONFIG_KEY_0"), String::from("credential.helper")),
+                (String::from("GIT_CONFIG_VALUE_0"), String::from("store")),
+                (
+                    String::from("GIT_CONFIG_KEY_1"),
+                    String::from("http.https://gitlab.com/.extraHeader")
+                ),
+                (
+                    String::from("GIT_CONFIG_VALUE_1"),
+                    String::from("Authorization: Bearer abc")
+                ),
+            ]
+        );
+        let creds = [&cred];
+        assert_eq!(Credential::find(&creds, "https://GitLab.com/a/b.git"), Some(&cred));
+        assert_eq!(Credential::find(&creds, "https://github.com/a/b.git"), None);
+    }
+
+    #[test]
+    fn http_auth_schemes() {
+        std::env::set_var("ORBIT_TEST_CRED_USER", "deployer");
+        std::env::set_var("ORBIT_TEST_CRED_TOKEN", "abc");
+        let cred = Credential::from_str(
+            "host = \"artifactory.example.com\"\nauth = \"basic\"\nuser-env = \"ORBIT_TEST_CRED_USER\"\ntoken-env = \"ORBIT_TEST_CRED_TOKEN\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cred.get_http_auth().unwrap(),
+            Some(HttpAuth::Basic {
+                user: String::from("deployer"),
+                password: String::from("abc")
+            })
+        );
+
+        let cred = Credential::from_str(
+            "host = \"s3.amazonaws.com\"\nauth = \"aws-sigv4\"\nregion = \"us-east-1\"\nuser-env = \"ORBIT_TEST_CRED_USER\"\ntoken-env = \"ORBIT_TEST_CRED_TOKEN\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cred.get_http_auth().unwrap(),
+            Some(HttpAuth::AwsSigv4 {
+                region: String::from("us-east-1"),
+                key: String::from("deployer"),
+                secret: String::from("abc")
+            })
+        );
+
+        // a scheme missing its required fields is an error
+        let cred = Credential::from_str(
+            "host = \"a.com\"\nauth = \"header\"\ntoken-env = \"ORBIT_TEST_CRED_TOKEN\"\n",
+        )
+        .unwrap();
+        assert!(cred.get_http_auth().is_err());
+        // even when the token is not set
+        let cred = Credential::from_str(
+            "host = \"a.com\"\nauth = \"header\"\ntoken-env = \"ORBIT_TEST_CRED_UNSET\"\n",
+        )
+        .unwrap();
+        assert!(cred.get_http_auth().is_err());
+
+        // the token defaults to a bearer token
+        let cred =
+            Credential::from_str("host = \"a.com\"\ntoken-env = \"ORBIT_TEST_CRED_TOKEN\"\n")
+                .unwrap();
+        assert_eq!(
+            cred.get_http_auth().unwrap(),
+            Some(HttpAuth::Bearer(String::from("abc")))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Lists the exact versions of the ip used in a build so that backend plugins
+//! can stamp bitstreams and reports with what they were built from.
+//!
+//! The report is written into the build directory during planning as the
+//! [DEPENDENCY_REPORT_FILE]. Each entry is taken from the lockfile that planning
+//! writes.
+
+use super::ip::Ip;
+use super::lockfile::LockFile;
+use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use serde_derive::Serialize;
+use std::path::PathBuf;
+
+/// The file within the build directory listing the dependencies as JSON.
+pub const DEPENDENCY_REPORT_FILE: &str = "dependencies.json";
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DependencyReport {
+    ip: Vec<DependencyEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct DependencyEntry {
+    name: String,
+    version: String,
+    uuid: String,
+    /// The checksum of the ip's contents as recorded in the lockfile.
+    checksum: Option<String>,
+    /// The directory the ip's files were read from.
+    path: String,
+}
+
+impl DependencyReport {
+    /// Collects every ip in the `build_list`, ordered by name and then version.
+    ///
+    /// The uuid and checksum of each ip are taken from its entry in the `lock`
+    /// file. An ip without an entry, such as a copy in development, only
+    /// reports the checksum recorded within its installation.
+    pub fn from_build_list(build_list: &[&Ip], lock: Option<&LockFile>) -> Self {
+        let mut build_list = build_list.to_vec();
+        build_list.sort_by(|a, b| {
+            let (a, b) = (a.get_man().get_ip(), b.get_man().get_ip());
+            a.get_name()
+                .cmp(b.get_name())
+                .then(a.get_version().cmp(b.get_version()))
+        });
+        let ip = build_list
+            .into_iter()
+            .map(|ip| {
+                let man = ip.get_man().get_ip();
+                let locked = lock
+                    .and_then(|lf| lf.get(man.get_name(), man.get_version()))
+                    .filter(|e| e.get_uuid() == ip.get_uuid());
+                DependencyEntry {
+                    name: man.get_name().to_string(),
+                    version: man.get_version().to_string(),
+                    uuid: ip.get_uuid().get().to_string(),
+                    checksum: match locked {
+                        Some(entry) => entry.get_sum().map(|sum| sum.to_string()),
+                        None => Ip::read_checksum_proof(ip.get_root()).map(|sum| sum.to_string()),
+                    },
+                    path: filesystem::into_std_str(ip.get_root().clone()),
+                }
+            })
+            .collect();
+        Self { ip }
+    }
+
+    /// Writes the [DEPENDENCY_REPORT_FILE] into `dir`.
+    pub fn save(&self, dir: &PathBuf) -> Result<(), Fault> {
+        std::fs::write(
+            dir.join(DEPENDENCY_REPORT_FILE),
+            serde_json::to_string_pretty(&self)?,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dependency_report_from_build_list() {
+        let ip = Ip::load(PathBuf::from("./tests/env/project1/")).unwrap();
+        let report = DependencyReport::from_build_list(&[&ip], None);
+        assert_eq!(report.ip.len(), 1);
+
+        let entry = &report.ip[0];
+        assert_eq!(entry.name, "project1");
+        assert_eq!(entry.version, "1.0.0");
+        // the checksum is never computed for an ip that is not locked or installed
+        assert_eq!(entry.checksum, None);
+
+        // the checksum is read from the lockfile
+        let b = Ip::load(PathBuf::from("./tests/env/projects/ip-b/")).unwrap();
+        let a = Ip::load(PathBuf::from("./tests/env/projects/ip-a/")).unwrap();
+        let report = DependencyReport::from_build_list(&[&ip, &b], Some(a.get_lock()));
+        assert_eq!(report.ip[0].name, "ip-b");
+        assert_eq!(
+            report.ip[0].checksum,
+            Some(String::from(
+                "eac05fa204a27b406ad45d5183bbfbdce7f3e66031ad13ef7602598982227edd"
+            ))
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        report.save(&dir.path().to_path_buf()).unwrap();
+        let text = std::fs::read_to_string(dir.path().join(DEPENDENCY_REPORT_FILE)).unwrap();
+        assert_eq!(text.contains("\"name\": \"project1\""), true);
+    }
+
+    #[test]
+    fn dependency_report_orders_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ips = Vec::new();
+        for version in ["1.10.0", "1.9.0"] {
+            let root = dir.path().join(version);
+            std::fs::create_dir(&root).unwrap();
+            std::fs::write(
+                root.join("Orbit.toml"),
+                format!("[ip]\nname = \"gates\"\nversion = \"{}\"\n", version),
+            )
+            .unwrap();
+            ips.push(Ip::load(root).unwrap());
+        }
+        let report = DependencyReport::from_build_list(&[&ips[0], &ips[1]], None);
+        assert_eq!(report.ip[0].version, "1.9.0");
+        assert_eq!(report.ip[1].version, "1.10.0");
+    }
+}
@@ -25,37 +25,163 @@ impl Filesets {
     // }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Fileset {
     name: String,
     pattern: Style,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Style(Pattern);
+/// A set of glob-style patterns where a file belongs to the set if it matches
+/// any one of the patterns.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Style(Vec<Pattern>);
 
 impl Style {
-    pub fn inner(&self) -> &Pattern {
+    /// Parses the glob set `s` into its individual patterns.
+    ///
+    /// Alternatives are separated by `|` and braces expand into one pattern
+    /// per comma-separated item (`*.{sv,svh}`). When `implicit_prefix` is set,
+    /// any pattern not starting with `.` is made recursive with `**/`.
+    fn parse(s: &str, implicit_prefix: bool) -> Result<Self, FilesetError> {
+        let mut patterns = Vec::new();
+        for alt in expand(s)? {
+            if alt.is_empty() == true {
+                return Err(FilesetError::EmptyPattern);
+            }
+            let alt = match implicit_prefix == true && alt.starts_with('.') == false {
+                true => String::from("**/") + &alt,
+                false => alt,
+            };
+            match Pattern::new(&alt) {
+                Ok(p) => patterns.push(p),
+                Err(e) => return Err(FilesetError::PatternError(alt, e)),
+            }
+        }
+        Ok(Style(patterns))
+    }
+
+    pub fn inner(&self) -> &Vec<Pattern> {
         &self.0
     }
+
+    /// Checks if the `file` matches any of the set's patterns.
+    pub fn matches_with(&self, file: &str, options: glob::MatchOptions) -> bool {
+        self.0.iter().any(|p| p.matches_with(file, options) == true)
+    }
 }
 
 impl From<Pattern> for Style {
     fn from(value: Pattern) -> Self {
-        Self(value)
+        Self(vec![value])
     }
 }
 
 impl FromStr for Style {
-    type Err = PatternError;
+    type Err = FilesetError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let prefix = match s.get(0..1) {
-            Some(".") => "", 
-            _ => "**/",
-        };
-        Ok(Style(Pattern::new(&(prefix.to_owned() + s))?.into()))
+        Self::parse(s, true)
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns: Vec<&str> = self.0.iter().map(|p| p.as_str()).collect();
+        write!(f, "{}", patterns.join("|"))
+    }
+}
+
+/// Splits the glob set `s` on its top-level `|` separators and expands any
+/// braces within each alternative.
+fn expand(s: &str) -> Result<Vec<String>, FilesetError> {
+    let mut alts = Vec::new();
+    let mut depth: usize = 0;
+    let mut current = String::new();
+    for c in s.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => {
+                alts.push(current);
+                current = String::new();
+                continue;
+            }
+            _ => (),
+        }
+        current.push(c);
+    }
+    alts.push(current);
+
+    let mut result = Vec::new();
+    for alt in alts {
+        result.append(&mut expand_braces(&alt)?);
+    }
+    Ok(result)
+}
+
+/// Expands the first brace group in `s` into one string per comma-separated
+/// item, recursing to handle nested and remaining groups.
+///
+/// Variable placeholders (`{{ orbit.bench }}`) are left untouched.
+fn expand_braces(s: &str) -> Result<Vec<String>, FilesetError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                // skip over the variable placeholder
+                match (i + 2..chars.len()).find(|&j| chars[j] == '}' && chars.get(j + 1) == Some(&'}')) {
+                    Some(j) => i = j + 2,
+                    None => return Err(FilesetError::UnclosedBrace(s.to_string())),
+                }
+            }
+            '{' => {
+                let mut depth: usize = 0;
+                let mut items: Vec<String> = Vec::new();
+                let mut item = String::new();
+                let mut end = None;
+                for (j, c) in chars.iter().enumerate().skip(i) {
+                    match c {
+                        '{' => {
+                            depth += 1;
+                            if depth == 1 {
+                                continue;
+                            }
+                        }
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                items.push(item);
+                                end = Some(j);
+                                break;
+                            }
+                        }
+                        ',' if depth == 1 => {
+                            items.push(item);
+                            item = String::new();
+                            continue;
+                        }
+                        _ => (),
+                    }
+                    item.push(*c);
+                }
+                let end = match end {
+                    Some(j) => j,
+                    None => return Err(FilesetError::UnclosedBrace(s.to_string())),
+                };
+                let prefix: String = chars[..i].iter().collect();
+                let suffix: String = chars[end + 1..].iter().collect();
+                let mut result = Vec::new();
+                for item in items {
+                    result.append(&mut expand_braces(&(prefix.clone() + &item + &suffix))?);
+                }
+                return Ok(result);
+            }
+            '}' => return Err(FilesetError::UnopenedBrace(s.to_string())),
+            _ => i += 1,
+        }
     }
+    Ok(vec![s.to_string()])
 }
 
 use serde::de::{self};
@@ -97,7 +223,7 @@ impl Serialize for Style {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.0.to_string())
+        serializer.serialize_str(&self.to_string())
     }
 }
 
@@ -107,6 +233,8 @@ pub enum FilesetError {
     EmptyPattern,
     EmptyName,
     PatternError(String, PatternError),
+    UnclosedBrace(String),
+    UnopenedBrace(String),
 }
 
 impl std::error::Error for FilesetError {}
@@ -118,6 +246,8 @@ impl std::fmt::Display for FilesetError {
             Self::EmptyName => write!(f, "empty name"),
             Self::MissingSeparator(c) => write!(f, "missing separator '{}'", c),
             Self::PatternError(p, e) => write!(f, "'{}' {}", p, e.to_string().to_lowercase()),
+            Self::UnclosedBrace(p) => write!(f, "'{}' has a '{{' without a closing '}}'", p),
+            Self::UnopenedBrace(p) => write!(f, "'{}' has a '}}' without an opening '{{'", p),
         }
     }
 }
@@ -137,17 +267,8 @@ impl FromStr for Fileset {
             return Err(Self::Err::EmptyName);
         }
         Ok(Fileset {
-            pattern: match Pattern::new(pattern) {
-                // pattern must not be empty
-                Ok(p) => {
-                    if p.as_str().is_empty() {
-                        return Err(Self::Err::EmptyPattern);
-                    } else {
-                        p.into()
-                    }
-                }
-                Err(e) => return Err(Self::Err::PatternError(pattern.to_string(), e)),
-            },
+            // each pattern must not be empty
+            pattern: Style::parse(pattern, false)?,
             name: Self::standardize_name(name),
         })
     }
@@ -170,15 +291,30 @@ impl Fileset {
 
     /// Set the `Fileset` glob-style pattern.
     /// 
+    /// Multiple patterns can be given by separating them with `|` or by using
+    /// braces (`*.{sv,svh}`).
+    ///
     /// If no explicit relative file path character is present (`.`), then
     /// it implicitly sets a recursive directory glob pattern as the prefix
-    /// (`**/`).
-    pub fn pattern(mut self, p: &str) -> Result<Self, PatternError> {
-        let prefix = match p.get(0..1) {
-            Some(".") => "", 
-            _ => "**/",
-        };
-        self.pattern = Pattern::new(&(prefix.to_owned() + p))?.into();
+    /// (`**/`) for each pattern.
+    pub fn pattern(mut self, p: &str) -> Result<Self, FilesetError> {
+        self.pattern = Style::parse(p, true)?;
+        Ok(self)
+    }
+
+    /// Set the `Fileset` glob-style patterns, where a file is collected if it
+    /// matches any one of them.
+    ///
+    /// Each pattern follows the same rules as [Fileset::pattern].
+    pub fn patterns(mut self, p: &[String]) -> Result<Self, FilesetError> {
+        let mut patterns = Vec::new();
+        for glob in p {
+            patterns.append(&mut Style::parse(glob, true)?.0);
+        }
+        if patterns.is_empty() == true {
+            return Err(FilesetError::EmptyPattern);
+        }
+        self.pattern = Style(patterns);
         Ok(self)
     }
 
@@ -200,7 +336,7 @@ impl Fileset {
         files
             .iter()
             .filter_map(|f| {
-                if self.pattern.inner().matches_with(&f, match_opts) == true {
+                if self.pattern.matches_with(&f, match_opts) == true {
                     Some(f)
                 } else {
                     None
@@ -215,8 +351,8 @@ impl Fileset {
     }
 
     /// Access pattern.
-    pub fn get_pattern(&self) -> &Pattern {
-        &self.pattern.inner()
+    pub fn get_pattern(&self) -> &Style {
+        &self.pattern
     }
 
     /// Creates format for blueprint.tsv file for a custom fileset.
@@ -359,6 +495,69 @@ mod test {
         assert_eq!(fset.is_err(), true); // pattern error
     }
 
+    #[test]
+    fn multiple_patterns() {
+        let fset = Fileset::new().name("sv").pattern("*.svh|*.sv").unwrap();
+        assert_eq!(fset.get_pattern().to_string(), "**/*.svh|**/*.sv");
+
+        let fset = Fileset::new().name("sv").pattern("*.{sv,svh}").unwrap();
+        assert_eq!(fset.get_pattern().to_string(), "**/*.sv|**/*.svh");
+
+        let fset = Fileset::new()
+            .name("data")
+            .pattern("./{rom,ram}/*.{mem,coe}|*.hex")
+            .unwrap();
+        assert_eq!(
+            fset.get_pattern().to_string(),
+            "./rom/*.mem|./rom/*.coe|./ram/*.mem|./ram/*.coe|**/*.hex"
+        );
+
+        let fset = Fileset::new().name("sv").pattern("*.{s{v,vh},v}").unwrap();
+        assert_eq!(fset.get_pattern().to_string(), "**/*.sv|**/*.svh|**/*.v");
+
+        let fset = Fileset::new()
+            .name("sv")
+            .patterns(&[String::from("*.sv"), String::from("./include/*.svh")])
+            .unwrap();
+        assert_eq!(fset.get_pattern().to_string(), "**/*.sv|./include/*.svh");
+
+        let files = vec![
+            String::from("rtl/adder.sv"),
+            String::from("include/defs.svh"),
+            String::from("rtl/adder.vhd"),
+        ];
+        let fset = Fileset::new().name("sv").pattern("*.svh|*.sv").unwrap();
+        assert_eq!(fset.collect_files(&files), vec![&files[0], &files[1]]);
+
+        // variable placeholders are not expanded
+        let style = Style::from_str("{{orbit.bench}}.py").unwrap();
+        assert_eq!(style.to_string(), "**/{{orbit.bench}}.py");
+    }
+
+    #[test]
+    fn pattern_errors() {
+        assert!(matches!(
+            Fileset::new().pattern("*.{sv,svh"),
+            Err(FilesetError::UnclosedBrace(_))
+        ));
+        assert!(matches!(
+            Fileset::new().pattern("*.sv}"),
+            Err(FilesetError::UnopenedBrace(_))
+        ));
+        assert!(matches!(
+            Fileset::new().pattern("*.sv||*.svh"),
+            Err(FilesetError::EmptyPattern)
+        ));
+        assert!(matches!(
+            Fileset::from_str("sv=*.sv|["),
+            Err(FilesetError::PatternError(_, _))
+        ));
+        assert!(matches!(
+            Fileset::new().patterns(&[]),
+            Err(FilesetError::EmptyPattern)
+        ));
+    }
+
     #[test]
     fn std_name() {
         let s: &str = "VHDL-RTL";
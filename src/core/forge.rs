@@ -0,0 +1,294 @@
+//! A forge hosts an ip's repository and publishes its releases, such as GitHub
+//! or GitLab.
+//!
+//! Releasing an ip through its forge uploads an archive of the ip's files so
+//! others can download it over https with the default protocol instead of
+//! cloning the repository.
+
+use crate::core::ip::IpSpec;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::sha256;
+use curl::easy::{Easy, List};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The response code for a request that created a resource.
+const RESPONSE_CREATED: u32 = 201;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    Github,
+    Gitlab,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Release {
+    forge: Forge,
+    /// The path of the repository on the forge, such as `c-rus/gates`.
+    repository: String,
+    /// The base url of the forge's api for self-hosted instances.
+    api: Option<String>,
+    /// The git remote that pushes to the forge's repository, which defaults to
+    /// the remote of the current branch's upstream.
+    remote: Option<String>,
+    /// Requires the release's git tag to be signed.
+    #[serde(rename = "signed-tag", default)]
+    signed_tag: bool,
+}
+
+/// A file to attach to a release.
+pub struct Asset {
+    name: String,
+    content_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+impl Asset {
+    /// Creates the assets for the zipped `archive` of the ip `spec`: the archive
+    /// itself and a checksum file in the format read by `sha256sum --check`.
+    pub fn from_archive(spec: &IpSpec, archive: Vec<u8>) -> Vec<Asset> {
+        let name = format!("{}-{}.zip", spec.get_name(), spec.get_version());
+        let sum = format!("{}  {}\n", sha256::compute_sha256(&archive), name);
+        vec![
+            Asset {
+                name: format!("{}.sha256", name),
+                content_type: "text/plain",
+                bytes: sum.into_bytes(),
+            },
+            Asset {
+                name,
+                content_type: "application/zip",
+                bytes: archive,
+            },
+        ]
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Release {
+    pub fn get_forge(&self) -> &Forge {
+        &self.forge
+    }
+
+    pub fn get_remote(&self) -> Option<&str> {
+        self.remote.as_deref()
+    }
+
+    pub fn requires_signed_tag(&self) -> bool {
+        self.signed_tag
+    }
+
+    /// Returns the base url of the forge's api.
+    pub fn get_api(&self) -> String {
+        match &self.api {
+            Some(api) => api.trim_end_matches('/').to_string(),
+            None => match self.forge {
+                Forge::Github => String::from("https://api.github.com"),
+                Forge::Gitlab => String::from("https://gitlab.com/api/v4"),
+            },
+        }
+    }
+
+    /// Returns the url used to select the credential for the forge.
+    ///
+    /// The public forges are matched by their website's host rather than the
+    /// host of their api.
+    pub fn get_host_url(&self) -> String {
+        match (&self.api, &self.forge) {
+            (Some(api), _) => api.clone(),
+            (None, Forge::Github) => format!("https://github.com/{}", self.repository),
+            (None, Forge::Gitlab) => format!("https://gitlab.com/{}", self.repository),
+        }
+    }
+
+    /// Creates a release for the ip `spec` tagged as `tag` and attaches the
+    /// `assets`.
+    ///
+    /// When `commit` is given, the tag is created at that commit if it does not
+    /// yet exist. Returns the download url of each asset.
+    pub fn publish(
+        &self,
+        spec: &IpSpec,
+        tag: &str,
+        commit: Option<&str>,
+        notes: &str,
+        assets: &[Asset],
+        token: &str,
+    ) -> Result<Vec<String>, Fault> {
+        match self.forge {
+            Forge::Github => self.publish_github(spec, tag, commit, notes, assets, token),
+            Forge::Gitlab => self.publish_gitlab(spec, tag, commit, notes, assets, token),
+        }
+    }
+
+    fn publish_github(
+        &self,
+        spec: &IpSpec,
+        tag: &str,
+        commit: Option<&str>,
+        notes: &str,
+        assets: &[Asset],
+        token: &str,
+    ) -> Result<Vec<String>, Fault> {
+        let mut body = json!({
+            "tag_name": tag,
+            "name": format!("{} {}", spec.get_name(), spec.get_version()),
+            "body": notes,
+        });
+        if let Some(sha) = commit {
+            body["target_commitish"] = json!(sha);
+        }
+        let url = format!("{}/repos/{}/releases", self.get_api(), self.repository);
+        let release = request(&url, "POST", token, "application/json", body.to_string().as_bytes())?;
+        // the upload url is a template such as '.../assets{?name,label}'
+        let upload_url = match release["upload_url"].as_str() {
+            Some(u) => u.split('{').next().unwrap_or(u).to_string(),
+            None => return Err(AnyError(format!("forge did not return an upload url for the release at {}", url)))?,
+        };
+        assets
+            .iter()
+            .map(|asset| {
+                let url = format!("{}?name={}", upload_url, asset.name);
+                let uploaded = request(&url, "POST", token, asset.content_type, &asset.bytes)?;
+                Ok(uploaded["browser_download_url"]
+                    .as_str()
+                    .unwrap_or(&url)
+                    .to_string())
+            })
+            .collect()
+    }
+
+    fn publish_gitlab(
+        &self,
+        spec: &IpSpec,
+        tag: &str,
+        commit: Option<&str>,
+        notes: &str,
+        assets: &[Asset],
+        token: &str,
+    ) -> Result<Vec<String>, Fault> {
+        let project = format!("{}/projects/{}", self.get_api(), encode_path(&self.repository));
+        // gitlab releases only link to files, so store the assets in the generic package registry
+        let mut links = Vec::new();
+        for asset in assets {
+            let url = format!(
+                "{}/packages/generic/{}/{}/{}",
+                project,
+                spec.get_name(),
+                spec.get_version(),
+                asset.name
+            );
+            request(&url, "PUT", token, asset.content_type, &asset.bytes)?;
+            links.push(url);
+        }
+        let mut body = json!({
+            "tag_name": tag,
+            "name": format!("{} {}", spec.get_name(), spec.get_version()),
+            "description": notes,
+            "assets": {
+                "links": assets
+                    .iter()
+                    .zip(links.iter())
+                    .map(|(a, url)| json!({ "name": a.name, "url": url, "link_type": "package" }))
+                    .collect::<Vec<Value>>(),
+            },
+        });
+        if let Some(sha) = commit {
+            body["ref"] = json!(sha);
+        }
+        request(
+            &format!("{}/releases", project),
+            "POST",
+            token,
+            "application/json",
+            body.to_string().as_bytes(),
+        )?;
+        Ok(links)
+    }
+}
+
+/// Sends the `body` to the `url` with the `method` and reads the json response.
+///
+/// Errors if the forge does not respond that the resource was created.
+fn request(url: &str, method: &str, token: &str, content_type: &str, body: &[u8]) -> Result<Value, Fault> {
+    let mut response = Vec::new();
+    let mut easy = Easy::new();
+    easy.url(url)?;
+    easy.useragent("Orbit")?;
+    easy.post(true)?;
+    easy.post_fields_copy(body)?;
+    if method != "POST" {
+        easy.custom_request(method)?;
+    }
+    let mut list = List::new();
+    list.append(&format!("Authorization: Bearer {}", token))?;
+    list.append(&format!("Content-Type: {}", content_type))?;
+    list.append("Accept: application/json")?;
+    easy.http_headers(list)?;
+    {
+        let mut transfer = easy.transfer();
+        transfer.write_function(|data| {
+            response.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+    let rc = easy.response_code()?;
+    let value: Value = serde_json::from_slice(&response).unwrap_or(Value::Null);
+    if rc != RESPONSE_CREATED {
+        return Err(AnyError(format!(
+            "forge rejected request to {} with code {}{}",
+            url,
+            rc,
+            match value["message"].as_str() {
+                Some(msg) => format!(": {}", msg),
+                None => String::new(),
+            }
+        )))?;
+    }
+    Ok(value)
+}
+
+/// Percent-encodes the '/' characters of a repository `path` to use it as a
+/// single segment of a url.
+fn encode_path(path: &str) -> String {
+    path.trim_matches('/').replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_toml_string() {
+        let rel: Release = toml::from_str("forge = \"gitlab\"\nrepository = \"hw/gates\"\napi = \"https://git.example.com/api/v4/\"\n").unwrap();
+        assert_eq!(rel.get_forge(), &Forge::Gitlab);
+        assert_eq!(rel.get_api(), "https://git.example.com/api/v4");
+        assert_eq!(rel.get_host_url(), "https://git.example.com/api/v4/");
+
+        let rel: Release = toml::from_str("forge = \"github\"\nrepository = \"c-rus/gates\"\n").unwrap();
+        assert_eq!(rel.get_api(), "https://api.github.com");
+        assert_eq!(rel.get_host_url(), "https://github.com/c-rus/gates");
+        assert_eq!(rel.requires_signed_tag(), false);
+        assert!(toml::from_str::<Release>("forge = \"bitbucket\"\nrepository = \"a/b\"\n").is_err());
+    }
+
+    #[test]
+    fn archive_assets() {
+        let spec = IpSpec::from_str("gates:1.0.0").unwrap();
+        let assets = Asset::from_archive(&spec, b"zip".to_vec());
+        assert_eq!(assets[0].get_name(), "gates-1.0.0.zip.sha256");
+        assert_eq!(
+            String::from_utf8(assets[0].bytes.clone()).unwrap(),
+            format!("{}  gates-1.0.0.zip\n", sha256::compute_sha256(b"zip"))
+        );
+        assert_eq!(assets[1].get_name(), "gates-1.0.0.zip");
+        assert_eq!(encode_path("/group/sub/gates"), "group%2Fsub%2Fgates");
+    }
+}
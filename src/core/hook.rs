@@ -0,0 +1,296 @@
+//! A hook is a command bound to an event within the lifecycle of an orbit
+//! command, such as before an ip is installed or after an ip is created.
+
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::plugin::Process;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment;
+use crate::util::environment::EnvVar;
+use crate::util::environment::Environment;
+use crate::util::filesystem;
+use crate::util::logger;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub type Hooks = Vec<Hook>;
+
+/// The environment variable storing the name of the event that triggered the hook.
+pub const ORBIT_HOOK: &str = "ORBIT_HOOK";
+
+/// The variables passed from the current environment into the sandbox of an
+/// [InstallHook].
+const SANDBOX_VARS: [&str; 5] = ["PATH", "SYSTEMROOT", "TEMP", "TMP", "TMPDIR"];
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum Event {
+    PreInstall,
+    PostInstall,
+    PreLaunch,
+    PostLaunch,
+    PrePublish,
+    PostPublish,
+    PostNew,
+}
+
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PreInstall => write!(f, "pre-install"),
+            Self::PostInstall => write!(f, "post-install"),
+            Self::PreLaunch => write!(f, "pre-launch"),
+            Self::PostLaunch => write!(f, "post-launch"),
+            Self::PrePublish => write!(f, "pre-publish"),
+            Self::PostPublish => write!(f, "post-publish"),
+            Self::PostNew => write!(f, "post-new"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    event: Event,
+    command: String,
+    args: Option<Vec<String>>,
+    summary: Option<String>,
+    #[serde(skip_serializing, skip_deserializing)]
+    root: Option<PathBuf>,
+}
+
+impl FromStr for Hook {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl Hook {
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = Some(root);
+    }
+
+    pub fn get_event(&self) -> &Event {
+        &self.event
+    }
+
+    /// Runs every hook within `hooks` that is bound to `event` for the subject `ip`.
+    ///
+    /// The hooks are ran in the given order from the ip's root directory with
+    /// environment variables describing the ip and the event. The first hook to
+    /// fail stops any remaining hooks from running.
+    pub fn dispatch(hooks: &[&Hook], event: Event, ip: &Ip) -> Result<(), Fault> {
+        let hooks: Vec<&&Hook> = hooks.iter().filter(|h| h.event == event).collect();
+        if hooks.is_empty() == true {
+            return Ok(());
+        }
+        Environment::new()
+            .from_ip(ip)?
+            .add(EnvVar::new().key(ORBIT_HOOK).value(&event.to_string()))
+            .add(
+                EnvVar::new()
+                    .key(environment::ORBIT_IP_PATH)
+                    .value(&filesystem::into_std_str(ip.get_root().clone())),
+            )
+            .initialize();
+
+        let dir = filesystem::into_std_str(ip.get_root().clone());
+        for hook in hooks {
+            hook.execute(&[], false, &dir, None)?;
+        }
+        Ok(())
+    }
+}
+
+impl Process for Hook {
+    fn get_root(&self) -> &PathBuf {
+        &self.root.as_ref().unwrap()
+    }
+
+    fn get_command(&self) -> &String {
+        &self.command
+    }
+
+    fn get_args(&self) -> Vec<&String> {
+        match &self.args {
+            Some(list) => list.iter().map(|e| e).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A command declared in an ip's manifest to generate files within its
+/// installation, such as decompressing test vectors or running a register-map
+/// generator.
+///
+/// The hook only runs for ip allowed by the `allow-install-hooks` list in the
+/// configuration. The files it creates are recorded in the installation's
+/// [ORBIT_GENERATED_FILE](crate::core::manifest::ORBIT_GENERATED_FILE) so they
+/// are left out of its checksum.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InstallHook {
+    command: String,
+    args: Option<Vec<String>>,
+    summary: Option<String>,
+}
+
+impl FromStr for InstallHook {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl InstallHook {
+    /// Checks if the `allowed` list of the configuration permits the `ip` to run
+    /// its post-install hook.
+    ///
+    /// An entry matches an ip by its uuid, or by its name for any ip of that name.
+    pub fn is_allowed(ip: &Ip, allowed: &[&str]) -> bool {
+        allowed.iter().any(|entry| {
+            match uuid::Uuid::parse_str(entry) {
+                Ok(id) => &id == ip.get_uuid().get(),
+                Err(_) => *entry == ip.get_man().get_ip().get_name().as_ref(),
+            }
+        })
+    }
+
+    /// Runs the post-install hook of the `ip` within its installation directory
+    /// `dir` when `allowed` is set; otherwise reports that it was skipped.
+    ///
+    /// The hook is sandboxed to a cleared environment that keeps only the
+    /// variables needed to locate programs and temporary files, along with
+    /// variables describing the ip.
+    pub fn run(ip: &Ip, dir: &PathBuf, allowed: bool) -> Result<(), Fault> {
+        let hook = match ip.get_man().get_ip().get_post_install() {
+            Some(h) => h,
+            None => return Ok(()),
+        };
+        let spec = ip.get_man().get_ip().into_ip_spec();
+        if allowed == false {
+            logger::warn(format!(
+                "skipping post-install hook of ip {}; add '{}' to `allow-install-hooks` in the configuration to run it",
+                spec,
+                ip.get_uuid().get()
+            ));
+            return Ok(());
+        }
+        logger::info(format!("Running post-install hook for IP {} ...", spec));
+        let root = filesystem::into_std_str(dir.clone());
+        let mut env: BTreeMap<String, String> = SANDBOX_VARS
+            .iter()
+            .filter_map(|k| std::env::var(k).ok().map(|v| (k.to_string(), v)))
+            .collect();
+        env.insert(ORBIT_HOOK.to_string(), Event::PostInstall.to_string());
+        env.insert(String::from("ORBIT_IP_NAME"), spec.get_name().to_string());
+        env.insert(String::from("ORBIT_IP_VERSION"), spec.get_version().to_string());
+        env.insert(environment::ORBIT_IP_PATH.to_string(), root.clone());
+
+        let mut proc = filesystem::invoke_with_env(
+            &root,
+            &filesystem::resolve_rel_path(dir, &hook.command),
+            &hook.get_args(dir),
+            Some(&env),
+            Context::enable_windows_bat_file_match(),
+            false,
+        )?;
+        match proc.wait()?.code() {
+            Some(0) => Ok(()),
+            Some(num) => Err(AnyError(format!(
+                "post-install hook of ip {} exited with error code: {}",
+                spec, num
+            )))?,
+            None => Err(AnyError(format!(
+                "post-install hook of ip {} was terminated by signal",
+                spec
+            )))?,
+        }
+    }
+
+    /// Resolves the arguments that are relative paths within the installation
+    /// directory `dir`.
+    fn get_args(&self, dir: &PathBuf) -> Vec<String> {
+        match &self.args {
+            Some(list) => list
+                .iter()
+                .map(|a| filesystem::resolve_rel_path(dir, a))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_summary(&self) -> Option<&String> {
+        self.summary.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const H_1: &str = r#"
+event = "post-new"
+command = "python"
+args = ["./scripts/header.py"]
+summary = "Inject license headers into new files"
+"#;
+
+    #[test]
+    fn from_toml_string() {
+        let hook = Hook::from_str(H_1).unwrap();
+        assert_eq!(
+            hook,
+            Hook {
+                event: Event::PostNew,
+                command: String::from("python"),
+                args: Some(vec![String::from("./scripts/header.py")]),
+                summary: Some(String::from("Inject license headers into new files")),
+                root: None,
+            }
+        );
+        assert_eq!(hook.get_event().to_string(), "post-new");
+        assert!(Hook::from_str("event = \"post-build\"\ncommand = \"echo\"").is_err());
+        assert_eq!(
+            Hook::from_str("event = \"pre-publish\"\ncommand = \"make\"")
+                .unwrap()
+                .get_event(),
+            &Event::PrePublish
+        );
+    }
+
+    #[test]
+    fn install_hook_from_toml_string() {
+        let hook = InstallHook::from_str(
+            "command = \"python\"\nargs = [\"gen/regmap.py\"]\nsummary = \"Generate the register map\"",
+        )
+        .unwrap();
+        assert_eq!(hook.get_summary(), Some(&String::from("Generate the register map")));
+        assert_eq!(
+            hook.get_args(&PathBuf::from("/nonexistent")),
+            vec![String::from("gen/regmap.py")]
+        );
+        assert!(InstallHook::from_str("event = \"post-install\"\ncommand = \"make\"").is_err());
+    }
+
+    #[test]
+    fn install_hook_allowed() {
+        let ip = Ip::load(PathBuf::from("./tests/env/projects/ip-b")).unwrap();
+        assert_eq!(InstallHook::is_allowed(&ip, &["ip-b"]), true);
+        assert_eq!(
+            InstallHook::is_allowed(&ip, &["0FE73B27-07F1-4068-9E4F-9032ECF72829"]),
+            true
+        );
+        // a uuid only allows the ip it identifies
+        assert_eq!(
+            InstallHook::is_allowed(&ip, &["c34799dd-9c20-4aa1-8123-687d9d692b0e"]),
+            false
+        );
+        assert_eq!(InstallHook::is_allowed(&ip, &["ip-a"]), false);
+    }
+}
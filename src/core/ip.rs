@@ -12,17 +12,22 @@ use crate::core::lang::vhdl::primaryunit::PrimaryUnit;
 use crate::core::lang::vhdl::token::Identifier;
 use crate::core::lockfile::LockEntry;
 use crate::core::manifest::IP_MANIFEST_FILE;
+use crate::core::manifest::ORBIT_GENERATED_FILE;
 use crate::core::manifest::ORBIT_METADATA_FILE;
 use crate::core::manifest::ORBIT_SUM_FILE;
 use crate::core::uuid::Uuid;
 use crate::util::sha256::Sha256Hash;
-use colored::Colorize;
+use crate::util::logger;
+use crate::util::parallel;
 use std::collections::HashMap;
 use std::error::Error;
 use std::str::FromStr;
 use std::collections::HashSet;
 use toml_edit::Document;
 
+/// The fewest installations or archives worth reading across multiple threads.
+pub const MIN_PARALLEL_SLOTS: usize = 16;
+
 // add state to `root` (make enum) to determine if is real path or not
 #[derive(Debug, PartialEq)]
 pub enum Mapping {
@@ -109,12 +114,11 @@ impl Ip {
         let lock = match LockFile::from_file(&lock_path) {
             Ok(l) => l,
             Err(e) => {
-                println!(
-                    "{}: failed to parse lock file \"{}\": {}",
-                    "warning".yellow().bold(),
+                logger::warn(format!(
+                    "failed to parse lock file \"{}\": {}",
                     filesystem::into_std_str(lock_path),
                     e
-                );
+                ));
                 LockFile::new()
             }
         };
@@ -148,14 +152,31 @@ impl Ip {
     ///
     /// Errors if on filesystem problems.
     fn detect_all_sub(path: &PathBuf, name: &str, is_exclusive: bool) -> Result<Vec<Self>, Fault> {
-        let mut result = Vec::new();
+        Self::load_all(path, name, is_exclusive)?
+            .into_iter()
+            .map(|(_, r)| r.map_err(|e| Fault::from(AnyError(e))))
+            .collect()
+    }
+
+    /// Loads every ip with a manifest file `name` along `path`, pairing each
+    /// ip's root directory with the result of loading it.
+    fn load_all(path: &PathBuf, name: &str, is_exclusive: bool) -> Result<Vec<(PathBuf, Result<Self, String>)>, Fault> {
         // walk the ORBIT_PATH directory @TODO recursively walk inner directories until hitting first 'Orbit.toml' file
-        for mut entry in manifest::find_file(&path, &name, is_exclusive)? {
-            // remove the manifest file to access the ip's root directory
-            entry.pop();
-            result.push(Ip::load(entry)?);
-        }
-        Ok(result)
+        let mut roots: Vec<PathBuf> = manifest::find_file(&path, &name, is_exclusive)?
+            .into_iter()
+            .map(|mut entry| {
+                // remove the manifest file to access the ip's root directory
+                entry.pop();
+                entry
+            })
+            .collect();
+        // load in the same order regardless of how the directories are listed
+        roots.sort();
+        // errors are not sendable between threads, so keep only their messages
+        let results = parallel::map_ordered(&roots, MIN_PARALLEL_SLOTS, |root| {
+            Ip::load(root.clone()).map_err(|e| e.to_string())
+        });
+        Ok(roots.into_iter().zip(results).collect())
     }
 
     /// Finds all IP manifest files along the provided path `path`.
@@ -165,6 +186,28 @@ impl Ip {
         Self::detect_all_sub(path, IP_MANIFEST_FILE, true)
     }
 
+    /// Finds all IP manifest files along `path` like [Ip::detect_all], skipping
+    /// any ip that cannot be loaded with a warning.
+    ///
+    /// A workspace often holds ip that are mid-edit, so one broken manifest
+    /// should not hide the others.
+    pub fn detect_all_lenient(path: &PathBuf) -> Result<Vec<Self>, Fault> {
+        Ok(Self::load_all(path, IP_MANIFEST_FILE, true)?
+            .into_iter()
+            .filter_map(|(root, r)| match r {
+                Ok(ip) => Some(ip),
+                Err(e) => {
+                    logger::warn(format!(
+                        "skipping ip at {:?} that failed to load: {}",
+                        filesystem::into_std_str(root),
+                        e
+                    ));
+                    None
+                }
+            })
+            .collect())
+    }
+
     /// Checks the metadata file for a entry for `dynamic`.
     pub fn is_dynamic(&self) -> bool {
         self.get_mapping().is_physical() == true
@@ -219,12 +262,72 @@ impl Ip {
     /// Computes the checksum on the root of the IP.
     ///
     /// Changes the current working directory to the root for consistent computation.
+    ///
+    /// Files created by a post-install hook are not part of the ip and are left
+    /// out of the checksum.
     pub fn compute_checksum(dir: &PathBuf) -> Sha256Hash {
-        let ip_files = crate::util::filesystem::gather_current_files(&dir, true);
+        let generated = Self::read_generated_files(dir);
+        let ip_files: Vec<String> = crate::util::filesystem::gather_current_files(&dir, true)
+            .into_iter()
+            .filter(|f| generated.contains(f) == false)
+            .collect();
         let checksum = crate::util::checksum::checksum(&ip_files, &dir);
         checksum
     }
 
+    /// Reads the files a post-install hook created within the installation at
+    /// `dir` from its [ORBIT_GENERATED_FILE].
+    pub fn read_generated_files(dir: &PathBuf) -> HashSet<String> {
+        match fs::read_to_string(dir.join(ORBIT_GENERATED_FILE)) {
+            Ok(text) => text.lines().map(|l| l.to_string()).collect(),
+            Err(_) => HashSet::new(),
+        }
+    }
+
+    /// Records the `files` a post-install hook created within the installation
+    /// at `dir` into its [ORBIT_GENERATED_FILE].
+    pub fn write_generated_files(dir: &PathBuf, files: &[String]) -> Result<(), Fault> {
+        let mut text = files.join("\n");
+        text.push('\n');
+        fs::write(dir.join(ORBIT_GENERATED_FILE), text)?;
+        Ok(())
+    }
+
+    /// Computes the checksum of the files the ip keeps once it is installed.
+    ///
+    /// Only the files copied by a minimal installation are hashed, so the result
+    /// matches the checksum recorded in [ORBIT_SUM_FILE] when the ip's current
+    /// files are installed.
+    pub fn compute_minimal_checksum(&self) -> Sha256Hash {
+        let keep = self.get_files_to_keep();
+        let ip_files: Vec<String> = filesystem::gather_current_files(self.get_root(), false)
+            .into_iter()
+            .filter(|f| {
+                let path = PathBuf::from(f);
+                filesystem::is_minimal(&path.file_name().unwrap().to_string_lossy()) == true
+                    || filesystem::is_keep_override(&path, &keep) == true
+            })
+            .map(|f| {
+                filesystem::into_std_str(filesystem::remove_base(
+                    self.get_root(),
+                    &PathBuf::from(f),
+                ))
+            })
+            .collect();
+        crate::util::checksum::checksum(&ip_files, self.get_root())
+    }
+
+    /// Checks if the ip's current files differ from those of the installed
+    /// `release`.
+    ///
+    /// The release's recorded checksum is used when available, otherwise it is
+    /// computed from its files.
+    pub fn is_modified_since(&self, release: &Ip) -> bool {
+        let sum = Ip::read_checksum_proof(release.get_root())
+            .unwrap_or_else(|| Ip::compute_checksum(release.get_root()));
+        self.compute_minimal_checksum() != sum
+    }
+
     /// Gets the already calculated checksum from an installed IP from [ORBIT_SUM_FILE].
     ///
     /// Returns `None` if the file does not exist, is unable to read into a string, or
@@ -244,25 +347,34 @@ impl Ip {
         }
     }
 
-    /// Caches the result of collecting all the primary design units for the given package.
+    /// Caches the result of collecting all the primary design units for the ip at
+    /// `dir` into its [ORBIT_METADATA_FILE].
     ///
-    /// Writes the data to the toml data structure. Note, this function does not save the manifest data to file.
-    // pub fn stash_units(&mut self) -> () {
-    //     // collect the units
-    //     let units = Self::collect_units(true).unwrap();
-    //     let tbl = self.get_manifest_mut().get_mut_doc()["ip"].as_table_mut().unwrap();
-    //     tbl.insert("units", toml_edit::Item::Value(toml_edit::Value::Array(Array::new())));
-    //     let arr = tbl["units"].as_array_mut().unwrap();
-    //     // map the units into a serialized data format
-    //     for (_, unit) in &units {
-    //         arr.push(unit.to_toml());
-    //     }
-    //     tbl["units"].as_array_mut().unwrap().iter_mut().for_each(|f| {
-    //         f.decor_mut().set_prefix("\n    ");
-    //         f.decor_mut().set_suffix("");
-    //     });
-    //     tbl["units"].as_array_mut().unwrap().set_trailing("\n");
-    // }
+    /// Reading the units afterward with [Ip::collect_units] does not require
+    /// parsing the source code again.
+    pub fn write_units_to_metadata(
+        dir: &PathBuf,
+        units: &HashMap<Identifier, PrimaryUnit>,
+    ) -> Result<(), Fault> {
+        let mut doc = Document::new();
+        doc["ip"] = toml_edit::table();
+        let mut arr = toml_edit::Array::new();
+        // sort the units for a consistent file
+        let mut units: Vec<&PrimaryUnit> = units.values().collect();
+        units.sort_by(|a, b| a.get_iden().cmp(b.get_iden()));
+        for unit in units {
+            arr.push(unit.to_toml(dir));
+        }
+        arr.iter_mut().for_each(|f| {
+            f.decor_mut().set_prefix("\n    ");
+            f.decor_mut().set_suffix("");
+        });
+        arr.set_trailing("\n");
+        arr.set_trailing_comma(true);
+        doc["ip"]["units"] = toml_edit::value(arr);
+        fs::write(dir.join(ORBIT_METADATA_FILE), doc.to_string())?;
+        Ok(())
+    }
 
     /// Gathers the list of primary design units for the current ip.
     ///
@@ -288,17 +400,7 @@ impl Ip {
         let meta_file: PathBuf = dir.join(ORBIT_METADATA_FILE);
         if Path::exists(&meta_file) == true {
             if let Ok(contents) = fs::read_to_string(&meta_file) {
-                if let Ok(toml) = contents.parse::<Document>() {
-                    let entry = toml.get("ip")?.as_table()?.get("units")?.as_array()?;
-                    let mut map = HashMap::new();
-                    for unit in entry {
-                        let pdu = PrimaryUnit::from_toml(unit.as_inline_table()?)?;
-                        map.insert(pdu.get_iden().clone(), pdu);
-                    }
-                    Some(map)
-                } else {
-                    None
-                }
+                Self::parse_units_metadata(&contents, dir)
             } else {
                 None
             }
@@ -307,14 +409,48 @@ impl Ip {
         }
     }
 
+    /// Parses the primary design units from the `contents` of an
+    /// [ORBIT_METADATA_FILE], where the source files are relative to `dir`.
+    ///
+    /// Returns `None` if the contents are not valid metadata.
+    pub fn parse_units_metadata(
+        contents: &str,
+        dir: &PathBuf,
+    ) -> Option<HashMap<Identifier, PrimaryUnit>> {
+        let toml = contents.parse::<Document>().ok()?;
+        let entry = toml.get("ip")?.as_table()?.get("units")?.as_array()?;
+        let mut map = HashMap::new();
+        for unit in entry {
+            let pdu = PrimaryUnit::from_toml(unit.as_inline_table()?, dir)?;
+            map.insert(pdu.get_iden().clone(), pdu);
+        }
+        Some(map)
+    }
+
     /// Compile a list of referenced paths to make sure are copied into a directory
     /// when moving an IP around the filesystem.
+    ///
+    /// Includes the README and every file matching a `collateral` pattern of
+    /// the manifest.
     pub fn get_files_to_keep(&self) -> HashSet<PathBuf> {
         let mut list = HashSet::new();
         if let Some(readme) = self.get_man().get_ip().get_readme() {
             // resolve a relative path
             list.insert(filesystem::resolve_rel_path2(self.get_root(), readme));
         }
+        let collateral = self.get_man().get_ip().get_collateral();
+        if collateral.is_empty() == false {
+            filesystem::walk_ip(self.get_root())
+                .into_iter()
+                .filter(|p| p.is_file() == true)
+                .filter(|p| {
+                    let rel = filesystem::into_std_str(filesystem::remove_base(self.get_root(), p));
+                    collateral.iter().any(|pat| filesystem::matches_path(pat, &rel))
+                })
+                .for_each(|p| {
+                    list.insert(p);
+                });
+        }
         list
     }
 }
@@ -488,6 +624,60 @@ mod test {
         )
     }
 
+    #[test]
+    fn compute_minimal_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        filesystem::copy(&PathBuf::from("./tests/env/project1/"), &root, false, None).unwrap();
+        // files that are not installed do not affect the checksum
+        std::fs::write(root.join("notes.txt"), "not installed").unwrap();
+
+        let ip = Ip::load(root.clone()).unwrap();
+        assert_ne!(Ip::compute_checksum(&root), ip.compute_minimal_checksum());
+        assert_eq!(
+            ip.compute_minimal_checksum(),
+            Ip::compute_checksum(&PathBuf::from("./tests/env/project1/"))
+        );
+    }
+
+    #[test]
+    fn generated_files_skip_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        filesystem::copy(&PathBuf::from("./tests/env/project1/"), &root, false, None).unwrap();
+        let sum = Ip::compute_checksum(&root);
+
+        std::fs::write(root.join("regmap.vhd"), "-- generated").unwrap();
+        assert_ne!(Ip::compute_checksum(&root), sum);
+        Ip::write_generated_files(&root, &[String::from("regmap.vhd")]).unwrap();
+        assert_eq!(Ip::compute_checksum(&root), sum);
+    }
+
+    #[test]
+    fn minimal_copy_keeps_collateral() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        filesystem::copy(&PathBuf::from("./tests/env/project1/"), &root, false, None).unwrap();
+        let manifest = std::fs::read_to_string(root.join(IP_MANIFEST_FILE)).unwrap();
+        std::fs::write(
+            root.join(IP_MANIFEST_FILE),
+            manifest.replacen("[ip]\n", "[ip]\ncollateral = [\"mem/*.hex\"]\n", 1),
+        )
+        .unwrap();
+        std::fs::create_dir(root.join("mem")).unwrap();
+        std::fs::write(root.join("mem/rom.hex"), "00\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "not installed").unwrap();
+
+        let ip = Ip::load(root.clone()).unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let installed = dest.path().join("slot");
+        filesystem::copy(&root, &installed, true, Some(ip.get_files_to_keep())).unwrap();
+        assert_eq!(installed.join("mem/rom.hex").exists(), true);
+        assert_eq!(installed.join("notes.txt").exists(), false);
+        // the collateral is part of the checksum of the installed files
+        assert_eq!(ip.compute_minimal_checksum(), Ip::compute_checksum(&installed));
+    }
+
     #[test]
     fn from_str_ip_spec() {
         let ip = format!("name{}1.0.0", SPEC_DELIM);
@@ -1,12 +1,21 @@
 use super::ip::Ip;
+use super::ip::MIN_PARALLEL_SLOTS;
 use super::lockfile::LockFile;
+use super::fileset;
+use super::lang::vhdl::primaryunit;
+use super::lang::vhdl::primaryunit::PrimaryUnitStore;
 use super::manifest::Manifest;
+use super::manifest::ORBIT_METADATA_FILE;
 use crate::util::anyerror::{Fault, AnyError};
 use crate::util::compress;
+use crate::util::filesystem;
+use crate::util::logger;
+use crate::util::parallel;
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use std::fs::{self, File};
+use std::io::Cursor;
 use std::io::Read;
 use std::io::Write;
 use std::path::Path;
@@ -102,11 +111,11 @@ impl IpArchive {
                 match repairing {
                     true => panic!("Repairing function failed"),
                     false => {
-                        println!("info: {}", "Failed to parse downloaded file's header bytes; running repair function ...");
+                        logger::info("Failed to parse downloaded file's header bytes; running repair function ...");
                         let repaired_bytes = Self::repair(archive, &path)?;
                         match Self::parse(repaired_bytes, true, &path) {
                             Ok(rp) => {
-                                println!("info: {}", "Repair successful");
+                                logger::info("Repair successful");
                                 return Ok(rp)
                             }
                             Err(e) => return Err(e)?
@@ -123,11 +132,11 @@ impl IpArchive {
                 match repairing {
                     true => panic!("Repairing function failed"),
                     false => {
-                        println!("info: {}", "Failed to parse downloaded file's header bytes; running repair function ...");
+                        logger::info("Failed to parse downloaded file's header bytes; running repair function ...");
                         let repaired_bytes = Self::repair(archive, &path)?;
                         match Self::parse(repaired_bytes, true, &path) {
                             Ok(rp) => {
-                                println!("info: {}", "Repair successful");
+                                logger::info("Repair successful");
                                 return Ok(rp)
                             }
                             Err(e) => return Err(e)?
@@ -189,11 +198,42 @@ impl IpArchive {
         let mut temp_file = tempfile::tempfile()?;
         temp_file.write_all(bytes)?;
         let mut zip_archive = ZipArchive::new(temp_file)?;
-        zip_archive.extract(dest)?;
+        zip_archive.extract(filesystem::to_extended(dest))?;
 
         Ok(())
     }
 
+    /// Collects the primary design units of the ip stored in the compressed
+    /// `bytes` without extracting the archive.
+    ///
+    /// The units are read from the archive's [ORBIT_METADATA_FILE] when it has
+    /// one, otherwise only the VHDL files are decompressed and parsed. Source
+    /// files are named relative to the root of the archive.
+    pub fn read_units(bytes: &[u8]) -> Result<PrimaryUnitStore, Fault> {
+        let mut zip_archive = ZipArchive::new(Cursor::new(bytes))?;
+
+        // use the units recorded when the archive was made from an installation
+        if let Ok(mut entry) = zip_archive.by_name(ORBIT_METADATA_FILE) {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            if let Some(units) = Ip::parse_units_metadata(&contents, &PathBuf::new()) {
+                return Ok(units);
+            }
+        }
+
+        let mut sources = Vec::new();
+        for i in 0..zip_archive.len() {
+            let mut entry = zip_archive.by_index(i)?;
+            if entry.is_file() == false || fileset::is_vhdl(entry.name()) == false {
+                continue;
+            }
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            sources.push((entry.name().to_string(), contents));
+        }
+        primaryunit::collect_units_from_sources(&sources)
+    }
+
     /// Stores the project's state and additional metadata into a .zip archive.
     pub fn write(ip: &Ip, dest: &PathBuf) -> Result<(), Fault> {
         // compress the ip package
@@ -242,24 +282,46 @@ impl IpArchive {
     /// Detects all Ip found as archives.
     pub fn detect_all(dir: &PathBuf) -> Result<Vec<Ip>, Fault> {
         // for each .ip file
-        fs::read_dir(&dir)?
+        let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
             .filter_map(|result| if let Ok(r) = result { Some(r) } else { None })
             .map(|entry| entry.path().to_path_buf())
             .filter(|path| path.extension().is_some() && path.extension().unwrap() == ARCHIVE_EXT)
-            .map(|path| match IpArchive::read(&path) {
-                Ok(arc) => Ok(Ip::from(arc)),
-                Err(e) => Err(e),
-            })
-            .collect()
+            .collect();
+        // read in the same order regardless of how the directory is listed
+        paths.sort();
+        // errors are not sendable between threads, so keep only their messages
+        parallel::map_ordered(&paths, MIN_PARALLEL_SLOTS, |path| match IpArchive::read(&path) {
+            Ok(arc) => Ok(Ip::from(arc)),
+            Err(e) => Err(e.to_string()),
+        })
+        .into_iter()
+        .map(|r| r.map_err(|e| Fault::from(AnyError(e))))
+        .collect()
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use super::*;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-//     #[test]
-//     fn write_and_read() {
+    #[test]
+    fn read_units_without_extracting() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("project1.ip");
+        let ip = Ip::load(PathBuf::from("./tests/env/project1/")).unwrap();
+        IpArchive::write(&ip, &dest).unwrap();
 
-//     }
-// }
+        let (_, _, bytes) = IpArchive::read(&dest).unwrap().decouple();
+        let units = IpArchive::read_units(&bytes).unwrap();
+        let mut expected: Vec<String> = Ip::collect_units(true, ip.get_root())
+            .unwrap()
+            .into_keys()
+            .map(|k| k.to_string())
+            .collect();
+        let mut names: Vec<String> = units.into_keys().map(|k| k.to_string()).collect();
+        expected.sort();
+        names.sort();
+        assert_eq!(names.is_empty(), false);
+        assert_eq!(names, expected);
+    }
+}
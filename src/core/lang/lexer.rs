@@ -1,3 +1,4 @@
+use crate::util::diagnostic::{Diagnostic, Span};
 use std::fmt::Display;
 use std::iter::Peekable;
 
@@ -64,6 +65,26 @@ impl<T: Display> TokenError<T> {
             err: err,
         }
     }
+
+    /// Returns the position in the file where the error was detected.
+    pub fn locate(&self) -> &Position {
+        &self.position
+    }
+
+    /// References the inner error type.
+    pub fn as_ref(&self) -> &T {
+        &self.err
+    }
+
+    /// Reports the error as a `Diagnostic` spanning the character where it was
+    /// detected.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(&self.err.to_string()).span(Span::new(
+            self.position.line(),
+            self.position.col(),
+            1,
+        ))
+    }
 }
 
 impl<T: Display> Display for TokenError<T> {
@@ -5,3 +5,28 @@ pub mod lexer;
 pub mod parser;
 
 pub mod node;
+
+use crate::util::anyerror::AnyError;
+use std::str::FromStr;
+
+/// A hardware description language that code can be generated in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Lang {
+    Vhdl,
+    Verilog,
+}
+
+impl FromStr for Lang {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "vhdl" => Ok(Self::Vhdl),
+            "verilog" => Ok(Self::Verilog),
+            _ => Err(AnyError(format!(
+                "unknown language '{}'; expected 'vhdl' or 'verilog'",
+                s
+            ))),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::core::algo::IpFileNode;
 use crate::core::lang::vhdl::subunit::SubUnit;
+use crate::core::lang::vhdl::symbol::CompoundIdentifier;
+use crate::core::lang::vhdl::symbol::Configuration;
 use crate::core::lang::vhdl::symbol::VHDLSymbol;
 use crate::core::lang::vhdl::token::Identifier;
 use crate::util::anyerror::AnyError;
@@ -9,6 +11,9 @@ use colored::Colorize;
 pub struct HdlNode<'a> {
     sym: VHDLSymbol,
     files: Vec<&'a IpFileNode<'a>>, // must use a vector to retain file order in blueprint
+    configs: Vec<Configuration>,
+    unresolved: Vec<CompoundIdentifier>,
+    external: bool,
 }
 
 impl<'a> HdlNode<'a> {
@@ -18,6 +23,9 @@ impl<'a> HdlNode<'a> {
         Self {
             sym: sym,
             files: set,
+            configs: Vec::new(),
+            unresolved: Vec::new(),
+            external: false,
         }
     }
 
@@ -36,6 +44,29 @@ impl<'a> HdlNode<'a> {
         &mut self.sym
     }
 
+    /// Stores a configuration declared for this entity.
+    pub fn add_config(&mut self, cfg: Configuration) {
+        self.configs.push(cfg);
+    }
+
+    /// References the configurations declared for this entity.
+    pub fn get_configs(&self) -> &Vec<Configuration> {
+        &self.configs
+    }
+
+    /// Stores a reference made by this unit (or its architectures) to a unit
+    /// that does not exist in the graph.
+    pub fn add_unresolved(&mut self, dep: CompoundIdentifier) {
+        if self.unresolved.contains(&dep) == false {
+            self.unresolved.push(dep);
+        }
+    }
+
+    /// References the references made to units that do not exist in the graph.
+    pub fn get_unresolved(&self) -> &Vec<CompoundIdentifier> {
+        &self.unresolved
+    }
+
     pub fn get_associated_files(&self) -> &Vec<&'a IpFileNode<'a>> {
         &self.files
     }
@@ -48,12 +79,36 @@ impl<'a> HdlNode<'a> {
         Self {
             sym: sym,
             files: Vec::new(),
+            configs: Vec::new(),
+            unresolved: Vec::new(),
+            external: false,
+        }
+    }
+
+    /// Creates a node for a unit declared as a blackbox in a manifest, which is
+    /// resolved despite having no source files.
+    pub fn external(sym: VHDLSymbol) -> Self {
+        Self {
+            sym: sym,
+            files: Vec::new(),
+            configs: Vec::new(),
+            unresolved: Vec::new(),
+            external: true,
         }
     }
 
+    pub fn is_external(&self) -> bool {
+        self.external
+    }
+
     pub fn display(&self, fmt: &IdentifierFormat) -> String {
         let name = self.sym.as_iden().unwrap_or(&Identifier::new()).to_string();
-        if self.is_black_box() == true {
+        if self.is_external() == true {
+            match fmt {
+                IdentifierFormat::Long => format!("{} (blackbox)", &name),
+                IdentifierFormat::Short => format!("{}", &name),
+            }
+        } else if self.is_black_box() == true {
             format!("{} {}", &name.yellow(), "?".yellow())
         } else {
             match fmt {
@@ -90,6 +145,11 @@ impl<'a> SubUnitNode<'a> {
     pub fn get_file(&self) -> &'a IpFileNode<'a> {
         &self.file
     }
+
+    /// Transforms the node into its underlying secondary design unit.
+    pub fn into_sub(self) -> SubUnit {
+        self.sub
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -1,4 +1,5 @@
-use super::lexer::Token;
+use super::lexer::{Position, Token};
+use crate::util::diagnostic::{Diagnostic, Span};
 use std::fmt::Display;
 
 pub trait Parse<T> {
@@ -34,18 +35,37 @@ impl<T> Symbol<T> {
 
 #[derive(Debug, PartialEq)]
 pub struct SymbolError<T: Display> {
+    position: Position,
     err: T,
 }
 
 impl<T: Display> SymbolError<T> {
     /// Creates a new `SymbolError` struct at position `loc` with error `T`.
-    pub fn new(err: T) -> Self {
-        Self { err: err }
+    pub fn new(err: T, loc: Position) -> Self {
+        Self {
+            position: loc,
+            err: err,
+        }
+    }
+
+    /// Returns the position in the file where the error was detected.
+    pub fn locate(&self) -> &Position {
+        &self.position
+    }
+
+    /// Reports the error as a `Diagnostic` spanning the character where it was
+    /// detected.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(&self.err.to_string()).span(Span::new(
+            self.position.line(),
+            self.position.col(),
+            1,
+        ))
     }
 }
 
 impl<T: Display> Display for SymbolError<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.err)
+        write!(f, "{} {}", self.position, self.err)
     }
 }
@@ -1,7 +1,196 @@
+use super::token::Identifier;
+use super::token::Keyword;
+use crate::util::anyerror::AnyError;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// How an instance connects its ports and generics.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Association {
+    /// Each connection names its formal: `a => a`.
+    Named,
+    /// Connections are listed in the order of the interface declarations.
+    Positional,
+}
+
+impl FromStr for Association {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "named" => Ok(Self::Named),
+            "positional" => Ok(Self::Positional),
+            _ => Err(AnyError(format!(
+                "unknown association '{}'; expected 'named' or 'positional'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The syntax used to instantiate an entity.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceStyle {
+    /// Direct entity instantiation: `u0 : entity work.foo`.
+    Entity,
+    /// Component instantiation, which requires a component declaration.
+    Component,
+}
+
+impl FromStr for InstanceStyle {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "entity" => Ok(Self::Entity),
+            "component" => Ok(Self::Component),
+            _ => Err(AnyError(format!(
+                "unknown instance style '{}'; expected 'entity' or 'component'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The revision of the VHDL language whose conventions generated code follows.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub enum VhdlStandard {
+    #[serde(rename = "93")]
+    Vhdl93,
+    #[serde(rename = "2008")]
+    Vhdl08,
+}
+
+impl FromStr for VhdlStandard {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "93" => Ok(Self::Vhdl93),
+            "08" | "2008" => Ok(Self::Vhdl08),
+            _ => Err(AnyError(format!(
+                "unknown vhdl standard '{}'; expected '93' or '2008'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The letter case applied to words of generated code.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Case {
+    Lower,
+    Upper,
+    /// Words are written as they were declared.
+    Preserve,
+}
+
+impl Case {
+    fn apply(&self, word: &str) -> String {
+        match self {
+            Self::Lower => word.to_lowercase(),
+            Self::Upper => word.to_uppercase(),
+            Self::Preserve => word.to_string(),
+        }
+    }
+}
+
+/// The casing conventions that generated code follows, stored as the
+/// `[codegen]` table of the configuration.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Codegen {
+    #[serde(rename = "keyword-case")]
+    keyword_case: Option<Case>,
+    #[serde(rename = "identifier-case")]
+    identifier_case: Option<Case>,
+}
+
+impl Codegen {
+    pub fn new() -> Self {
+        Self {
+            keyword_case: Some(Case::Lower),
+            identifier_case: Some(Case::Preserve),
+        }
+    }
+
+    pub fn get_keyword_case(&self) -> Case {
+        self.keyword_case.unwrap_or(Case::Lower)
+    }
+
+    pub fn get_identifier_case(&self) -> Case {
+        self.identifier_case.unwrap_or(Case::Preserve)
+    }
+
+    /// Rewrites the keywords and basic identifiers of the generated VHDL `code`
+    /// in their configured case.
+    ///
+    /// Extended identifiers, literals, comments, and any terminal color codes
+    /// are left untouched.
+    pub fn apply(&self, code: &str) -> String {
+        let (kw_case, id_case) = (self.get_keyword_case(), self.get_identifier_case());
+        if kw_case == Case::Preserve && id_case == Case::Preserve {
+            return code.to_string();
+        }
+        let chars: Vec<char> = code.chars().collect();
+        // finds the index after the first character at or past `i` that satisfies `end`
+        let find = |i: usize, end: &dyn Fn(char) -> bool| match chars[i..].iter().position(|c| end(*c)) {
+            Some(j) => i + j + 1,
+            None => chars.len(),
+        };
+        // finds the index of the first character at or past `i` that is not within `word`
+        let span = |i: usize, word: &dyn Fn(char) -> bool| match chars[i..].iter().position(|c| word(*c) == false) {
+            Some(j) => i + j,
+            None => chars.len(),
+        };
+
+        let mut result = String::with_capacity(code.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1);
+            let end = match c {
+                '\x1b' => find(i + 1, &|c| c == 'm'),
+                '"' => find(i + 1, &|c| c == '"'),
+                '\\' => find(i + 1, &|c| c == '\\'),
+                '-' if next == Some(&'-') => span(i, &|c| c != '\n'),
+                '\'' if chars.get(i + 2) == Some(&'\'') => i + 3,
+                _ if c.is_ascii_digit() => span(i, &|c| c.is_alphanumeric() || c == '_' || c == '#' || c == '.'),
+                _ if c.is_alphabetic() => span(i, &|c| c.is_alphanumeric() || c == '_'),
+                _ => i + 1,
+            };
+            let text: String = chars[i..end].iter().collect();
+            match c.is_alphabetic() {
+                true => match Keyword::match_keyword(&text) {
+                    Some(_) => result.push_str(&kw_case.apply(&text)),
+                    None => result.push_str(&id_case.apply(&text)),
+                },
+                false => result.push_str(&text),
+            }
+            i = end;
+        }
+        result
+    }
+
+    /// Merges any populated data from `rhs` into attributes that do not already
+    /// have data defined in `self`.
+    pub fn merge(&mut self, rhs: Option<Self>) -> () {
+        if let Some(rhs) = rhs {
+            if self.keyword_case.is_some() == false {
+                self.keyword_case = rhs.keyword_case
+            }
+            if self.identifier_case.is_some() == false {
+                self.identifier_case = rhs.identifier_case
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct VhdlFormat {
     #[serde(rename = "highlight-syntax")]
@@ -22,6 +211,19 @@ pub struct VhdlFormat {
     space_interface_parenthesis: Option<bool>,
     #[serde(rename = "instance-name")]
     instance_name: Option<String>,
+    #[serde(rename = "instance-association")]
+    instance_association: Option<Association>,
+    #[serde(rename = "instance-style")]
+    instance_style: Option<InstanceStyle>,
+    #[serde(rename = "signal-prefix")]
+    signal_prefix: Option<String>,
+    #[serde(rename = "signal-suffix")]
+    signal_suffix: Option<String>,
+    #[serde(rename = "signal-defaults")]
+    signal_defaults: Option<bool>,
+    #[serde(rename = "omit-default-generics")]
+    omit_default_generics: Option<bool>,
+    standard: Option<VhdlStandard>,
 }
 
 impl VhdlFormat {
@@ -36,9 +238,56 @@ impl VhdlFormat {
             indent_interfaces: Some(true),
             space_interface_parenthesis: Some(false),
             instance_name: Some(String::from("uX")),
+            instance_association: Some(Association::Named),
+            instance_style: Some(InstanceStyle::Entity),
+            signal_prefix: Some(String::new()),
+            signal_suffix: Some(String::new()),
+            signal_defaults: Some(true),
+            omit_default_generics: Some(false),
+            standard: Some(VhdlStandard::Vhdl93),
         }
     }
 
+    pub fn tab_size(mut self, size: Option<u8>) -> Self {
+        self.tab_size = size;
+        self
+    }
+
+    pub fn instance_association(mut self, assoc: Option<Association>) -> Self {
+        self.instance_association = assoc;
+        self
+    }
+
+    pub fn instance_style(mut self, style: Option<InstanceStyle>) -> Self {
+        self.instance_style = style;
+        self
+    }
+
+    pub fn signal_prefix(mut self, prefix: Option<String>) -> Self {
+        self.signal_prefix = prefix;
+        self
+    }
+
+    pub fn signal_suffix(mut self, suffix: Option<String>) -> Self {
+        self.signal_suffix = suffix;
+        self
+    }
+
+    pub fn signal_defaults(mut self, enable: Option<bool>) -> Self {
+        self.signal_defaults = enable;
+        self
+    }
+
+    pub fn omit_default_generics(mut self, enable: Option<bool>) -> Self {
+        self.omit_default_generics = enable;
+        self
+    }
+
+    pub fn standard(mut self, std: Option<VhdlStandard>) -> Self {
+        self.standard = std;
+        self
+    }
+
     pub fn is_syntax_highlighted(&self) -> bool {
         self.highlight_syntax.unwrap_or(false)
     }
@@ -75,6 +324,42 @@ impl VhdlFormat {
         self.instance_name.as_ref().unwrap_or(&String::from("uX")).clone()
     }
 
+    pub fn get_instance_association(&self) -> Association {
+        self.instance_association.unwrap_or(Association::Named)
+    }
+
+    pub fn get_instance_style(&self) -> InstanceStyle {
+        self.instance_style.unwrap_or(InstanceStyle::Entity)
+    }
+
+    /// Checks if declared signals are initialized with their port's default value.
+    pub fn is_signal_defaulted(&self) -> bool {
+        self.signal_defaults.unwrap_or(true)
+    }
+
+    /// Checks if generics that keep their default value are left out of
+    /// instances and constant declarations.
+    pub fn is_default_generics_omitted(&self) -> bool {
+        self.omit_default_generics.unwrap_or(false)
+    }
+
+    pub fn get_standard(&self) -> VhdlStandard {
+        self.standard.unwrap_or(VhdlStandard::Vhdl93)
+    }
+
+    /// Applies the signal prefix and suffix to the name of a port to create the
+    /// name of the signal connected to it.
+    ///
+    /// Extended identifiers keep the affixes within their backslashes.
+    pub fn to_signal_name(&self, port: &Identifier) -> Identifier {
+        let prefix = self.signal_prefix.as_deref().unwrap_or("");
+        let suffix = self.signal_suffix.as_deref().unwrap_or("");
+        match port {
+            Identifier::Basic(s) => Identifier::Basic(format!("{}{}{}", prefix, s, suffix)),
+            Identifier::Extended(s) => Identifier::Extended(format!("{}{}{}", prefix, s, suffix)),
+        }
+    }
+
     /// Merges any populated data from `rhs` into attributes that do not already
     /// have data defined in `self`.
     pub fn merge(&mut self, rhs: Option<Self>) -> () {
@@ -106,6 +391,96 @@ impl VhdlFormat {
             if self.instance_name.is_some() == false {
                 self.instance_name = rhs.instance_name
             }
+            if self.instance_association.is_some() == false {
+                self.instance_association = rhs.instance_association
+            }
+            if self.instance_style.is_some() == false {
+                self.instance_style = rhs.instance_style
+            }
+            if self.signal_prefix.is_some() == false {
+                self.signal_prefix = rhs.signal_prefix
+            }
+            if self.signal_suffix.is_some() == false {
+                self.signal_suffix = rhs.signal_suffix
+            }
+            if self.signal_defaults.is_some() == false {
+                self.signal_defaults = rhs.signal_defaults
+            }
+            if self.omit_default_generics.is_some() == false {
+                self.omit_default_generics = rhs.omit_default_generics
+            }
+            if self.standard.is_some() == false {
+                self.standard = rhs.standard
+            }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn instance_options() {
+        let fmt: VhdlFormat = toml::from_str(
+            "instance-association = \"positional\"\ninstance-style = \"component\"\nsignal-prefix = \"w_\"\nstandard = \"2008\"\n",
+        )
+        .unwrap();
+        assert_eq!(fmt.get_instance_association(), Association::Positional);
+        assert_eq!(fmt.get_instance_style(), InstanceStyle::Component);
+        assert_eq!(fmt.get_standard(), VhdlStandard::Vhdl08);
+        assert_eq!(
+            fmt.to_signal_name(&Identifier::Basic(String::from("clk"))),
+            Identifier::Basic(String::from("w_clk"))
+        );
+
+        // command-line values take precedence over the configuration
+        let mut cli = VhdlFormat::default()
+            .instance_association(Some(Association::Named))
+            .signal_suffix(Some(String::from("_s")));
+        cli.merge(Some(fmt));
+        assert_eq!(cli.get_instance_association(), Association::Named);
+        assert_eq!(cli.get_instance_style(), InstanceStyle::Component);
+        assert_eq!(
+            cli.to_signal_name(&Identifier::Extended(String::from("data in"))),
+            Identifier::Extended(String::from("w_data in_s"))
+        );
+        assert!(VhdlStandard::from_str("87").is_err());
+    }
+
+    #[test]
+    fn codegen_casing() {
+        let code = "\
+u0 : entity work.Adder
+  generic map (
+    N => 16#fF#
+  )
+  port map (
+    \\Data In\\ => data_in, -- Keep Me
+    en => '1'
+  );";
+        assert_eq!(Codegen::new().apply(code), code);
+
+        let cg: Codegen = toml::from_str("keyword-case = \"upper\"\nidentifier-case = \"lower\"\n").unwrap();
+        assert_eq!(
+            cg.apply(code),
+            "\
+u0 : ENTITY work.adder
+  GENERIC MAP (
+    n => 16#fF#
+  )
+  PORT MAP (
+    \\Data In\\ => data_in, -- Keep Me
+    en => '1'
+  );"
+        );
+
+        // only the keywords change when identifiers are preserved
+        let mut cg: Codegen = toml::from_str("keyword-case = \"upper\"\n").unwrap();
+        cg.merge(Some(Codegen::new()));
+        assert_eq!(cg.get_identifier_case(), Case::Preserve);
+        assert_eq!(
+            cg.apply("\x1b[31msignal\x1b[0m Clk : std_logic;"),
+            "\x1b[31mSIGNAL\x1b[0m Clk : std_logic;"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use colored::Colorize;
 use palette::*;
 
 use crate::util::anyerror::AnyError;
+use crate::util::environment;
 
 pub type Rgb = (u8, u8, u8);
 
@@ -49,13 +50,42 @@ pub const INSTANCE_LHS_IDENTIFIER: Rgb = LT_SKY_BLUE;
 pub const DATA_TYPE: Rgb = NATURE_GREEN;
 pub const ENTITY_NAME: Rgb = NATURE_GREEN;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ColorMode {
     Always,
     Auto,
     Never,
 }
 
+impl ColorMode {
+    /// Determines if output should be colored. `Auto` disables color when
+    /// `no_color` is a non-empty value and otherwise leaves the decision to the
+    /// terminal (`None`).
+    pub fn is_enabled(&self, no_color: Option<&str>) -> Option<bool> {
+        match self {
+            Self::Always => Some(true),
+            Self::Never => Some(false),
+            Self::Auto => match no_color {
+                Some(v) if v.is_empty() == false => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    /// Sets the coloring for all output written afterward according to the mode
+    /// and the NO_COLOR environment variable.
+    ///
+    /// Returns the decision so that other output handlers can follow it.
+    pub fn apply(&self) -> Option<bool> {
+        let no_color = std::env::var(environment::NO_COLOR).ok();
+        let enabled = self.is_enabled(no_color.as_deref());
+        if let Some(e) = enabled {
+            colored::control::set_override(e);
+        }
+        enabled
+    }
+}
+
 impl FromStr for ColorMode {
     type Err = AnyError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -69,3 +99,18 @@ impl FromStr for ColorMode {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn color_mode_enabled() {
+        assert_eq!(ColorMode::Always.is_enabled(Some("1")), Some(true));
+        assert_eq!(ColorMode::Never.is_enabled(None), Some(false));
+        assert_eq!(ColorMode::Auto.is_enabled(Some("1")), Some(false));
+        // an empty value does not disable color
+        assert_eq!(ColorMode::Auto.is_enabled(Some("")), None);
+        assert_eq!(ColorMode::Auto.is_enabled(None), None);
+    }
+}
@@ -1,4 +1,4 @@
-use super::format::VhdlFormat;
+use super::format::{Association, VhdlFormat};
 use super::highlight::*;
 use super::token::{Identifier, ToColor};
 use colored::ColoredString;
@@ -216,6 +216,24 @@ impl Generics {
     pub fn new() -> Self {
         Self(InterfaceDeclarations(Vec::new()))
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 .0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0 .0.len()
+    }
+
+    /// Iterates through the generics in their declared order.
+    pub fn iter(&self) -> std::slice::Iter<'_, InterfaceDeclaration> {
+        self.0.iter()
+    }
+
+    /// Finds the generic declared as `name`.
+    pub fn get(&self, name: &Identifier) -> Option<&InterfaceDeclaration> {
+        self.0.get(name)
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize)]
@@ -233,6 +251,16 @@ impl Ports {
     pub fn len(&self) -> usize {
         self.0 .0.len()
     }
+
+    /// Iterates through the ports in their declared order.
+    pub fn iter(&self) -> std::slice::Iter<'_, InterfaceDeclaration> {
+        self.0.iter()
+    }
+
+    /// Finds the port declared as `name`.
+    pub fn get(&self, name: &Identifier) -> Option<&InterfaceDeclaration> {
+        self.0.get(name)
+    }
 }
 
 impl std::fmt::Display for StaticExpression {
@@ -282,8 +310,41 @@ impl Serialize for Expr {
     }
 }
 
+/// The class of item an interface declaration introduces.
+///
+/// VHDL-2008 allows generics to be types, subprograms, and packages in
+/// addition to constants.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
+    Object,
+    Type,
+    Subprogram,
+    Package,
+}
+
+impl InterfaceKind {
+    /// Determines the class of a declaration from its leading `keyword`.
+    fn from_keyword(keyword: &Keyword) -> Option<Self> {
+        match keyword {
+            Keyword::Type => Some(Self::Type),
+            Keyword::Function | Keyword::Procedure | Keyword::Pure | Keyword::Impure => {
+                Some(Self::Subprogram)
+            }
+            Keyword::Package => Some(Self::Package),
+            _ => None,
+        }
+    }
+
+    fn is_object(&self) -> bool {
+        self == &Self::Object
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize)]
 pub struct InterfaceDeclaration {
+    #[serde(skip_serializing_if = "InterfaceKind::is_object")]
+    kind: InterfaceKind,
     #[serde(skip_serializing)]
     initial_keyword: Option<Keyword>,
     #[serde(rename = "name")]
@@ -324,7 +385,16 @@ fn tokens_to_string(tokens: &Vec<VHDLToken>) -> ColorVec {
     while let Some(t) = iter.next() {
         // determine if to add trailing space after the token
         let trailing_space = match t {
-            VHDLToken::Delimiter(d) => is_spaced_token(d),
+            // separate a closing parenthesis from a following word
+            VHDLToken::Delimiter(Delimiter::ParenR) => match iter.peek() {
+                Some(VHDLToken::Delimiter(_)) | None => false,
+                Some(_) => true,
+            },
+            // keep a box '<>' tight against its closing parenthesis
+            VHDLToken::Delimiter(d) => {
+                is_spaced_token(d)
+                    && iter.peek().map(|m| m.check_delimiter(&Delimiter::ParenR)) != Some(true)
+            }
             _ => {
                 // make sure the next token is not a tight token (no-spaced)
                 if let Some(m) = iter.peek() {
@@ -352,9 +422,128 @@ fn tokens_to_string(tokens: &Vec<VHDLToken>) -> ColorVec {
 }
 
 impl InterfaceDeclaration {
+    /// Accesses the declaration's identifier.
+    pub fn get_name(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    /// Returns the lowercase mode of the declaration (`in`, `out`, `inout`,
+    /// `buffer`, or `linkage`).
+    ///
+    /// A declaration without an explicit mode is `in`, as in VHDL.
+    pub fn get_mode(&self) -> String {
+        self.mode
+            .0
+            .as_ref()
+            .unwrap_or(&Keyword::In)
+            .to_string()
+            .to_lowercase()
+    }
+
+    /// Accesses the class of item the declaration introduces.
+    pub fn get_kind(&self) -> &InterfaceKind {
+        &self.kind
+    }
+
+    /// Returns the subtype indication as written in the source code, such as
+    /// `std_logic_vector(7 downto 0)`.
+    ///
+    /// Type, subprogram, and package generics return their entire declaration.
+    pub fn get_type(&self) -> String {
+        tokens_to_string(&self.datatype.0).into_all_bland()
+    }
+
+    /// Returns the default value expression, if one was assigned with `:=`.
+    pub fn get_default(&self) -> Option<String> {
+        self.expr
+            .0
+            .as_ref()
+            .map(|e| tokens_to_string(&e.0).into_all_bland())
+    }
+
+    /// Returns the number of bits carried by the subtype, such as `8` for
+    /// `std_logic_vector(7 downto 0)` or `WIDTH` for
+    /// `std_logic_vector(WIDTH-1 downto 0)`.
+    ///
+    /// Bounds that are not numbers are kept as an expression. Returns `None`
+    /// for subtypes that are not single bits or constrained vectors of bits.
+    pub fn get_width(&self) -> Option<String> {
+        let range = self.to_verilog_range()?;
+        if range.is_empty() == true {
+            return Some(String::from("1"));
+        }
+        let (left, right) = range.strip_prefix('[')?.strip_suffix(']')?.split_once(':')?;
+        let ascending = self
+            .datatype
+            .0
+            .iter()
+            .any(|t| t.check_keyword(&Keyword::To));
+        let (high, low) = match ascending {
+            true => (right, left),
+            false => (left, right),
+        };
+        match (high.parse::<i64>(), low.parse::<i64>()) {
+            (Ok(h), Ok(l)) => Some((h - l + 1).max(0).to_string()),
+            _ => match low == "0" {
+                true => match high.strip_suffix("-1") {
+                    Some(w) => Some(w.trim().to_string()),
+                    None => Some(format!("{}+1", high)),
+                },
+                false => Some(format!("({})-({})+1", high, low)),
+            },
+        }
+    }
+
+    /// Checks if the declaration is a signal of kind `bus`.
+    pub fn is_bus(&self) -> bool {
+        self.bus_present
+    }
+
+    /// Checks if the declaration can be left out of an association list.
+    ///
+    /// Objects have a default with an expression after `:=`, and subprograms
+    /// have a default with a name or `<>` after `is`.
+    fn has_default(&self) -> bool {
+        match self.kind {
+            InterfaceKind::Object => self.expr.0.is_some(),
+            InterfaceKind::Subprogram => self.datatype.0.iter().any(|t| t.check_keyword(&Keyword::Is)),
+            InterfaceKind::Type | InterfaceKind::Package => false,
+        }
+    }
+
+    /// Translates the subtype into a Verilog packed range when it is a
+    /// constrained vector of bits, such as `[7:0]` for `std_logic_vector(7 downto 0)`.
+    ///
+    /// Returns an empty range for single bits and `None` for subtypes without a
+    /// Verilog equivalent.
+    fn to_verilog_range(&self) -> Option<String> {
+        let type_mark = self.datatype.0.first()?.as_identifier()?.to_string().to_lowercase();
+        match type_mark.as_str() {
+            "std_logic" | "std_ulogic" | "bit" | "boolean" => return Some(String::new()),
+            "std_logic_vector" | "std_ulogic_vector" | "bit_vector" | "unsigned" | "signed" => (),
+            _ => return None,
+        }
+        let tokens = &self.datatype.0;
+        let open = tokens.iter().position(|t| t.check_delimiter(&Delimiter::ParenL))?;
+        let close = tokens.iter().rposition(|t| t.check_delimiter(&Delimiter::ParenR))?;
+        let inner = tokens.get(open + 1..close)?;
+        let dir = inner
+            .iter()
+            .position(|t| t.check_keyword(&Keyword::Downto) || t.check_keyword(&Keyword::To))?;
+        Some(format!(
+            "[{}:{}]",
+            tokens_to_string(&inner[..dir].to_vec()).into_all_bland().trim(),
+            tokens_to_string(&inner[dir + 1..].to_vec()).into_all_bland().trim()
+        ))
+    }
 
     fn into_interface_string(&self, offset: usize) -> ColorVec {
         let mut result = ColorVec::new();
+        // type, subprogram, and package declarations are written as they are
+        if self.kind.is_object() == false {
+            result.append(tokens_to_string(&self.datatype.0));
+            return result;
+        }
         // identifier
         result.push_color(self.identifier.to_color());
         // whitespace
@@ -392,8 +581,10 @@ impl InterfaceDeclaration {
 
     /// Creates a declaration string to be copied into architecture declaration parts.
     ///
+    /// The default value is only assigned when `with_default` is set.
+    ///
     /// Note: `offset` is used for padding after the identifier string and before ':'.
-    fn into_declaration_string(&self, def_keyword: &Keyword, offset: usize) -> ColorVec {
+    fn into_declaration_string(&self, def_keyword: &Keyword, name: &Identifier, offset: usize, with_default: bool) -> ColorVec {
         let mut result = ColorVec::new();
         // keyword
         result.push_color(
@@ -404,7 +595,7 @@ impl InterfaceDeclaration {
         );
         result.push_str(" ");
         // identifier
-        result.push_color(color(&self.identifier.to_string(), SIGNAL_DEC_IDENTIFIER));
+        result.push_color(color(&name.to_string(), SIGNAL_DEC_IDENTIFIER));
         // whitespace
         if offset > 0 {
             result.push_whitespace(offset);
@@ -419,7 +610,7 @@ impl InterfaceDeclaration {
             result.push_color(Keyword::Bus.to_color())
         }
         // rhs initial assignment
-        if self.expr.0.is_some() == true {
+        if self.expr.0.is_some() == true && with_default == true {
             result.push_str(" ");
             result.append(self.expr.0.as_ref().unwrap().to_color_vec())
         }
@@ -427,18 +618,31 @@ impl InterfaceDeclaration {
     }
 
     /// Creates an instantiation line to be copied into an architecture region.
-    fn into_instance_string(&self, offset: usize) -> ColorVec {
+    ///
+    /// The formal is omitted when `actual` is associated by position.
+    fn into_instance_string(&self, actual: &Identifier, assoc: Association, offset: usize) -> ColorVec {
         let mut result = ColorVec::new();
 
-        result.push_color(color(&self.identifier.to_string(), INSTANCE_LHS_IDENTIFIER));
-        result.push_whitespace(offset);
-        result.push_color(Delimiter::Arrow.to_color());
-        result.push_str(" ");
-        result.push_color(self.identifier.to_color());
+        if assoc == Association::Named {
+            result.push_color(color(&self.identifier.to_string(), INSTANCE_LHS_IDENTIFIER));
+            result.push_whitespace(offset);
+            result.push_color(Delimiter::Arrow.to_color());
+            result.push_str(" ");
+        }
+        result.push_color(actual.to_color());
         result
     }
 }
 
+/// Writes the VHDL identifier `id` as a Verilog identifier, where extended
+/// identifiers become escaped identifiers.
+fn to_verilog_identifier(id: &Identifier) -> String {
+    match id {
+        Identifier::Basic(s) => s.clone(),
+        Identifier::Extended(s) => format!("\\{} ", s),
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize)]
 pub struct InterfaceDeclarations(Vec<InterfaceDeclaration>);
 
@@ -451,10 +655,27 @@ impl InterfaceDeclarations {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Finds the declaration with the identifier `name`.
+    pub fn get(&self, name: &Identifier) -> Option<&InterfaceDeclaration> {
+        self.0.iter().find(|d| &d.identifier == name)
+    }
+
+    /// Iterates through the declarations in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, InterfaceDeclaration> {
+        self.0.iter()
+    }
+
     /// Determines the length of the longest identifier.
     pub fn longest_identifier(&self) -> usize {
-        let longest = self
-            .0
+        Self::longest_of(&self.0.iter().collect())
+    }
+
+    fn longest_of(decls: &Vec<&InterfaceDeclaration>) -> usize {
+        let longest = decls
             .iter()
             .max_by(|x, y| x.identifier.len().cmp(&y.identifier.len()));
         match longest {
@@ -463,6 +684,22 @@ impl InterfaceDeclarations {
         }
     }
 
+    /// Collects the declarations that are connected when mapping to the signals
+    /// or constants declared with `def_keyword`.
+    ///
+    /// Generics that keep their default value are left out when the formatting
+    /// omits them.
+    pub fn to_mapped(&self, def_keyword: &Keyword, fmt: &VhdlFormat) -> Vec<&InterfaceDeclaration> {
+        self.0
+            .iter()
+            .filter(|d| {
+                def_keyword != &Keyword::Constant
+                    || fmt.is_default_generics_omitted() == false
+                    || d.has_default() == false
+            })
+            .collect()
+    }
+
     /// Creates a set of `InterfaceDeclaration`s from VHDL Tokens.
     pub fn from_double_listed_tokens(tokens: Vec<Vec<lexer::Token<VHDLToken>>>) -> Self {
         let mut inner = Vec::new();
@@ -480,6 +717,11 @@ impl InterfaceDeclarations {
     where
         I: Iterator<Item = lexer::Token<VHDLToken>>,
     {
+        // check if the declaration is a type, subprogram, or package
+        let token = tokens.peek()?;
+        if let Some(kind) = token.as_ref().as_keyword().and_then(InterfaceKind::from_keyword) {
+            return Self::from_non_object_tokens(kind, tokens);
+        }
         // check if optional 'signal'/'constant'/'file'? keyword is present
         let token = tokens.peek()?;
         let initial_keyword = if token.as_ref().as_keyword().is_some() {
@@ -542,6 +784,7 @@ impl InterfaceDeclarations {
         let mut signals = Vec::new();
         for identifier in identifiers.0 {
             let signal = InterfaceDeclaration {
+                kind: InterfaceKind::Object,
                 initial_keyword: initial_keyword.clone(),
                 identifier: identifier,
                 mode: Mode(mode.clone()),
@@ -557,6 +800,34 @@ impl InterfaceDeclarations {
         Some(Self(signals))
     }
 
+    /// Parses the VHDL tokens of an interface type, subprogram, or package
+    /// declaration.
+    ///
+    /// The declaration is named by the designator following its keyword and
+    /// keeps all of its tokens.
+    fn from_non_object_tokens<I>(kind: InterfaceKind, tokens: &mut Peekable<I>) -> Option<Self>
+    where
+        I: Iterator<Item = lexer::Token<VHDLToken>>,
+    {
+        let declaration: Vec<VHDLToken> = tokens.map(|t| t.take()).collect();
+        // skip the keywords to find the designator
+        let identifier = match declaration.iter().find(|t| t.as_keyword().is_none())? {
+            VHDLToken::Identifier(id) => id.clone(),
+            // an operator symbol designates an overloaded operator
+            VHDLToken::StrLiteral(s) => Identifier::Basic(format!("\"{}\"", s)),
+            _ => return None,
+        };
+        Some(Self(vec![InterfaceDeclaration {
+            kind: kind,
+            initial_keyword: None,
+            identifier: identifier,
+            mode: Mode(None),
+            datatype: SubtypeIndication(declaration),
+            bus_present: false,
+            expr: Expr(None),
+        }]))
+    }
+
     /// Creates the body of the component list of interface connections.
     pub fn to_interface_part_string(&self, fmt: &VhdlFormat, tab_count: usize) -> ColorVec {
         let mut result = ColorVec::new();
@@ -597,28 +868,107 @@ impl InterfaceDeclarations {
 
     pub fn to_declaration_part_string(&self, def_keyword: Keyword, fmt: &VhdlFormat) -> ColorVec {
         let mut result = ColorVec::new();
+        // only objects can be declared to connect to the interfaces
+        let ports: Vec<&InterfaceDeclaration> = self
+            .to_mapped(&def_keyword, fmt)
+            .into_iter()
+            .filter(|p| p.kind.is_object() == true)
+            .collect();
         // auto-align by first finding longest offset needed
         let offset = match fmt.is_auto_type_aligned() {
-            true => self.longest_identifier(),
+            true => Self::longest_of(&ports),
             false => fmt.get_type_offset() as usize,
         };
-        for port in &self.0 {
+        // constants always require their value
+        let with_default = def_keyword != Keyword::Signal || fmt.is_signal_defaulted() == true;
+        for port in ports {
             // compute the offset of the ':' and type of declaration
             let port_offset = match fmt.is_auto_type_aligned() {
                 true => offset - port.identifier.len() + fmt.get_type_offset() as usize,
                 false => offset,
             };
-            result.append(port.into_declaration_string(&def_keyword, port_offset));
+            result.append(port.into_declaration_string(&def_keyword, &Self::to_actual(&def_keyword, &port.identifier, fmt), port_offset, with_default));
             result.push_color(Delimiter::Terminator.to_color());
             result.push_str("\n");
         }
         result
     }
 
-    pub fn to_instantiation_part(&self, fmt: &VhdlFormat, tab_count: usize) -> ColorVec {
+    /// Creates the Verilog connections of a module instance to the signals or
+    /// constants declared with `def_keyword`, one per line.
+    ///
+    /// Type, subprogram, and package generics have no Verilog equivalent and
+    /// are left out.
+    pub fn to_verilog_connections(&self, def_keyword: Keyword, fmt: &VhdlFormat) -> String {
+        let tab = " ".repeat(fmt.get_tab_size() as usize);
+        self.to_mapped(&def_keyword, fmt)
+            .into_iter()
+            .filter(|port| port.kind.is_object() == true)
+            .map(|port| {
+                let actual = to_verilog_identifier(&Self::to_actual(&def_keyword, &port.identifier, fmt));
+                match fmt.get_instance_association() {
+                    Association::Named => format!(
+                        "{}.{}({})",
+                        tab,
+                        to_verilog_identifier(&port.identifier),
+                        actual
+                    ),
+                    Association::Positional => format!("{}{}", tab, actual),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(",\n")
+    }
+
+    /// Creates Verilog declarations for the parameters and wires that connect
+    /// to the interfaces, where the interfaces are declared with `def_keyword`.
+    ///
+    /// Parameters are only declared when they have a default value. Wires
+    /// whose VHDL subtype has no Verilog equivalent keep the subtype as a comment.
+    pub fn to_verilog_declarations(&self, def_keyword: Keyword, fmt: &VhdlFormat) -> String {
+        let mut result = String::new();
+        for port in self.to_mapped(&def_keyword, fmt) {
+            let name = to_verilog_identifier(&Self::to_actual(&def_keyword, &port.identifier, fmt));
+            match def_keyword {
+                Keyword::Signal => match port.to_verilog_range() {
+                    Some(range) if range.is_empty() => result.push_str(&format!("wire {};\n", name)),
+                    Some(range) => result.push_str(&format!("wire {} {};\n", range, name)),
+                    None => result.push_str(&format!(
+                        "wire {}; // {}\n",
+                        name,
+                        tokens_to_string(&port.datatype.0).into_all_bland().trim()
+                    )),
+                },
+                _ => {
+                    if let Some(expr) = &port.expr.0 {
+                        result.push_str(&format!(
+                            "localparam {} = {};\n",
+                            name,
+                            tokens_to_string(&expr.0).into_all_bland().trim()
+                        ));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Names the signal or constant declared to connect to the interface
+    /// `port`, where only signals take the formatting's prefix and suffix.
+    fn to_actual(def_keyword: &Keyword, port: &Identifier, fmt: &VhdlFormat) -> Identifier {
+        match def_keyword {
+            Keyword::Signal => fmt.to_signal_name(port),
+            _ => port.clone(),
+        }
+    }
+
+    /// Creates the mapping of the interfaces to the signals or constants
+    /// declared with `def_keyword`.
+    pub fn to_instantiation_part(&self, def_keyword: Keyword, fmt: &VhdlFormat, tab_count: usize) -> ColorVec {
+        let ports = self.to_mapped(&def_keyword, fmt);
         // auto-align by first finding longest offset needed
         let offset = match fmt.is_auto_mapping_aligned() {
-            true => self.longest_identifier(),
+            true => Self::longest_of(&ports),
             false => fmt.get_mapping_offset() as usize,
         };
         let mut result = ColorVec::new();
@@ -630,8 +980,8 @@ impl InterfaceDeclarations {
         result.push_color(Delimiter::ParenL.to_color());
         result.push_str("\n");
 
-        for port in &self.0 {
-            if port != self.0.first().unwrap() {
+        for (i, port) in ports.iter().enumerate() {
+            if i > 0 {
                 result.push_color(Delimiter::Comma.to_color());
                 result.push_str("\n");
             }
@@ -643,7 +993,11 @@ impl InterfaceDeclarations {
                 true => offset - port.identifier.len() + fmt.get_mapping_offset() as usize,
                 false => offset,
             };
-            result.append(port.into_instance_string(port_offset));
+            result.append(port.into_instance_string(
+                &Self::to_actual(&def_keyword, &port.identifier, fmt),
+                fmt.get_instance_association(),
+                port_offset,
+            ));
         }
         result.push_str("\n");
         if fmt.get_tab_size() > 0 && tab_count > 1 {
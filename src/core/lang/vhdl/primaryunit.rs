@@ -2,6 +2,7 @@ use super::super::lexer::Position;
 use super::symbol::VHDLSymbol;
 use crate::core::ip::IpSpec;
 use crate::core::lang::vhdl::symbol::VHDLParser;
+use crate::util::diagnostic::{Diagnostic, Span};
 use crate::util::filesystem;
 use crate::{core::lang::vhdl::token::Identifier, util::anyerror::Fault};
 use std::{collections::HashMap, path::PathBuf, str::FromStr};
@@ -38,7 +39,12 @@ impl PrimaryUnit {
     }
 
     /// Serializes the data into a toml inline table
-    pub fn to_toml(&self) -> toml_edit::Value {
+    ///
+    /// Source code files are written relative to the ip's `root` directory.
+    pub fn to_toml(&self, root: &PathBuf) -> toml_edit::Value {
+        let relative =
+            |f: &str| filesystem::into_std_str(filesystem::remove_base(root, &PathBuf::from(f)));
+        let unit = self.get_unit();
         let mut item = toml_edit::Value::InlineTable(InlineTable::new());
         let tbl = item.as_inline_table_mut().unwrap();
         tbl.insert(
@@ -51,15 +57,57 @@ impl PrimaryUnit {
             "type",
             toml_edit::value(&self.to_string()).into_value().unwrap(),
         );
+        tbl.insert(
+            "source",
+            toml_edit::value(relative(&unit.source))
+                .into_value()
+                .unwrap(),
+        );
+        tbl.insert(
+            "line",
+            toml_edit::value(unit.position.line() as i64)
+                .into_value()
+                .unwrap(),
+        );
+        tbl.insert(
+            "col",
+            toml_edit::value(unit.position.col() as i64)
+                .into_value()
+                .unwrap(),
+        );
+        if unit.architectures.is_empty() == false {
+            tbl.insert(
+                "architectures",
+                toml_edit::Value::Array(unit.architectures.iter().map(|f| relative(f)).collect()),
+            );
+        }
         item
     }
 
     /// Deserializes the data from a toml inline table.
-    pub fn from_toml(tbl: &toml_edit::InlineTable) -> Option<Self> {
+    ///
+    /// Source code files are read relative to the ip's `root` directory.
+    pub fn from_toml(tbl: &toml_edit::InlineTable, root: &PathBuf) -> Option<Self> {
+        let absolute = |f: &str| root.join(f).display().to_string();
         let unit = Unit {
             name: Identifier::from_str(tbl.get("identifier")?.as_str()?).unwrap(),
             symbol: None,
-            source: String::new(),
+            // metadata written by older versions only stored the name and type
+            source: match tbl.get("source").and_then(|v| v.as_str()) {
+                Some(f) => absolute(f),
+                None => String::new(),
+            },
+            position: Position::place(
+                tbl.get("line").and_then(|v| v.as_integer()).unwrap_or(1) as usize,
+                tbl.get("col").and_then(|v| v.as_integer()).unwrap_or(0) as usize,
+            ),
+            architectures: match tbl.get("architectures").and_then(|v| v.as_array()) {
+                Some(arr) => arr
+                    .iter()
+                    .filter_map(|f| Some(absolute(f.as_str()?)))
+                    .collect(),
+                None => Vec::new(),
+            },
         };
         Some(match tbl.get("type")?.as_str()? {
             "entity" => Self::Entity(unit),
@@ -91,6 +139,9 @@ pub struct Unit {
     symbol: Option<VHDLSymbol>,
     /// source code file
     source: String,
+    position: Position,
+    /// source code files holding the architectures of an entity
+    architectures: Vec<String>,
 }
 
 impl Unit {
@@ -101,6 +152,18 @@ impl Unit {
     pub fn get_source_code_file(&self) -> &str {
         &self.source
     }
+
+    /// References the location of the unit within its source code file.
+    ///
+    /// Available even when the unit was read from metadata without its symbol.
+    pub fn get_position(&self) -> &Position {
+        &self.position
+    }
+
+    /// References the source code files that define architectures for the unit.
+    pub fn get_architecture_files(&self) -> &Vec<String> {
+        &self.architectures
+    }
 }
 
 impl std::hash::Hash for Unit {
@@ -118,65 +181,102 @@ impl PartialEq for Unit {
 impl Eq for Unit {}
 
 pub fn collect_units(files: &Vec<String>) -> Result<HashMap<Identifier, PrimaryUnit>, Fault> {
+    // only read the HDL files
+    let sources: Vec<(String, String)> = files
+        .iter()
+        .filter(|f| crate::core::fileset::is_vhdl(f) == true)
+        .map(|f| (f.clone(), std::fs::read_to_string(&f).unwrap()))
+        .collect();
+    collect_units_from_sources(&sources)
+}
+
+/// Collects the primary design units from the `sources`, where each source is
+/// the name of a VHDL file along with its contents.
+///
+/// This is useful for reading source code that is not on the filesystem, such
+/// as the files within an archive.
+pub fn collect_units_from_sources(
+    sources: &Vec<(String, String)>,
+) -> Result<HashMap<Identifier, PrimaryUnit>, Fault> {
     let mut result: HashMap<Identifier, PrimaryUnit> = HashMap::new();
+    // track which files define architectures for each entity
+    let mut arch_files: HashMap<Identifier, Vec<String>> = HashMap::new();
     // iterate through all source files
-    for source_file in files {
-        // only read the HDL files
-        if crate::core::fileset::is_vhdl(&source_file) == true {
-            // parse text into VHDL symbols
-            let contents = std::fs::read_to_string(&source_file).unwrap();
-            let symbols = VHDLParser::read(&contents).into_symbols();
-            // transform into primary design units
-            let units: Vec<PrimaryUnit> = symbols
-                .into_iter()
-                .filter_map(|sym| {
-                    let name = sym.as_iden()?.clone();
-                    match sym {
-                        VHDLSymbol::Entity(_) => Some(PrimaryUnit::Entity(Unit {
-                            name: name,
-                            symbol: Some(sym),
-                            source: source_file.clone(),
-                        })),
-                        VHDLSymbol::Package(_) => Some(PrimaryUnit::Package(Unit {
-                            name: name,
-                            symbol: Some(sym),
-                            source: source_file.clone(),
-                        })),
-                        VHDLSymbol::Configuration(_) => Some(PrimaryUnit::Configuration(Unit {
-                            name: name,
-                            symbol: Some(sym),
-                            source: source_file.clone(),
-                        })),
-                        VHDLSymbol::Context(_) => Some(PrimaryUnit::Context(Unit {
-                            name: name,
-                            symbol: Some(sym),
-                            source: source_file.clone(),
-                        })),
-                        _ => None,
+    for (source_file, contents) in sources {
+        // parse text into VHDL symbols
+        let symbols = VHDLParser::read(&contents).into_symbols();
+        // transform into primary design units
+        let units: Vec<PrimaryUnit> = symbols
+            .into_iter()
+            .filter_map(|sym| {
+                if let VHDLSymbol::Architecture(arch) = &sym {
+                    let list = arch_files
+                        .entry(arch.entity().clone())
+                        .or_insert(Vec::new());
+                    if list.contains(source_file) == false {
+                        list.push(source_file.clone());
                     }
-                })
-                .collect();
-
-            for primary in units {
-                if let Some(dupe) = result.insert(primary.get_iden().clone(), primary) {
-                    return Err(VhdlIdentifierError::DuplicateIdentifier(
-                        dupe.get_iden().clone(),
-                        PathBuf::from(source_file),
-                        result
-                            .get(dupe.get_iden())
-                            .unwrap()
-                            .get_unit()
-                            .get_symbol()
-                            .unwrap()
-                            .get_position()
-                            .clone(),
-                        PathBuf::from(dupe.get_unit().get_source_code_file()),
-                        dupe.get_unit().get_symbol().unwrap().get_position().clone(),
-                    ))?;
+                    return None;
                 }
+                let name = sym.as_iden()?.clone();
+                let position = sym.get_position().clone();
+                match sym {
+                    VHDLSymbol::Entity(_) => Some(PrimaryUnit::Entity(Unit {
+                        name: name,
+                        symbol: Some(sym),
+                        source: source_file.clone(),
+                        position: position,
+                        architectures: Vec::new(),
+                    })),
+                    VHDLSymbol::Package(_) => Some(PrimaryUnit::Package(Unit {
+                        name: name,
+                        symbol: Some(sym),
+                        source: source_file.clone(),
+                        position: position,
+                        architectures: Vec::new(),
+                    })),
+                    VHDLSymbol::Configuration(_) => Some(PrimaryUnit::Configuration(Unit {
+                        name: name,
+                        symbol: Some(sym),
+                        source: source_file.clone(),
+                        position: position,
+                        architectures: Vec::new(),
+                    })),
+                    VHDLSymbol::Context(_) => Some(PrimaryUnit::Context(Unit {
+                        name: name,
+                        symbol: Some(sym),
+                        source: source_file.clone(),
+                        position: position,
+                        architectures: Vec::new(),
+                    })),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        for primary in units {
+            if let Some(dupe) = result.insert(primary.get_iden().clone(), primary) {
+                return Err(VhdlIdentifierError::DuplicateIdentifier(
+                    dupe.get_iden().clone(),
+                    PathBuf::from(source_file),
+                    result
+                        .get(dupe.get_iden())
+                        .unwrap()
+                        .get_unit()
+                        .get_position()
+                        .clone(),
+                    PathBuf::from(dupe.get_unit().get_source_code_file()),
+                    dupe.get_unit().get_position().clone(),
+                ))?;
             }
         }
     }
+    // link each entity to the files holding its architectures
+    for (owner, files) in arch_files {
+        if let Some(PrimaryUnit::Entity(unit)) = result.get_mut(&owner) {
+            unit.architectures = files;
+        }
+    }
     Ok(result)
 }
 
@@ -188,6 +288,39 @@ pub enum VhdlIdentifierError {
 
 impl std::error::Error for VhdlIdentifierError {}
 
+impl VhdlIdentifierError {
+    /// Reports the error as a `Diagnostic` at each location of the conflicting
+    /// units.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        let current_dir = std::env::current_dir().unwrap();
+        let locate = |path: &PathBuf| filesystem::remove_base(&current_dir, path);
+        let span = |iden: &Identifier, pos: &Position| {
+            Span::new(pos.line(), pos.col(), iden.to_string().len())
+        };
+        match self {
+            Self::DuplicateIdentifier(iden, path1, loc1, path2, loc2) => vec![
+                Diagnostic::error(&format!(
+                    "duplicate primary design units identified as '{}'",
+                    iden
+                ))
+                .file(locate(path1))
+                .span(span(iden, loc1))
+                .help("rename one of the units to a unique identifier or add one of the file paths to a .orbitignore file"),
+                Diagnostic::note(&format!("'{}' is also declared here", iden))
+                    .file(locate(path2))
+                    .span(span(iden, loc2)),
+            ],
+            Self::DuplicateAcrossDirect(iden, dep, path, pos) => vec![Diagnostic::error(&format!(
+                "duplicate primary design units identified as '{}' conflicts with direct dependency {}",
+                iden, dep
+            ))
+            .file(locate(path))
+            .span(span(iden, pos))
+            .help("rename the unit in the current ip, remove the direct dependency from Orbit.toml, or add the unit's file path to a .orbitignore file")],
+        }
+    }
+}
+
 impl std::fmt::Display for VhdlIdentifierError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
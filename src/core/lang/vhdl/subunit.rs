@@ -18,6 +18,14 @@ impl SubUnit {
         Self::Configuration(cfg)
     }
 
+    /// Returns the architecture's name, or `None` for a configuration.
+    pub fn get_arch_name(&self) -> Option<&Identifier> {
+        match self {
+            Self::Architecture(u) => Some(u.name()),
+            Self::Configuration(_) => None,
+        }
+    }
+
     pub fn get_edges(&self) -> &IdentifierList {
         match self {
             Self::Architecture(u) => u.edges(),
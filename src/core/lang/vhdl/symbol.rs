@@ -6,7 +6,7 @@ use serde_derive::Serialize;
 
 use super::super::lexer::*;
 use super::super::parser::*;
-use super::format::VhdlFormat;
+use super::format::{InstanceStyle, VhdlFormat, VhdlStandard};
 
 use crate::core::lang::vhdl::interface::*;
 use crate::core::lang::vhdl::token::*;
@@ -263,6 +263,16 @@ impl Entity {
         &self.refs
     }
 
+    /// Accesses the entity's generic interface list.
+    pub fn get_generics(&self) -> &Generics {
+        &self.generics
+    }
+
+    /// Accesses the entity's port interface list.
+    pub fn get_ports(&self) -> &Ports {
+        &self.ports
+    }
+
     // Generates VHDL component code from the entity.
     pub fn into_component(&self, fmt: &VhdlFormat) -> String {
         let mut result = format!("{} ", Keyword::Component.to_color());
@@ -318,15 +328,24 @@ impl Entity {
     }
 
     /// Generates VHDL instantiation code from the entity data.
+    ///
+    /// The entity is instantiated directly from its `library` unless the
+    /// formatting selects the component style or no library is given.
     pub fn into_instance(&self, inst: &Option<Identifier>, library: Option<Identifier>, fmt: &VhdlFormat) -> String {
-        let prefix = match library {
-            Some(lib) => format!(
+        let library = match fmt.get_instance_style() {
+            InstanceStyle::Entity => library,
+            InstanceStyle::Component => None,
+        };
+        let prefix = match (library, fmt.get_standard()) {
+            (Some(lib), _) => format!(
                 "{} {}{}",
                 Keyword::Entity.to_color(),
                 color(&lib.to_string(), ENTITY_NAME),
                 Delimiter::Dot.to_color()
             ),
-            None => String::new(),
+            // vhdl-2008 convention is to state the component keyword explicitly
+            (None, VhdlStandard::Vhdl08) => format!("{} ", Keyword::Component.to_color()),
+            (None, VhdlStandard::Vhdl93) => String::new(),
         };
 
         let name = match &inst {
@@ -350,13 +369,13 @@ impl Entity {
             prefix,
             color(&self.get_name().to_string(), ENTITY_NAME)
         ));
-        if self.generics.0.len() > 0 {
+        if self.generics.0.to_mapped(&Keyword::Constant, &fmt).is_empty() == false {
             result.push('\n');
             if fmt.is_indented_interfaces() == true && fmt.get_tab_size() > 0 {
                 result.push_str(&format!("{:<width$}", " ", width = fmt.get_tab_size() as usize));
             }
             result.push_str(&(format!("{}", Keyword::Generic.to_color())));
-            result.push_str(&self.generics.0.to_instantiation_part(&fmt, mapping_depth).to_string())
+            result.push_str(&self.generics.0.to_instantiation_part(Keyword::Constant, &fmt, mapping_depth).to_string())
         }
         if self.ports.0.len() > 0 {
             // add extra spacing
@@ -365,12 +384,196 @@ impl Entity {
                 result.push_str(&format!("{:<width$}", " ", width = fmt.get_tab_size() as usize));
             }
             result.push_str(&format!("{}", Keyword::Port.to_color()));
-            result.push_str(&self.ports.0.to_instantiation_part(&fmt, mapping_depth).to_string())
+            result.push_str(&self.ports.0.to_instantiation_part(Keyword::Signal, &fmt, mapping_depth).to_string())
         }
         result.push_str(&Delimiter::Terminator.to_string());
         result
     }
 
+    /// Generates Verilog instantiation code from the entity data to instantiate
+    /// the entity from a Verilog module.
+    pub fn into_verilog_instance(&self, inst: &Option<Identifier>, fmt: &VhdlFormat) -> String {
+        let name = match &inst {
+            Some(iden) => iden.to_string(),
+            None => fmt.get_instance_name(),
+        };
+        let mut result = self.get_name().to_string();
+        let parameters = self.generics.0.to_verilog_connections(Keyword::Constant, &fmt);
+        if parameters.is_empty() == false {
+            result.push_str(&format!(" #(\n{}\n)", parameters));
+        }
+        result.push_str(&format!(" {} (", name));
+        if self.ports.0.len() > 0 {
+            result.push_str(&format!(
+                "\n{}\n",
+                self.ports.0.to_verilog_connections(Keyword::Signal, &fmt)
+            ));
+        }
+        result.push_str(");");
+        result
+    }
+
+    /// Generates Verilog parameter and wire declarations to connect to the
+    /// entity from a Verilog module.
+    pub fn into_verilog_declarations(&self, fmt: &VhdlFormat) -> String {
+        format!(
+            "{}{}",
+            self.generics.0.to_verilog_declarations(Keyword::Constant, &fmt),
+            self.ports.0.to_verilog_declarations(Keyword::Signal, &fmt)
+        )
+    }
+
+    /// Generates a VHDL testbench skeleton that instantiates the entity as the
+    /// design under test.
+    ///
+    /// Input ports with a name segment like a clock (`clk`, `clock`) and reset
+    /// (`rst`, `reset`) are driven by stimulus processes. A negated reset segment
+    /// (`rst_n`, `resetn`) is treated as active-low.
+    /// Following vhdl-2008 conventions ends the simulation with `std.env.finish`.
+    pub fn into_testbench(
+        &self,
+        inst: &Option<Identifier>,
+        library: Option<Identifier>,
+        fmt: &VhdlFormat,
+    ) -> String {
+        let tab = " ".repeat(fmt.get_tab_size() as usize);
+        // indent every non-empty line of `text` by `depth` tabs
+        let indent = |text: &str, depth: usize| -> String {
+            text.lines()
+                .map(|l| match l.is_empty() {
+                    true => String::from("\n"),
+                    false => format!("{}{}\n", tab.repeat(depth), l),
+                })
+                .collect()
+        };
+        // find the first input port with a name segment matching one of `keys`
+        let find_port = |keys: &[&str]| {
+            self.ports.0.iter().find_map(|p| {
+                let name = p.get_name().to_string().to_lowercase();
+                let is_match = p.get_mode() == "in"
+                    && name.split('_').any(|seg| keys.contains(&seg));
+                match is_match {
+                    true => Some((name, fmt.to_signal_name(p.get_name()).to_string())),
+                    false => None,
+                }
+            })
+        };
+        let clock = find_port(&["clk", "clock", "aclk"]).map(|(_, sig)| sig);
+        let reset = find_port(&[
+            "rst", "reset", "arst", "areset", "rstn", "resetn", "arstn", "aresetn", "nrst",
+            "nreset",
+        ]);
+        // a reset is active-low when a segment is negated (`rst_n`, `aresetn`, `nrst`)
+        let active_low = match &reset {
+            Some((name, _)) => name.split('_').any(|seg| {
+                seg == "n"
+                    || seg == "nrst"
+                    || seg == "nreset"
+                    || seg.ends_with("rstn")
+                    || seg.ends_with("resetn")
+            }),
+            None => false,
+        };
+        let reset = reset.map(|(_, sig)| sig);
+        let (assert, deassert) = match active_low {
+            true => ("'0'", "'1'"),
+            false => ("'1'", "'0'"),
+        };
+        let name = Identifier::Basic(format!("{}_tb", self.get_name()));
+        let inst = match inst {
+            Some(i) => Some(i.clone()),
+            None => Some(Identifier::Basic(String::from("dut"))),
+        };
+
+        let mut result = String::from("library ieee;\nuse ieee.std_logic_1164.all;\n\n");
+        result.push_str(&format!("entity {} is\nend entity;\n\n", name));
+        result.push_str(&format!("architecture sim of {} is\n", name));
+        if fmt.get_instance_style() == InstanceStyle::Component {
+            result.push_str(&indent(&self.into_component(&fmt), 1));
+        }
+        let constants = self.into_constants(&fmt);
+        if constants.is_empty() == false {
+            result.push_str(&indent(&constants, 1));
+        }
+        let signals = self.into_signals(&fmt);
+        if signals.is_empty() == false {
+            result.push_str(&indent(&signals, 1));
+        }
+        if clock.is_some() {
+            result.push_str(&format!("{}constant CLK_PERIOD : time := 10 ns;\n", tab));
+        }
+        result.push_str(&format!("{}signal sim_done : boolean := false;\n", tab));
+        result.push_str("begin\n\n");
+
+        // instantiate the design under test
+        result.push_str(&indent(&self.into_instance(&inst, library, &fmt), 1));
+        result.push('\n');
+
+        // generate the clock
+        if let Some(clk) = &clock {
+            result.push_str(&indent(
+                &format!(
+                    "\
+clock: process
+begin
+{tab}while sim_done = false loop
+{tab}{tab}{clk} <= '0';
+{tab}{tab}wait for CLK_PERIOD/2;
+{tab}{tab}{clk} <= '1';
+{tab}{tab}wait for CLK_PERIOD/2;
+{tab}end loop;
+{tab}wait;
+end process;
+"
+                ),
+                1,
+            ));
+            result.push('\n');
+        }
+
+        // drive the reset
+        if let Some(rst) = &reset {
+            let delay = match clock.is_some() {
+                true => "4*CLK_PERIOD",
+                false => "40 ns",
+            };
+            result.push_str(&indent(
+                &format!(
+                    "\
+reset: process
+begin
+{tab}{rst} <= {assert};
+{tab}wait for {delay};
+{tab}{rst} <= {deassert};
+{tab}wait;
+end process;
+"
+                ),
+                1,
+            ));
+            result.push('\n');
+        }
+
+        // provide the stimulus and end the simulation
+        let mut stimulus = String::from("stimulus: process\nbegin\n");
+        if let Some(rst) = &reset {
+            stimulus.push_str(&format!("{tab}wait until {rst} = {deassert};\n"));
+        }
+        if let Some(clk) = &clock {
+            stimulus.push_str(&format!("{tab}wait until rising_edge({clk});\n"));
+        }
+        stimulus.push_str(&format!(
+            "{tab}-- @todo: drive the inputs and check the outputs\n\n{tab}sim_done <= true;\n"
+        ));
+        if fmt.get_standard() == VhdlStandard::Vhdl08 {
+            stimulus.push_str(&format!("{tab}std.env.finish;\n"));
+        }
+        stimulus.push_str(&format!("{tab}wait;\nend process;\n"));
+        result.push_str(&indent(&stimulus, 1));
+        result.push_str("\nend architecture;\n");
+        result
+    }
+
     /// Generates list of available architectures.
     ///
     /// Note: This fn must be ran after linking entities and architectures in the
@@ -494,7 +697,9 @@ pub struct UseClause {
 pub struct Configuration {
     name: Identifier,
     owner: Identifier,
+    architecture: Option<Identifier>,
     dependencies: IdentifierList,
+    bindings: Vec<Binding>,
     refs: IdentifierList,
     pos: Position,
 }
@@ -516,12 +721,41 @@ impl Configuration {
         &self.dependencies
     }
 
+    /// Accesses the architecture selected for the owning entity in the
+    /// top-level block configuration.
+    pub fn architecture(&self) -> Option<&Identifier> {
+        self.architecture.as_ref()
+    }
+
+    /// Accesses the entity aspects bound to component instances throughout
+    /// the configured hierarchy.
+    pub fn bindings(&self) -> &Vec<Binding> {
+        &self.bindings
+    }
+
     /// Accesses the references for the entity.
     pub fn get_refs(&self) -> &IdentifierList {
         &self.refs
     }
 }
 
+/// An entity aspect `use entity lib.name(arch)` found within a configuration.
+#[derive(Debug, PartialEq)]
+pub struct Binding {
+    entity: CompoundIdentifier,
+    architecture: Option<Identifier>,
+}
+
+impl Binding {
+    pub fn entity(&self) -> &CompoundIdentifier {
+        &self.entity
+    }
+
+    pub fn architecture(&self) -> Option<&Identifier> {
+        self.architecture.as_ref()
+    }
+}
+
 /*
     @NOTE In order to detect if a package was used, the best bet is to just
     iterate through the the tokens and collect all simple names, i.e.
@@ -631,6 +865,7 @@ impl CompoundIdentifier {
 #[derive(Debug, PartialEq)]
 pub struct VHDLParser {
     symbols: Vec<Symbol<VHDLSymbol>>,
+    errors: Vec<SymbolError<String>>,
 }
 
 impl Parse<VHDLToken> for VHDLParser {
@@ -649,6 +884,14 @@ impl Parse<VHDLToken> for VHDLParser {
         let mut global_refs = IdentifierList::new();
 
         while let Some(t) = tokens.next() {
+            // a design unit's keyword must be followed by the unit's name
+            if let Some(kw) = Self::missing_name(t.as_ref(), tokens.peek()) {
+                symbols.push(Err(SymbolError::new(
+                    format!("expected an identifier after keyword '{}'", kw),
+                    t.into_position(),
+                )));
+                continue;
+            }
             // create entity symbol
             if t.as_ref().check_keyword(&Keyword::Entity) {
                 // get the position
@@ -700,18 +943,48 @@ impl Parse<VHDLToken> for VHDLParser {
 
 impl VHDLParser {
     pub fn read(s: &str) -> Self {
-        let symbols = VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens());
+        let mut symbols = Vec::new();
+        let mut errors = Vec::new();
+        for result in VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens()) {
+            match result {
+                Ok(sym) => symbols.push(sym),
+                Err(e) => errors.push(e),
+            }
+        }
         Self {
-            symbols: symbols
-                .into_iter()
-                .filter_map(|f| if f.is_ok() { Some(f.unwrap()) } else { None })
-                .collect(),
+            symbols: symbols,
+            errors: errors,
         }
     }
 
+    /// References the errors detected while reading the design units.
+    pub fn get_errors(&self) -> &Vec<SymbolError<String>> {
+        &self.errors
+    }
+
     pub fn into_symbols(self) -> Vec<VHDLSymbol> {
         self.symbols.into_iter().map(|f| f.take()).collect()
     }
+
+    /// Checks if the token `t` begins a design unit whose name is not the
+    /// `next` token, returning the unit's keyword.
+    ///
+    /// A package may also be followed by `body`.
+    fn missing_name(t: &VHDLToken, next: Option<&Token<VHDLToken>>) -> Option<Keyword> {
+        let kw = [
+            Keyword::Entity,
+            Keyword::Architecture,
+            Keyword::Configuration,
+            Keyword::Package,
+        ]
+        .into_iter()
+        .find(|kw| t.check_keyword(kw))?;
+        match next.map(|n| n.as_type()) {
+            Some(VHDLToken::Identifier(_)) => None,
+            Some(n) if kw == Keyword::Package && n.check_keyword(&Keyword::Body) => None,
+            _ => Some(kw),
+        }
+    }
 }
 
 use std::iter::Peekable;
@@ -1203,7 +1476,9 @@ impl VHDLSymbol {
             panic!("expecting keyword 'is'")
         }
 
+        let mut arch = None;
         let mut deps = IdentifierList::new();
+        let mut bindings = Vec::new();
         let mut refs = IdentifierList::new();
         // parse configuration section
         while let Some(t) = tokens.peek() {
@@ -1216,7 +1491,13 @@ impl VHDLSymbol {
             } else if t.as_type().check_keyword(&Keyword::For) {
                 // take the 'for' keyword
                 tokens.next().unwrap();
-                deps.append(&mut Self::parse_block_configuration(tokens));
+                let (block, mut idens, mut binds) = Self::parse_block_configuration(tokens);
+                // the outermost block names the architecture of the owning entity
+                if arch.is_none() {
+                    arch = block;
+                }
+                deps.append(&mut idens);
+                bindings.append(&mut binds);
             // @todo handle `use` clauses
             } else {
                 let mut clause = Self::parse_statement(tokens);
@@ -1227,19 +1508,28 @@ impl VHDLSymbol {
         VHDLSymbol::Configuration(Configuration {
             name: config_name,
             owner: entity_name,
+            architecture: arch,
             dependencies: deps,
+            bindings: bindings,
             refs: refs,
             pos: pos,
         })
     }
 
-    fn parse_block_configuration<I>(tokens: &mut Peekable<I>) -> IdentifierList
+    /// Parses a block configuration, returning the block's name along with the
+    /// identifiers and entity bindings found within it.
+    ///
+    /// Assumes the last token consumed was the 'for' keyword.
+    fn parse_block_configuration<I>(
+        tokens: &mut Peekable<I>,
+    ) -> (Option<Identifier>, IdentifierList, Vec<Binding>)
     where
         I: Iterator<Item = Token<VHDLToken>>,
     {
         let mut ids = IdentifierList::new();
+        let mut bindings = Vec::new();
         // take the identifier
-        tokens.next().unwrap();
+        let block = tokens.next().unwrap().take().take_identifier();
         // if next token is '(', take until leveling out to ')'
         if tokens
             .peek()
@@ -1270,14 +1560,72 @@ impl VHDLSymbol {
             } else {
                 // take configuration specification by composing statement
                 let stmt = Self::parse_statement(tokens);
+                let binding = Self::parse_binding_indication(&stmt);
                 if let Some(mut idens) = Self::parse_configuration_spec(stmt) {
                     ids.append(&mut idens);
+                    if let Some(b) = binding {
+                        bindings.push(b);
+                    }
+                    // enter any block configurations for the bound entity
+                    while let Some(t) = tokens.peek() {
+                        if t.as_type().check_keyword(&Keyword::For) == false {
+                            break;
+                        }
+                        tokens.next();
+                        let (_, mut idens, mut binds) = Self::parse_block_configuration(tokens);
+                        ids.append(&mut idens);
+                        bindings.append(&mut binds);
+                    }
                     // take next `end for`
                     let _ending = Self::parse_statement(tokens);
                 }
             }
         }
-        ids
+        (block, ids, bindings)
+    }
+
+    /// Detects the entity aspect `use entity lib.name(arch)` within a component
+    /// configuration statement.
+    ///
+    /// Returns `None` if the statement does not bind an entity, such as when it is
+    /// bound to another configuration or left `open`.
+    fn parse_binding_indication(statement: &Statement) -> Option<Binding> {
+        let mut tokens = statement.0.iter().map(|t| t.as_type()).peekable();
+        // skip to the 'use' keyword
+        while let Some(t) = tokens.next() {
+            if t.check_keyword(&Keyword::Use) == true {
+                break;
+            }
+        }
+        // force keyword 'entity'
+        if tokens.next()?.check_keyword(&Keyword::Entity) == false {
+            return None;
+        }
+        // collect the selected name of the entity
+        let mut name = vec![tokens.next()?.as_identifier()?.clone()];
+        while let Some(t) = tokens.peek() {
+            if t.check_delimiter(&Delimiter::Dot) == false {
+                break;
+            }
+            tokens.next();
+            name.push(tokens.next()?.as_identifier()?.clone());
+        }
+        // take the optional architecture identifier
+        let architecture = match tokens.next() {
+            Some(t) if t.check_delimiter(&Delimiter::ParenL) == true => {
+                tokens.next().and_then(|t| t.as_identifier()).cloned()
+            }
+            _ => None,
+        };
+        let suffix = name.pop().unwrap();
+        let entity = match name.pop() {
+            Some(prefix) => CompoundIdentifier::new(prefix, suffix),
+            None => CompoundIdentifier::new_minimal(suffix),
+        };
+        Some(Binding {
+            entity: entity,
+            architecture: architecture,
+        })
     }
 
     /// Consumes tokens after the USE keyword.
@@ -2285,6 +2633,214 @@ end entity nor_gate;";
         assert_eq!(e.ports.0.len(), 3);
     }
 
+    #[test]
+    fn entity_testbench() {
+        let s = "\
+ counter is
+    port(
+        clk: in std_logic;
+        rst_n: in std_logic;
+        count: out std_logic_vector(3 downto 0)
+    );
+end entity counter;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        colored::control::set_override(false);
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+        let tb = e.into_testbench(&None, Some(Identifier::new_working()), &VhdlFormat::new());
+        assert_eq!(tb.contains("entity counter_tb is"), true);
+        assert_eq!(tb.contains("dut : entity work.counter"), true);
+        assert_eq!(tb.contains("clk <= '1';"), true);
+        // the reset is detected as active-low
+        assert_eq!(tb.contains("rst_n <= '0';"), true);
+        assert_eq!(tb.contains("wait until rst_n = '1';"), true);
+        assert_eq!(tb.contains("end architecture;"), true);
+        assert_eq!(tb.contains("std.env.finish;"), false);
+    }
+
+    #[test]
+    fn entity_testbench_ignores_non_inputs() {
+        let s = "\
+ pll is
+    port(
+        rst_in: in std_logic;
+        clk_out: out std_logic;
+        clk_ref: in std_logic;
+        burst: in std_logic
+    );
+end entity pll;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        colored::control::set_override(false);
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+        let tb = e.into_testbench(&None, Some(Identifier::new_working()), &VhdlFormat::new());
+        // the output clock is never driven by the testbench
+        assert_eq!(tb.contains("clk_out <="), false);
+        assert_eq!(tb.contains("clk_ref <= '1';"), true);
+        // `rst_in` is an active-high reset and `burst` is not a reset at all
+        assert_eq!(tb.contains("rst_in <= '1';"), true);
+        assert_eq!(tb.contains("wait until rst_in = '0';"), true);
+        assert_eq!(tb.contains("burst <="), false);
+    }
+
+    #[test]
+    fn entity_verilog_instance() {
+        let s = "\
+ counter is
+    generic(
+        WIDTH: positive := 4
+    );
+    port(
+        clk: in std_logic;
+        count: out std_logic_vector(WIDTH-1 downto 0);
+        state: out t_state
+    );
+end entity counter;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+        assert_eq!(
+            e.into_verilog_instance(&Some(Identifier::Basic(String::from("u0"))), &VhdlFormat::new()),
+            "counter #(\n  .WIDTH(WIDTH)\n) u0 (\n  .clk(clk),\n  .count(count),\n  .state(state)\n);"
+        );
+        assert_eq!(
+            e.into_verilog_declarations(&VhdlFormat::new()),
+            "localparam WIDTH = 4;\nwire clk;\nwire [WIDTH-1:0] count;\nwire state; // t_state\n"
+        );
+    }
+
+    #[test]
+    fn entity_instance_styles() {
+        let s = "\
+ counter is
+    generic(
+        WIDTH: positive := 4
+    );
+    port(
+        clk: in std_logic;
+        count: out std_logic_vector(WIDTH-1 downto 0)
+    );
+end entity counter;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        colored::control::set_override(false);
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+
+        let fmt: VhdlFormat = toml::from_str("instance-association = \"positional\"\nsignal-prefix = \"w_\"\n").unwrap();
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("uX : entity work.counter"), true);
+        assert_eq!(inst.contains("WIDTH\n"), true);
+        assert_eq!(inst.contains("w_clk,"), true);
+        assert_eq!(inst.contains("=>"), false);
+        assert_eq!(e.into_signals(&fmt).contains("signal w_count"), true);
+
+        let fmt: VhdlFormat = toml::from_str("instance-style = \"component\"\nstandard = \"2008\"\n").unwrap();
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("uX : component counter"), true);
+        assert_eq!(inst.contains("clk   => clk"), true);
+        let tb = e.into_testbench(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(tb.contains("component counter"), true);
+        assert_eq!(tb.contains("std.env.finish;"), true);
+    }
+
+    #[test]
+    fn entity_interface_defaults() {
+        let s = "\
+ fifo is
+    generic(
+        DEPTH: positive := 16;
+        WIDTH: positive
+    );
+    port(
+        clk: in std_logic;
+        full: out std_logic := '0'
+    );
+end entity fifo;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        colored::control::set_override(false);
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+
+        // by default every generic is mapped and signals take the port defaults
+        let fmt = VhdlFormat::new();
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("DEPTH => DEPTH"), true);
+        assert_eq!(e.into_signals(&fmt).contains("signal full : std_logic := '0';"), true);
+        assert_eq!(e.into_constants(&fmt).contains("constant DEPTH : positive := 16;"), true);
+
+        let mut fmt: VhdlFormat = toml::from_str("signal-defaults = false\nomit-default-generics = true\n").unwrap();
+        fmt.merge(Some(VhdlFormat::new()));
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("DEPTH"), false);
+        assert_eq!(inst.contains("WIDTH => WIDTH"), true);
+        assert_eq!(e.into_signals(&fmt).contains("signal full : std_logic;"), true);
+        assert_eq!(e.into_constants(&fmt).contains("DEPTH"), false);
+        assert_eq!(e.into_constants(&fmt).contains("constant WIDTH : positive;"), true);
+    }
+
+    #[test]
+    fn entity_generic_types_and_packages() {
+        let s = "\
+ sorter is
+    generic(
+        type T;
+        impure function less(a, b : T) return boolean is <>;
+        package P is new work.gp generic map (<>);
+        N: positive := 8
+    );
+    port(
+        din: in T;
+        dout: out T
+    );
+end entity sorter;";
+        let mut tokens = VHDLTokenizer::from_source_code(&s)
+            .into_tokens()
+            .into_iter()
+            .peekable();
+        colored::control::set_override(false);
+        let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
+        assert_eq!(e.generics.len(), 4);
+        assert_eq!(e.ports.len(), 2);
+        let t = e.generics.get(&Identifier::Basic(String::from("T"))).unwrap();
+        assert_eq!(t.get_kind(), &InterfaceKind::Type);
+        let p = e.generics.get(&Identifier::Basic(String::from("P"))).unwrap();
+        assert_eq!(p.get_kind(), &InterfaceKind::Package);
+        assert_eq!(p.get_type(), "package P is new work.gp generic map(<>)");
+
+        let fmt = VhdlFormat::new();
+        let comp = e.into_component(&fmt);
+        assert_eq!(comp.contains("type T;"), true);
+        assert_eq!(comp.contains("impure function less(a, b : T) return boolean is <>;"), true);
+        assert_eq!(comp.contains("N    : positive := 8"), true);
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("T    => T"), true);
+        assert_eq!(inst.contains("P    => P"), true);
+        // only objects are declared to connect to the generics
+        assert_eq!(e.into_constants(&fmt), "constant N : positive := 8;\n");
+
+        // subprograms with a default are omitted along with defaulted constants
+        let mut fmt: VhdlFormat = toml::from_str("omit-default-generics = true\n").unwrap();
+        fmt.merge(Some(VhdlFormat::new()));
+        let inst = e.into_instance(&None, Some(Identifier::new_working()), &fmt);
+        assert_eq!(inst.contains("less"), false);
+        assert_eq!(inst.contains("N =>"), false);
+        assert_eq!(inst.contains("T => T"), true);
+        assert_eq!(
+            e.into_verilog_instance(&None, &fmt).starts_with("sorter uX ("),
+            true
+        );
+    }
+
     use std::str::FromStr;
 
     #[test]
@@ -2434,6 +2990,58 @@ end HA_Config;
         );
     }
 
+    #[test]
+    fn configuration_bindings() {
+        let s = r#"
+configuration soc_cfg of soc is
+    for rtl
+        for u0 : core
+            use entity work.core(fast);
+            for fast
+                for all : alu use entity lib2.alu;
+                end for;
+            end for;
+        end for;
+        for u1 : uart
+            use configuration work.uart_cfg;
+        end for;
+    end for;
+end configuration soc_cfg;
+"#;
+        let symbols = VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens());
+        let cfg = symbols
+            .first()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .as_configuration()
+            .unwrap();
+        assert_eq!(
+            cfg.architecture(),
+            Some(&Identifier::Basic(String::from("rtl")))
+        );
+        assert_eq!(
+            cfg.bindings(),
+            &vec![
+                Binding {
+                    entity: CompoundIdentifier::new(
+                        Identifier::Basic(String::from("work")),
+                        Identifier::Basic(String::from("core"))
+                    ),
+                    architecture: Some(Identifier::Basic(String::from("fast"))),
+                },
+                Binding {
+                    entity: CompoundIdentifier::new(
+                        Identifier::Basic(String::from("lib2")),
+                        Identifier::Basic(String::from("alu"))
+                    ),
+                    architecture: None,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn configuration_spec() {
         let s = r#"
@@ -409,7 +409,7 @@ impl Keyword {
     /// Attempts to match the given string of characters `s` to a VHDL keyword.
     ///
     /// Compares `s` against keywords using ascii lowercase comparison.
-    fn match_keyword(s: &str) -> Option<Self> {
+    pub fn match_keyword(s: &str) -> Option<Self> {
         Some(match s.to_ascii_lowercase().as_ref() {
             "abs" => Self::Abs,
             "access" => Self::Access,
@@ -1272,6 +1272,11 @@ impl VHDLToken {
                     '<' => match c {
                         // move on to next round (could be a len-3 delimiter)
                         '=' => delim.push(train.consume().unwrap()),
+                        // complete the len-2 delimiters '<>' and '<<'
+                        '>' | '<' => {
+                            delim.push(train.consume().unwrap());
+                            return Self::match_delimiter(&delim);
+                        }
                         _ => return Ok(Self::match_delimiter(&delim).expect("invalid token")),
                     },
                     _ => {
@@ -1791,6 +1796,17 @@ impl VHDLTokenizer {
             .collect()
     }
 
+    /// References every error encountered while tokenizing the source code.
+    pub fn as_errors(&self) -> Vec<&lexer::TokenError<VHDLTokenError>> {
+        self.tokens
+            .iter()
+            .filter_map(|f| match &f.0 {
+                Ok(_) => None,
+                Err(e) => Some(e),
+            })
+            .collect()
+    }
+
     /// Transforms the list of results into a list of tokens, silently skipping over
     /// errors.
     pub fn as_tokens_all(&self) -> Vec<&lexer::Token<VHDLToken>> {
@@ -2750,6 +2766,15 @@ entity fa is end entity;";
         );
         assert_eq!(tc.peekable().clone().collect::<String>(), "WIDTH");
         assert_eq!(tc.locate(), &Position::place(1, 2));
+
+        let contents = "<>)";
+        let mut tc = TrainCar::new(contents.chars());
+        assert_eq!(
+            VHDLToken::collect_delimiter(&mut tc, None),
+            Ok(VHDLToken::Delimiter(Delimiter::Box))
+        );
+        assert_eq!(tc.peekable().clone().collect::<String>(), ")");
+        assert_eq!(tc.locate(), &Position::place(1, 2));
     }
 
     #[test]
@@ -10,7 +10,7 @@ use crate::core::{
 };
 use crate::util::anyerror::AnyError;
 use crate::util::sha256::Sha256Hash;
-use colored::Colorize;
+use crate::util::logger;
 use serde_derive::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt::Display;
@@ -54,12 +54,10 @@ impl LockFile {
                     Ok(r) => r,
                     // enter a blank lock file if failed (do not exit)
                     Err(e) => {
-                        println!(
-                            "{}: failed to parse {} file: {}",
-                            "warning".yellow().bold(),
-                            IP_LOCK_FILE,
-                            e
-                        );
+                        logger::warn(format!(
+                            "failed to parse {} file: {}",
+                            IP_LOCK_FILE, e
+                        ));
                         v1::LockFile::new()
                     }
                 },
@@ -0,0 +1,739 @@
+//! A minimal language server that shares orbit's knowledge of the design units
+//! within the current ip and its dependencies with a text editor.
+//!
+//! Messages are exchanged as JSON-RPC over stdio following the Language Server
+//! Protocol. The server supports document symbols, go-to-definition for primary
+//! design units, and diagnostics from the tokenizer and parser. Documents are
+//! always synced in full.
+
+use crate::core::fileset;
+use crate::core::lang::lexer::{Position, Token};
+use crate::core::lang::vhdl::primaryunit::{self, VhdlIdentifierError};
+use crate::core::lang::vhdl::symbol::{VHDLParser, VHDLSymbol};
+use crate::core::lang::vhdl::token::{Identifier, VHDLToken, VHDLTokenizer};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::diagnostic::{Diagnostic, Severity, Span};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// The JSON-RPC error code for a request the server does not support.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The LSP severities for a diagnostic.
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFORMATION: u8 = 3;
+
+/// The LSP kinds used to describe each design unit in a document.
+const KIND_MODULE: u8 = 2;
+const KIND_NAMESPACE: u8 = 3;
+const KIND_PACKAGE: u8 = 4;
+const KIND_CLASS: u8 = 5;
+const KIND_OBJECT: u8 = 19;
+
+/// The place in a file where a primary design unit's name is declared.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Declaration {
+    path: PathBuf,
+    pos: Position,
+    len: usize,
+}
+
+impl Declaration {
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn get_position(&self) -> &Position {
+        &self.pos
+    }
+
+    /// Serializes the declaration into a LSP `Location`.
+    fn to_location(&self) -> Value {
+        json!({
+            "uri": path_to_uri(&self.path),
+            "range": to_range(&self.pos, self.len),
+        })
+    }
+}
+
+/// The primary design units known to the server, looked up by name.
+///
+/// A name may be declared more than once when dependencies share a unit name.
+#[derive(Debug, PartialEq)]
+pub struct UnitIndex {
+    units: HashMap<Identifier, Vec<Declaration>>,
+}
+
+impl UnitIndex {
+    pub fn new() -> Self {
+        Self {
+            units: HashMap::new(),
+        }
+    }
+
+    /// Creates an index from every VHDL file in `files`.
+    ///
+    /// Files that cannot be read are skipped.
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        let mut index = Self::new();
+        for file in files {
+            if fileset::is_vhdl(&file.to_string_lossy()) == false {
+                continue;
+            }
+            if let Ok(text) = std::fs::read_to_string(file) {
+                index.update(file, &text);
+            }
+        }
+        index
+    }
+
+    /// Records the primary design units declared in `text` for the file at
+    /// `path`, replacing any units previously recorded for that file.
+    ///
+    /// Nothing is recorded for a file that declares the same unit twice.
+    pub fn update(&mut self, path: &PathBuf, text: &str) {
+        self.remove(path);
+        let sources = vec![(path.display().to_string(), text.to_string())];
+        let units = match primaryunit::collect_units_from_sources(&sources) {
+            Ok(units) => units,
+            Err(_) => return,
+        };
+        let tokens = VHDLTokenizer::from_source_code(text).into_tokens();
+        for (name, unit) in units {
+            let pos = locate_name(&tokens, unit.get_unit().get_position(), &name);
+            self.units
+                .entry(name.clone())
+                .or_insert(Vec::new())
+                .push(Declaration {
+                    path: path.clone(),
+                    pos: pos,
+                    len: name.len(),
+                });
+        }
+    }
+
+    /// Forgets every unit recorded for the file at `path`.
+    pub fn remove(&mut self, path: &PathBuf) {
+        self.units.retain(|_, decls| {
+            decls.retain(|d| &d.path != path);
+            decls.is_empty() == false
+        });
+    }
+
+    /// References every declaration of the primary design unit `name`.
+    pub fn locate(&self, name: &Identifier) -> &[Declaration] {
+        match self.units.get(name) {
+            Some(decls) => decls.as_slice(),
+            None => &[],
+        }
+    }
+}
+
+/// A language server answering requests about VHDL documents.
+pub struct Server {
+    index: UnitIndex,
+    /// The latest text of each document open in the editor, keyed by uri.
+    documents: HashMap<String, String>,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new(index: UnitIndex) -> Self {
+        Self {
+            index: index,
+            documents: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    /// Answers the messages read from `input` by writing to `output` until the
+    /// client sends the `exit` notification.
+    ///
+    /// Errors if the client leaves without first requesting a shutdown.
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        input: &mut R,
+        output: &mut W,
+    ) -> Result<(), Fault> {
+        while let Some(msg) = read_message(input)? {
+            if msg["method"].as_str() == Some("exit") {
+                break;
+            }
+            for reply in self.handle(&msg) {
+                write_message(output, &reply)?;
+            }
+        }
+        match self.shutdown {
+            true => Ok(()),
+            false => Err(AnyError(format!(
+                "language client exited before requesting a shutdown"
+            )))?,
+        }
+    }
+
+    /// Processes a single message, returning the messages to send back.
+    fn handle(&mut self, msg: &Value) -> Vec<Value> {
+        let method = msg["method"].as_str().unwrap_or_default();
+        let params = &msg["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match msg.get("id").cloned() {
+            // requests expect a response
+            Some(id) => {
+                let result = match method {
+                    "initialize" => json!({
+                        "capabilities": {
+                            "textDocumentSync": 1,
+                            "documentSymbolProvider": true,
+                            "definitionProvider": true,
+                        },
+                        "serverInfo": {
+                            "name": "orbit",
+                            "version": env!("CARGO_PKG_VERSION"),
+                        },
+                    }),
+                    "shutdown" => {
+                        self.shutdown = true;
+                        Value::Null
+                    }
+                    "textDocument/documentSymbol" => self.document_symbols(uri),
+                    "textDocument/definition" => self.definition(
+                        uri,
+                        params["position"]["line"].as_u64().unwrap_or_default() as usize,
+                        params["position"]["character"].as_u64().unwrap_or_default() as usize,
+                    ),
+                    _ => {
+                        return vec![json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": {
+                                "code": METHOD_NOT_FOUND,
+                                "message": format!("unsupported method '{}'", method),
+                            },
+                        })]
+                    }
+                };
+                vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })]
+            }
+            // notifications expect nothing unless diagnostics are published
+            None => match method {
+                "textDocument/didOpen" => {
+                    let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                    self.change(uri, text.to_string())
+                }
+                "textDocument/didChange" => {
+                    // full sync sends the entire document as the last change
+                    match params["contentChanges"]
+                        .as_array()
+                        .and_then(|changes| changes.last())
+                        .and_then(|change| change["text"].as_str())
+                    {
+                        Some(text) => self.change(uri, text.to_string()),
+                        None => Vec::new(),
+                    }
+                }
+                "textDocument/didClose" => self.close(uri),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Stores the latest `text` of the document at `uri` and reports its
+    /// diagnostics.
+    fn change(&mut self, uri: &str, text: String) -> Vec<Value> {
+        if let Some(path) = uri_to_path(uri) {
+            if fileset::is_vhdl(&path.to_string_lossy()) == true {
+                self.index.update(&path, &text);
+            }
+        }
+        let diagnostics = diagnose(&text);
+        self.documents.insert(uri.to_string(), text);
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    /// Forgets the unsaved text of the document at `uri` and clears its
+    /// diagnostics.
+    fn close(&mut self, uri: &str) -> Vec<Value> {
+        self.documents.remove(uri);
+        // return to what is saved on disk
+        if let Some(path) = uri_to_path(uri) {
+            match std::fs::read_to_string(&path) {
+                Ok(text) => self.index.update(&path, &text),
+                Err(_) => self.index.remove(&path),
+            }
+        }
+        vec![publish_diagnostics(uri, Vec::new())]
+    }
+
+    /// Reads the text of the document at `uri`, favoring unsaved changes from the
+    /// editor over the file on disk.
+    fn read_document(&self, uri: &str) -> Option<String> {
+        match self.documents.get(uri) {
+            Some(text) => Some(text.clone()),
+            None => std::fs::read_to_string(uri_to_path(uri)?).ok(),
+        }
+    }
+
+    /// Lists each design unit in the document at `uri` as a `DocumentSymbol`.
+    fn document_symbols(&self, uri: &str) -> Value {
+        let text = match self.read_document(uri) {
+            Some(t) => t,
+            None => return Value::Null,
+        };
+        let tokens = VHDLTokenizer::from_source_code(&text).into_tokens();
+        let symbols: Vec<Value> = VHDLParser::read(&text)
+            .into_symbols()
+            .iter()
+            .map(|sym| {
+                let (name, kind, detail) = match sym {
+                    VHDLSymbol::Entity(_) => {
+                        (sym.as_iden().unwrap(), KIND_MODULE, String::from("entity"))
+                    }
+                    VHDLSymbol::Package(_) => (
+                        sym.as_iden().unwrap(),
+                        KIND_PACKAGE,
+                        String::from("package"),
+                    ),
+                    VHDLSymbol::Context(_) => (
+                        sym.as_iden().unwrap(),
+                        KIND_NAMESPACE,
+                        String::from("context"),
+                    ),
+                    VHDLSymbol::Configuration(_) => (
+                        sym.as_iden().unwrap(),
+                        KIND_OBJECT,
+                        String::from("configuration"),
+                    ),
+                    VHDLSymbol::Architecture(arch) => (
+                        arch.name(),
+                        KIND_CLASS,
+                        format!("architecture of {}", arch.entity()),
+                    ),
+                    VHDLSymbol::PackageBody(body) => {
+                        (body.get_owner(), KIND_PACKAGE, String::from("package body"))
+                    }
+                };
+                let range = to_range(&locate_name(&tokens, sym.get_position(), name), name.len());
+                json!({
+                    "name": name.to_string(),
+                    "detail": detail,
+                    "kind": kind,
+                    "range": range,
+                    "selectionRange": range,
+                })
+            })
+            .collect();
+        Value::Array(symbols)
+    }
+
+    /// Finds where the primary design unit named at the zero-based `line` and
+    /// `character` of the document at `uri` is declared.
+    fn definition(&self, uri: &str, line: usize, character: usize) -> Value {
+        let text = match self.read_document(uri) {
+            Some(t) => t,
+            None => return Value::Null,
+        };
+        // positions from the tokenizer start at line 1 and column 1
+        let (line, col) = (line + 1, character + 1);
+        let name = VHDLTokenizer::from_source_code(&text)
+            .into_tokens()
+            .into_iter()
+            .find_map(|t| {
+                let pos = t.locate().clone();
+                match t.take() {
+                    VHDLToken::Identifier(id)
+                        if pos.line() == line && pos.col() <= col && col < pos.col() + id.len() =>
+                    {
+                        Some(id)
+                    }
+                    _ => None,
+                }
+            });
+        match name {
+            Some(name) => Value::Array(
+                self.index
+                    .locate(&name)
+                    .iter()
+                    .map(|d| d.to_location())
+                    .collect(),
+            ),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Finds the position of the first `name` identifier at or after `pos`, which
+/// is where a unit's keyword begins.
+///
+/// Falls back to `pos` if the name is never found.
+fn locate_name(tokens: &[Token<VHDLToken>], pos: &Position, name: &Identifier) -> Position {
+    tokens
+        .iter()
+        .filter(|t| t.locate() >= pos)
+        .find(|t| match t.as_type() {
+            VHDLToken::Identifier(id) => id == name,
+            _ => false,
+        })
+        .map(|t| t.locate().clone())
+        .unwrap_or(pos.clone())
+}
+
+/// Reports every error from tokenizing and parsing the source code `text`,
+/// including primary design units that are declared more than once.
+fn diagnose(text: &str) -> Vec<Diagnostic> {
+    let tokenizer = VHDLTokenizer::from_source_code(text);
+    let mut diagnostics: Vec<Diagnostic> = tokenizer
+        .as_errors()
+        .into_iter()
+        .map(|e| e.to_diagnostic())
+        .collect();
+    diagnostics.extend(
+        VHDLParser::read(text)
+            .get_errors()
+            .iter()
+            .map(|e| e.to_diagnostic()),
+    );
+    let sources = vec![(String::new(), text.to_string())];
+    if let Err(e) = primaryunit::collect_units_from_sources(&sources) {
+        if let Some(VhdlIdentifierError::DuplicateIdentifier(name, _, pos, _, _)) =
+            e.downcast_ref::<VhdlIdentifierError>()
+        {
+            let tokens = tokenizer.into_tokens();
+            let pos = locate_name(&tokens, pos, name);
+            diagnostics.push(
+                Diagnostic::error(&format!("duplicate primary design unit '{}'", name))
+                    .span(Span::new(pos.line(), pos.col(), name.len())),
+            );
+        }
+    }
+    diagnostics
+}
+
+/// Serializes a `Diagnostic` into a LSP `Diagnostic`.
+fn to_lsp_diagnostic(d: &Diagnostic) -> Value {
+    let range = match d.get_span() {
+        Some(s) => to_range(&Position::place(s.get_line(), s.get_col()), s.get_len()),
+        None => to_range(&Position::new(), 0),
+    };
+    let mut value = json!({
+        "range": range,
+        "severity": match d.get_severity() {
+            Severity::Error => SEVERITY_ERROR,
+            Severity::Warning => SEVERITY_WARNING,
+            Severity::Note => SEVERITY_INFORMATION,
+        },
+        "source": "orbit",
+        "message": d.get_message(),
+    });
+    if let Some(code) = d.get_code() {
+        value["code"] = json!(code);
+    }
+    value
+}
+
+/// Creates the notification that replaces the diagnostics for the document at
+/// `uri`.
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Diagnostic>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {
+            "uri": uri,
+            "diagnostics": diagnostics.iter().map(to_lsp_diagnostic).collect::<Vec<Value>>(),
+        },
+    })
+}
+
+/// Serializes a span of `len` characters starting at `pos` into a LSP `Range`.
+fn to_range(pos: &Position, len: usize) -> Value {
+    let line = pos.line().saturating_sub(1);
+    let character = pos.col().saturating_sub(1);
+    json!({
+        "start": { "line": line, "character": character },
+        "end": { "line": line, "character": character + len },
+    })
+}
+
+/// Reads the next message from `input`.
+///
+/// Returns `None` once the input is closed.
+fn read_message<R: BufRead>(input: &mut R) -> Result<Option<Value>, Fault> {
+    let mut len: Option<usize> = None;
+    // read the headers until the empty line before the content
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() == true {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim().eq_ignore_ascii_case("content-length") == true {
+                len = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+    let len = match len {
+        Some(n) => n,
+        None => {
+            return Err(AnyError(format!(
+                "message is missing a 'Content-Length' header"
+            )))?
+        }
+    };
+    let mut content = vec![0; len];
+    input.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Writes the message `msg` to `output`.
+fn write_message<W: Write>(output: &mut W, msg: &Value) -> Result<(), Fault> {
+    let content = msg.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Converts a `file://` uri into a path on the filesystem.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // decode any percent-encoded bytes
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+    while let Some(b) = chars.next() {
+        match b {
+            b'%' => {
+                let hex = [chars.next()?, chars.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            _ => bytes.push(b),
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // windows paths keep their drive letter without the leading slash: /C:/...
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// Converts a path on the filesystem into a `file://` uri.
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if path.starts_with('/') == false {
+        uri.push('/');
+    }
+    for b in path.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b':' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(b as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GATES: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity and_gate is
+    port (
+        a, b : in std_logic;
+        y : out std_logic
+    );
+end entity;
+
+architecture rtl of and_gate is
+begin
+    y <= a and b;
+end architecture;
+";
+
+    const TOP: &str = "\
+entity top is
+end entity;
+
+architecture rtl of top is
+begin
+    u0 : entity work.and_gate port map (a => '1', b => '0', y => open);
+end architecture;
+";
+
+    /// Frames each message in `msgs` as it would be sent by a client.
+    fn to_input(msgs: &[Value]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for msg in msgs {
+            write_message(&mut input, msg).unwrap();
+        }
+        input
+    }
+
+    /// Reads back every message written by the server.
+    fn from_output(output: &[u8]) -> Vec<Value> {
+        let mut reader = output;
+        let mut msgs = Vec::new();
+        while let Some(msg) = read_message(&mut reader).unwrap() {
+            msgs.push(msg);
+        }
+        msgs
+    }
+
+    #[test]
+    fn uris() {
+        assert_eq!(
+            uri_to_path("file:///home/user/my%20ip/and_gate.vhd"),
+            Some(PathBuf::from("/home/user/my ip/and_gate.vhd"))
+        );
+        assert_eq!(
+            uri_to_path("file:///C:/Users/user/and_gate.vhd"),
+            Some(PathBuf::from("C:/Users/user/and_gate.vhd"))
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+        assert_eq!(
+            path_to_uri(&PathBuf::from("/home/user/my ip/and_gate.vhd")),
+            "file:///home/user/my%20ip/and_gate.vhd"
+        );
+        assert_eq!(
+            path_to_uri(&PathBuf::from("C:\\Users\\user\\and_gate.vhd")),
+            "file:///C:/Users/user/and_gate.vhd"
+        );
+    }
+
+    #[test]
+    fn index_units() {
+        let mut index = UnitIndex::new();
+        let path = PathBuf::from("/ip/and_gate.vhd");
+        index.update(&path, GATES);
+        let decls = index.locate(&Identifier::Basic(String::from("AND_GATE")));
+        assert_eq!(decls.len(), 1);
+        assert_eq!(decls[0].get_path(), &path);
+        assert_eq!(decls[0].get_position(), &Position::place(4, 8));
+        // architectures are not primary design units
+        assert_eq!(
+            index.locate(&Identifier::Basic(String::from("rtl"))).len(),
+            0
+        );
+
+        index.remove(&path);
+        assert_eq!(index, UnitIndex::new());
+    }
+
+    #[test]
+    fn diagnose_parser_errors() {
+        // a unit's keyword without a name
+        let diagnostics = diagnose("entity is\nend entity;\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].get_message(),
+            "expected an identifier after keyword 'entity'"
+        );
+        assert_eq!(diagnostics[0].get_span().unwrap().get_line(), 1);
+
+        // a primary design unit declared twice
+        let text = format!("{}\n{}", GATES, GATES);
+        let diagnostics = diagnose(&text);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].get_message(),
+            "duplicate primary design unit 'and_gate'"
+        );
+        assert_eq!(diagnostics[0].get_span().unwrap().get_line(), 19);
+
+        assert_eq!(diagnose(GATES).len(), 0);
+    }
+
+    #[test]
+    fn session() {
+        let dir = tempfile::tempdir().unwrap();
+        let gates = dir.path().join("and_gate.vhd");
+        std::fs::write(&gates, GATES).unwrap();
+        let top = path_to_uri(&dir.path().join("top.vhd"));
+
+        let input = to_input(&[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                "textDocument": {"uri": top, "languageId": "vhdl", "version": 1, "text": TOP}
+            }}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/documentSymbol", "params": {
+                "textDocument": {"uri": top}
+            }}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/definition", "params": {
+                "textDocument": {"uri": top}, "position": {"line": 5, "character": 24}
+            }}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+                "textDocument": {"uri": top, "version": 2},
+                "contentChanges": [{"text": "entity top is end entity; constant c : string := \"open"}]
+            }}),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "textDocument/hover", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 5, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ]);
+        let mut output = Vec::new();
+        let mut server = Server::new(UnitIndex::from_files(&[gates.clone()]));
+        server.run(&mut input.as_slice(), &mut output).unwrap();
+
+        let replies = from_output(&output);
+        assert_eq!(replies.len(), 7);
+        assert_eq!(
+            replies[0]["result"]["capabilities"]["definitionProvider"],
+            true
+        );
+        // opening a valid document clears its diagnostics
+        assert_eq!(replies[1]["method"], "textDocument/publishDiagnostics");
+        assert_eq!(replies[1]["params"]["diagnostics"], json!([]));
+        // symbols
+        assert_eq!(replies[2]["result"][0]["name"], "top");
+        assert_eq!(replies[2]["result"][0]["kind"], KIND_MODULE);
+        assert_eq!(replies[2]["result"][1]["detail"], "architecture of top");
+        // definition is found in another file
+        assert_eq!(
+            replies[3]["result"],
+            json!([{
+                "uri": path_to_uri(&gates),
+                "range": {
+                    "start": {"line": 3, "character": 7},
+                    "end": {"line": 3, "character": 15},
+                },
+            }])
+        );
+        // an unterminated string literal is reported
+        assert_eq!(
+            replies[4]["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(replies[5]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[6]["result"], Value::Null);
+    }
+
+    #[test]
+    fn exit_without_shutdown() {
+        let input = to_input(&[json!({"jsonrpc": "2.0", "method": "exit"})]);
+        let mut output = Vec::new();
+        let mut server = Server::new(UnitIndex::new());
+        assert_eq!(
+            server.run(&mut input.as_slice(), &mut output).is_err(),
+            true
+        );
+    }
+}
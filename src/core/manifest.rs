@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+use crate::core::forge::Release;
+use crate::core::hook::InstallHook;
 use crate::core::ip::IpSpec;
 use crate::core::pkgid::PkgPart;
 use crate::core::source;
@@ -20,6 +22,8 @@ pub const IP_MANIFEST_FILE: &str = "Orbit.toml";
 // pub const IP_MANIFEST_PATTERN_FILE : &str = "Orbit-*.toml";
 pub const ORBIT_SUM_FILE: &str = ".orbit-checksum";
 pub const ORBIT_METADATA_FILE: &str = ".orbit-metadata";
+/// Lists the files a post-install hook created within an installation.
+pub const ORBIT_GENERATED_FILE: &str = ".orbit-generated";
 
 const DEPENDENCIES_KEY: &str = "dependencies";
 
@@ -31,6 +35,14 @@ pub struct Manifest {
     dependencies: Dependencies,
     #[serde(rename = "dev-dependencies", skip_serializing_if = "map_is_empty", default)]
     dev_dependencies: Dependencies,
+    /// Maps each fileset's name to its glob patterns
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    fileset: HashMap<String, Vec<String>>,
+    /// Maps each externally-provided design unit's name to its files' glob patterns
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    blackbox: HashMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    plan: HashMap<String, PlanProfile>,
 }
 
 pub trait FromFile: FromStr
@@ -91,26 +103,44 @@ impl Manifest {
                 summary: None,
                 library: None,
                 readme: None,
+                vcs: None,
+                release: None,
+                post_install: None,
+                testbenches: Vec::new(),
+                top: None,
+                bench: None,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                collateral: Vec::new(),
                 authors: None,
                 metadata: HashMap::new(),
             },
             dependencies: Dependencies::new(),
             dev_dependencies: Dependencies::new(),
+            fileset: HashMap::new(),
+            blackbox: HashMap::new(),
+            plan: HashMap::new(),
         }
     }
 
     /// Composes a [String] to write to a clean manifest file.
-    pub fn write_empty_manifest(name: &Id) -> String {
+    ///
+    /// The `vcs` is only written when it differs from the default.
+    pub fn write_empty_manifest(name: &Id, vcs: &Vcs) -> String {
         format!(
             r#"[ip]
 name = "{}"
 version = "0.1.0"
-
+{}
 # See more keys and their definitions at https://c-rus.github.io/orbit/reference/manifest.html
 
 [dependencies]
 "#,
-            name
+            name,
+            match vcs {
+                Vcs::Git => String::new(),
+                _ => format!("vcs = \"{}\"\n", vcs),
+            }
         )
     }
 
@@ -128,6 +158,27 @@ version = "0.1.0"
         &self.dev_dependencies
     }
 
+    /// Lists the filesets defined in the `[fileset]` table with their glob
+    /// patterns, sorted by name.
+    pub fn get_filesets(&self) -> Vec<(&String, &Vec<String>)> {
+        let mut result: Vec<(&String, &Vec<String>)> = self.fileset.iter().collect();
+        result.sort_by(|a, b| a.0.cmp(b.0));
+        result
+    }
+
+    /// Lists the design units defined in the `[blackbox]` table with the glob
+    /// patterns of their files, sorted by name.
+    pub fn get_blackboxes(&self) -> Vec<(&String, &Vec<String>)> {
+        let mut result: Vec<(&String, &Vec<String>)> = self.blackbox.iter().collect();
+        result.sort_by(|a, b| a.0.cmp(b.0));
+        result
+    }
+
+    /// References the plan profile defined in the `[plan.<name>]` table.
+    pub fn get_plan_profile(&self, name: &str) -> Option<&PlanProfile> {
+        self.plan.get(name)
+    }
+
     pub fn is_deps_valid(&self) -> Result<(), AnyError> {
         for (key, _) in &self.dependencies {
             if let Some(_) = self.dev_dependencies.get(key) {
@@ -191,6 +242,28 @@ pub struct Package {
     #[serde(deserialize_with = "source::string_or_struct", default)]
     source: Source,
     readme: Option<PathBuf>,
+    vcs: Option<Vcs>,
+    /// Publishes a release to the ip's forge during a launch
+    release: Option<Release>,
+    /// Generates files within the ip's installation after it is extracted
+    #[serde(rename = "post-install")]
+    post_install: Option<InstallHook>,
+    /// Design units to run as testbenches instead of the entities without ports
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    testbenches: Vec<String>,
+    /// Default top-level unit to plan when neither `--top` nor `--bench` is given
+    top: Option<String>,
+    /// Default testbench to plan when neither `--top` nor `--bench` is given
+    bench: Option<String>,
+    /// Glob patterns selecting the only files that belong to the ip
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    include: Vec<String>,
+    /// Glob patterns for files that do not belong to the ip
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    exclude: Vec<String>,
+    /// Glob patterns for non-HDL files kept by a minimal installation
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    collateral: Vec<String>,
     /// Ignore this field and never use it for any processing
     #[serde(skip_serializing_if = "map_is_empty", default)]
     metadata: HashMap<String, toml::Value>,
@@ -225,6 +298,142 @@ impl Package {
     pub fn get_readme(&self) -> &Option<PathBuf> {
         &self.readme
     }
+
+    /// Returns the version control system managing the ip, which is git unless
+    /// specified otherwise.
+    pub fn get_vcs(&self) -> Vcs {
+        self.vcs.clone().unwrap_or_default()
+    }
+
+    pub fn get_release(&self) -> Option<&Release> {
+        self.release.as_ref()
+    }
+
+    /// References the hook to run within the ip's installation, if one is set.
+    pub fn get_post_install(&self) -> Option<&InstallHook> {
+        self.post_install.as_ref()
+    }
+
+    /// References the names of the design units tagged as testbenches.
+    pub fn get_testbenches(&self) -> &Vec<String> {
+        &self.testbenches
+    }
+
+    /// References the name of the default top-level unit, if one is set.
+    pub fn get_top(&self) -> Option<&String> {
+        self.top.as_ref()
+    }
+
+    /// References the name of the default testbench, if one is set.
+    pub fn get_bench(&self) -> Option<&String> {
+        self.bench.as_ref()
+    }
+
+    /// References the patterns of the only files that belong to the ip. An
+    /// empty list means every file not excluded belongs to the ip.
+    pub fn get_include(&self) -> &Vec<String> {
+        &self.include
+    }
+
+    /// References the patterns of the files that do not belong to the ip.
+    pub fn get_exclude(&self) -> &Vec<String> {
+        &self.exclude
+    }
+
+    /// References the patterns of the files besides the HDL source code that
+    /// an installation keeps, such as memory initialization files.
+    pub fn get_collateral(&self) -> &Vec<String> {
+        &self.collateral
+    }
+}
+
+/// A named set of options for `orbit plan`, recorded to keep frequent
+/// invocations short and reproducible.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PlanProfile {
+    plugin: Option<String>,
+    top: Option<String>,
+    bench: Option<String>,
+    #[serde(rename = "build-dir")]
+    build_dir: Option<String>,
+    /// Maps each fileset's name to its glob pattern
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    fileset: HashMap<String, String>,
+    #[serde(default)]
+    all: bool,
+}
+
+impl PlanProfile {
+    pub fn get_plugin(&self) -> Option<&String> {
+        self.plugin.as_ref()
+    }
+
+    pub fn get_top(&self) -> Option<&String> {
+        self.top.as_ref()
+    }
+
+    pub fn get_bench(&self) -> Option<&String> {
+        self.bench.as_ref()
+    }
+
+    pub fn get_build_dir(&self) -> Option<&String> {
+        self.build_dir.as_ref()
+    }
+
+    /// Lists the filesets as `<name>=<glob>` pairs sorted by name.
+    pub fn get_filesets(&self) -> Vec<String> {
+        let mut result: Vec<String> = self
+            .fileset
+            .iter()
+            .map(|(n, p)| format!("{}={}", n, p))
+            .collect();
+        result.sort();
+        result
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.all
+    }
+}
+
+/// The version control system that manages an ip's source code.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Vcs {
+    Git,
+    /// The ip is not managed by git, such as when using Perforce or SVN.
+    None,
+}
+
+impl Default for Vcs {
+    fn default() -> Self {
+        Self::Git
+    }
+}
+
+impl FromStr for Vcs {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "git" => Ok(Self::Git),
+            "none" => Ok(Self::None),
+            _ => Err(AnyError(format!(
+                "unknown vcs '{}'; expecting 'git' or 'none'",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for Vcs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git => write!(f, "git"),
+            Self::None => write!(f, "none"),
+        }
+    }
 }
 
 /// Takes an iterative approach to iterating through directories to find a file
@@ -313,6 +522,157 @@ mod test {
             assert_eq!(man.ip.library, Some(PkgPart::from_str("common").unwrap()));
         }
 
+        #[test]
+        fn ut_vcs() {
+            let name = PkgPart::from_str("gates").unwrap();
+            let man = Manifest::from_str(&Manifest::write_empty_manifest(&name, &Vcs::None)).unwrap();
+            assert_eq!(man.ip.get_vcs(), Vcs::None);
+            let man = Manifest::from_str(&Manifest::write_empty_manifest(&name, &Vcs::Git)).unwrap();
+            assert_eq!(man.ip.vcs, None);
+            assert_eq!(man.ip.get_vcs(), Vcs::Git);
+        }
+
+        #[test]
+        fn ut_release() {
+            let man = Manifest::from_str(
+                "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\nrelease = { forge = \"github\", repository = \"c-rus/gates\" }\n",
+            )
+            .unwrap();
+            assert_eq!(
+                man.ip.get_release().map(|r| r.get_host_url()),
+                Some(String::from("https://github.com/c-rus/gates"))
+            );
+            assert_eq!(Manifest::new().ip.get_release(), None);
+        }
+
+        #[test]
+        fn ut_testbenches() {
+            let man = Manifest::from_str(
+                "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\ntestbenches = [\"and_gate_tb\", \"cocotb_top\"]\n",
+            )
+            .unwrap();
+            assert_eq!(
+                man.ip.get_testbenches(),
+                &vec![String::from("and_gate_tb"), String::from("cocotb_top")]
+            );
+            assert_eq!(Manifest::new().ip.get_testbenches().is_empty(), true);
+        }
+
+        #[test]
+        fn ut_include_exclude() {
+            let man = Manifest::from_str(
+                "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\ninclude = [\"rtl/\"]\nexclude = [\"*.pdf\"]\n",
+            )
+            .unwrap();
+            assert_eq!(man.ip.get_include(), &vec![String::from("rtl/")]);
+            assert_eq!(man.ip.get_exclude(), &vec![String::from("*.pdf")]);
+            assert_eq!(Manifest::new().ip.get_include().is_empty(), true);
+        }
+
+        #[test]
+        fn ut_filesets() {
+            let man = Manifest::from_str(
+                r#"[ip]
+name = "soc"
+version = "1.0.0"
+
+[fileset]
+XDC = ["constr/*.xdc"]
+MEM-INIT = ["rom/*.mem", "ram/*.hex"]
+"#,
+            )
+            .unwrap();
+            assert_eq!(
+                man.get_filesets(),
+                vec![
+                    (
+                        &String::from("MEM-INIT"),
+                        &vec![String::from("rom/*.mem"), String::from("ram/*.hex")]
+                    ),
+                    (&String::from("XDC"), &vec![String::from("constr/*.xdc")]),
+                ]
+            );
+            assert_eq!(Manifest::new().get_filesets().is_empty(), true);
+        }
+
+        #[test]
+        fn ut_blackboxes() {
+            let man = Manifest::from_str(
+                r#"[ip]
+name = "soc"
+version = "1.0.0"
+
+[blackbox]
+sys_pll = ["netlist/sys_pll.edf"]
+aes_core = ["enc/aes_core.vp", "enc/aes_core_stub.v"]
+"#,
+            )
+            .unwrap();
+            assert_eq!(
+                man.get_blackboxes(),
+                vec![
+                    (
+                        &String::from("aes_core"),
+                        &vec![String::from("enc/aes_core.vp"), String::from("enc/aes_core_stub.v")]
+                    ),
+                    (&String::from("sys_pll"), &vec![String::from("netlist/sys_pll.edf")]),
+                ]
+            );
+            assert_eq!(Manifest::new().get_blackboxes().is_empty(), true);
+        }
+
+        #[test]
+        fn ut_plan_profiles() {
+            let man = Manifest::from_str(
+                r#"[ip]
+name = "soc"
+version = "1.0.0"
+
+[plan.impl]
+plugin = "vivado"
+top = "soc_top"
+fileset = { XDC = "constr/*.xdc", TCL = "scripts/*.tcl" }
+
+[plan.sim]
+bench = "soc_tb"
+all = true
+"#,
+            )
+            .unwrap();
+            let imp = man.get_plan_profile("impl").unwrap();
+            assert_eq!(imp.get_plugin(), Some(&String::from("vivado")));
+            assert_eq!(imp.get_top(), Some(&String::from("soc_top")));
+            assert_eq!(imp.get_bench(), None);
+            assert_eq!(
+                imp.get_filesets(),
+                vec![
+                    String::from("TCL=scripts/*.tcl"),
+                    String::from("XDC=constr/*.xdc")
+                ]
+            );
+            assert_eq!(imp.is_all(), false);
+            assert_eq!(man.get_plan_profile("sim").unwrap().is_all(), true);
+            assert_eq!(man.get_plan_profile("synth"), None);
+            // profiles reject unknown options
+            assert_eq!(
+                Manifest::from_str("[ip]\nname = \"soc\"\nversion = \"1.0.0\"\n[plan.impl]\nclean = true\n")
+                    .is_err(),
+                true
+            );
+        }
+
+        #[test]
+        fn ut_default_units() {
+            let man = Manifest::from_str(
+                "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\ntop = \"harness\"\nbench = \"harness_tb\"\n",
+            )
+            .unwrap();
+            assert_eq!(man.ip.get_top(), Some(&String::from("harness")));
+            assert_eq!(man.ip.get_bench(), Some(&String::from("harness_tb")));
+            assert_eq!(Manifest::new().ip.get_top(), None);
+            assert_eq!(Manifest::new().ip.get_bench(), None);
+        }
+
         #[test]
         fn ut_bad() {
             let man = toml::from_str::<Manifest>(ERR1);
@@ -0,0 +1,255 @@
+//! Upgrades the data written by an older version of orbit on the first run of a
+//! newer version.
+//!
+//! The version that last ran is stamped into the home directory. Each step
+//! checks for the older layout itself, so running a step on data that is already
+//! up to date changes nothing.
+
+use crate::core::catalog::CacheSlot;
+use crate::core::config::CONFIG_FILE;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::manifest::{IP_MANIFEST_FILE, ORBIT_SUM_FILE};
+use crate::core::version::Version;
+use crate::util::anyerror::Fault;
+use crate::util::logger;
+use std::path::PathBuf;
+use std::str::FromStr;
+use toml_edit::Document;
+
+/// The file within the home directory storing the version of orbit that last ran.
+pub const VERSION_STAMP_FILE: &str = "orbit-version";
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Runs every migration step if the version that last ran is older than this
+/// version, reporting each change that was made.
+///
+/// A missing stamp is treated as an older version, since versions before the
+/// stamp existed never wrote one.
+pub fn run(c: &Context) -> Result<(), Fault> {
+    let stamp = c.get_home_path().join(VERSION_STAMP_FILE);
+    let current = Version::from_str(VERSION)?;
+    let previous = std::fs::read_to_string(&stamp)
+        .ok()
+        .and_then(|s| Version::from_str(s.trim()).ok());
+    // never touch data from a newer version
+    if let Some(prev) = &previous {
+        if prev >= &current {
+            return Ok(());
+        }
+    }
+
+    let mut changes = Vec::new();
+    changes.append(&mut migrate_config(
+        &c.get_config_home_path().join(CONFIG_FILE),
+    )?);
+    changes.append(&mut migrate_cache(c.get_cache_path())?);
+
+    if changes.is_empty() == false {
+        logger::info(format!(
+            "upgraded data from orbit {} to {}:\n{}",
+            match &previous {
+                Some(v) => v.to_string(),
+                None => String::from("(unknown version)"),
+            },
+            current,
+            changes
+                .iter()
+                .map(|c| format!("    {}", c))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ));
+    }
+    std::fs::write(&stamp, format!("{}\n", current))?;
+    Ok(())
+}
+
+/// Renames the `alias` entry of each `[[plugin]]` in the configuration file at
+/// `path` to `name`.
+fn migrate_config(path: &PathBuf) -> Result<Vec<String>, Fault> {
+    if path.is_file() == false {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(path)?;
+    let (text, changes) = rename_plugin_alias(&text)?;
+    if changes.is_empty() == false {
+        std::fs::write(path, text)?;
+    }
+    Ok(changes
+        .into_iter()
+        .map(|c| format!("{} in {:?}", c, path))
+        .collect())
+}
+
+/// Replaces the `alias` key of each `[[plugin]]` table with `name`.
+///
+/// Returns the updated text and a description of each plugin that changed.
+fn rename_plugin_alias(text: &str) -> Result<(String, Vec<String>), Fault> {
+    let mut doc = text.parse::<Document>()?;
+    let mut changes = Vec::new();
+    if let Some(plugins) = doc
+        .get_mut("plugin")
+        .and_then(|p| p.as_array_of_tables_mut())
+    {
+        for plugin in plugins.iter_mut() {
+            if plugin.contains_key("name") == true {
+                continue;
+            }
+            if let Some(alias) = plugin.remove("alias") {
+                changes.push(format!(
+                    "renamed 'alias' to 'name' for plugin {}",
+                    alias.as_str().unwrap_or("?")
+                ));
+                plugin.insert("name", alias);
+            }
+        }
+    }
+    Ok((doc.to_string(), changes))
+}
+
+/// Writes the checksum file and the design unit metadata into every installation
+/// in the cache at `dir` that was installed before they were recorded, and moves
+/// each installation into its shard directory.
+fn migrate_cache(dir: &PathBuf) -> Result<Vec<String>, Fault> {
+    let mut changes = Vec::new();
+    if dir.is_dir() == false {
+        return Ok(changes);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let slot = entry?.path();
+        if slot.join(IP_MANIFEST_FILE).is_file() == false {
+            continue;
+        }
+        if Ip::read_checksum_proof(&slot).is_none() == true {
+            std::fs::write(
+                slot.join(ORBIT_SUM_FILE),
+                Ip::compute_checksum(&slot).to_string(),
+            )?;
+            changes.push(format!(
+                "recorded the checksum of installation {:?}",
+                slot.file_name().unwrap_or_default()
+            ));
+        }
+        // older metadata files did not record where each unit is defined
+        if has_unit_sources(&slot) == false {
+            // installations with conflicting units are left to be parsed when read
+            if let Ok(units) = Ip::collect_units(true, &slot) {
+                Ip::write_units_to_metadata(&slot, &units)?;
+                changes.push(format!(
+                    "recorded the design units of installation {:?}",
+                    slot.file_name().unwrap_or_default()
+                ));
+            }
+        }
+        // older versions placed every installation directly in the cache
+        if let Some(to) = to_shard_path(dir, &slot) {
+            std::fs::create_dir_all(to.parent().unwrap())?;
+            std::fs::rename(&slot, &to)?;
+            changes.push(format!(
+                "moved installation {:?} into {:?}",
+                slot.file_name().unwrap_or_default(),
+                to.parent().unwrap()
+            ));
+        }
+    }
+    Ok(changes)
+}
+
+/// Determines where the installation at `slot` belongs within the sharded cache
+/// at `dir`.
+///
+/// Returns `None` if the installation's uuid cannot be read from its lock file
+/// or the installation is already in place.
+fn to_shard_path(dir: &PathBuf, slot: &PathBuf) -> Option<PathBuf> {
+    let name = CacheSlot::try_from_str(&slot.file_name()?.to_string_lossy())?;
+    let ip = Ip::load(slot.clone()).ok()?;
+    let entry = ip
+        .get_lock()
+        .get(ip.get_man().get_ip().get_name(), ip.get_man().get_ip().get_version())?;
+    let to = name.to_path(dir, entry.get_uuid());
+    match to.exists() {
+        true => None,
+        false => Some(to),
+    }
+}
+
+/// Checks if the metadata of the installation at `slot` records the source file
+/// of each of its units.
+fn has_unit_sources(slot: &PathBuf) -> bool {
+    match Ip::read_units_from_metadata(slot) {
+        Some(units) => units
+            .values()
+            .all(|u| u.get_unit().get_source_code_file().is_empty() == false),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plugin_alias() {
+        let text = "\
+[[plugin]]
+alias = \"ghdl\"
+command = \"python\"
+
+[[plugin]]
+name = \"vivado\"
+command = \"vivado\"
+";
+        let (text, changes) = rename_plugin_alias(text).unwrap();
+        assert_eq!(
+            changes,
+            vec![String::from("renamed 'alias' to 'name' for plugin ghdl")]
+        );
+        assert_eq!(text.contains("alias"), false);
+        assert_eq!(text.contains("name = \"ghdl\""), true);
+
+        // migrating again changes nothing
+        let (_, changes) = rename_plugin_alias(&text).unwrap();
+        assert_eq!(changes.is_empty(), true);
+    }
+
+    #[test]
+    fn cache_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().to_path_buf();
+        let slot = cache.join("gates-1.0.0-0123456789");
+        std::fs::create_dir(&slot).unwrap();
+        std::fs::write(slot.join(IP_MANIFEST_FILE), "[ip]\n").unwrap();
+        std::fs::write(cache.join("CACHEDIR.TAG"), "").unwrap();
+
+        assert_eq!(migrate_cache(&cache).unwrap().len(), 2);
+        assert_eq!(Ip::read_checksum_proof(&slot).is_some(), true);
+        assert_eq!(has_unit_sources(&slot), true);
+        assert_eq!(migrate_cache(&cache).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn cache_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().to_path_buf();
+        let slot = cache.join("gates-1.0.0-0123456789");
+        std::fs::create_dir(&slot).unwrap();
+        std::fs::write(
+            slot.join(IP_MANIFEST_FILE),
+            "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            slot.join(crate::core::lockfile::IP_LOCK_FILE),
+            "version = 1\n\n[[ip]]\nname = \"gates\"\nversion = \"1.0.0\"\nuuid = \"00000000-0000-0000-0000-000000000000\"\ndependencies = []\n",
+        )
+        .unwrap();
+
+        migrate_cache(&cache).unwrap();
+        assert_eq!(slot.exists(), false);
+        assert_eq!(
+            cache.join("00").join("00").join("gates-1.0.0-0123456789").is_dir(),
+            true
+        );
+    }
+}
@@ -2,16 +2,26 @@ pub mod algo;
 pub mod catalog;
 pub mod config;
 pub mod context;
+pub mod credential;
+pub mod depreport;
 pub mod fileset;
+pub mod forge;
+pub mod hook;
 pub mod ip;
 pub mod iparchive;
 pub mod lang;
 pub mod lockfile;
+pub mod lsp;
 pub mod manifest;
+pub mod migrate;
 pub mod pkgid;
+pub mod portreport;
 pub mod plugin;
 pub mod protocol;
+pub mod rewrite;
 pub mod source;
+pub mod template;
 pub mod uuid;
 pub mod variable;
+pub mod vendor;
 pub mod version;
@@ -6,16 +6,27 @@ use crate::core::fileset::Fileset;
 use crate::core::fileset::Style;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
+use crate::core::manifest::Manifest;
+use crate::util::environment::EnvVar;
+use crate::util::environment::Environment;
+use crate::util::environment::{ORBIT_BENCH, ORBIT_TOP};
 use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
+use crate::util::logger;
+use crate::util::logger::Level;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::error::Error;
+use std::io::Write;
 use std::path::PathBuf;
 use std::str::FromStr;
 
 pub type Plugins = Vec<Plugin>;
 
+/// The version of the document format written to a plugin's standard input.
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
 type Filesets = HashMap<String, Style>;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -26,13 +37,32 @@ pub struct Plugin {
     command: String,
     args: Option<Vec<String>>,
     fileset: Option<Filesets>,
+    options: Option<Vec<String>>,
+    #[serde(rename = "require-env")]
+    require_env: Option<Vec<String>>,
+    stdin: Option<InputFormat>,
+    env: Option<HashMap<String, String>>,
     summary: Option<String>,
     details: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
     root: Option<PathBuf>,
+    #[serde(skip_serializing, skip_deserializing)]
+    origin: Option<PathBuf>,
+}
+
+/// The format of the document a plugin expects to read from its standard input.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    Json,
 }
 
 impl Plugin {
+    /// References the format the plugin expects on its standard input, if any.
+    pub fn get_input_format(&self) -> Option<&InputFormat> {
+        self.stdin.as_ref()
+    }
+
     pub fn get_filesets(&self) -> Option<&Filesets> {
         self.fileset.as_ref()
     }
@@ -40,8 +70,9 @@ impl Plugin {
     /// Displays a plugin's information in a single line for quick glance.
     pub fn quick_info(&self) -> String {
         format!(
-            "{:<16}{}",
+            "{:<16}{:<16}{}",
             self.alias,
+            self.command,
             self.summary.as_ref().unwrap_or(&String::new())
         )
     }
@@ -68,10 +99,69 @@ impl Plugin {
         self.root = Some(root);
     }
 
+    /// Sets the file where the plugin is defined.
+    pub fn set_origin(&mut self, origin: PathBuf) {
+        self.origin = Some(origin);
+    }
+
+    /// References the file where the plugin is defined, if known.
+    pub fn get_origin(&self) -> Option<&PathBuf> {
+        self.origin.as_ref()
+    }
+
     /// References the alias to call this plugin.
     pub fn get_alias(&self) -> &str {
         &self.alias
     }
+
+    /// References the plugin's short description, if any.
+    pub fn get_summary(&self) -> Option<&String> {
+        self.summary.as_ref()
+    }
+
+    /// Collects the environment variables to set when the plugin runs.
+    pub fn get_env(&self) -> Environment {
+        let mut env = Environment::new();
+        self.env
+            .as_ref()
+            .unwrap_or(&HashMap::new())
+            .iter()
+            .for_each(|(k, v)| {
+                env.insert(EnvVar::new().key(k).value(v));
+            });
+        env
+    }
+
+    /// Verifies every environment variable the plugin requires is set either
+    /// within `env`, the plugin's own environment, or the current process's environment.
+    pub fn validate_env(&self, env: &Environment) -> Result<(), PluginError> {
+        let own_env = self.get_env();
+        for key in self.require_env.as_ref().unwrap_or(&Vec::new()) {
+            if env.get(key).is_none() && own_env.get(key).is_none() && std::env::var(key).is_err() {
+                return Err(PluginError::MissingEnv(self.alias.clone(), key.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies every option within `args` is supported by the plugin.
+    ///
+    /// An option is any argument beginning with '-', and only the text before
+    /// a '=' is compared. All arguments are accepted if the plugin does not
+    /// declare its options.
+    pub fn validate_args(&self, args: &[String]) -> Result<(), PluginError> {
+        let options = match &self.options {
+            Some(o) => o,
+            None => return Ok(()),
+        };
+        for arg in args.iter().filter(|a| a.starts_with('-')) {
+            let opt = arg.split_once('=').map(|(k, _)| k).unwrap_or(arg);
+            if options.iter().any(|o| o == opt) == false {
+                return Err(PluginError::UnknownOption(self.alias.clone(), opt.to_string()));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for Plugin {
@@ -82,6 +172,9 @@ impl std::fmt::Display for Plugin {
 Name:    {}
 Command: {} {}
 Root:    {}
+Origin:  {}
+Options: {}
+Require: {}
 Filesets:
 {}{}{}",
             self.alias,
@@ -92,6 +185,18 @@ Filesets:
                 .iter()
                 .fold(String::new(), |x, y| { x + "\"" + &y + "\" " }),
             PathBuf::standardize(self.root.as_ref().unwrap()).display(),
+            match &self.origin {
+                Some(o) => PathBuf::standardize(o).display().to_string(),
+                None => String::new(),
+            },
+            self.options
+                .as_ref()
+                .unwrap_or(&Vec::new())
+                .join(" "),
+            self.require_env
+                .as_ref()
+                .unwrap_or(&Vec::new())
+                .join(" "),
             {
                 if self.fileset.is_none() {
                     String::from("  None\n")
@@ -101,7 +206,7 @@ Filesets:
                         .unwrap()
                         .iter()
                         .fold(String::new(), |x, (n, p)| {
-                            x + &format!("  {:<16}{}\n", Fileset::standardize_name(n), p.inner())
+                            x + &format!("  {:<16}{}\n", Fileset::standardize_name(n), p)
                         })
                 }
             },
@@ -138,35 +243,80 @@ pub trait Process {
 
     fn get_args(&self) -> Vec<&String>;
 
-    /// Runs the given `command` with the set `args` for the plugin.
-    fn execute(&self, extra_args: &[String], verbose: bool, dir: &str) -> Result<(), Fault> {
-        // resolve the relative paths in the command and arguments defined in original configuration
+    /// Combines the defined arguments with the `extra_args` set on the command-line.
+    ///
+    /// Relative paths in the defined arguments are resolved from the root
+    /// directory, while the `extra_args` are appended verbatim in the order received.
+    fn get_full_args(&self, extra_args: &[String]) -> Vec<String> {
         let root_path = self.get_root();
-        let command = filesystem::resolve_rel_path(root_path, &self.get_command());
-        let arguments: Vec<String> = self
-            .get_args()
+        self.get_args()
             .iter()
             .map(|f| filesystem::resolve_rel_path(root_path, f))
-            .collect();
+            .chain(extra_args.iter().cloned())
+            .collect()
+    }
 
+    /// Captures the process that runs the `command` with the set `args` from the
+    /// directory `dir` under the current environment.
+    fn to_invocation(&self, extra_args: &[String], dir: &str, input: Option<String>) -> Invocation {
+        Invocation::new(
+            filesystem::resolve_rel_path(self.get_root(), &self.get_command()),
+            self.get_full_args(extra_args),
+            dir,
+            input,
+        )
+    }
+
+    /// Runs the given `command` with the set `args` for the plugin.
+    ///
+    /// When `input` is provided, it is written to the process's standard input.
+    fn execute(
+        &self,
+        extra_args: &[String],
+        verbose: bool,
+        dir: &str,
+        input: Option<&[u8]>,
+    ) -> Result<(), Fault> {
+        // resolve the relative paths in the command defined in original configuration
+        let command = filesystem::resolve_rel_path(self.get_root(), &self.get_command());
         // append args set on the command-line to the base-line of arguments
-        let args = [&arguments, extra_args].concat();
+        let args = self.get_full_args(extra_args);
         // display the literal command being ran
-        if verbose == true {
+        if verbose == true || logger::is_enabled(Level::Verbose) == true {
             let s = args
                 .iter()
                 .fold(String::new(), |x, y| x + "\"" + &y + "\" ");
             println!("info: Running: {} {}", command, s);
         }
-        let mut proc =
-            filesystem::invoke(dir, &command, &args, Context::enable_windows_bat_file_match())?;
+        let mut proc = filesystem::invoke(
+            dir,
+            &command,
+            &args,
+            Context::enable_windows_bat_file_match(),
+            input.is_some(),
+        )?;
+        let written = match input {
+            Some(data) => {
+                // dropping the handle closes the pipe to signal the end of input
+                let mut stdin = proc.stdin.take().unwrap();
+                stdin.write_all(data)
+            }
+            None => Ok(()),
+        };
+        // always wait on the process, even if it stopped reading its input early
         let exit_code = proc.wait()?;
         match exit_code.code() {
             Some(num) => {
                 if num != 0 {
                     Err(AnyError(format!("Exited with error code: {}", num)))?
                 } else {
-                    Ok(())
+                    match written {
+                        // a process is free to exit without reading all of its input
+                        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(AnyError(
+                            format!("Failed to write to the process's standard input: {}", e),
+                        ))?,
+                        _ => Ok(()),
+                    }
                 }
             }
             None => Err(AnyError(format!("Terminated by signal")))?,
@@ -191,9 +341,205 @@ impl Process for Plugin {
     }
 }
 
+/// A snapshot of everything passed to a process: its command, arguments,
+/// working directory, environment, and standard input.
+///
+/// Saving an invocation allows the exact same run to be replayed later.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Invocation {
+    command: String,
+    args: Vec<String>,
+    cwd: PathBuf,
+    input: Option<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl Invocation {
+    /// Captures the `command` and `args` to run from the directory `dir` along
+    /// with every variable in the current environment.
+    pub fn new(command: String, args: Vec<String>, dir: &str, input: Option<String>) -> Self {
+        Self {
+            command: command,
+            args: args,
+            cwd: match std::env::current_dir() {
+                Ok(cur) => cur.join(dir),
+                Err(_) => PathBuf::from(dir),
+            },
+            input: input,
+            env: std::env::vars().collect(),
+        }
+    }
+
+    /// Reads a previously saved invocation from the file at `path`.
+    pub fn load(path: &PathBuf) -> Result<Self, Fault> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(AnyError(format!(
+                    "Failed to read invocation file '{}': {}",
+                    PathBuf::standardize(path).display(),
+                    e
+                )))?
+            }
+        };
+        match toml::from_str(&contents) {
+            Ok(inv) => Ok(inv),
+            Err(e) => Err(AnyError(format!(
+                "Failed to parse invocation file '{}': {}",
+                PathBuf::standardize(path).display(),
+                e
+            )))?,
+        }
+    }
+
+    /// Writes the invocation to the file at `path`.
+    ///
+    /// The captured environment may hold secrets such as access tokens, so on
+    /// unix the file is only readable and writable by its owner.
+    pub fn save(&self, path: &PathBuf) -> Result<(), Fault> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // an existing file keeps its permissions when it is opened
+            if path.exists() == true {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let mut file = options.open(path)?;
+        file.write_all(toml::to_string(&self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Spawns the process with exactly the captured environment and waits for
+    /// it to complete.
+    pub fn run(&self, verbose: bool) -> Result<(), Fault> {
+        // display the literal command being ran
+        let s = self
+            .args
+            .iter()
+            .fold(String::new(), |x, y| x + "\"" + &y + "\" ");
+        match verbose {
+            true => logger::info(format!("Running: {} {}", self.command, s)),
+            false => logger::verbose(format!("Running: {} {}", self.command, s)),
+        }
+        let mut proc = filesystem::invoke_with_env(
+            &self.cwd.to_string_lossy(),
+            &self.command,
+            &self.args,
+            Some(&self.env),
+            Context::enable_windows_bat_file_match(),
+            self.input.is_some(),
+        )?;
+        if let Some(data) = &self.input {
+            // dropping the handle closes the pipe to signal the end of input
+            let mut stdin = proc.stdin.take().unwrap();
+            stdin.write_all(data.as_bytes())?;
+        }
+        let exit_code = proc.wait()?;
+        match exit_code.code() {
+            Some(num) => {
+                if num != 0 {
+                    Err(AnyError(format!("Exited with error code: {}", num)))?
+                } else {
+                    Ok(())
+                }
+            }
+            None => Err(AnyError(format!("Terminated by signal")))?,
+        }
+    }
+}
+
+/// A file listed within the blueprint.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BlueprintRule {
+    fileset: String,
+    library: String,
+    path: String,
+}
+
+impl BlueprintRule {
+    pub fn new(fileset: &str, library: &str, path: &str) -> Self {
+        Self {
+            fileset: fileset.to_string(),
+            library: library.to_string(),
+            path: path.to_string(),
+        }
+    }
+}
+
+/// Information about the ip being built.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IpInfo {
+    name: String,
+    version: String,
+    library: Option<String>,
+}
+
+/// The document written to a plugin's standard input during a build.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PluginInput {
+    protocol: u32,
+    ip: IpInfo,
+    top: Option<String>,
+    bench: Option<String>,
+    files: Vec<BlueprintRule>,
+    filesets: BTreeMap<String, Vec<String>>,
+    env: BTreeMap<String, String>,
+}
+
+impl PluginInput {
+    /// Creates the document from the ip's manifest `man`, the blueprint's rules
+    /// `files`, and the environment variables `env`.
+    ///
+    /// The files keep the order they appear in the blueprint. The top and bench
+    /// names are taken from `env`, and only variables beginning with "ORBIT_" are kept.
+    pub fn new(man: &Manifest, files: Vec<BlueprintRule>, env: &[(String, String)]) -> Self {
+        let mut filesets: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for rule in &files {
+            filesets
+                .entry(rule.fileset.clone())
+                .or_insert(Vec::new())
+                .push(rule.path.clone());
+        }
+        let env: BTreeMap<String, String> = env
+            .iter()
+            .filter(|(k, _)| k.starts_with("ORBIT_"))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        // an empty top or bench means the design does not have one
+        let lookup = |key: &str| env.get(key).filter(|v| v.is_empty() == false).cloned();
+        Self {
+            protocol: PLUGIN_PROTOCOL_VERSION,
+            ip: IpInfo {
+                name: man.get_ip().get_name().to_string(),
+                version: man.get_ip().get_version().to_string(),
+                library: man.get_ip().get_library().as_ref().map(|l| l.to_string()),
+            },
+            top: lookup(ORBIT_TOP),
+            bench: lookup(ORBIT_BENCH),
+            files: files,
+            filesets: filesets,
+            env: env,
+        }
+    }
+
+    /// Serializes the document into the `format`.
+    pub fn to_format(&self, format: &InputFormat) -> Result<String, Fault> {
+        match format {
+            InputFormat::Json => Ok(serde_json::to_string_pretty(&self)?),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum PluginError {
     Missing(String),
+    MissingEnv(String, String),
+    UnknownOption(String, String),
 }
 
 impl Error for PluginError {}
@@ -206,6 +552,16 @@ impl std::fmt::Display for PluginError {
                 "No plugin named '{}'\n\nTry `orbit plan --list` to see available plugins",
                 name
             ),
+            Self::MissingEnv(name, key) => write!(
+                f,
+                "Plugin '{}' requires environment variable '{}' to be set\n\nTry adding the variable to the `[env]` table in a configuration file",
+                name, key
+            ),
+            Self::UnknownOption(name, opt) => write!(
+                f,
+                "Plugin '{0}' does not support option '{1}'\n\nTry `orbit plan --plugin {0} --list` to see the plugin's supported options",
+                name, opt
+            ),
         }
     }
 }
@@ -267,8 +623,13 @@ args = ["~/scripts/download.bash"]
                     ),
                     (String::from("text"), Style::from_str("*.txt").unwrap()),
                 ])),
+                options: None,
+                require_env: None,
+                stdin: None,
+                env: None,
                 details: None,
                 root: None,
+                origin: None,
             }
         );
 
@@ -281,12 +642,150 @@ args = ["~/scripts/download.bash"]
                 args: Some(vec![String::from("~/scripts/download.bash")]),
                 summary: None,
                 fileset: None,
+                options: None,
+                require_env: None,
+                stdin: None,
+                env: None,
                 details: None,
                 root: None,
+                origin: None,
             }
         );
     }
 
+    #[test]
+    fn full_args() {
+        let plug = Plugin::from_str(P_2).unwrap().root(PathBuf::from("./tests/data"));
+        let extra = vec![
+            String::from("--gui"),
+            String::from("-do"),
+            String::from("wave.do"),
+        ];
+        assert_eq!(
+            plug.get_full_args(&extra),
+            vec!["~/scripts/download.bash", "--gui", "-do", "wave.do"]
+        );
+        assert_eq!(plug.get_full_args(&[]), vec!["~/scripts/download.bash"]);
+    }
+
+    #[test]
+    fn plugin_input() {
+        let man = Manifest::from_str("[ip]\nname = \"gates\"\nversion = \"1.0.0\"\n").unwrap();
+        let blueprint = vec![
+            BlueprintRule::new("VHDL-RTL", "work", "/gates/and_gate.vhd"),
+            BlueprintRule::new("VHDL-SIM", "work", "/gates/and_gate_tb.vhd"),
+            BlueprintRule::new("VHDL-RTL", "work", "/gates/or_gate.vhd"),
+        ];
+        let env = vec![
+            (String::from("ORBIT_TOP"), String::from("and_gate")),
+            (String::from("ORBIT_BENCH"), String::from("")),
+            (String::from("HOME"), String::from("/home")),
+        ];
+        let input = PluginInput::new(&man, blueprint, &env);
+        assert_eq!(input.protocol, PLUGIN_PROTOCOL_VERSION);
+        assert_eq!(input.ip.name, "gates");
+        assert_eq!(input.top, Some(String::from("and_gate")));
+        assert_eq!(input.bench, None);
+        assert_eq!(input.files.len(), 3);
+        assert_eq!(input.files[1].fileset, "VHDL-SIM");
+        assert_eq!(
+            input.filesets.get("VHDL-RTL"),
+            Some(&vec![String::from("/gates/and_gate.vhd"), String::from("/gates/or_gate.vhd")])
+        );
+        assert_eq!(input.env.contains_key("HOME"), false);
+    }
+
+    const P_3: &str = r#"
+name = "vivado"
+command = "vivado"
+options = ["--mode", "--lint"]
+require-env = ["ORBIT_ENV_VIVADO_PATH"]
+env.VIVADO_FLAGS = "-nojournal"
+"#;
+
+    #[test]
+    fn validate() {
+        let plug = Plugin::from_str(P_3).unwrap();
+        assert_eq!(
+            plug.validate_args(&[String::from("--mode=batch"), String::from("top")]),
+            Ok(())
+        );
+        assert_eq!(
+            plug.validate_args(&[String::from("--gui")]),
+            Err(PluginError::UnknownOption(String::from("vivado"), String::from("--gui")))
+        );
+        assert_eq!(
+            plug.validate_env(&Environment::new()),
+            Err(PluginError::MissingEnv(
+                String::from("vivado"),
+                String::from("ORBIT_ENV_VIVADO_PATH")
+            ))
+        );
+
+        // the plugin's own environment satisfies its requirements
+        assert_eq!(
+            plug.get_env().get("VIVADO_FLAGS").unwrap().get_value(),
+            "-nojournal"
+        );
+
+        // plugins without declared options accept any arguments
+        let plug = Plugin::from_str(P_2).unwrap();
+        assert_eq!(plug.validate_args(&[String::from("--gui")]), Ok(()));
+        assert_eq!(plug.validate_env(&Environment::new()), Ok(()));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn execute_ignores_unread_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let plug = Plugin::from_str("name = \"quit\"\ncommand = \"true\"\n")
+            .unwrap()
+            .root(dir.path().to_path_buf());
+        // the process exits without reading, so writing the input breaks the pipe
+        let input = vec![b'x'; 4 * 1024 * 1024];
+        assert_eq!(
+            plug.execute(&[], false, dir.path().to_str().unwrap(), Some(&input))
+                .is_ok(),
+            true
+        );
+    }
+
+    #[test]
+    fn invocation_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("build.env.toml");
+
+        let mut inv = Invocation::new(
+            String::from("python"),
+            vec![String::from("synth.py"), String::from("--top=\"and_gate\"")],
+            "target",
+            Some(String::from("{\"protocol\": 1}\n")),
+        );
+        inv.env = BTreeMap::from([
+            (String::from("ORBIT_TOP"), String::from("and_gate")),
+            (String::from("PATH"), String::from("/usr/bin:/bin")),
+        ]);
+        assert_eq!(inv.cwd.ends_with("target"), true);
+
+        inv.save(&file).unwrap();
+        assert_eq!(Invocation::load(&file).unwrap(), inv);
+        // only the owner can read the saved environment
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // unknown fields are rejected
+        std::fs::write(
+            &file,
+            "command = \"python\"\nargs = []\ncwd = \".\"\nuser = \"me\"\n[env]\n",
+        )
+        .unwrap();
+        assert_eq!(Invocation::load(&file).is_err(), true);
+    }
+
     #[test]
     fn series_of_plugins() {
         let contents = format!("{0}{1}\n{0}{2}", "[[plugin]]", P_1, P_2);
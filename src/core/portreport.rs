@@ -0,0 +1,126 @@
+//! Lists the ports of the top-level entity so that board-level designs can be
+//! cross-checked against the HDL.
+//!
+//! The report is written into the build directory during planning as both a
+//! [PORT_REPORT_CSV] for spreadsheets and a [PORT_REPORT_JSON] for scripts.
+
+use super::lang::vhdl::interface::InterfaceKind;
+use super::lang::vhdl::symbol::Entity;
+use crate::util::anyerror::Fault;
+use serde_derive::Serialize;
+use std::path::PathBuf;
+
+/// The file within the build directory listing the ports as comma-separated values.
+pub const PORT_REPORT_CSV: &str = "ports.csv";
+/// The file within the build directory listing the ports as JSON.
+pub const PORT_REPORT_JSON: &str = "ports.json";
+
+const CSV_HEADER: &str = "name,direction,width,type";
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PortReport {
+    entity: String,
+    ports: Vec<PortEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct PortEntry {
+    name: String,
+    /// The lowercase mode of the port, such as `in` or `out`.
+    direction: String,
+    /// The number of bits, or `None` when the type is not made of bits.
+    width: Option<String>,
+    #[serde(rename = "type")]
+    datatype: String,
+}
+
+impl PortReport {
+    /// Collects the ports of the entity `e` in their declared order.
+    pub fn from_entity(e: &Entity) -> Self {
+        Self {
+            entity: e.get_name().to_string(),
+            ports: e
+                .get_ports()
+                .iter()
+                .filter(|p| p.get_kind() == &InterfaceKind::Object)
+                .map(|p| PortEntry {
+                    name: p.get_name().to_string(),
+                    direction: p.get_mode(),
+                    width: p.get_width(),
+                    datatype: p.get_type().trim().to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Formats the ports as comma-separated values with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut text = format!("{}\n", CSV_HEADER);
+        for port in &self.ports {
+            text.push_str(&format!(
+                "{},{},{},{}\n",
+                to_csv_field(&port.name),
+                to_csv_field(&port.direction),
+                to_csv_field(port.width.as_ref().map(|w| w.as_str()).unwrap_or("")),
+                to_csv_field(&port.datatype)
+            ));
+        }
+        text
+    }
+
+    /// Writes the [PORT_REPORT_CSV] and [PORT_REPORT_JSON] files into `dir`.
+    pub fn save(&self, dir: &PathBuf) -> Result<(), Fault> {
+        std::fs::write(dir.join(PORT_REPORT_CSV), self.to_csv())?;
+        std::fs::write(
+            dir.join(PORT_REPORT_JSON),
+            serde_json::to_string_pretty(&self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Quotes the `field` when it contains a delimiter, quote, or line break.
+fn to_csv_field(field: &str) -> String {
+    match field.contains(|c| c == ',' || c == '"' || c == '\n') {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::lang::vhdl::symbol::VHDLParser;
+
+    #[test]
+    fn port_report_from_entity() {
+        let s = "\
+entity counter is
+    generic(
+        WIDTH: positive := 4
+    );
+    port(
+        clk: in std_logic;
+        count: out std_logic_vector(WIDTH-1 downto 0);
+        addr: in unsigned(0 to 11);
+        data: inout std_logic_vector(15 downto 8);
+        state: buffer t_state
+    );
+end entity counter;";
+        let symbols = VHDLParser::read(&s).into_symbols();
+        let e = symbols[0].as_entity().unwrap();
+        let report = PortReport::from_entity(e);
+        assert_eq!(
+            report.to_csv(),
+            "\
+name,direction,width,type
+clk,in,1,std_logic
+count,out,WIDTH,std_logic_vector(WIDTH-1 downto 0)
+addr,in,12,unsigned(0 to 11)
+data,inout,8,std_logic_vector(15 downto 8)
+state,buffer,,t_state
+"
+        );
+        assert_eq!(to_csv_field("a,b"), "\"a,b\"");
+    }
+}
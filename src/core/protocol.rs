@@ -65,7 +65,9 @@ impl Process for Protocol {
 
 use crate::commands::orbit::UpgradeError;
 use crate::commands::orbit::RESPONSE_OKAY;
-use crate::util::anyerror::Fault;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::core::credential;
+use crate::core::credential::HttpAuth;
 use curl::easy::Easy;
 use std::io::Write;
 use tempfile;
@@ -73,6 +75,18 @@ use zip::ZipArchive;
 
 use super::variable::VariableTable;
 
+/// The number of redirects followed before a download is abandoned.
+const MAX_REDIRECTS: usize = 10;
+
+/// Checks if the urls `a` and `b` use the same scheme and host, ignoring case.
+fn is_same_host(a: &str, b: &str) -> bool {
+    let scheme = |url: &str| url.split_once("://").map(|(s, _)| s.to_ascii_lowercase());
+    match (credential::host_of(a), credential::host_of(b)) {
+        (Some(x), Some(y)) => x.eq_ignore_ascii_case(y) && scheme(a) == scheme(b),
+        _ => false,
+    }
+}
+
 impl Protocol {
     pub fn new() -> Self {
         Self {
@@ -117,35 +131,56 @@ impl Protocol {
     /// Performs the default behavior for a protocol.
     /// 
     /// This will attempt to download the url as a zip file and extract it to
-    /// its queue directory.
-    pub fn single_download(url: &str, dst: &PathBuf) -> Result<(), Fault> {
-        let mut body_bytes = Vec::new();
-        {
+    /// its queue directory. The `auth` is applied to the request to authenticate
+    /// with the server.
+    ///
+    /// Redirects are followed one at a time so that `auth` is only sent to the
+    /// host of the original `url`.
+    pub fn single_download(url: &str, dst: &PathBuf, auth: Option<&HttpAuth>) -> Result<(), Fault> {
+        let mut next = url.to_string();
+        let mut redirects = 0;
+        let temp_file = loop {
+            // place the bytes into a file as they arrive
+            let mut temp_file = tempfile::tempfile()?;
             let mut easy = Easy::new();
-            easy.url(&url).unwrap();
-            easy.follow_location(true).unwrap();
+            easy.url(&next).unwrap();
+            if let Some(a) = auth {
+                if is_same_host(url, &next) == true {
+                    a.apply(&mut easy)?;
+                }
+            }
             {
                 let mut transfer = easy.transfer();
                 transfer
-                    .write_function(|data| {
-                        body_bytes.extend_from_slice(data);
-                        Ok(data.len())
+                    .write_function(|data| match temp_file.write_all(data) {
+                        Ok(()) => Ok(data.len()),
+                        // reporting fewer bytes than given aborts the transfer
+                        Err(_) => Ok(0),
                     })
                     .unwrap();
 
                 transfer.perform()?;
             }
             let rc = easy.response_code()?;
+            if let Some(location) = easy.redirect_url()? {
+                if redirects >= MAX_REDIRECTS {
+                    return Err(AnyError(format!(
+                        "exceeded {} redirects while downloading {}",
+                        MAX_REDIRECTS, url
+                    )))?;
+                }
+                redirects += 1;
+                next = location.to_string();
+                continue;
+            }
             if rc != RESPONSE_OKAY {
                 return Err(Box::new(UpgradeError::FailedConnection(
-                    url.to_string(),
+                    next,
                     rc,
                 )));
             }
-        }
-        // place the bytes into a file
-        let mut temp_file = tempfile::tempfile()?;
-        temp_file.write_all(&body_bytes)?;
+            break temp_file;
+        };
         let mut zip_archive = ZipArchive::new(temp_file)?;
 
         // decompress the zip file to the queue
@@ -285,4 +320,13 @@ args = ["~/scripts/download.bash"]
             }
         );
     }
+
+    #[test]
+    fn redirect_hosts() {
+        let url = "https://gitlab.example.com/api/v4/projects/1/archive.zip";
+        assert_eq!(is_same_host(url, "https://GitLab.example.com/other.zip"), true);
+        assert_eq!(is_same_host(url, "https://storage.example.net/blob.zip"), false);
+        // never send the credentials over an unencrypted redirect
+        assert_eq!(is_same_host(url, "http://gitlab.example.com/archive.zip"), false);
+    }
 }
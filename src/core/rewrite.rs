@@ -0,0 +1,128 @@
+//! A rewrite replaces the beginning of a source's url before the source is
+//! downloaded or cloned, similar to git's `url.<base>.insteadOf` setting.
+//!
+//! Rewrites are applied before the credential for a url's host disables ssh,
+//! so a host's rule applies to the url after it is rewritten.
+
+use crate::core::credential::{self, Credential};
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub type Rewrites = Vec<Rewrite>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Rewrite {
+    /// The prefix to place at the start of a matching url.
+    url: String,
+    /// The prefixes of urls to replace with `url`.
+    instead_of: Vec<String>,
+}
+
+impl FromStr for Rewrite {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl Rewrite {
+    /// Replaces the start of the `url` using the rewrite within `rewrites` that
+    /// has the longest matching prefix.
+    ///
+    /// Returns the `url` unchanged if no prefix matches.
+    pub fn apply(rewrites: &[&Rewrite], url: &str) -> String {
+        rewrites
+            .iter()
+            .flat_map(|r| r.instead_of.iter().map(move |prefix| (prefix, &r.url)))
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()) == true)
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, base)| format!("{}{}", base, &url[prefix.len()..]))
+            .unwrap_or(url.to_string())
+    }
+}
+
+/// Determines the url to access for a source's `url` after applying the
+/// `rewrites` and switching to https for hosts whose credential disables ssh.
+pub fn resolve_url(url: &str, rewrites: &[&Rewrite], creds: &[&Credential]) -> String {
+    let url = Rewrite::apply(rewrites, url);
+    match Credential::find(creds, &url) {
+        Some(cred) if cred.is_ssh_allowed() == false => to_https(&url).unwrap_or(url),
+        _ => url,
+    }
+}
+
+/// Converts an ssh `url`, written as `ssh://[user@]host[:port]/path` or as the
+/// scp-like `user@host:path`, into its https equivalent.
+///
+/// Returns `None` if the `url` does not use ssh.
+fn to_https(url: &str) -> Option<String> {
+    let host = credential::host_of(url)?;
+    let path = match url.strip_prefix("ssh://") {
+        Some(rest) => rest.split_once('/')?.1,
+        None => match url.contains("://") {
+            true => return None,
+            false => url.split_once(':')?.1,
+        },
+    };
+    Some(format!("https://{}/{}", host, path.trim_start_matches('/')))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn longest_prefix() {
+        let github = Rewrite::from_str(
+            "url = \"https://github.com/\"\ninstead-of = [\"gh:\", \"git@github.com:\"]\n",
+        )
+        .unwrap();
+        let mirror = Rewrite::from_str(
+            "url = \"https://mirror.example.com/c-rus/\"\ninstead-of = [\"git@github.com:c-rus/\"]\n",
+        )
+        .unwrap();
+        let rules = [&github, &mirror];
+        assert_eq!(
+            Rewrite::apply(&rules, "gh:hw/gates.git"),
+            "https://github.com/hw/gates.git"
+        );
+        assert_eq!(
+            Rewrite::apply(&rules, "git@github.com:c-rus/gates.git"),
+            "https://mirror.example.com/c-rus/gates.git"
+        );
+        assert_eq!(
+            Rewrite::apply(&rules, "https://gitlab.com/hw/gates.git"),
+            "https://gitlab.com/hw/gates.git"
+        );
+    }
+
+    #[test]
+    fn ssh_to_https() {
+        assert_eq!(
+            to_https("git@github.com:c-rus/gates.git"),
+            Some(String::from("https://github.com/c-rus/gates.git"))
+        );
+        assert_eq!(
+            to_https("ssh://git@github.com:22/c-rus/gates.git"),
+            Some(String::from("https://github.com/c-rus/gates.git"))
+        );
+        assert_eq!(to_https("https://github.com/c-rus/gates.git"), None);
+    }
+
+    #[test]
+    fn per_host_ssh() {
+        let github = Credential::from_str("host = \"github.com\"\nssh = false\n").unwrap();
+        let internal = Credential::from_str("host = \"git.corp.com\"\n").unwrap();
+        let creds = [&github, &internal];
+        assert_eq!(
+            resolve_url("git@github.com:c-rus/gates.git", &[], &creds),
+            "https://github.com/c-rus/gates.git"
+        );
+        assert_eq!(
+            resolve_url("git@git.corp.com:hw/gates.git", &[], &creds),
+            "git@git.corp.com:hw/gates.git"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::util::anyerror::AnyError;
 use serde_derive::Deserialize;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 /// A [Source] outlines the process and location for extracting packages from the internet.
@@ -10,6 +11,9 @@ pub struct Source {
     url: String,
     /// A `tag`is optional user-defined information that is needed to proceed with the protocol.
     tag: Option<String>,
+    /// The directory of the ip relative to the root of the url's contents, such
+    /// as when the ip lives within a larger repository.
+    path: Option<PathBuf>,
     // Valid is triggered true when built with a function other than "default".
     #[serde(skip, default = "set_true")]
     valid: bool,
@@ -36,12 +40,18 @@ impl Source {
         self
     }
 
+    pub fn path(mut self, path: Option<PathBuf>) -> Self {
+        self.path = path;
+        self
+    }
+
     pub fn new() -> Self {
         Self {
             protocol: None,
             url: String::new(),
             valid: true,
             tag: None,
+            path: None,
         }
     }
 
@@ -61,6 +71,10 @@ impl Source {
         self.tag.as_ref()
     }
 
+    pub fn get_path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
     pub fn is_default(&self) -> bool {
         self.protocol.is_none()
     }
@@ -102,6 +116,7 @@ impl Default for Source {
             url: String::new(),
             valid: false,
             tag: None,
+            path: None,
         }
     }
 }
@@ -114,6 +129,7 @@ impl FromStr for Source {
             url: s.to_string(),
             protocol: None,
             tag: None,
+            path: None,
             valid: true,
         })
     }
@@ -185,6 +201,9 @@ impl Serialize for Source {
                 if let Some(p) = self.get_tag() {
                     map.serialize_entry("tag", p)?;
                 }
+                if let Some(p) = self.get_path() {
+                    map.serialize_entry("path", p)?;
+                }
                 map.end()
             }
             false => serializer.serialize_none(),
@@ -205,6 +224,7 @@ mod test {
             Source {
                 protocol: None,
                 tag: None,
+                path: None,
                 url: String::from("https://some.url"),
                 valid: true,
             }
@@ -221,6 +241,17 @@ mod test {
         assert_eq!(src.is_valid(), true);
     }
 
+    #[test]
+    fn subdirectory_path() {
+        let src: Source =
+            toml::from_str("url = \"https://github.com/hw/cores.git\"\npath = \"ips/fifo\"").unwrap();
+        assert_eq!(src.get_path(), Some(&PathBuf::from("ips/fifo")));
+        assert_eq!(
+            toml::to_string(&src).unwrap(),
+            "url = \"https://github.com/hw/cores.git\"\npath = \"ips/fifo\"\n"
+        );
+    }
+
     const EX1: &str = r#"url = "https://some.url"
 protocol = "ktsp""#;
 }
@@ -0,0 +1,1246 @@
+//! A template is a user-defined directory of files to import when creating
+//! a new ip.
+
+use crate::core::manifest;
+use crate::core::lockfile;
+use crate::core::protocol::Protocol;
+use crate::core::variable;
+use crate::core::variable::Assignment;
+use crate::core::variable::VariableTable;
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use crate::util::date;
+use crate::util::environment;
+use crate::util::filesystem;
+use crate::util::filesystem::Standardize;
+use crate::util::prompt;
+use ignore::WalkBuilder;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub type Templates = Vec<Template>;
+
+pub const TEMPLATE_MANIFEST_FILE: &str = "Template.toml";
+
+/// The directory within `ORBIT_HOME` where remote templates are fetched.
+pub const TEMPLATE_CACHE_DIR: &str = "templates";
+
+/// The file within the [TEMPLATE_CACHE_DIR] that records when each remote
+/// template was last fetched.
+pub const TEMPLATE_INDEX_FILE: &str = "index.toml";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    name: String,
+    path: Option<PathBuf>,
+    /// The location of a zip archive holding the template's files.
+    url: Option<String>,
+    summary: Option<String>,
+    ignore: Option<Vec<String>>,
+    #[serde(skip_serializing, skip_deserializing)]
+    root: Option<PathBuf>,
+}
+
+impl Template {
+    /// Sets the root directory from where the template's path should be referenced from.
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = Some(root);
+    }
+
+    /// References the name to call this template.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// References the template's short description, if any.
+    pub fn get_summary(&self) -> Option<&String> {
+        self.summary.as_ref()
+    }
+
+    /// References the url of a remote template, if any.
+    pub fn get_url(&self) -> Option<&String> {
+        self.url.as_ref()
+    }
+
+    /// Checks if the template's files are fetched from a url.
+    pub fn is_remote(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// Returns the template's directory with any relative path resolved against
+    /// its root.
+    ///
+    /// A remote template's directory is where its files are fetched within the
+    /// [TEMPLATE_CACHE_DIR].
+    pub fn get_path(&self) -> PathBuf {
+        if self.is_remote() == true {
+            return Self::get_cache_path().join(self.get_cache_dir_name());
+        }
+        let path = self.path.clone().unwrap_or_default();
+        match &self.root {
+            Some(root) => filesystem::resolve_rel_path2(root, &path),
+            None => path,
+        }
+    }
+
+    /// Returns the name of the remote template's directory within the
+    /// [TEMPLATE_CACHE_DIR], where every character that is not alphanumeric, `-`,
+    /// or `_` is replaced with `_` so the name never escapes the cache.
+    fn get_cache_dir_name(&self) -> String {
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                true => c,
+                false => '_',
+            })
+            .collect();
+        match name.is_empty() {
+            true => String::from("_"),
+            false => name,
+        }
+    }
+
+    /// Verifies the template's files are found at exactly one of a path or a url.
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        match (&self.path, &self.url) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            _ => Err(TemplateError::BadLocation(self.name.clone())),
+        }
+    }
+
+    /// Returns the directory where remote templates are fetched.
+    fn get_cache_path() -> PathBuf {
+        PathBuf::from(std::env::var(environment::ORBIT_HOME).unwrap_or_default())
+            .join(TEMPLATE_CACHE_DIR)
+    }
+
+    /// Returns the date a remote template was last fetched from its url.
+    ///
+    /// Returns `None` if the template is not remote or was never fetched.
+    pub fn get_last_fetched(&self) -> Option<String> {
+        let index = TemplateIndex::load(&Self::get_cache_path());
+        let entry = index.0.get(&self.name)?;
+        match Some(&entry.url) == self.get_url() {
+            true => Some(entry.fetched.clone()),
+            false => None,
+        }
+    }
+
+    /// Downloads the files of a remote template from its url, replacing any
+    /// files fetched earlier.
+    ///
+    /// Does nothing for a template that is not remote.
+    pub fn refresh(&self) -> Result<(), Fault> {
+        let url = match &self.url {
+            Some(url) => url,
+            None => return Ok(()),
+        };
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path().to_path_buf();
+        Protocol::single_download(url, &dir, None)?;
+        // archives of a repository place every file within a single directory
+        let entries: Vec<PathBuf> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        let src = match entries.len() == 1 && entries[0].is_dir() == true {
+            true => entries[0].clone(),
+            false => dir,
+        };
+
+        // stage the files within the cache so they replace the earlier files by a rename
+        let cache = Self::get_cache_path();
+        std::fs::create_dir_all(&cache)?;
+        let staging = tempfile::tempdir_in(&cache)?;
+        let staged = staging.path().join("new");
+        filesystem::copy(&src, &staged, false, None)?;
+        // keep the earlier files until the new files are in place
+        let dest = self.get_path();
+        let previous = staging.path().join("old");
+        if dest.exists() == true {
+            std::fs::rename(&dest, &previous)?;
+        }
+        if let Err(e) = std::fs::rename(&staged, &dest) {
+            if previous.exists() == true {
+                std::fs::rename(&previous, &dest)?;
+            }
+            return Err(e)?;
+        }
+
+        let mut index = TemplateIndex::load(&cache);
+        index.0.insert(
+            self.name.clone(),
+            TemplateIndexEntry {
+                url: url.clone(),
+                fetched: date::today(),
+            },
+        );
+        index.save(&cache)
+    }
+
+    /// Displays a template's information in a single line for quick glance.
+    ///
+    /// A remote template also displays when it was last fetched.
+    pub fn quick_info(&self) -> String {
+        let summary = self.summary.clone().unwrap_or_default();
+        match self.is_remote() {
+            true => format!(
+                "{:<16}{}{}(remote, {})",
+                self.name,
+                summary,
+                match summary.is_empty() {
+                    true => "",
+                    false => " ",
+                },
+                match self.get_last_fetched() {
+                    Some(date) => format!("fetched {}", date),
+                    None => String::from("not fetched"),
+                }
+            ),
+            false => format!("{:<16}{}", self.name, summary),
+        }
+    }
+
+    /// Creates a string to display a list of templates.
+    ///
+    /// The string lists the templates in alphabetical order by `name`.
+    pub fn list_templates(temps: &mut [&&Template]) -> String {
+        let mut list = String::from("Templates:\n");
+        temps.sort_by(|a, b| a.name.cmp(&b.name));
+        for temp in temps {
+            list += &format!("  {}\n", temp.quick_info());
+        }
+        list
+    }
+
+    /// Reads the [TEMPLATE_MANIFEST_FILE] at the template's directory.
+    ///
+    /// Returns a blank manifest if the template does not have one.
+    pub fn load_manifest(&self) -> Result<TemplateManifest, Fault> {
+        let path = self.get_path().join(TEMPLATE_MANIFEST_FILE);
+        match path.is_file() {
+            true => TemplateManifest::from_file(&path),
+            false => Ok(TemplateManifest::new()),
+        }
+    }
+
+    /// Checks if the relative path `file` should not be copied from the template.
+    ///
+    /// Orbit always skips the template's manifest, any ip manifest, and any lockfile
+    /// at the template's root directory.
+    fn is_ignored(&self, file: &str) -> bool {
+        if file == TEMPLATE_MANIFEST_FILE
+            || file == manifest::IP_MANIFEST_FILE
+            || file == lockfile::IP_LOCK_FILE
+        {
+            return true;
+        }
+        let match_opts = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: false,
+            require_literal_leading_dot: false,
+        };
+        self.ignore
+            .as_ref()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .filter_map(|p| glob::Pattern::new(p.trim_end_matches('/')).ok())
+            .any(|p| {
+                p.matches_with(file, match_opts)
+                    || file.starts_with(&format!("{}/", p.as_str()))
+            })
+    }
+
+    /// Performs variable substitution on each component of the relative path
+    /// `rel_path`, allowing file and directory names to contain variables.
+    fn substitute_path(rel_path: &PathBuf, vtable: &VariableTable) -> Result<PathBuf, TemplateError> {
+        rel_path
+            .components()
+            .map(|c| substitute(c.as_os_str().to_string_lossy().to_string(), vtable))
+            .collect()
+    }
+
+    /// Walks the template's directory to find every file that is not ignored.
+    ///
+    /// The returned paths are relative to the template's directory.
+    fn walk(&self) -> Result<Vec<PathBuf>, Fault> {
+        let src = self.get_path();
+        if src.is_dir() == false && self.is_remote() == true {
+            return Err(TemplateError::NotFetched(self.name.clone()))?;
+        }
+        if src.is_dir() == false {
+            return Err(TemplateError::MissingDirectory(
+                self.name.clone(),
+                PathBuf::standardize(src),
+            ))?;
+        }
+        let mut files = Vec::new();
+        for result in WalkBuilder::new(&src)
+            .hidden(false)
+            .git_ignore(false)
+            .filter_entry(|p| p.file_name() != ".git")
+            .build()
+        {
+            let entry = match result {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            if entry.path().is_file() == false {
+                continue;
+            }
+            let rel_path = filesystem::remove_base(&src, &entry.path().to_path_buf());
+            if self.is_ignored(&filesystem::into_std_str(rel_path.clone())) == true {
+                continue;
+            }
+            files.push(rel_path);
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Collects the files to import from the template as pairs of the source
+    /// path and the variable-substituted path relative to the destination.
+    ///
+    /// The pairs are sorted by their destination path.
+    pub fn collect_files(&self, vtable: &VariableTable) -> Result<Vec<(PathBuf, PathBuf)>, Fault> {
+        let src = self.get_path();
+        let manifest = self.load_manifest()?;
+        let mut files = Vec::new();
+        for rel_path in self.walk()? {
+            if manifest.is_excluded(&filesystem::into_std_str(rel_path.clone()), vtable)? == true {
+                continue;
+            }
+            files.push((src.join(&rel_path), Self::substitute_path(&rel_path, vtable)?));
+        }
+        files.sort_by(|a, b| a.1.cmp(&b.1));
+        Ok(files)
+    }
+
+    /// Creates a string to display a list of the files available within the template.
+    pub fn list_files(&self) -> Result<String, Fault> {
+        let mut list = String::from("Files:\n");
+        for rel_path in self.walk()? {
+            list += &format!("  {}\n", filesystem::into_std_str(rel_path));
+        }
+        Ok(list)
+    }
+
+    /// Returns the full path to the file at the relative path `file` within the template.
+    ///
+    /// Errors if the file does not exist or is ignored by the template.
+    pub fn get_file(&self, file: &PathBuf) -> Result<PathBuf, TemplateError> {
+        let rel_path = PathBuf::from(
+            filesystem::into_std_str(file.clone()).trim_start_matches("./"),
+        );
+        let path = self.get_path().join(&rel_path);
+        match path.is_file() == false || self.is_ignored(&filesystem::into_std_str(rel_path.clone())) == true {
+            true => Err(TemplateError::MissingFile(self.name.clone(), rel_path)),
+            false => Ok(path),
+        }
+    }
+
+    /// Copies the template's files into the `dest` directory and performs
+    /// variable substitution on each file's name and contents using `vtable`.
+    ///
+    /// Assumes `dest` is an existing directory.
+    ///
+    /// Binary files and files matching the manifest's `copy-only` patterns are
+    /// copied verbatim.
+    pub fn import(&self, dest: &PathBuf, vtable: &VariableTable) -> Result<(), Fault> {
+        let src = self.get_path();
+        let manifest = self.load_manifest()?;
+        for (from, rel_path) in self.collect_files(vtable)? {
+            let to = dest.join(&rel_path);
+            if let Some(parent) = to.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&from, &to)?;
+            let rel_src = filesystem::into_std_str(filesystem::remove_base(&src, &from));
+            if manifest.is_copy_only(&rel_src) == false {
+                TemplateFile::new(&to).substitute(vtable)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a string to display the files that would be imported and their
+    /// variable-substituted contents without writing anything.
+    pub fn preview(&self, vtable: &VariableTable) -> Result<String, Fault> {
+        let src = self.get_path();
+        let manifest = self.load_manifest()?;
+        let files = self.collect_files(vtable)?;
+        let mut result = String::from("Files:\n");
+        for (_, rel_path) in &files {
+            result += &format!("  {}\n", filesystem::into_std_str(rel_path.clone()));
+        }
+        for (from, rel_path) in &files {
+            result += &format!("\n--- {} ---\n", filesystem::into_std_str(rel_path.clone()));
+            let rel_src = filesystem::into_std_str(filesystem::remove_base(&src, &from));
+            if manifest.is_copy_only(&rel_src) == true {
+                result += "(copied verbatim)\n";
+                continue;
+            }
+            match read_text(&from) {
+                Some(contents) => match substitute(contents, vtable) {
+                    Ok(text) => result += &text,
+                    Err(e) => Err(AnyError(format!(
+                        "failed to import \"{}\": {}",
+                        PathBuf::standardize(from).display(),
+                        e
+                    )))?,
+                },
+                None => result += "(binary file)\n",
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl FromStr for Template {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\
+Name:    {}
+Path:    {}{}
+Ignore:  {}{}",
+            self.name,
+            PathBuf::standardize(self.get_path()).display(),
+            match &self.url {
+                Some(url) => format!(
+                    "\nUrl:     {}\nFetched: {}",
+                    url,
+                    self.get_last_fetched()
+                        .unwrap_or(String::from("never; try `orbit new --list --refresh`"))
+                ),
+                None => String::new(),
+            },
+            self.ignore
+                .as_ref()
+                .unwrap_or(&Vec::new())
+                .iter()
+                .fold(String::new(), |x, y| { x + "\"" + &y + "\" " }),
+            {
+                if let Some(text) = &self.summary {
+                    format!("\n\n{}\n", text)
+                } else {
+                    String::new()
+                }
+            },
+        )
+    }
+}
+
+/// The record of when each remote template was last fetched, stored as the
+/// [TEMPLATE_INDEX_FILE].
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+struct TemplateIndex(HashMap<String, TemplateIndexEntry>);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateIndexEntry {
+    url: String,
+    /// The date in the form `YYYY-MM-DD`.
+    fetched: String,
+}
+
+impl TemplateIndex {
+    /// Reads the index within the template cache at `dir`.
+    ///
+    /// Returns an empty index if the file is missing or cannot be read.
+    fn load(dir: &PathBuf) -> Self {
+        std::fs::read_to_string(dir.join(TEMPLATE_INDEX_FILE))
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the index within the template cache at `dir`.
+    fn save(&self, dir: &PathBuf) -> Result<(), Fault> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(TEMPLATE_INDEX_FILE), toml::to_string(&self)?)?;
+        Ok(())
+    }
+}
+
+/// The description of the variables a template expects to be defined before
+/// it is imported.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateManifest {
+    #[serde(rename = "variable", default)]
+    variables: Vec<TemplateVariable>,
+    #[serde(rename = "file", default)]
+    files: Vec<TemplateFileRule>,
+    /// Glob-style patterns of files to copy without variable substitution.
+    #[serde(rename = "copy-only", default)]
+    copy_only: Vec<String>,
+}
+
+/// A condition that must hold for the files matching `path` to be imported.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateFileRule {
+    path: String,
+    #[serde(rename = "if")]
+    condition: String,
+}
+
+impl TemplateFileRule {
+    /// Checks if the relative path `file` falls under this rule's glob pattern.
+    fn matches(&self, file: &str) -> bool {
+        filesystem::matches_path(&self.path, file)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateVariable {
+    name: String,
+    summary: Option<String>,
+    default: Option<String>,
+}
+
+impl TemplateVariable {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_default(&self) -> Option<&String> {
+        self.default.as_ref()
+    }
+
+    /// A variable is required when it does not supply a default value.
+    pub fn is_required(&self) -> bool {
+        self.default.is_none()
+    }
+}
+
+impl TemplateManifest {
+    pub fn new() -> Self {
+        Self {
+            variables: Vec::new(),
+            files: Vec::new(),
+            copy_only: Vec::new(),
+        }
+    }
+
+    /// Checks if the relative path `file` should be skipped because a file rule's
+    /// condition does not hold under `vtable`.
+    pub fn is_excluded(&self, file: &str, vtable: &VariableTable) -> Result<bool, TemplateError> {
+        for rule in self.files.iter().filter(|r| r.matches(file)) {
+            if Condition::from_str(&rule.condition)?.eval(vtable) == false {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Checks if the relative path `file` should be copied without variable
+    /// substitution.
+    pub fn is_copy_only(&self, file: &str) -> bool {
+        self.copy_only.iter().any(|p| filesystem::matches_path(p, file))
+    }
+
+    pub fn get_variables(&self) -> &Vec<TemplateVariable> {
+        &self.variables
+    }
+
+    /// Reads the template manifest file at `path`.
+    pub fn from_file(path: &PathBuf) -> Result<Self, Fault> {
+        let contents = std::fs::read_to_string(&path)?;
+        match Self::from_str(&contents) {
+            Ok(r) => Ok(r),
+            Err(e) => Err(AnyError(format!(
+                "failed to parse \"{}\" file: {}",
+                PathBuf::standardize(path).display(),
+                e
+            )))?,
+        }
+    }
+
+    /// Fills in `vtable` with a value for every declared variable.
+    ///
+    /// Values are taken from `assignments` first. When `interactive` is `true`,
+    /// the user is prompted for any variable not assigned, otherwise the
+    /// variable's default is used. Errors if a required variable is left without
+    /// a value.
+    pub fn resolve(
+        &self,
+        vtable: &mut VariableTable,
+        assignments: &[Assignment],
+        interactive: bool,
+    ) -> Result<(), Fault> {
+        let given: HashMap<&str, &str> = assignments
+            .iter()
+            .map(|a| (a.get_key(), a.get_value()))
+            .collect();
+        for var in &self.variables {
+            let value = match given.get(var.get_name()) {
+                Some(v) => Some(v.to_string()),
+                None => match interactive {
+                    true => {
+                        let text = match &var.summary {
+                            Some(s) => format!("{} ({})", var.get_name(), s),
+                            None => var.get_name().to_string(),
+                        };
+                        let answer = prompt::input(&text, var.get_default().map(|d| d.as_str()))?;
+                        match answer.is_empty() {
+                            true => None,
+                            false => Some(answer),
+                        }
+                    }
+                    false => var.get_default().cloned(),
+                },
+            };
+            match value {
+                Some(v) => {
+                    vtable.add(var.get_name(), &v);
+                }
+                None => return Err(TemplateError::MissingVariable(var.get_name().to_string()))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TemplateManifest {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+/// A file copied from a template that may contain variables.
+pub struct TemplateFile<'a>(&'a PathBuf);
+
+impl<'a> TemplateFile<'a> {
+    pub fn new(path: &'a PathBuf) -> Self {
+        Self(path)
+    }
+
+    /// Evaluates conditional blocks and performs variable substitution on the
+    /// file's contents and rewrites the file.
+    ///
+    /// Binary files and files that cannot be read as text are silently left
+    /// unmodified.
+    pub fn substitute(&self, vtable: &VariableTable) -> Result<(), Fault> {
+        let contents = match read_text(&self.0) {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        let transform = match substitute(contents, vtable) {
+            Ok(t) => t,
+            Err(e) => Err(AnyError(format!(
+                "failed to import \"{}\": {}",
+                PathBuf::standardize(self.0).display(),
+                e
+            )))?,
+        };
+        std::fs::write(&self.0, transform)?;
+        Ok(())
+    }
+}
+
+/// The number of leading bytes inspected to detect a binary file.
+const BINARY_PROBE_SIZE: usize = 8000;
+
+/// Checks if the `bytes` belong to a binary file, which is detected by a NUL
+/// character appearing within the leading bytes.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_PROBE_SIZE).any(|b| *b == 0)
+}
+
+/// Reads the file at `path` as text.
+///
+/// Returns `None` if the file cannot be read, is binary, or is not valid UTF-8.
+pub fn read_text(path: &PathBuf) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if is_binary(&bytes) == true {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+const L_BLOCK_OPEN: &str = "{{#";
+const L_BLOCK_CLOSE: &str = "{{/";
+const R_BLOCK: &str = "}}";
+
+/// A boolean expression for a conditional block or file rule.
+#[derive(Debug, PartialEq)]
+enum Condition {
+    Defined(String),
+    Eq(String, String),
+    NotEq(String, String),
+}
+
+impl Condition {
+    /// Checks if the condition holds for the variables in `vtable`.
+    ///
+    /// A lone variable is true when it is defined with a value other than an
+    /// empty string or "false".
+    fn eval(&self, vtable: &VariableTable) -> bool {
+        match self {
+            Self::Defined(key) => match vtable.get(key) {
+                Some(v) => v.is_empty() == false && v != "false",
+                None => false,
+            },
+            Self::Eq(key, value) => vtable.get(key) == Some(value),
+            Self::NotEq(key, value) => vtable.get(key) != Some(value),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // remove surrounding quotes from a literal value
+        let unquote = |v: &str| -> String {
+            let v = v.trim();
+            match v.len() >= 2 && v.starts_with('"') && v.ends_with('"') {
+                true => v[1..v.len() - 1].to_string(),
+                false => v.to_string(),
+            }
+        };
+        let (key, cond) = if let Some((k, v)) = s.split_once("!=") {
+            (k.trim(), Self::NotEq(k.trim().to_string(), unquote(v)))
+        } else if let Some((k, v)) = s.split_once("==") {
+            (k.trim(), Self::Eq(k.trim().to_string(), unquote(v)))
+        } else {
+            (s.trim(), Self::Defined(s.trim().to_string()))
+        };
+        match key.is_empty() || key.contains(char::is_whitespace) {
+            true => Err(TemplateError::InvalidCondition(s.trim().to_string())),
+            false => Ok(cond),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    Text(String),
+    If(Condition),
+    Else,
+    EndIf,
+}
+
+/// Splits `text` into text and conditional block tags.
+///
+/// A tag on a line of its own is removed along with its line so conditional
+/// blocks do not leave behind blank lines.
+fn tokenize(text: &str) -> Result<Vec<Block>, TemplateError> {
+    let mut blocks = Vec::new();
+    let mut buf = String::new();
+    let mut at_line_start = true;
+    let mut rest = text;
+    loop {
+        let start = match (rest.find(L_BLOCK_OPEN), rest.find(L_BLOCK_CLOSE)) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) => a,
+            (None, Some(b)) => b,
+            (None, None) => break,
+        };
+        let end = match rest[start..].find(R_BLOCK) {
+            Some(e) => start + e + R_BLOCK.len(),
+            None => break,
+        };
+        let inner = rest[start + L_BLOCK_OPEN.len()..end - R_BLOCK.len()].trim();
+        let tag = match rest[start..].starts_with(L_BLOCK_OPEN) {
+            true => match inner.split_once(char::is_whitespace) {
+                Some(("if", cond)) => Some(Block::If(Condition::from_str(cond)?)),
+                _ => match inner == "else" {
+                    true => Some(Block::Else),
+                    false => None,
+                },
+            },
+            false => match inner == "if" {
+                true => Some(Block::EndIf),
+                false => None,
+            },
+        };
+        let raw = &rest[start..end];
+        buf.push_str(&rest[..start]);
+        rest = &rest[end..];
+        let tag = match tag {
+            Some(t) => t,
+            // leave unrecognized tags as-is
+            None => {
+                buf.push_str(raw);
+                continue;
+            }
+        };
+        // check if the tag is the only thing on its line
+        let line_start = match buf.rfind('\n') {
+            Some(i) => Some(i + 1),
+            None => match at_line_start {
+                true => Some(0),
+                false => None,
+            },
+        };
+        let line_end = rest.find('\n').map(|i| i + 1).unwrap_or(rest.len());
+        let standalone = line_start.is_some()
+            && buf[line_start.unwrap()..].trim().is_empty()
+            && rest[..line_end].trim().is_empty();
+        if standalone == true {
+            buf.truncate(line_start.unwrap());
+            rest = &rest[line_end..];
+        }
+        at_line_start = standalone;
+        if buf.is_empty() == false {
+            blocks.push(Block::Text(std::mem::take(&mut buf)));
+        }
+        blocks.push(tag);
+    }
+    buf.push_str(rest);
+    if buf.is_empty() == false {
+        blocks.push(Block::Text(buf));
+    }
+    Ok(blocks)
+}
+
+/// Splits `s` into its lowercase words, separated by '_', '-', '.', whitespace,
+/// or a change from lowercase to uppercase.
+fn split_words(s: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in s.chars() {
+        if c == '_' || c == '-' || c == '.' || c.is_whitespace() {
+            if word.is_empty() == false {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() == true && prev_lower == true {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    if word.is_empty() == false {
+        words.push(word);
+    }
+    words
+}
+
+/// Capitalizes the first character of `word`.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Transforms `value` according to the filter called `name`.
+fn apply_filter(value: &str, name: &str) -> Result<String, TemplateError> {
+    Ok(match name {
+        "upper" => value.to_uppercase(),
+        "lower" => value.to_lowercase(),
+        "snake" => split_words(value).join("_"),
+        "kebab" => split_words(value).join("-"),
+        "camel" => split_words(value)
+            .iter()
+            .enumerate()
+            .map(|(i, w)| match i {
+                0 => w.clone(),
+                _ => capitalize(w),
+            })
+            .collect(),
+        "pascal" => split_words(value).iter().map(|w| capitalize(w)).collect(),
+        _ => return Err(TemplateError::UnknownFilter(name.to_string())),
+    })
+}
+
+/// Replaces variables followed by filters, such as `{{ orbit.name | upper }}`,
+/// with their transformed values.
+///
+/// Variables that are not defined in `vtable` are left as-is.
+fn substitute_filters(text: &str, vtable: &VariableTable) -> Result<String, TemplateError> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(e) => start + e + 2,
+            None => break,
+        };
+        result.push_str(&rest[..start]);
+        let raw = &rest[start..end];
+        rest = &rest[end..];
+        let mut parts = raw[2..raw.len() - 2].split('|').map(|p| p.trim());
+        let key = parts.next().unwrap_or_default();
+        match raw.contains('|') {
+            true => match vtable.get(key) {
+                Some(value) => {
+                    let mut value = value.clone();
+                    for filter in parts {
+                        value = apply_filter(&value, filter)?;
+                    }
+                    result.push_str(&value);
+                }
+                None => result.push_str(raw),
+            },
+            false => result.push_str(raw),
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Evaluates the conditional blocks in `text` and then performs variable
+/// substitution on the remaining text.
+///
+/// Conditional blocks follow the syntax `{{# if <condition> }} ... {{# else }} ... {{/ if }}`,
+/// where the `else` branch is optional and blocks may be nested. A condition is
+/// either a variable name or a comparison of a variable to a value with `==` or `!=`.
+///
+/// A variable can be followed by filters separated with '|' to transform its value,
+/// such as `{{ orbit.name | upper }}`. The supported filters are `upper`, `lower`,
+/// `snake`, `kebab`, `camel`, and `pascal`.
+pub fn substitute(text: String, vtable: &VariableTable) -> Result<String, TemplateError> {
+    // track (parent is active, condition, in else branch) for each open block
+    let mut stack: Vec<(bool, bool, bool)> = Vec::new();
+    let is_active = |stack: &Vec<(bool, bool, bool)>| match stack.last() {
+        Some((parent, cond, in_else)) => *parent && (*cond != *in_else),
+        None => true,
+    };
+    let mut result = String::new();
+    for block in tokenize(&text)? {
+        match block {
+            Block::Text(t) => {
+                if is_active(&stack) == true {
+                    result.push_str(&t);
+                }
+            }
+            Block::If(c) => {
+                let parent = is_active(&stack);
+                stack.push((parent, c.eval(vtable), false));
+            }
+            Block::Else => match stack.last_mut() {
+                Some(frame) if frame.2 == false => frame.2 = true,
+                _ => return Err(TemplateError::UnbalancedBlock(String::from("{{# else }}"))),
+            },
+            Block::EndIf => {
+                if stack.pop().is_none() {
+                    return Err(TemplateError::UnbalancedBlock(String::from("{{/ if }}")));
+                }
+            }
+        }
+    }
+    if stack.is_empty() == false {
+        return Err(TemplateError::UnbalancedBlock(String::from("{{# if }}")));
+    }
+    Ok(variable::substitute(substitute_filters(&result, vtable)?, vtable))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    Missing(String),
+    NotFetched(String),
+    BadLocation(String),
+    MissingDirectory(String, PathBuf),
+    MissingVariable(String),
+    MissingFile(String, PathBuf),
+    InvalidCondition(String),
+    UnbalancedBlock(String),
+    UnknownFilter(String),
+}
+
+impl Error for TemplateError {}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => write!(
+                f,
+                "No template named '{}'\n\nTry `orbit new --list` to see available templates",
+                name
+            ),
+            Self::NotFetched(name) => write!(
+                f,
+                "Template '{}' has not been fetched from its url\n\nTry `orbit new --list --refresh` to fetch remote templates",
+                name
+            ),
+            Self::BadLocation(name) => write!(
+                f,
+                "Template '{}' must define exactly one of a 'path' or a 'url'",
+                name
+            ),
+            Self::MissingDirectory(name, path) => write!(
+                f,
+                "Template '{}' has no directory at \"{}\"",
+                name,
+                path.display()
+            ),
+            Self::MissingVariable(key) => write!(
+                f,
+                "Template requires a value for variable '{}'\n\nTry `--var {0}=<value>` to set the variable",
+                key
+            ),
+            Self::MissingFile(name, file) => write!(
+                f,
+                "No file '{1}' in template '{0}'\n\nTry `orbit new --file --template {0} --list` to see available files",
+                name,
+                file.display()
+            ),
+            Self::InvalidCondition(cond) => write!(f, "Invalid condition '{}'", cond),
+            Self::UnbalancedBlock(tag) => write!(f, "Unbalanced conditional block at '{}'", tag),
+            Self::UnknownFilter(name) => write!(
+                f,
+                "Unknown filter '{}'\n\nSupported filters are: upper, lower, snake, kebab, camel, pascal",
+                name
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const T_1: &str = r#"
+name = "base"
+path = "./templates/base"
+summary = "standard project structure with rtl and sim folders"
+ignore = ["extra/"]
+"#;
+
+    const M_1: &str = r#"
+[[variable]]
+name = "orbit.author"
+summary = "the name of the project's author"
+
+[[variable]]
+name = "orbit.bus"
+default = "axi"
+"#;
+
+    const M_2: &str = r#"
+[[file]]
+path = "sim/"
+if = "orbit.bench"
+
+[[file]]
+path = "rtl/wishbone.vhd"
+if = 'orbit.bus == "wishbone"'
+"#;
+
+    #[test]
+    fn from_toml_string() {
+        let temp = Template::from_str(T_1).unwrap();
+        assert_eq!(
+            temp,
+            Template {
+                name: String::from("base"),
+                path: Some(PathBuf::from("./templates/base")),
+                url: None,
+                summary: Some(String::from(
+                    "standard project structure with rtl and sim folders"
+                )),
+                ignore: Some(vec![String::from("extra/")]),
+                root: None,
+            }
+        );
+    }
+
+    #[test]
+    fn remote_template() {
+        let temp = Template::from_str(
+            r#"
+name = "soc"
+url = "https://example.com/templates/soc.zip"
+summary = "system-on-chip starter"
+"#,
+        )
+        .unwrap();
+        assert_eq!(temp.is_remote(), true);
+        assert_eq!(
+            temp.get_url(),
+            Some(&String::from("https://example.com/templates/soc.zip"))
+        );
+        assert_eq!(temp.get_path().ends_with("templates/soc"), true);
+        assert_eq!(temp.validate(), Ok(()));
+    }
+
+    #[test]
+    fn template_location() {
+        let both = Template::from_str(
+            r#"
+name = "soc"
+path = "./templates/soc"
+url = "https://example.com/templates/soc.zip"
+"#,
+        )
+        .unwrap();
+        assert_eq!(both.validate(), Err(TemplateError::BadLocation(String::from("soc"))));
+        let neither = Template::from_str("name = \"soc\"").unwrap();
+        assert_eq!(neither.validate().is_err(), true);
+        assert_eq!(Template::from_str(T_1).unwrap().validate(), Ok(()));
+    }
+
+    #[test]
+    fn cache_dir_name() {
+        let temp = Template::from_str(
+            r#"
+name = "../../escape"
+url = "https://example.com/templates/soc.zip"
+"#,
+        )
+        .unwrap();
+        assert_eq!(temp.get_cache_dir_name(), "______escape");
+        assert_eq!(temp.get_path().ends_with("templates/______escape"), true);
+    }
+
+    #[test]
+    fn ignore_files() {
+        let temp = Template::from_str(T_1).unwrap();
+        assert_eq!(temp.is_ignored("Orbit.toml"), true);
+        assert_eq!(temp.is_ignored("Template.toml"), true);
+        assert_eq!(temp.is_ignored("extra/notes.txt"), true);
+        assert_eq!(temp.is_ignored("rtl/top.vhd"), false);
+    }
+
+    #[test]
+    fn substitute_file_names() {
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.name", "fifo");
+        assert_eq!(
+            Template::substitute_path(&PathBuf::from("rtl/{{ orbit.name }}_top.vhd"), &vtable),
+            Ok(PathBuf::from("rtl/fifo_top.vhd"))
+        );
+        assert_eq!(
+            Template::substitute_path(&PathBuf::from("{{orbit.name|upper}}/{{ orbit.unknown }}.vhd"), &vtable),
+            Ok(PathBuf::from("FIFO/{{ orbit.unknown }}.vhd"))
+        );
+    }
+
+    #[test]
+    fn conditional_blocks() {
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.name", "fifo");
+        vtable.add("orbit.vcs", "git");
+        vtable.add("orbit.bench", "false");
+
+        let text = "\
+{{# if orbit.vcs == \"git\" }}
+uses git
+{{# else }}
+no vcs
+{{/ if }}
+name: {{ orbit.name }}
+";
+        assert_eq!(substitute(text.to_string(), &vtable).unwrap(), "uses git\nname: fifo\n");
+
+        // inline and nested blocks
+        let text = "a{{# if orbit.bench }}b{{# else }}c{{# if orbit.vcs != \"svn\" }}d{{/ if }}{{/ if }}e";
+        assert_eq!(substitute(text.to_string(), &vtable).unwrap(), "acde");
+
+        // unrecognized tags are left as-is
+        let text = "{{# unknown }}";
+        assert_eq!(substitute(text.to_string(), &vtable).unwrap(), "{{# unknown }}");
+
+        // unbalanced blocks
+        assert_eq!(substitute("{{# if orbit.vcs }}".to_string(), &vtable).is_err(), true);
+        assert_eq!(substitute("{{/ if }}".to_string(), &vtable).is_err(), true);
+    }
+
+    #[test]
+    fn variable_filters() {
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.name", "fifo-buffer");
+        vtable.add("orbit.author", "Kepler");
+
+        let text = "{{ orbit.name | upper }} {{orbit.name|snake}} {{ orbit.name | camel }} {{ orbit.name | pascal | upper }}";
+        assert_eq!(
+            substitute(text.to_string(), &vtable).unwrap(),
+            "FIFO-BUFFER fifo_buffer fifoBuffer FIFOBUFFER"
+        );
+        // unknown variables are left as-is
+        let text = "{{ orbit.unknown | upper }} {{ orbit.author }}";
+        assert_eq!(
+            substitute(text.to_string(), &vtable).unwrap(),
+            "{{ orbit.unknown | upper }} Kepler"
+        );
+        // unknown filters are an error
+        assert_eq!(
+            substitute("{{ orbit.name | reverse }}".to_string(), &vtable),
+            Err(TemplateError::UnknownFilter(String::from("reverse")))
+        );
+
+        assert_eq!(split_words("FifoBuffer"), vec!["fifo", "buffer"]);
+        assert_eq!(split_words("axi_lite.bus"), vec!["axi", "lite", "bus"]);
+        assert_eq!(apply_filter("fifoBuffer", "kebab").unwrap(), "fifo-buffer");
+    }
+
+    #[test]
+    fn conditional_files() {
+        let man = TemplateManifest::from_str(M_2).unwrap();
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.bench", "true");
+        vtable.add("orbit.bus", "axi");
+        assert_eq!(man.is_excluded("sim/fifo_tb.vhd", &vtable), Ok(false));
+        assert_eq!(man.is_excluded("rtl/wishbone.vhd", &vtable), Ok(true));
+        assert_eq!(man.is_excluded("rtl/top.vhd", &vtable), Ok(false));
+
+        vtable.add("orbit.bench", "false");
+        assert_eq!(man.is_excluded("sim/fifo_tb.vhd", &vtable), Ok(true));
+    }
+
+    #[test]
+    fn copy_only_files() {
+        let man = TemplateManifest::from_str(
+            r#"
+copy-only = ["*.xci", "assets/"]
+
+[[variable]]
+name = "orbit.author"
+"#,
+        )
+        .unwrap();
+        assert_eq!(man.is_copy_only("ip/clk_wiz.xci"), true);
+        assert_eq!(man.is_copy_only("assets/logo.bmp"), true);
+        assert_eq!(man.is_copy_only("rtl/top.vhd"), false);
+        assert_eq!(TemplateManifest::new().is_copy_only("ip/clk_wiz.xci"), false);
+    }
+
+    #[test]
+    fn detect_binary() {
+        assert_eq!(is_binary(b"entity top is\nend entity;\n"), false);
+        assert_eq!(is_binary(&[0x42, 0x4d, 0x00, 0x01]), true);
+        assert_eq!(is_binary(b""), false);
+    }
+
+    #[test]
+    fn resolve_variables() {
+        let man = TemplateManifest::from_str(M_1).unwrap();
+        assert_eq!(man.get_variables().len(), 2);
+        assert_eq!(man.get_variables()[0].is_required(), true);
+        assert_eq!(man.get_variables()[1].is_required(), false);
+
+        // required variable is missing
+        let mut vtable = VariableTable::new();
+        assert_eq!(man.resolve(&mut vtable, &[], false).is_err(), true);
+
+        // required variable is assigned and default is used for the other
+        let mut vtable = VariableTable::new();
+        let vars = vec![Assignment::from_str("orbit.author=kepler").unwrap()];
+        man.resolve(&mut vtable, &vars, false).unwrap();
+        assert_eq!(vtable.get("orbit.author"), Some(&String::from("kepler")));
+        assert_eq!(vtable.get("orbit.bus"), Some(&String::from("axi")));
+    }
+}
@@ -1,5 +1,8 @@
+use crate::core::config::Config;
+use crate::util::anyerror::AnyError;
 use crate::util::{anyerror::Fault, environment::Environment};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 pub struct VariableTable(HashMap<String, String>);
 
@@ -16,6 +19,14 @@ impl VariableTable {
         Ok(self)
     }
 
+    /// Adds the user-defined variables from the `[variables]` table of the `config`.
+    pub fn load_config(mut self, config: &Config) -> Result<Self, Fault> {
+        for (key, value) in config.get_variables() {
+            self.0.insert(key, value);
+        }
+        Ok(self)
+    }
+
     pub fn add(&mut self, key: &str, value: &str) -> Option<String> {
         self.0.insert(key.to_string(), value.to_string())
     }
@@ -25,6 +36,35 @@ impl VariableTable {
     }
 }
 
+/// A user-defined `key=value` pair to enter into a [VariableTable].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Assignment(String, String);
+
+impl Assignment {
+    pub fn get_key(&self) -> &str {
+        &self.0
+    }
+
+    pub fn get_value(&self) -> &str {
+        &self.1
+    }
+}
+
+impl FromStr for Assignment {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // split on first '=' sign
+        match s.split_once('=') {
+            Some((k, v)) => match k.trim().is_empty() {
+                true => Err(AnyError(format!("missing variable name"))),
+                false => Ok(Self(k.trim().to_owned(), v.to_owned())),
+            },
+            None => Err(AnyError(format!("missing '=' separator"))),
+        }
+    }
+}
+
 const L_VAR_DELIMITER: char = '{';
 const R_VAR_DELIMITER: char = '}';
 
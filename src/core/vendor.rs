@@ -0,0 +1,367 @@
+//! Republishes external ip into an internal vendor index.
+//!
+//! A vendor index is a directory laid out like the downloads directory, so
+//! setting ORBIT_DOWNLOADS to its path lets a machine install ip only from the
+//! archives approved into it. Every archive is recorded in the
+//! [VENDOR_INDEX_FILE] along with where it originally came from.
+//!
+//! A vendor index published at a url is fetched into the [VENDOR_CACHE_DIR] and
+//! only fetched again once its copy is older than the configured time-to-live.
+
+use super::catalog::DownloadSlot;
+use super::credential::HttpAuth;
+use super::ip::Ip;
+use super::iparchive::IpArchive;
+use super::protocol::Protocol;
+use super::source::Source;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::date;
+use crate::util::filelock::FileLock;
+use crate::util::filesystem;
+use serde_derive::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The file within a vendor index recording the provenance of each archive.
+pub const VENDOR_INDEX_FILE: &str = "index.toml";
+
+/// The directory within ORBIT_HOME holding the fetched copy of a remote vendor
+/// index.
+pub const VENDOR_CACHE_DIR: &str = "vendor";
+
+/// The file within the [VENDOR_CACHE_DIR] that records when the vendor index
+/// was last fetched.
+const VENDOR_FETCH_FILE: &str = "fetched.toml";
+
+/// The number of hours a fetched vendor index is used before it is fetched
+/// again, when `vendor-index-ttl` is not set.
+pub const DEFAULT_VENDOR_INDEX_TTL: u64 = 24;
+
+/// The record of every archive published into a vendor index, stored as the
+/// [VENDOR_INDEX_FILE].
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct VendorIndex {
+    #[serde(rename = "ip", default)]
+    entries: Vec<Provenance>,
+}
+
+/// Where a mirrored archive originally came from.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Provenance {
+    name: String,
+    version: String,
+    uuid: String,
+    /// The file name of the archive within the vendor index.
+    archive: String,
+    checksum: Option<String>,
+    /// The url the ip was originally fetched from.
+    source: Option<String>,
+    /// The date in the form `YYYY-MM-DD`.
+    mirrored: String,
+}
+
+impl Provenance {
+    pub fn get_archive(&self) -> &str {
+        &self.archive
+    }
+
+    pub fn get_source(&self) -> Option<&String> {
+        self.source.as_ref()
+    }
+
+    /// Checks if the record describes the same installation as `other`.
+    fn is_same_ip(&self, other: &Provenance) -> bool {
+        self.name == other.name && self.version == other.version && self.uuid == other.uuid
+    }
+}
+
+impl VendorIndex {
+    /// Reads the index of the vendor directory `dir`.
+    ///
+    /// Returns an empty index if the file is missing. An index that cannot be
+    /// parsed is an error so its records are never overwritten.
+    pub fn load(dir: &PathBuf) -> Result<Self, Fault> {
+        let path = dir.join(VENDOR_INDEX_FILE);
+        if path.exists() == false {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)?;
+        match toml::from_str(&text) {
+            Ok(index) => Ok(index),
+            Err(e) => Err(AnyError(format!(
+                "failed to parse vendor index \"{}\": {}",
+                path.display(),
+                e
+            )))?,
+        }
+    }
+
+    /// Writes the index of the vendor directory `dir`.
+    pub fn save(&self, dir: &PathBuf) -> Result<(), Fault> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(VENDOR_INDEX_FILE), toml::to_string(&self)?)?;
+        Ok(())
+    }
+
+    pub fn get_entries(&self) -> &Vec<Provenance> {
+        &self.entries
+    }
+
+    /// References the record of the archive for the ip `name` at `version`.
+    pub fn find(&self, name: &str, version: &str) -> Option<&Provenance> {
+        self.entries
+            .iter()
+            .find(|e| e.name == name && e.version == version)
+    }
+
+    /// Records the `entry`, replacing any earlier record of the same ip.
+    fn insert(&mut self, entry: Provenance) {
+        match self.entries.iter_mut().find(|e| e.is_same_ip(&entry)) {
+            Some(e) => *e = entry,
+            None => self.entries.push(entry),
+        }
+        self.entries.sort_by(|a, b| a.archive.cmp(&b.archive));
+    }
+}
+
+/// The local copy of a vendor index fetched from a url.
+#[derive(Debug, PartialEq)]
+pub struct VendorCache {
+    root: PathBuf,
+}
+
+/// The record of when the cached vendor index was fetched, stored as the
+/// [VENDOR_FETCH_FILE].
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FetchRecord {
+    url: String,
+    /// The time in seconds since 1970-01-01.
+    fetched: u64,
+}
+
+impl VendorCache {
+    /// Places the cache within the orbit `home` directory.
+    pub fn new(home: &PathBuf) -> Self {
+        Self {
+            root: home.join(VENDOR_CACHE_DIR),
+        }
+    }
+
+    /// Returns the directory holding the fetched vendor index.
+    pub fn get_index_path(&self) -> PathBuf {
+        self.root.join("index")
+    }
+
+    fn load_record(&self) -> Option<FetchRecord> {
+        std::fs::read_to_string(self.root.join(VENDOR_FETCH_FILE))
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+    }
+
+    /// Returns the number of seconds since the vendor index at `url` was last
+    /// fetched.
+    ///
+    /// Returns `None` if the index was never fetched from `url`.
+    pub fn get_age(&self, url: &str) -> Option<u64> {
+        match self.load_record() {
+            Some(rec) if rec.url == url && self.get_index_path().is_dir() == true => {
+                Some(date::now().saturating_sub(rec.fetched))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if the vendor index at `url` must be fetched again because its
+    /// copy is missing or older than `ttl` hours.
+    pub fn is_stale(&self, url: &str, ttl: u64) -> bool {
+        match self.get_age(url) {
+            Some(age) => age >= ttl * 3600,
+            None => true,
+        }
+    }
+
+    /// Downloads the zip archive of the vendor index at `url`, replacing the
+    /// copy fetched earlier.
+    pub fn refresh(&self, url: &str, auth: Option<&HttpAuth>) -> Result<(), Fault> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path().to_path_buf();
+        Protocol::single_download(url, &dir, auth)?;
+        // archives of a repository place every file within a single directory
+        let entries: Vec<PathBuf> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        let src = match entries.len() == 1 && entries[0].is_dir() == true {
+            true => entries[0].clone(),
+            false => dir,
+        };
+        self.replace(&src, url)
+    }
+
+    /// Swaps the files of the vendor index at `src` into the cache and records
+    /// them as fetched from `url`.
+    fn replace(&self, src: &PathBuf, url: &str) -> Result<(), Fault> {
+        // never replace a working copy with an index that cannot be read
+        VendorIndex::load(src)?;
+        std::fs::create_dir_all(&self.root)?;
+        // prevent another orbit process from swapping the index at the same time
+        let _lock = FileLock::acquire(&self.root.join(VENDOR_FETCH_FILE))?;
+
+        // stage the files within the cache so they replace the earlier files by a rename
+        let staging = tempfile::tempdir_in(&self.root)?;
+        let staged = staging.path().join("new");
+        filesystem::copy(src, &staged, false, None)?;
+        // keep the earlier files until the new files are in place
+        let dest = self.get_index_path();
+        let previous = staging.path().join("old");
+        if dest.exists() == true {
+            std::fs::rename(&dest, &previous)?;
+        }
+        if let Err(e) = std::fs::rename(&staged, &dest) {
+            if previous.exists() == true {
+                std::fs::rename(&previous, &dest)?;
+            }
+            return Err(e)?;
+        }
+
+        let record = FetchRecord {
+            url: url.to_string(),
+            fetched: date::now(),
+        };
+        std::fs::write(self.root.join(VENDOR_FETCH_FILE), toml::to_string(&record)?)?;
+        Ok(())
+    }
+}
+
+/// Publishes the installed `ip` as an archive into the vendor index at `dir`,
+/// recording the `source` it was originally fetched from.
+///
+/// Mirroring the same ip again replaces its archive and provenance.
+pub fn mirror(ip: &Ip, source: Option<&Source>, dir: &PathBuf) -> Result<Provenance, Fault> {
+    std::fs::create_dir_all(dir)?;
+    let man = ip.get_man().get_ip();
+    let slot = DownloadSlot::new(man.get_name(), man.get_version(), ip.get_uuid());
+    let dest = dir.join(slot.as_ref());
+    {
+        // prevent another orbit process from writing to the same archive
+        let _lock = FileLock::acquire(&dest)?;
+        IpArchive::write(ip, &dest)?;
+    }
+
+    let entry = Provenance {
+        name: man.get_name().to_string(),
+        version: man.get_version().to_string(),
+        uuid: ip.get_uuid().get().to_string(),
+        archive: slot.as_ref().to_string(),
+        checksum: Some(
+            Ip::read_checksum_proof(ip.get_root())
+                .unwrap_or_else(|| Ip::compute_checksum(ip.get_root()))
+                .to_string(),
+        ),
+        source: source.map(|s| s.get_url().to_string()),
+        mirrored: date::today(),
+    };
+    // prevent another orbit process from losing this record while updating the index
+    let _lock = FileLock::acquire(&dir.join(VENDOR_INDEX_FILE))?;
+    let mut index = VendorIndex::load(dir)?;
+    index.insert(entry.clone());
+    index.save(dir)?;
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::catalog::Catalog;
+
+    fn record(name: &str, version: &str, source: Option<&str>) -> Provenance {
+        Provenance {
+            name: name.to_string(),
+            version: version.to_string(),
+            uuid: String::from("0"),
+            archive: format!("{}-{}-0.ip", name, version),
+            checksum: None,
+            source: source.map(|s| s.to_string()),
+            mirrored: String::from("2024-01-01"),
+        }
+    }
+
+    #[test]
+    fn index_replaces_same_ip() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+
+        let mut index = VendorIndex::load(&root).unwrap();
+        index.insert(record("gates", "1.0.0", Some("https://a.com/gates.zip")));
+        index.insert(record("alu", "0.1.0", None));
+        index.insert(record("gates", "1.0.0", Some("https://b.com/gates.zip")));
+        index.save(&root).unwrap();
+
+        let index = VendorIndex::load(&root).unwrap();
+        assert_eq!(index.get_entries().len(), 2);
+        assert_eq!(index.get_entries()[0].get_archive(), "alu-0.1.0-0.ip");
+        assert_eq!(
+            index.get_entries()[1].get_source(),
+            Some(&String::from("https://b.com/gates.zip"))
+        );
+    }
+
+    #[test]
+    fn index_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        // a missing index is empty
+        assert_eq!(VendorIndex::load(&root).unwrap(), VendorIndex::default());
+        std::fs::write(root.join(VENDOR_INDEX_FILE), "[[ip]\nname = ").unwrap();
+        assert!(VendorIndex::load(&root).is_err());
+    }
+
+    #[test]
+    fn mirror_records_provenance() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let ip = Ip::load(PathBuf::from("./tests/env/project1/")).unwrap();
+
+        let entry = mirror(&ip, None, &root).unwrap();
+        assert_eq!(root.join(entry.get_archive()).is_file(), true);
+        assert_eq!(entry.checksum.is_some(), true);
+        assert_eq!(VendorIndex::load(&root).unwrap().get_entries(), &vec![entry]);
+    }
+
+    #[test]
+    fn cache_expires() {
+        let home = tempfile::tempdir().unwrap();
+        let cache = VendorCache::new(&home.path().to_path_buf());
+        let url = "https://example.com/vendor.zip";
+        assert_eq!(cache.get_age(url), None);
+        assert_eq!(cache.is_stale(url, 24), true);
+
+        let src = tempfile::tempdir().unwrap();
+        let src = src.path().to_path_buf();
+        let ip = Ip::load(PathBuf::from("./tests/env/project1/")).unwrap();
+        let entry = mirror(&ip, None, &src).unwrap();
+        cache.replace(&src, url).unwrap();
+
+        let index = VendorIndex::load(&cache.get_index_path()).unwrap();
+        assert_eq!(index.find("project1", "1.0.0"), Some(&entry));
+        // the archives are available to the catalog
+        let catalog = Catalog::new().available(&cache.get_index_path()).unwrap();
+        assert_eq!(
+            catalog.inner().values().next().unwrap().is_available(),
+            true
+        );
+        assert_eq!(cache.is_stale(url, 24), false);
+        // a time-to-live of zero always fetches the index again
+        assert_eq!(cache.is_stale(url, 0), true);
+        // an index from a different url is never used
+        assert_eq!(cache.get_age("https://example.com/other.zip"), None);
+
+        // an unreadable index does not replace the working copy
+        std::fs::write(src.join(VENDOR_INDEX_FILE), "[[ip]\nname = ").unwrap();
+        assert!(cache.replace(&src, url).is_err());
+        assert_eq!(
+            VendorIndex::load(&cache.get_index_path()).unwrap().get_entries(),
+            &vec![entry]
+        );
+    }
+}
@@ -1,22 +1,62 @@
+//! Orbit is a package manager and build tool for hardware description languages.
+//!
+//! Besides the `orbit` command-line interface, the following modules are the
+//! public library interface for tools that link against Orbit to read ip and
+//! designs without running the `orbit` command:
+//!
+//! - [catalog]: look up ip installed to the cache and downloaded to the archive
+//! - [manifest]: read an ip's manifest and lockfile
+//! - [plan]: build the graph of ip and files used to plan a design
+//! - [vhdl]: read entity interfaces from VHDL source code
 #![allow(dead_code)]
 
+pub mod catalog;
 mod commands;
 mod core;
+pub mod manifest;
+pub mod plan;
 pub mod util;
+pub mod vhdl;
 
+use crate::commands::errcode::ErrorCode;
+use crate::commands::errcode::ExitCode;
+use crate::commands::external::External;
 use crate::commands::orbit::*;
+use crate::commands::suggest;
+use crate::core::lang::vhdl::highlight::ColorMode;
+use crate::util::diagnostic;
+use crate::util::diagnostic::Diagnostic;
+use crate::util::environment;
+use crate::util::output::OutputFormat;
 use clif::cmd::Command;
 use clif::cmd::FromCli;
 use clif::*;
-use colored::*;
+use std::str::FromStr;
 
 pub fn go() -> u8 {
+    // defer to an external subcommand if one is requested
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(ext) = External::detect(&args) {
+        return match ext.exec() {
+            Ok(code) => code,
+            Err(err) => report(Diagnostic::error(&err.to_string())),
+        };
+    }
+
+    // suggest the closest match for a misspelled subcommand or option
+    if let Err(err) = suggest::check(&args) {
+        return report(Diagnostic::error(&err.to_string()).code(&ErrorCode::E0101.to_string()));
+    }
+
     // interface level
-    let mut cli = Cli::new()
-        .emphasize_help()
-        .color()
-        .threshold(2)
-        .tokenize(std::env::args());
+    let cli = Cli::new().emphasize_help();
+    // respect NO_COLOR before any help or error is displayed
+    let no_color = std::env::var(environment::NO_COLOR).ok();
+    let cli = match ColorMode::Auto.is_enabled(no_color.as_deref()) {
+        Some(false) => cli,
+        _ => cli.color(),
+    };
+    let mut cli = cli.threshold(2).tokenize(std::env::args());
 
     let orbit = match Orbit::from_cli(&mut cli) {
         Ok(app) => {
@@ -24,20 +64,47 @@ pub fn go() -> u8 {
             app
         }
         Err(err) => {
-            match err.kind() {
-                ErrorKind::Help => println!("{}", err),
-                _ => eprintln!("{}: {}", "error".red().bold(), err),
-            }
-            return err.code();
+            return match err.kind() {
+                ErrorKind::Help => {
+                    println!("{}", err);
+                    err.code()
+                }
+                _ => {
+                    report(Diagnostic::error(&err.to_string()).code(&ErrorCode::E0101.to_string()))
+                }
+            };
         }
     };
 
     // program level
     match orbit.exec(&()) {
-        Ok(_) => 0,
-        Err(err) => {
-            eprintln!("{}: {}", "error".red().bold(), err);
-            101
-        }
+        Ok(_) => ExitCode::Success.value(),
+        Err(err) => report_all(
+            orbit.get_command_name(),
+            ErrorCode::diagnose_all(err.as_ref()),
+            orbit.get_format(),
+        ),
+    }
+}
+
+/// Displays the error `diagnostic` and returns the exit code for its class of
+/// failure.
+fn report(diagnostic: Diagnostic) -> u8 {
+    report_all("orbit", vec![diagnostic], OutputFormat::Text)
+}
+
+/// Displays the `diagnostics` reported by the `command` in the format `fmt` and
+/// returns the exit code for the class of the first coded failure.
+fn report_all(command: &str, diagnostics: Vec<Diagnostic>, fmt: OutputFormat) -> u8 {
+    match diagnostic::render_all(command, &diagnostics, fmt, true) {
+        Ok(text) => eprintln!("{}", text.trim_end()),
+        Err(e) => eprintln!("{}", e),
+    }
+    match diagnostics
+        .iter()
+        .find_map(|d| d.get_code().and_then(|c| ErrorCode::from_str(c).ok()))
+    {
+        Some(c) => c.get_class().value(),
+        None => ExitCode::Failure.value(),
     }
 }
@@ -0,0 +1,44 @@
+//! An ip's manifest and lockfile.
+//!
+//! ```no_run
+//! use orbit::manifest::{FromFile, LockFile, Manifest, IP_LOCK_FILE, IP_MANIFEST_FILE};
+//! use std::path::PathBuf;
+//!
+//! let root = PathBuf::from("gates");
+//! let man = Manifest::from_file(&root.join(IP_MANIFEST_FILE)).unwrap();
+//! println!("{} {}", man.get_ip().get_name(), man.get_ip().get_version());
+//!
+//! let lock = LockFile::from_file(&root.join(IP_LOCK_FILE)).unwrap();
+//! for entry in lock.inner() {
+//!     println!("{} {}", entry.get_name(), entry.get_version());
+//! }
+//! ```
+
+pub use crate::core::lockfile::{LockEntry, LockFile, IP_LOCK_FILE};
+pub use crate::core::manifest::{FromFile, Manifest, IP_MANIFEST_FILE};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn read_from_disk() {
+        let dir = tempdir().unwrap();
+        let root = PathBuf::from(dir.path());
+        std::fs::write(
+            root.join(IP_MANIFEST_FILE),
+            "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+
+        let man = Manifest::from_file(&root.join(IP_MANIFEST_FILE)).unwrap();
+        assert_eq!(man.get_ip().get_name().to_string(), "gates");
+        assert_eq!(man.get_ip().get_version().to_string(), "1.0.0");
+
+        // a missing lockfile reads as empty
+        let lock = LockFile::from_file(&root.join(IP_LOCK_FILE)).unwrap();
+        assert_eq!(lock.is_empty(), true);
+    }
+}
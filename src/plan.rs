@@ -0,0 +1,48 @@
+//! The graph of ip and files used to plan a design.
+//!
+//! ```no_run
+//! use orbit::catalog::{Catalog, Ip};
+//! use std::path::PathBuf;
+//!
+//! let cache = orbit::catalog::cache_path().unwrap();
+//! let catalog = Catalog::new().installations(&cache).unwrap();
+//! let target = Ip::load(PathBuf::from("gates")).unwrap();
+//!
+//! let graph = orbit::plan::ip_graph(&target, &catalog).unwrap();
+//! for node in orbit::plan::vhdl_files(&graph) {
+//!     println!("{} {}", node.get_library(), node.get_file());
+//! }
+//! ```
+
+pub use crate::core::algo::{IpFileNode, IpNode};
+pub use crate::core::lang::vhdl::token::Identifier;
+pub use crate::util::graphmap::GraphMap;
+
+use crate::core::algo;
+use crate::core::catalog::Catalog;
+use crate::core::ip::{Ip, IpSpec};
+use crate::util::anyerror::Fault;
+
+/// Builds the graph of ip that `target` depends on from its lockfile, resolving
+/// each dependency from the `catalog`.
+///
+/// Dependencies with conflicting unit names are transformed the same way they
+/// are when running `orbit plan`.
+pub fn ip_graph<'a>(
+    target: &'a Ip,
+    catalog: &'a Catalog<'a>,
+) -> Result<GraphMap<IpSpec, IpNode<'a>, ()>, Fault> {
+    algo::compute_final_ip_graph(target, catalog)
+}
+
+/// Collects every VHDL file across the ip in the `graph` along with the library
+/// it belongs to.
+///
+/// Only the ignore files within each ip are respected; the user's global
+/// ignore file is not read.
+pub fn vhdl_files<'a>(graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>) -> Vec<IpFileNode<'a>> {
+    match graph.get_node_by_index(0) {
+        Some(root) => algo::build_ip_file_list(graph, root.as_ref().as_ip(), None),
+        None => Vec::new(),
+    }
+}
@@ -1,6 +1,14 @@
-use crate::util::sha256;
+use crate::util::parallel;
+use crate::util::sha256::{self, Sha256, Sha256Hash};
+use std::io::Read;
 use std::path::PathBuf;
 
+/// The fewest files worth spreading across multiple threads.
+const MIN_PARALLEL_FILES: usize = 64;
+
+/// The number of bytes read from a file at a time.
+const READ_SIZE: usize = 64 * 1024;
+
 /// Given a list of files, compute a single sha256 digest to encapsulate the
 /// entire directory state.
 ///
@@ -9,29 +17,22 @@ use std::path::PathBuf;
 /// files by removing \r carriage return bytes from windows system file reads.
 /// This function also skips binary files (not intended for reading) by detecting
 /// if a NUL character appears in the byte vector.
-pub fn checksum(files: &[String], root: &PathBuf) -> sha256::Sha256Hash {
+///
+/// Files are hashed concurrently, but the results are always combined in the
+/// order of `files` so the digest does not depend on the number of threads.
+pub fn checksum(files: &[String], root: &PathBuf) -> Sha256Hash {
     // determine the amount of bytes required
     let total_hashes = files.len() + 1;
     let mut total_bytes = Vec::<u8>::with_capacity(total_hashes * 32);
 
     let mut filename_bytes = Vec::<u8>::new();
-    // use a single vector to keep allocated capacity throughout rounds
-    let mut bytes = Vec::new();
     // perform a hash on contents
-    for file in files {
-        bytes.clear();
-        bytes.append(&mut std::fs::read(&root.join(file)).expect("failed to read as bytes"));
-        // detect and skip binary-encoded files (.pdf, .jpg, etc.) by reading NUL char
-        if bytes.contains(&0x00) == true {
-            continue;
+    for (file, sum) in files.iter().zip(hash_files(files, root)) {
+        // binary files do not contribute their contents or their names
+        if let Some(sum) = sum {
+            total_bytes.extend_from_slice(&sum.into_bytes());
+            filename_bytes.extend_from_slice(file.as_bytes());
         }
-        // @NOTE windows uses \r\n for newlines, compared to unix systems using just \n
-        bytes = bytes
-            .into_iter()
-            .filter(|f| f != &0x0d)
-            .collect::<Vec<u8>>();
-        total_bytes.append(&mut sha256::compute_sha256(&bytes).into_bytes().to_vec());
-        filename_bytes.append(&mut file.as_bytes().to_vec());
     }
     // perform hash on filenames
     total_bytes.append(
@@ -44,6 +45,44 @@ pub fn checksum(files: &[String], root: &PathBuf) -> sha256::Sha256Hash {
     sha256::compute_sha256(&total_bytes)
 }
 
+/// Hashes the contents of every file in `files` across the available threads.
+///
+/// Returns the hashes in the same order as `files`, with `None` for each binary
+/// file.
+fn hash_files(files: &[String], root: &PathBuf) -> Vec<Option<Sha256Hash>> {
+    parallel::map_ordered(files, MIN_PARALLEL_FILES, |f| hash_file(&root.join(f)))
+}
+
+/// Hashes the contents of the file at `path` with every \r carriage return byte
+/// removed, reading only a portion of the file into memory at a time.
+///
+/// Returns `None` if a NUL character appears, as the file is binary.
+fn hash_file(path: &PathBuf) -> Option<Sha256Hash> {
+    let mut file = std::fs::File::open(path).expect("failed to read as bytes");
+    let mut hasher = Sha256::new();
+    // use the same buffers to keep allocated capacity throughout reads
+    let mut buffer = vec![0; READ_SIZE];
+    let mut text = Vec::with_capacity(READ_SIZE);
+    loop {
+        let len = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => panic!("failed to read as bytes"),
+        };
+        let bytes = &buffer[..len];
+        // detect and skip binary-encoded files (.pdf, .jpg, etc.) by reading NUL char
+        if bytes.contains(&0x00) == true {
+            return None;
+        }
+        // @NOTE windows uses \r\n for newlines, compared to unix systems using just \n
+        text.clear();
+        text.extend(bytes.iter().filter(|b| *b != &0x0d));
+        hasher.update(&text);
+    }
+    Some(hasher.finalize())
+}
+
 #[cfg(test)]
 mod test {
     use std::env::set_current_dir;
@@ -103,6 +142,35 @@ mod test {
         assert_ne!(sum3, sum2);
     }
 
+    #[test]
+    fn parallel_matches_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let mut files = Vec::new();
+        for i in 0..(MIN_PARALLEL_FILES * 3) {
+            let name = format!("file{:04}.txt", i);
+            // mix in windows newlines and a binary file to be skipped
+            let contents = match i % 10 {
+                0 => vec![0x00, 0x01, 0x02],
+                1 => format!("line {}\r\nline {}\r\n", i, i + 1).into_bytes(),
+                _ => format!("line {}\nline {}\n", i, i + 1).into_bytes(),
+            };
+            std::fs::write(root.join(&name), contents).unwrap();
+            files.push(name);
+        }
+
+        let sums = hash_files(&files, &root);
+        let expected: Vec<Option<Sha256Hash>> =
+            files.iter().map(|f| hash_file(&root.join(f))).collect();
+        assert_eq!(sums, expected);
+        assert_eq!(sums.iter().filter(|s| s.is_none()).count(), 20);
+        // carriage returns do not change the hash
+        assert_eq!(
+            hash_file(&root.join("file0001.txt")),
+            Some(sha256::compute_sha256(b"line 1\nline 2\n"))
+        );
+    }
+
     #[test]
     fn from_filesystem() {
         set_current_dir(env!("CARGO_MANIFEST_DIR")).unwrap();
@@ -9,7 +9,7 @@ use zip::result::ZipError;
 use zip::write::FileOptions;
 use zip::CompressionMethod;
 
-use ignore::{DirEntry, WalkBuilder};
+use crate::util::filesystem;
 use std::fs::File;
 use std::path::{Path, PathBuf};
 
@@ -17,7 +17,7 @@ use std::path::{Path, PathBuf};
 const COMPRESSION_METHOD: CompressionMethod = CompressionMethod::ZSTD;
 
 fn zip_dir<T>(
-    it: &mut dyn Iterator<Item = DirEntry>,
+    it: &mut dyn Iterator<Item = PathBuf>,
     prefix: &PathBuf,
     writer: T,
     method: zip::CompressionMethod,
@@ -31,8 +31,8 @@ where
         .unix_permissions(0o755);
 
     let mut buffer = Vec::new();
-    for entry in it {
-        let path = entry.path();
+    for path in it {
+        let path = path.as_path();
         let name = path.strip_prefix(Path::new(prefix)).unwrap();
 
         // Write file or directory explicitly
@@ -66,11 +66,13 @@ pub fn write_zip_dir(src_dir: &PathBuf, dst_file: &PathBuf) -> zip::result::ZipR
     let path = Path::new(dst_file);
     let file = File::create(path).unwrap();
 
-    let walkdir = WalkBuilder::new(src_dir).git_ignore(false).build();
-    let it = walkdir.into_iter();
+    // archive the same files that belong to the ip when planning and installing
+    let paths = filesystem::walk_ip(src_dir)
+        .into_iter()
+        .filter(|p| filesystem::remove_base(src_dir, p).starts_with(".git") == false);
 
     zip_dir(
-        &mut it.filter_map(|e| e.ok()),
+        &mut paths.into_iter(),
         src_dir,
         file,
         COMPRESSION_METHOD,
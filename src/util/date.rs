@@ -0,0 +1,63 @@
+//! Calendar dates in UTC, used to record when files were fetched or published.
+
+/// Returns the current time as the number of seconds since 1970-01-01.
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Returns the current date in UTC in the form `YYYY-MM-DD`.
+pub fn today() -> String {
+    to_date(now() / 86400)
+}
+
+/// Describes an age of `secs` seconds in its largest whole unit, such as
+/// `3 hours ago`.
+pub fn fmt_age(secs: u64) -> String {
+    let (count, unit) = match secs {
+        0..=59 => return String::from("just now"),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Converts the number of `days` since 1970-01-01 into a date of the form
+/// `YYYY-MM-DD`.
+fn to_date(days: u64) -> String {
+    // shift the epoch to 0000-03-01 so leap days fall at the end of each year
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!(to_date(0), "1970-01-01");
+        assert_eq!(to_date(11016), "2000-02-29");
+        assert_eq!(to_date(20742), "2026-10-16");
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(fmt_age(0), "just now");
+        assert_eq!(fmt_age(60), "1 minute ago");
+        assert_eq!(fmt_age(7199), "1 hour ago");
+        assert_eq!(fmt_age(7200), "2 hours ago");
+        assert_eq!(fmt_age(86400 * 3), "3 days ago");
+    }
+}
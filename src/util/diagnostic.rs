@@ -0,0 +1,241 @@
+//! A structured report of a problem found by a command, such as an error while
+//! reading source code or deciding a top-level unit.
+//!
+//! Diagnostics render as plain or colored text for the terminal, or as JSON for
+//! tools that consume orbit's output.
+
+use crate::util::anyerror::Fault;
+use crate::util::output;
+use crate::util::output::OutputFormat;
+use colored::Colorize;
+use serde_derive::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A range of characters on a single line of a file.
+///
+/// Lines and columns begin counting at 1.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Span {
+    line: usize,
+    col: usize,
+    len: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, col: usize, len: usize) -> Self {
+        Self { line, col, len }
+    }
+
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    pub fn get_col(&self) -> usize {
+        self.col
+    }
+
+    pub fn get_len(&self) -> usize {
+        self.len
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: Option<String>,
+    message: String,
+    file: Option<PathBuf>,
+    span: Option<Span>,
+    help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: &str) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.to_string(),
+            file: None,
+            span: None,
+            help: None,
+        }
+    }
+
+    pub fn error(message: &str) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: &str) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn note(message: &str) -> Self {
+        Self::new(Severity::Note, message)
+    }
+
+    /// Labels the diagnostic with an error code, such as `E0303`.
+    pub fn code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    pub fn file(mut self, file: PathBuf) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    pub fn span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Adds a suggestion for how to resolve the problem.
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn get_code(&self) -> Option<&String> {
+        self.code.as_ref()
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_file(&self) -> Option<&PathBuf> {
+        self.file.as_ref()
+    }
+
+    pub fn get_span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    pub fn get_help(&self) -> Option<&String> {
+        self.help.as_ref()
+    }
+
+    /// Formats the location of the problem as `<file>:<line>:<col>`, if it is
+    /// known.
+    fn location(&self) -> Option<String> {
+        match (&self.file, &self.span) {
+            (Some(f), Some(s)) => Some(format!("{}:{}:{}", f.display(), s.line, s.col)),
+            (Some(f), None) => Some(f.display().to_string()),
+            (None, Some(s)) => Some(format!("{}:{}", s.line, s.col)),
+            (None, None) => None,
+        }
+    }
+
+    /// Formats the diagnostic as text for the terminal, starting with its
+    /// severity and code.
+    ///
+    /// The header is colored by severity when `color` is enabled.
+    pub fn render(&self, color: bool) -> String {
+        let label = match &self.code {
+            Some(c) => format!("{}[{}]", self.severity, c),
+            None => self.severity.to_string(),
+        };
+        let label = match color {
+            true => match self.severity {
+                Severity::Error => label.red().bold().to_string(),
+                Severity::Warning => label.yellow().bold().to_string(),
+                Severity::Note => label.blue().bold().to_string(),
+            },
+            false => label,
+        };
+        format!("{}: {}", label, self)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(loc) = self.location() {
+            write!(f, "\n  --> {}", loc)?;
+        }
+        if let Some(help) = &self.help {
+            write!(f, "\n  = help: {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the `diagnostics` reported by the `command` in the output format
+/// `fmt`, with one diagnostic per paragraph for text.
+pub fn render_all(
+    command: &str,
+    diagnostics: &[Diagnostic],
+    fmt: OutputFormat,
+    color: bool,
+) -> Result<String, Fault> {
+    match fmt {
+        OutputFormat::Text => Ok(diagnostics
+            .iter()
+            .map(|d| d.render(color) + "\n")
+            .collect::<Vec<String>>()
+            .join("\n")),
+        OutputFormat::Json => output::to_json(command, &diagnostics),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_plain() {
+        let d = Diagnostic::error("No entity named 'adder' in the current IP")
+            .code("E0303")
+            .file(PathBuf::from("rtl/top.vhd"))
+            .span(Span::new(4, 8, 5))
+            .help("select the unit with `--top <unit>`");
+        assert_eq!(
+            d.render(false),
+            "\
+error[E0303]: No entity named 'adder' in the current IP
+  --> rtl/top.vhd:4:8
+  = help: select the unit with `--top <unit>`"
+        );
+        // only the known details are displayed
+        assert_eq!(
+            Diagnostic::warning("unused generic").render(false),
+            "warning: unused generic"
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let diagnostics = vec![Diagnostic::note("checked 2 files").file(PathBuf::from("a.vhd"))];
+        let json = render_all("plan", &diagnostics, OutputFormat::Json, false).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["command"], "plan");
+        assert_eq!(value["data"][0]["severity"], "note");
+        assert_eq!(value["data"][0]["message"], "checked 2 files");
+        assert_eq!(value["data"][0]["file"], "a.vhd");
+        assert_eq!(value["data"][0]["span"], serde_json::Value::Null);
+    }
+}
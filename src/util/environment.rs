@@ -6,8 +6,12 @@ use std::io::Read;
 use std::io::Write;
 
 use crate::core::ip::Ip;
+use crate::core::ip::IpSpec;
+use crate::core::pkgid::PkgPart;
 use std::collections::btree_set::IntoIter;
 use std::collections::btree_set::Iter;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 use std::collections::btree_set::BTreeSet;
 
@@ -120,6 +124,27 @@ impl Environment {
         Ok(self)
     }
 
+    /// Loads environment variables from the `.env` and `.env.local` files living at an ip's `root`.
+    ///
+    /// Entries in `.env.local` take precedence over entries in `.env`. Variables that
+    /// are already set in the current process's environment are not overridden.
+    /// This function will not add any environment variables if neither file exists.
+    pub fn from_dotenv(mut self, root: &std::path::PathBuf) -> Result<Self, Fault> {
+        for name in [DOT_ENV_LOCAL_FILE, DOT_ENV_FILE] {
+            let path = root.join(name);
+            if path.is_file() == false {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)?;
+            for var in parse_dotenv(&contents) {
+                if std::env::var(var.get_key()).is_err() {
+                    self.insert(var);
+                }
+            }
+        }
+        Ok(self)
+    }
+
     /// Loads environment variables from a target [Ip].
     pub fn from_ip(mut self, ip: &Ip) -> Result<Self, Fault> {
         self.insert(
@@ -141,6 +166,36 @@ impl Environment {
         Ok(self)
     }
 
+    /// Sets a variable for each dependency in `deps` that points to the
+    /// directory where the dependency is installed.
+    ///
+    /// The variable's key is the ip's name prefixed with `ORBIT_DEP_`. When more
+    /// than one version of an ip is a dependency, each key also ends with its
+    /// version.
+    pub fn from_dependencies(mut self, deps: &[(&IpSpec, &PathBuf)]) -> Self {
+        let mut versions: HashMap<&PkgPart, usize> = HashMap::new();
+        deps.iter().for_each(|(spec, _)| {
+            *versions.entry(spec.get_name()).or_insert(0) += 1;
+        });
+        deps.iter().for_each(|(spec, root)| {
+            let key = match versions.get(spec.get_name()) {
+                Some(1) => format!("{}{}", ORBIT_DEP_PREFIX, spec.get_name()),
+                _ => format!(
+                    "{}{}_{}",
+                    ORBIT_DEP_PREFIX,
+                    spec.get_name(),
+                    spec.get_version().to_string().replace('.', "_")
+                ),
+            };
+            self.insert(
+                EnvVar::new()
+                    .key(&key)
+                    .value(&crate::util::filesystem::into_std_str(root.to_path_buf())),
+            );
+        });
+        self
+    }
+
     /// Loads an `Environment` struct from a `Config` document.
     ///
     /// It searches the `[env]` table and collects all env variables.
@@ -165,6 +220,29 @@ impl Environment {
     }
 }
 
+/// Parses the `key=value` lines of a dotenv file into a list of `EnvVar`.
+///
+/// Blank lines and lines beginning with '#' are skipped. A line may begin with
+/// `export ` and its value may be wrapped in single or double quotes.
+fn parse_dotenv(contents: &str) -> Vec<EnvVar> {
+    contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| line.is_empty() == false && line.starts_with('#') == false)
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            // remove any surrounding quotes
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                .unwrap_or(value);
+            Some(EnvVar::new().key(key.trim()).value(value))
+        })
+        .collect()
+}
+
 /// Stores a list of `EnvVar` at root in a file named ".env".
 pub fn save_environment(env: &Environment, root: &std::path::PathBuf) -> Result<(), Fault> {
     // create the file
@@ -227,10 +305,86 @@ pub const ORBIT_CACHE: &str = "ORBIT_CACHE";
 pub const ORBIT_QUEUE: &str = "ORBIT_QUEUE";
 pub const ORBIT_DOWNLOADS: &str = "ORBIT_DOWNLOADS";
 pub const ORBIT_HOME: &str = "ORBIT_HOME";
+pub const ORBIT_CONFIG_HOME: &str = "ORBIT_CONFIG_HOME";
+pub const ORBIT_LAYOUT: &str = "ORBIT_LAYOUT";
+pub const ORBIT_LOCK_TIMEOUT: &str = "ORBIT_LOCK_TIMEOUT";
 pub const ORBIT_IP_PATH: &str = "ORBIT_IP_PATH";
 pub const ORBIT_BLUEPRINT: &str = "ORBIT_BLUEPRINT";
+pub const ORBIT_DEPENDENCIES: &str = "ORBIT_DEPENDENCIES";
 pub const ORBIT_WIN_LITERAL_CMD: &str = "ORBIT_WIN_LITERAL_CMD";
+pub const ORBIT_PROFILE: &str = "ORBIT_PROFILE";
+pub const ORBIT_NO_INTERACTIVE: &str = "ORBIT_NO_INTERACTIVE";
 
 pub const ORBIT_ENV_PREFIX: &str = "ORBIT_ENV_";
+pub const ORBIT_DEP_PREFIX: &str = "ORBIT_DEP_";
 
 pub const DOT_ENV_FILE: &str = ".env";
+pub const DOT_ENV_LOCAL_FILE: &str = ".env.local";
+
+/// Disables colored output when set to any non-empty value (https://no-color.org).
+pub const NO_COLOR: &str = "NO_COLOR";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::version::Version;
+    use std::str::FromStr;
+
+    #[test]
+    fn dotenv() {
+        let contents = "\
+# machine-specific tool paths
+VIVADO_PATH=/opt/xilinx/vivado
+
+export LICENSE-SERVER = \"2100@license.acme.com\"
+API_TOKEN='abc=123'
+invalid line
+";
+        let vars = parse_dotenv(contents);
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars[0].get_key(), "VIVADO_PATH");
+        assert_eq!(vars[0].get_value(), "/opt/xilinx/vivado");
+        assert_eq!(vars[1].get_key(), "LICENSE_SERVER");
+        assert_eq!(vars[1].get_value(), "2100@license.acme.com");
+        assert_eq!(vars[2].get_key(), "API_TOKEN");
+        assert_eq!(vars[2].get_value(), "abc=123");
+    }
+
+    #[test]
+    fn dependencies() {
+        let fifo = IpSpec::new(
+            PkgPart::from_str("fifo").unwrap(),
+            Version::from_str("1.2.0").unwrap(),
+        );
+        let gates_1 = IpSpec::new(
+            PkgPart::from_str("logic-gates").unwrap(),
+            Version::from_str("1.0.0").unwrap(),
+        );
+        let gates_2 = IpSpec::new(
+            PkgPart::from_str("logic-gates").unwrap(),
+            Version::from_str("2.0.0").unwrap(),
+        );
+        let fifo_root = PathBuf::from("/home/u/.orbit/cache/fifo-1.2.0-ab12cd34ef");
+        let gates_1_root = PathBuf::from("/home/u/.orbit/cache/logic-gates-1.0.0-0123456789");
+        let gates_2_root = PathBuf::from("/home/u/.orbit/cache/logic-gates-2.0.0-9876543210");
+        let env = Environment::new().from_dependencies(&[
+            (&fifo, &fifo_root),
+            (&gates_1, &gates_1_root),
+            (&gates_2, &gates_2_root),
+        ]);
+        assert_eq!(
+            env.get("ORBIT_DEP_FIFO").unwrap().get_value(),
+            "/home/u/.orbit/cache/fifo-1.2.0-ab12cd34ef"
+        );
+        // versions are only added to distinguish multiple versions of an ip
+        assert_eq!(env.get("ORBIT_DEP_LOGIC_GATES"), None);
+        assert_eq!(
+            env.get("ORBIT_DEP_LOGIC_GATES_1_0_0").unwrap().get_value(),
+            "/home/u/.orbit/cache/logic-gates-1.0.0-0123456789"
+        );
+        assert_eq!(
+            env.get("ORBIT_DEP_LOGIC_GATES_2_0_0").unwrap().get_value(),
+            "/home/u/.orbit/cache/logic-gates-2.0.0-9876543210"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Advisory lock files to keep concurrent orbit processes from writing to the
+//! same cache slot or download at the same time.
+//!
+//! A lock is a file named `<path>.lock` that holds the id of the process that
+//! created it. The file is removed when the lock is dropped.
+
+use crate::util::environment::ORBIT_LOCK_TIMEOUT;
+use crate::util::logger;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The file extension appended to the locked path.
+pub const LOCK_EXT: &str = "lock";
+
+/// The number of seconds to wait on a held lock before giving up.
+const DEFAULT_TIMEOUT: u64 = 60;
+
+/// The time between attempts to acquire a held lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, PartialEq)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Acquires the lock for `target`, waiting on another process to release
+    /// it for up to the timeout set by `ORBIT_LOCK_TIMEOUT`.
+    pub fn acquire(target: &PathBuf) -> Result<Self, LockError> {
+        Self::acquire_with_timeout(target, Self::get_timeout())
+    }
+
+    /// Acquires the lock for `target`, waiting on another process to release
+    /// it for up to `timeout`.
+    pub fn acquire_with_timeout(target: &PathBuf, timeout: Duration) -> Result<Self, LockError> {
+        let path = Self::to_lock_path(target);
+        let start = Instant::now();
+        let mut waiting = false;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id())
+                        .map_err(|e| LockError::Io(path.clone(), e.to_string()))?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let pid = Self::read_pid(&path);
+                    if start.elapsed() >= timeout {
+                        return Err(LockError::Locked(path, pid));
+                    }
+                    if waiting == false {
+                        logger::info(format!(
+                            "Waiting for lock on {:?} held by PID {} ...",
+                            target,
+                            pid.map(|p| p.to_string()).unwrap_or(String::from("?"))
+                        ));
+                        waiting = true;
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => return Err(LockError::Io(path, e.to_string())),
+            }
+        }
+    }
+
+    /// Returns the path of the lock file that guards `target`.
+    pub fn to_lock_path(target: &PathBuf) -> PathBuf {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(LOCK_EXT);
+        target.with_file_name(name)
+    }
+
+    /// Reads the id of the process holding the lock at `path`.
+    fn read_pid(path: &PathBuf) -> Option<u32> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    fn get_timeout() -> Duration {
+        let secs = std::env::var(ORBIT_LOCK_TIMEOUT)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Duration::from_secs(secs)
+    }
+
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LockError {
+    Locked(PathBuf, Option<u32>),
+    Io(PathBuf, String),
+}
+
+impl Error for LockError {}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Locked(path, pid) => write!(
+                f,
+                "Cache is locked by PID {}\n\nWait for the other orbit process to finish, or delete {:?} if that process is no longer running",
+                pid.map(|p| p.to_string()).unwrap_or(String::from("?")),
+                path
+            ),
+            Self::Io(path, err) => write!(f, "Failed to create lock file {:?}: {}", path, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lock_path() {
+        let target = PathBuf::from("cache/gates-1.0.0-abcdef0123");
+        assert_eq!(
+            FileLock::to_lock_path(&target),
+            PathBuf::from("cache/gates-1.0.0-abcdef0123.lock")
+        );
+    }
+
+    #[test]
+    fn acquire_and_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("gates-1.0.0-abcdef0123");
+
+        let lock = FileLock::acquire_with_timeout(&target, Duration::ZERO).unwrap();
+        assert_eq!(lock.get_path().exists(), true);
+        assert_eq!(
+            FileLock::read_pid(lock.get_path()),
+            Some(std::process::id())
+        );
+        // a second lock on the same target times out
+        assert_eq!(
+            FileLock::acquire_with_timeout(&target, Duration::ZERO),
+            Err(LockError::Locked(
+                FileLock::to_lock_path(&target),
+                Some(std::process::id())
+            ))
+        );
+        // dropping the lock releases it
+        drop(lock);
+        assert_eq!(FileLock::to_lock_path(&target).exists(), false);
+        assert!(FileLock::acquire_with_timeout(&target, Duration::ZERO).is_ok());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::core::fileset;
 use crate::core::lockfile;
 use crate::core::manifest;
+use crate::core::manifest::{FromFile, Manifest};
 use fs_extra;
 use home::home_dir;
 use ignore::WalkBuilder;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::env;
 use std::env::current_dir;
@@ -12,8 +14,123 @@ use std::path::PathBuf;
 use std::path::{Component, Path};
 
 use super::anyerror::Fault;
+use super::logger;
 
-/// Recursively walks the given `path` and ignores files defined in a .gitignore file or .orbitignore files.
+/// Creates a builder to walk `path` that respects .gitignore files, .orbitignore
+/// files, and the `global` ignore file.
+///
+/// Every command that collects an ip's files walks with this builder through
+/// [walk_ip] so that planning and installing agree on which files are ignored. The patterns in
+/// `global` have the lowest precedence, so a negation in a .orbitignore file can
+/// keep a file the `global` file ignores.
+///
+/// The `global` file is only given when walking the working ip, see [walk_working_ip].
+fn walker(path: &PathBuf, global: Option<&PathBuf>) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(path);
+    builder.hidden(false).add_custom_ignore_filename(ORBIT_IGNORE_FILE);
+    if let Some(file) = global {
+        // a missing global ignore file ignores nothing
+        if file.is_file() == true {
+            if let Some(e) = builder.add_ignore(file) {
+                logger::warn(format!("failed to read ignore file {:?}: {}", file, e));
+            }
+        }
+    }
+    builder
+}
+
+/// The patterns from an ip's manifest deciding which of its files belong to it.
+struct ManifestRules {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ManifestRules {
+    /// Reads the `include` and `exclude` patterns of the manifest at `root`.
+    ///
+    /// A directory without a readable manifest has no patterns.
+    fn read(root: &PathBuf) -> Self {
+        match Manifest::from_file(&root.join(manifest::IP_MANIFEST_FILE)) {
+            Ok(man) => Self {
+                include: man.get_ip().get_include().clone(),
+                exclude: man.get_ip().get_exclude().clone(),
+            },
+            Err(_) => Self {
+                include: Vec::new(),
+                exclude: Vec::new(),
+            },
+        }
+    }
+
+    /// Checks if the file at `rel_path` within the ip belongs to it.
+    fn allows(&self, rel_path: &str) -> bool {
+        // the ip's own metadata always belongs to it
+        if is_orbit_metadata(rel_path) == true {
+            return true;
+        }
+        if self.include.is_empty() == false
+            && self.include.iter().any(|p| matches_path(p, rel_path)) == false
+        {
+            return false;
+        }
+        self.exclude.iter().any(|p| matches_path(p, rel_path)) == false
+    }
+}
+
+/// Checks if the relative `file` path matches the glob `pattern`, or is beneath
+/// the directory named by `pattern`.
+///
+/// A pattern without a '/' is also compared against the file's name alone.
+pub fn matches_path(pattern: &str, file: &str) -> bool {
+    let match_opts = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+    let name_only = pattern.contains('/') == false;
+    let pattern = pattern.trim_end_matches('/');
+    match glob::Pattern::new(pattern) {
+        Ok(p) => {
+            p.matches_with(file, match_opts)
+                || file.starts_with(&format!("{}/", pattern))
+                || (name_only == true
+                    && file
+                        .rsplit_once('/')
+                        .map(|(_, name)| p.matches_with(name, match_opts))
+                        .unwrap_or(false))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Walks the ip at `root` and collects every file and directory that belongs
+/// to it.
+///
+/// This is the one set of rules deciding an ip's files, which planning, copying,
+/// computing checksums, and creating archives all share: .gitignore files,
+/// .orbitignore files, and then the `include` and `exclude` patterns of the
+/// ip's manifest.
+pub fn walk_ip(root: &PathBuf) -> Vec<PathBuf> {
+    walk_working_ip(root, None)
+}
+
+/// Walks the working ip at `root` like [walk_ip] while also ignoring the
+/// patterns within the user's `global` ignore file.
+///
+/// The global ignore file is a user preference, so it never applies to the files
+/// that are copied, hashed, or archived when an ip is installed.
+pub fn walk_working_ip(root: &PathBuf, global: Option<&PathBuf>) -> Vec<PathBuf> {
+    let rules = ManifestRules::read(root);
+    walker(root, global)
+        .build()
+        .filter_map(|result| result.ok())
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() == false || rules.allows(&into_std_str(remove_base(root, p))))
+        .collect()
+}
+
+/// Recursively walks the given `path` and collects the files belonging to it
+/// according to [walk_ip].
 ///
 /// Returns the resulting list of filepath strings. This function silently skips result errors
 /// while walking. The collected set of paths are also standardized to use forward slashes '/'.
@@ -21,34 +138,32 @@ use super::anyerror::Fault;
 /// Setting `strip_base` to `true` will remove the overlapping `path` components from the
 /// final [String] entries in the resulting vector.
 ///
-/// Ignores ORBIT_SUM_FILE, .git directory, ORBIT_METADATA_FILE, and IP_LOCK_FILE.
+/// Ignores ORBIT_SUM_FILE, ORBIT_METADATA_FILE, ORBIT_GENERATED_FILE, and
+/// IP_LOCK_FILE.
 pub fn gather_current_files(path: &PathBuf, strip_base: bool) -> Vec<String> {
-    let m = WalkBuilder::new(path)
-        .hidden(false)
-        .add_custom_ignore_filename(ORBIT_IGNORE_FILE)
-        .filter_entry(|p| match p.file_name().to_str().unwrap() {
-            manifest::ORBIT_SUM_FILE | lockfile::IP_LOCK_FILE | manifest::ORBIT_METADATA_FILE => {
-                false
-            }
+    gather_working_files(path, strip_base, None)
+}
+
+/// Collects the files belonging to the working ip at `path` like
+/// [gather_current_files] while also ignoring the patterns within the user's
+/// `global` ignore file.
+pub fn gather_working_files(path: &PathBuf, strip_base: bool, global: Option<&PathBuf>) -> Vec<String> {
+    let mut files: Vec<String> = walk_working_ip(path, global)
+        .into_iter()
+        .filter(|p| p.is_file() == true)
+        .filter(|p| match p.file_name().and_then(|f| f.to_str()) {
+            Some(manifest::ORBIT_SUM_FILE)
+            | Some(lockfile::IP_LOCK_FILE)
+            | Some(manifest::ORBIT_METADATA_FILE)
+            | Some(manifest::ORBIT_GENERATED_FILE) => false,
             _ => true,
         })
-        .build();
-    let mut files: Vec<String> = m
-        .filter_map(|result| {
-            match result {
-                Ok(entry) => {
-                    if entry.path().is_file() {
-                        // perform standardization
-                        Some(into_std_str(match strip_base {
-                            true => remove_base(&path, &entry.into_path()),
-                            false => entry.into_path(),
-                        }))
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            }
+        // perform standardization
+        .map(|p| {
+            into_std_str(match strip_base {
+                true => remove_base(&path, &p),
+                false => p,
+            })
         })
         .collect();
     // sort the fileset for reproducibility purposes
@@ -134,7 +249,12 @@ pub fn resolve_rel_path2(root: &std::path::PathBuf, s: &PathBuf) -> PathBuf {
 
 /// Removes common path components from `full` if they are found in `base` on
 /// the same iterations.
+///
+/// Extended-length prefixes (`\\?\`) are ignored so that an extended path and
+/// its regular form share the same components, including UNC share roots.
 pub fn remove_base(base: &PathBuf, full: &PathBuf) -> PathBuf {
+    let base = strip_extended(base);
+    let full = strip_extended(full);
     let mut b_comps = base.iter();
     let mut f_comps = full.iter();
 
@@ -158,6 +278,82 @@ pub fn remove_base(base: &PathBuf, full: &PathBuf) -> PathBuf {
     result.join(f_comps.as_path())
 }
 
+/// Converts the path `p` into its extended-length form (`\\?\`) on windows to
+/// lift the MAX_PATH limit of 260 characters. Relative paths are resolved against
+/// the current working directory.
+///
+/// Returns `p` unmodified on other operating systems.
+pub fn to_extended(p: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") == false {
+        return p.to_path_buf();
+    }
+    let p = match p.is_relative() {
+        true => current_dir().map(|d| d.join(p)).unwrap_or(p.to_path_buf()),
+        false => p.to_path_buf(),
+    };
+    PathBuf::from(extend_windows_path(&p.to_string_lossy()))
+}
+
+/// Transforms an absolute windows path `s` into its extended-length form.
+///
+/// The system does not normalize extended-length paths, so separators are
+/// converted to backslashes and any `.` and `..` components are resolved. Paths
+/// that are not absolute are returned with only their separators converted.
+fn extend_windows_path(s: &str) -> String {
+    let s = s.replace('/', "\\");
+    let bytes = s.as_bytes();
+    let (prefix, rest) = if s.starts_with(r"\\?\") == true {
+        return s;
+    } else if let Some(unc) = s.strip_prefix(r"\\") {
+        // the server and share names form the root of a UNC path
+        let mut parts = unc.splitn(3, '\\');
+        let server = parts.next().unwrap_or_default();
+        let share = parts.next().unwrap_or_default();
+        (
+            format!(r"\\?\UNC\{}\{}", server, share),
+            parts.next().unwrap_or_default(),
+        )
+    } else if bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic() == true
+        && bytes[1] == b':'
+        && bytes[2] == b'\\'
+    {
+        (format!(r"\\?\{}", &s[..2]), &s[3..])
+    } else {
+        return s;
+    };
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => (),
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    format!("{}\\{}", prefix, parts.join("\\"))
+}
+
+/// Removes the extended-length prefix (`\\?\`) from the path `p`, restoring UNC
+/// paths to their `\\server\share` form.
+pub fn strip_extended(p: &Path) -> PathBuf {
+    let s = p.to_string_lossy();
+    if let Some(unc) = s.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(rest) = s.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        p.to_path_buf()
+    }
+}
+
+/// Checks if the path `p` begins with a UNC share root (`\\server\share`).
+fn is_unc(p: &Path) -> bool {
+    let s = p.to_string_lossy();
+    (s.starts_with(r"\\") || s.starts_with("//")) && s.starts_with(r"\\?\") == false
+}
+
 pub fn is_orbit_metadata(s: &str) -> bool {
     s == manifest::IP_MANIFEST_FILE || s == ORBIT_IGNORE_FILE || s == lockfile::IP_LOCK_FILE
 }
@@ -178,27 +374,22 @@ pub fn is_keep_override(target: &PathBuf, vip_list: &HashSet<PathBuf>) -> bool {
 /// If immutable is `true`, then read_only permissions will be enabled, else the files
 /// will be mutable. Silently skips files that could be changed with mutability/permissions.
 pub fn copy(source: &PathBuf, target: &PathBuf, minimal: bool, keep: Option<HashSet<PathBuf>>) -> Result<(), Fault> {
+    // use extended-length paths to support deep directories on windows
+    let target = &to_extended(target);
     // create missing directories to `target`
     std::fs::create_dir_all(&target)?;
     // gather list of paths to copy
     let mut from_paths = Vec::new();
 
-    // respect .orbitignore by using `WalkBuilder`
-    for result in WalkBuilder::new(&source)
-        .hidden(false)
-        .add_custom_ignore_filename(ORBIT_IGNORE_FILE)
+    // copy the same files as when gathering files
+    for path in walk_ip(&source) {
         // only capture files that are required by minimal installations
-        .filter_entry(move |f| {
-            f.path().is_file() == false
-                || minimal == false
-                || is_minimal(&f.file_name().to_string_lossy()) == true
-                || (keep.is_some() && is_keep_override(&f.path().to_path_buf(), &keep.as_ref().unwrap()) == true)
-        })
-        .build()
-    {
-        match result {
-            Ok(entry) => from_paths.push(entry.path().to_path_buf()),
-            Err(_) => (),
+        if path.is_file() == false
+            || minimal == false
+            || is_minimal(&path.file_name().unwrap().to_string_lossy()) == true
+            || (keep.is_some() && is_keep_override(&path, &keep.as_ref().unwrap()) == true)
+        {
+            from_paths.push(path);
         }
     }
     // create all missing directories
@@ -214,7 +405,7 @@ pub fn copy(source: &PathBuf, target: &PathBuf, minimal: bool, keep: Option<Hash
             let to = target
                 .join(remove_base(&source, &parent.to_path_buf()))
                 .join(from.file_name().unwrap());
-            std::fs::copy(from, &to)?;
+            std::fs::copy(to_extended(from), &to)?;
         }
     }
     // remove all empty directories
@@ -284,7 +475,9 @@ impl Standardize for PathBuf {
     where
         T: Into<PathBuf>,
     {
-        let p: PathBuf = p.into();
+        let p: PathBuf = strip_extended(&p.into());
+        // the leading double slash of a UNC share root must be preserved
+        let unc = is_unc(&p);
         // break the path into parts
         let mut parts = p.components();
 
@@ -335,20 +528,22 @@ impl Standardize for PathBuf {
         }
         // assemble new path
         let mut first = true;
-        PathBuf::from(
-            result
-                .into_iter()
-                .fold(String::new(), |x, y| {
-                    if first == true {
-                        first = false;
-                        x + &y
-                    } else {
-                        x + "/" + &y
-                    }
-                })
-                .replace("\\", "/")
-                .replace("//", "/"),
-        )
+        let path = result
+            .into_iter()
+            .fold(String::new(), |x, y| {
+                if first == true {
+                    first = false;
+                    x + &y
+                } else {
+                    x + "/" + &y
+                }
+            })
+            .replace("\\", "/")
+            .replace("//", "/");
+        match unc {
+            true => PathBuf::from(format!("/{}", path)),
+            false => PathBuf::from(path),
+        }
         // @todo: add some fail-safe where if the final path does not exist then return the original path?
     }
 }
@@ -362,10 +557,32 @@ pub fn invoke(
     cmd: &String,
     args: &Vec<String>,
     try_again: bool,
+    piped_stdin: bool,
 ) -> std::io::Result<std::process::Child> {
-    match std::process::Command::new(cmd)
+    invoke_with_env(dir, cmd, args, None, try_again, piped_stdin)
+}
+
+/// Spawns the `cmd` like [invoke], but replaces the inherited environment with
+/// exactly the variables in `env` when it is provided.
+pub fn invoke_with_env(
+    dir: &str,
+    cmd: &String,
+    args: &Vec<String>,
+    env: Option<&BTreeMap<String, String>>,
+    try_again: bool,
+    piped_stdin: bool,
+) -> std::io::Result<std::process::Child> {
+    let mut proc = std::process::Command::new(cmd);
+    if let Some(vars) = env {
+        proc.env_clear().envs(vars);
+    }
+    match proc
         .current_dir(dir)
         .args(args)
+        .stdin(match piped_stdin {
+            true => std::process::Stdio::piped(),
+            false => std::process::Stdio::inherit(),
+        })
         .stdout(std::process::Stdio::inherit())
         .stderr(std::process::Stdio::inherit())
         .spawn()
@@ -379,7 +596,7 @@ pub fn invoke(
                     None => true,
                 };
             if repeat == true && e.kind() == std::io::ErrorKind::NotFound {
-                invoke(dir, &format!("{}.bat", cmd), args, false)
+                invoke_with_env(dir, &format!("{}.bat", cmd), args, env, false, piped_stdin)
             } else {
                 Err(e)
             }
@@ -387,7 +604,7 @@ pub fn invoke(
     }
 }
 
-const ORBIT_IGNORE_FILE: &str = ".orbitignore";
+pub const ORBIT_IGNORE_FILE: &str = ".orbitignore";
 
 #[cfg(test)]
 mod test {
@@ -465,6 +682,124 @@ mod test {
         assert_eq!(remove_base(&base, &full), PathBuf::from("Orbit.toml"));
     }
 
+    #[test]
+    fn rem_base_extended() {
+        let base = PathBuf::from(r"\\?\c:/users/kepler/cache");
+        let full = PathBuf::from("c:/users/kepler/cache/gates/Orbit.toml");
+        assert_eq!(remove_base(&base, &full), PathBuf::from("gates/Orbit.toml"));
+    }
+
+    #[test]
+    fn extended_paths() {
+        assert_eq!(
+            extend_windows_path("C:/users/kepler/./projects/../cache"),
+            r"\\?\C:\users\kepler\cache"
+        );
+        assert_eq!(extend_windows_path(r"C:\"), r"\\?\C:\");
+        assert_eq!(
+            extend_windows_path(r"\\server\share\ip\gates"),
+            r"\\?\UNC\server\share\ip\gates"
+        );
+        // already extended or relative paths are not modified
+        assert_eq!(extend_windows_path(r"\\?\C:\a"), r"\\?\C:\a");
+        assert_eq!(extend_windows_path("a/b"), r"a\b");
+
+        assert_eq!(
+            strip_extended(&PathBuf::from(r"\\?\UNC\server\share\ip")),
+            PathBuf::from(r"\\server\share\ip")
+        );
+        assert_eq!(
+            strip_extended(&PathBuf::from(r"\\?\C:\ip")),
+            PathBuf::from(r"C:\ip")
+        );
+    }
+
+    #[test]
+    fn standardize_unc() {
+        let p = PathBuf::from(r"\\server\share\ip\gates");
+        assert_eq!(PathBuf::standardize(p), PathBuf::from("//server/share/ip/gates"));
+        let p = PathBuf::from(r"\\?\UNC\server\share\ip");
+        assert_eq!(PathBuf::standardize(p), PathBuf::from("//server/share/ip"));
+    }
+
+    /// Creates an ip at `dir` whose .orbitignore file uses negation and directory
+    /// patterns.
+    fn create_ignored_ip(dir: &PathBuf) {
+        std::fs::create_dir_all(dir.join("rtl")).unwrap();
+        std::fs::create_dir_all(dir.join("sim")).unwrap();
+        for file in ["rtl/a.vhd", "rtl/b.vhd", "rtl/keep.txt", "rtl/skip.txt", "sim/out.vhd"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        std::fs::write(dir.join(ORBIT_IGNORE_FILE), "*.txt\n!keep.txt\nsim/\n!a.vhd\n").unwrap();
+    }
+
+    #[test]
+    fn ignore_patterns_agree() {
+        let root = tempdir().unwrap();
+        let source = root.path().join("source");
+        create_ignored_ip(&source);
+        let files = gather_current_files(&source, true);
+        assert_eq!(files, vec![".orbitignore", "rtl/a.vhd", "rtl/b.vhd", "rtl/keep.txt"]);
+        // installing copies exactly the files that are gathered
+        let target = root.path().join("target");
+        copy(&source, &target, false, None).unwrap();
+        assert_eq!(gather_current_files(&target, true), files);
+    }
+
+    #[test]
+    fn manifest_include_exclude() {
+        let root = tempdir().unwrap();
+        let source = root.path().join("source");
+        create_ignored_ip(&source);
+        std::fs::write(
+            source.join(manifest::IP_MANIFEST_FILE),
+            "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\ninclude = [\"rtl/\"]\nexclude = [\"b.vhd\"]\n",
+        )
+        .unwrap();
+        let files = gather_current_files(&source, true);
+        // the manifest and .orbitignore always belong to the ip
+        assert_eq!(files, vec![".orbitignore", "Orbit.toml", "rtl/a.vhd", "rtl/keep.txt"]);
+        // copying and archiving select exactly the files that are gathered
+        let target = root.path().join("target");
+        copy(&source, &target, false, None).unwrap();
+        assert_eq!(gather_current_files(&target, true), files);
+
+        let archive = root.path().join("source.zip");
+        crate::util::compress::write_zip_dir(&source, &archive).unwrap();
+        let zip = zip::ZipArchive::new(std::fs::File::open(&archive).unwrap()).unwrap();
+        let mut names: Vec<String> = zip
+            .file_names()
+            .filter(|n| n.ends_with('/') == false)
+            .map(|n| n.replace('\\', "/"))
+            .collect();
+        names.sort();
+        assert_eq!(names, files);
+    }
+
+    #[test]
+    fn global_ignore_precedence() {
+        let root = tempdir().unwrap();
+        let source = root.path().join("source");
+        create_ignored_ip(&source);
+        let global = root.path().join("ignore");
+        std::fs::write(&global, "*.vhd\nkeep.txt\n").unwrap();
+        let mut files: Vec<String> = walker(&source, Some(&global))
+            .build()
+            .filter_map(|r| r.ok())
+            .filter(|e| e.path().is_file())
+            .map(|e| into_std_str(remove_base(&source, &e.into_path())))
+            .collect();
+        files.sort();
+        // negations in the .orbitignore file override the global ignore file
+        assert_eq!(files, vec![".orbitignore", "rtl/a.vhd", "rtl/keep.txt"]);
+        assert_eq!(gather_working_files(&source, true, Some(&global)), files);
+        // the global ignore file never changes what gets hashed or archived
+        assert_eq!(
+            gather_current_files(&source, true),
+            vec![".orbitignore", "rtl/a.vhd", "rtl/b.vhd", "rtl/keep.txt"]
+        );
+    }
+
     #[test]
     fn copy_minimal() {
         let source = PathBuf::from("test/data/projects");
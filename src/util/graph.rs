@@ -312,6 +312,38 @@ impl<V, E> Graph<V, E> {
         self.recurse_treeview(target, Twig::EndLeaf(None))
     }
 
+    /// Collects every path through the predecessors of `source` that ends at
+    /// `target`, listing the nodes of each path from `source` to `target`.
+    ///
+    /// A node is never visited twice within the same path, so cycles are not followed.
+    pub fn find_paths(&self, source: NodeIndex, target: NodeIndex) -> Vec<Vec<NodeIndex>> {
+        let mut paths = Vec::new();
+        self.recurse_paths(&mut vec![source], target, &mut paths);
+        paths
+    }
+
+    /// Extends the current `path` through each predecessor of its last node,
+    /// storing it into `paths` once it reaches `target`.
+    fn recurse_paths(
+        &self,
+        path: &mut Vec<NodeIndex>,
+        target: NodeIndex,
+        paths: &mut Vec<Vec<NodeIndex>>,
+    ) {
+        let current = *path.last().unwrap();
+        if current == target {
+            paths.push(path.clone());
+            return;
+        }
+        for n in self.predecessors(current) {
+            if path.contains(&n) == false {
+                path.push(n);
+                self.recurse_paths(path, target, paths);
+                path.pop();
+            }
+        }
+    }
+
     /// Removes duplicate branches from the treeview and replaces them with labels.
     pub fn compress_treeview(&self, _tree: &Vec<(Twig, NodeIndex)>) -> Vec<(Twig, NodeIndex)> {
         todo!()
@@ -415,6 +447,14 @@ pub enum Twig {
 }
 
 impl Twig {
+    /// Computes the number of parents above `self` in the tree.
+    pub fn depth(&self) -> usize {
+        match self.get_upper() {
+            Some(upper) => upper.depth() + 1,
+            None => 0,
+        }
+    }
+
     /// Accesses what type of node was the parent to the current `self`.
     pub fn get_upper(&self) -> Option<&Twig> {
         match self {
@@ -664,6 +704,19 @@ mod test {
         g
     }
 
+    #[test]
+    fn find_paths() {
+        let mut g = binary_tree();
+        g.add_edge(4, 2, ());
+        assert_eq!(g.find_paths(0, 6), vec![vec![0, 4, 6], vec![0, 1, 2, 4, 6]]);
+        assert_eq!(g.find_paths(0, 3), vec![vec![0, 1, 3]]);
+        assert_eq!(g.find_paths(1, 5), vec![vec![1, 2, 4, 5]]);
+        assert_eq!(g.find_paths(4, 3).is_empty(), true);
+        // a cycle is not followed
+        g.add_edge(0, 6, ());
+        assert_eq!(g.find_paths(0, 3), vec![vec![0, 1, 3]]);
+    }
+
     #[test]
     fn is_cyclic() {
         let mut g = basic_graph();
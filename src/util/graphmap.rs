@@ -104,6 +104,33 @@ impl<K: Eq + Hash + Clone, V, E> GraphMap<K, V, E> {
         &self.graph
     }
 
+    /// Creates an iterator over the outgoing nodes from the node at `key`.
+    ///
+    /// Returns `None` if no node exists for the `key`.
+    pub fn successors_by_key(&self, key: &K) -> Option<SuccessorsGraphMap<'_, K, V, E>> {
+        Some(self.successors(self.map.get(key)?.index()))
+    }
+
+    /// Creates an iterator over the incoming nodes to the node at `key`.
+    ///
+    /// Returns `None` if no node exists for the `key`.
+    pub fn predecessors_by_key(&self, key: &K) -> Option<PredecessorsGraphMap<'_, K, V, E>> {
+        Some(self.predecessors(self.map.get(key)?.index()))
+    }
+
+    /// Orders the keys of the entire graph such that every key comes after the
+    /// keys it has incoming edges from.
+    pub fn topological_sort(&self) -> Vec<&K> {
+        self.to_keys(self.graph.topological_sort())
+    }
+
+    fn to_keys(&self, indices: Vec<NodeIndex>) -> Vec<&K> {
+        indices
+            .into_iter()
+            .map(|i| self.graph.get_node(i).unwrap())
+            .collect()
+    }
+
     pub fn iter(&self) -> IterGraphMap<K, V, E> {
         IterGraphMap {
             graph: &self,
@@ -200,3 +227,39 @@ impl<'graph, K: 'graph + Eq + Hash + Clone, V: 'graph, E: 'graph>
         graph
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Creates a graph where each letter depends on the letters after it.
+    fn letters() -> GraphMap<&'static str, usize, ()> {
+        let mut g = GraphMap::new();
+        ["d", "c", "b", "a"].iter().enumerate().for_each(|(i, k)| {
+            g.add_node(*k, i);
+        });
+        g.add_edge_by_key(&"b", &"a", ());
+        g.add_edge_by_key(&"c", &"a", ());
+        g.add_edge_by_key(&"d", &"c", ());
+        g
+    }
+
+    #[test]
+    fn sort_by_key() {
+        let g = letters();
+        assert_eq!(g.topological_sort(), vec![&"d", &"c", &"b", &"a"]);
+    }
+
+    #[test]
+    fn neighbors_by_key() {
+        let g = letters();
+        let deps: Vec<&&str> = g.predecessors_by_key(&"a").unwrap().map(|(k, _, _)| k).collect();
+        assert_eq!(deps.len(), 2);
+        assert!(deps.contains(&&"b") && deps.contains(&&"c"));
+        assert_eq!(
+            g.successors_by_key(&"d").unwrap().map(|(k, v, _)| (*k, *v)).collect::<Vec<_>>(),
+            vec![("c", 1)]
+        );
+        assert!(g.successors_by_key(&"e").is_none());
+    }
+}
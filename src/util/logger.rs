@@ -0,0 +1,94 @@
+//! A small logging facility for the messages reported while a command runs.
+//!
+//! Logged messages are written to stderr so that stdout only holds a command's
+//! results. The amount of messages is controlled by the global `--quiet` and
+//! `-v` options.
+
+use colored::Colorize;
+use std::fmt::Display;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Level {
+    /// Only warnings are displayed.
+    Quiet = 0,
+    /// Warnings and general information are displayed.
+    Normal = 1,
+    /// Additional details about each step are displayed (`-v`).
+    Verbose = 2,
+    /// Internal details useful for troubleshooting are displayed (`-vv`).
+    Debug = 3,
+}
+
+impl Level {
+    fn from_u8(n: u8) -> Self {
+        match n {
+            0 => Self::Quiet,
+            1 => Self::Normal,
+            2 => Self::Verbose,
+            _ => Self::Debug,
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+/// Sets the level of detail for every message logged afterward.
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn get_level() -> Level {
+    Level::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+/// Checks if messages at `level` are currently displayed.
+pub fn is_enabled(level: Level) -> bool {
+    get_level() >= level
+}
+
+/// Reports a possible problem that does not stop the command.
+pub fn warn<T: Display>(msg: T) {
+    eprintln!("{}: {}", "warning".yellow().bold(), msg);
+}
+
+/// Reports the progress of a command.
+pub fn info<T: Display>(msg: T) {
+    if is_enabled(Level::Normal) == true {
+        eprintln!("info: {}", msg);
+    }
+}
+
+/// Reports details about the progress of a command.
+pub fn verbose<T: Display>(msg: T) {
+    if is_enabled(Level::Verbose) == true {
+        eprintln!("info: {}", msg);
+    }
+}
+
+/// Reports internal details for troubleshooting.
+pub fn debug<T: Display>(msg: T) {
+    if is_enabled(Level::Debug) == true {
+        eprintln!("{}: {}", "debug".blue(), msg);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        assert!(Level::Quiet < Level::Normal);
+        assert!(Level::Verbose < Level::Debug);
+        assert_eq!(Level::from_u8(1), Level::Normal);
+        assert_eq!(Level::from_u8(9), Level::Debug);
+
+        set_level(Level::Verbose);
+        assert_eq!(is_enabled(Level::Normal), true);
+        assert_eq!(is_enabled(Level::Verbose), true);
+        assert_eq!(is_enabled(Level::Debug), false);
+        set_level(Level::Normal);
+    }
+}
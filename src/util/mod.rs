@@ -1,12 +1,20 @@
 pub mod anyerror;
 pub mod checksum;
 pub mod compress;
+pub mod date;
+pub mod diagnostic;
 pub mod environment;
+pub mod filelock;
 pub mod filesystem;
 pub mod graph;
 pub mod graphmap;
+pub mod logger;
+pub mod output;
 pub mod overdetsys;
+pub mod parallel;
+pub mod progress;
 pub mod prompt;
 pub mod seqalin;
 pub mod sha256;
 pub mod strcmp;
+pub mod timing;
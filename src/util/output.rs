@@ -0,0 +1,126 @@
+//! Formats for displaying the information reported by a command.
+//!
+//! Commands that support machine-readable output wrap their data in a versioned
+//! JSON document so that scripts can rely on a stable structure.
+
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use serde_derive::Serialize;
+use std::str::FromStr;
+
+/// The version of the structure for every JSON document written by a command.
+///
+/// This number increments whenever an existing field is removed or changes meaning.
+pub const OUTPUT_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!(
+                "unknown output format '{}'; expected 'text' or 'json'",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a, T: serde::Serialize> {
+    version: u32,
+    command: &'a str,
+    data: &'a T,
+}
+
+/// Serializes the `data` reported by the `command` into a versioned JSON document.
+pub fn to_json<T: serde::Serialize>(command: &str, data: &T) -> Result<String, Fault> {
+    Ok(serde_json::to_string_pretty(&Report {
+        version: OUTPUT_VERSION,
+        command,
+        data,
+    })?)
+}
+
+/// A named entry within a hierarchy, such as a design unit or an ip dependency.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TreeNode {
+    name: String,
+    children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Builds the trees from a list of nodes ordered from top to bottom, where
+    /// each node is paired with its depth in the hierarchy.
+    pub fn from_depths(nodes: Vec<(usize, String)>) -> Vec<Self> {
+        let mut roots = Vec::new();
+        let mut stack: Vec<(usize, TreeNode)> = Vec::new();
+        for (depth, name) in nodes {
+            Self::collapse(&mut stack, &mut roots, depth);
+            stack.push((depth, TreeNode::new(&name)));
+        }
+        Self::collapse(&mut stack, &mut roots, 0);
+        roots
+    }
+
+    /// Moves every node on the `stack` at or below `depth` into its parent, or
+    /// into `roots` when it has no parent.
+    fn collapse(stack: &mut Vec<(usize, TreeNode)>, roots: &mut Vec<TreeNode>, depth: usize) {
+        while stack.last().map(|(d, _)| *d >= depth).unwrap_or(false) == true {
+            let (_, node) = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tree_from_depths() {
+        let nodes = vec![
+            (0, String::from("top")),
+            (1, String::from("adder")),
+            (2, String::from("full_add")),
+            (1, String::from("reg")),
+            (0, String::from("top_tb")),
+        ];
+        let mut adder = TreeNode::new("adder");
+        adder.children.push(TreeNode::new("full_add"));
+        let mut top = TreeNode::new("top");
+        top.children.push(adder);
+        top.children.push(TreeNode::new("reg"));
+        assert_eq!(
+            TreeNode::from_depths(nodes),
+            vec![top, TreeNode::new("top_tb")]
+        );
+    }
+
+    #[test]
+    fn versioned_json() {
+        let json = to_json("search", &vec!["gates"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], OUTPUT_VERSION);
+        assert_eq!(value["command"], "search");
+        assert_eq!(value["data"][0], "gates");
+    }
+}
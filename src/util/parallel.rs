@@ -0,0 +1,52 @@
+//! Spreads independent work across a bounded number of threads.
+
+/// Applies `f` to every item in `items` using at most one thread per available
+/// core.
+///
+/// Each thread is given a contiguous group of items so the results are always
+/// returned in the same order as `items`, regardless of the number of threads.
+/// The work stays on the current thread when there are fewer than `min_items`
+/// items, since spawning threads costs more than it saves for small sets.
+pub fn map_ordered<T, R, F>(items: &[T], min_items: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    if workers < 2 || items.len() < min_items || items.len() < 2 {
+        return items.iter().map(f).collect();
+    }
+    let group_size = (items.len() + workers - 1) / workers;
+    let f = &f;
+    std::thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(group_size)
+            .map(|group| s.spawn(move || group.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keeps_order() {
+        let items: Vec<usize> = (0..1000).collect();
+        let squares = map_ordered(&items, 0, |i| i * i);
+        assert_eq!(squares, items.iter().map(|i| i * i).collect::<Vec<usize>>());
+        // small sets run on the current thread with the same results
+        assert_eq!(map_ordered(&items[..3], 64, |i| i + 1), vec![1, 2, 3]);
+        assert_eq!(
+            map_ordered(&Vec::<usize>::new(), 0, |i| i + 1),
+            Vec::<usize>::new()
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Reports the progress of long operations, such as installing ip or parsing
+//! the files of a design.
+//!
+//! Events are written to stderr so that stdout only holds a command's results.
+//! By default, each processed item is displayed as text at the verbose level.
+//! The global `--progress json` option instead writes every event as a single
+//! line of JSON for wrappers and editor integrations to consume.
+
+use crate::util::logger;
+use crate::util::output::OutputFormat;
+use serde_derive::Serialize;
+use std::sync::Mutex;
+
+/// The point reached within an operation.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Start,
+    Advance,
+    Finish,
+}
+
+/// A single update about the progress of an operation.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Event<'a> {
+    operation: &'a str,
+    stage: Stage,
+    /// The number of items processed so far.
+    current: usize,
+    /// The number of items to process, if known ahead of time.
+    total: Option<usize>,
+    /// The item that was just processed.
+    item: Option<&'a str>,
+}
+
+impl<'a> Event<'a> {
+    /// Formats the event as a human-readable message, if it is worth displaying.
+    ///
+    /// Only the items processed are displayed.
+    pub fn to_text(&self) -> Option<String> {
+        if self.stage != Stage::Advance {
+            return None;
+        }
+        let count = match self.total {
+            Some(t) => format!("[{}/{}]", self.current, t),
+            None => format!("[{}]", self.current),
+        };
+        Some(match self.item {
+            Some(item) => format!("{} {} {}", count, self.operation, item),
+            None => format!("{} {}", count, self.operation),
+        })
+    }
+}
+
+/// A destination for progress events.
+pub trait Reporter {
+    fn report(&self, event: &Event);
+}
+
+/// Displays events as text alongside the other logged messages.
+pub struct Terminal;
+
+impl Reporter for Terminal {
+    fn report(&self, event: &Event) {
+        if let Some(text) = event.to_text() {
+            logger::verbose(text);
+        }
+    }
+}
+
+/// Writes every event as a line of JSON.
+pub struct JsonLines;
+
+impl Reporter for JsonLines {
+    fn report(&self, event: &Event) {
+        if let Ok(line) = serde_json::to_string(event) {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+static REPORTER: Mutex<Option<Box<dyn Reporter + Send>>> = Mutex::new(None);
+
+/// Sends every event reported afterward to the `reporter`.
+pub fn set_reporter(reporter: Box<dyn Reporter + Send>) {
+    if let Ok(mut rep) = REPORTER.lock() {
+        *rep = Some(reporter);
+    }
+}
+
+/// Selects the builtin reporter for the `format`.
+pub fn set_format(format: OutputFormat) {
+    match format {
+        OutputFormat::Text => set_reporter(Box::new(Terminal)),
+        OutputFormat::Json => set_reporter(Box::new(JsonLines)),
+    }
+}
+
+fn emit(event: &Event) {
+    match REPORTER.lock() {
+        Ok(rep) => match rep.as_ref() {
+            Some(r) => r.report(event),
+            None => Terminal.report(event),
+        },
+        Err(_) => (),
+    }
+}
+
+/// An operation being tracked, which reports when it starts, as each item is
+/// processed, and once it goes out of scope.
+#[derive(Debug)]
+pub struct Progress {
+    operation: &'static str,
+    current: usize,
+    total: Option<usize>,
+}
+
+impl Progress {
+    /// Begins the `operation` that will process `total` items, if known.
+    pub fn start(operation: &'static str, total: Option<usize>) -> Self {
+        let progress = Self {
+            operation,
+            current: 0,
+            total,
+        };
+        progress.emit(Stage::Start, None);
+        progress
+    }
+
+    /// Records that the `item` was processed.
+    pub fn advance<T: AsRef<str>>(&mut self, item: T) {
+        self.current += 1;
+        self.emit(Stage::Advance, Some(item.as_ref()));
+    }
+
+    fn emit(&self, stage: Stage, item: Option<&str>) {
+        emit(&Event {
+            operation: self.operation,
+            stage,
+            current: self.current,
+            total: self.total,
+            item,
+        })
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.emit(Stage::Finish, None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn event_text() {
+        let event = Event {
+            operation: "install",
+            stage: Stage::Advance,
+            current: 2,
+            total: Some(5),
+            item: Some("gates:1.0.0"),
+        };
+        assert_eq!(
+            event.to_text(),
+            Some(String::from("[2/5] install gates:1.0.0"))
+        );
+
+        let event = Event {
+            operation: "scan",
+            stage: Stage::Advance,
+            current: 7,
+            total: None,
+            item: None,
+        };
+        assert_eq!(event.to_text(), Some(String::from("[7] scan")));
+
+        let event = Event {
+            operation: "scan",
+            stage: Stage::Start,
+            current: 0,
+            total: None,
+            item: None,
+        };
+        assert_eq!(event.to_text(), None);
+    }
+
+    #[test]
+    fn event_json() {
+        let event = Event {
+            operation: "fetch",
+            stage: Stage::Finish,
+            current: 3,
+            total: Some(3),
+            item: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"operation":"fetch","stage":"finish","current":3,"total":3,"item":null}"#
+        );
+    }
+}
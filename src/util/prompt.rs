@@ -2,6 +2,20 @@ use colored::ColoredString;
 use colored::Colorize;
 use std::io;
 use std::io::{Error, Read};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Sets if prompts wait for user input. When disabled, every prompt answers
+/// itself without reading stdin so that automated runs never hang.
+pub fn set_interactive(enabled: bool) {
+    INTERACTIVE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
 
 /// Conditionally operates on `status` to return an string representation.
 pub fn report_eval(status: bool) -> ColoredString {
@@ -13,11 +27,44 @@ pub fn report_eval(status: bool) -> ColoredString {
 
 /// Outputs the text `s` with a ? mark and y/n option. Accepts '\n' or
 /// 'y' to return `true`, and `n` to return `false`.
+///
+/// Always returns `true` when prompts are not interactive.
 pub fn prompt(s: &str) -> Result<bool, Error> {
+    if is_interactive() == false {
+        println!("{}? [y/n] y", s);
+        return Ok(true);
+    }
     println!("{}? [y/n]", s);
     check_for_response(&mut io::stdin().lock())
 }
 
+/// Outputs the text `s` and waits for a line of user input. An empty response
+/// is replaced with `default` if one is provided.
+///
+/// The returned value has its surrounding whitespace removed. When prompts are
+/// not interactive, the `default` (or an empty response) is returned immediately.
+pub fn input(s: &str, default: Option<&str>) -> Result<String, Error> {
+    if is_interactive() == false {
+        return Ok(default.unwrap_or_default().to_string());
+    }
+    match default {
+        Some(d) => println!("{} [{}]:", s, d),
+        None => println!("{}:", s),
+    }
+    read_input(&mut io::stdin().lock(), default)
+}
+
+/// Reads a single line from `input` and falls back to `default` when the line is empty.
+fn read_input(input: &mut (impl Read + std::io::BufRead), default: Option<&str>) -> Result<String, Error> {
+    let mut buffer: String = String::new();
+    input.read_line(&mut buffer)?;
+    let answer = buffer.trim();
+    match (answer.is_empty(), default) {
+        (true, Some(d)) => Ok(d.to_string()),
+        _ => Ok(answer.to_string()),
+    }
+}
+
 /// Infinitely loops until a valid response is entered. "Y\n" and "\n" map to `true`, while
 /// "N\n" maps to `false`.
 ///
@@ -58,6 +105,18 @@ mod test {
         assert_eq!(r, true);
     }
 
+    #[test]
+    fn line_input() {
+        let r = read_input(&mut "kepler\n".as_bytes(), None).unwrap();
+        assert_eq!(r, "kepler");
+        let r = read_input(&mut "  kepler \r\n".as_bytes(), Some("chase")).unwrap();
+        assert_eq!(r, "kepler");
+        let r = read_input(&mut "\n".as_bytes(), Some("chase")).unwrap();
+        assert_eq!(r, "chase");
+        let r = read_input(&mut "\n".as_bytes(), None).unwrap();
+        assert_eq!(r, "");
+    }
+
     #[test]
     fn windows_style() {
         let r = check_for_response(&mut "y\r\n".as_bytes()).unwrap();
@@ -0,0 +1,210 @@
+//! Measures how long the phases of a command take to help diagnose slow
+//! commands on large projects.
+//!
+//! Nothing is recorded until timings are enabled with the global `--timings`
+//! or `--trace` options.
+
+use crate::util::anyerror::Fault;
+use serde_derive::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug)]
+struct Phase {
+    name: String,
+    start: Duration,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+struct Recorder {
+    origin: Instant,
+    phases: Vec<Phase>,
+}
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Begins recording the phases for every span created afterward.
+pub fn enable() {
+    if let Ok(mut rec) = RECORDER.lock() {
+        *rec = Some(Recorder {
+            origin: Instant::now(),
+            phases: Vec::new(),
+        });
+    }
+}
+
+pub fn is_enabled() -> bool {
+    RECORDER.lock().map(|r| r.is_some()).unwrap_or(false)
+}
+
+/// Measures the phase called `name` until the returned span is dropped.
+pub fn span<T: AsRef<str>>(name: T) -> Span {
+    Span {
+        name: match is_enabled() {
+            true => Some(name.as_ref().to_string()),
+            false => None,
+        },
+        start: Instant::now(),
+    }
+}
+
+/// A phase being measured, which is recorded once it goes out of scope.
+#[derive(Debug)]
+pub struct Span {
+    name: Option<String>,
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let name = match self.name.take() {
+            Some(n) => n,
+            None => return,
+        };
+        let duration = self.start.elapsed();
+        if let Ok(mut rec) = RECORDER.lock() {
+            if let Some(rec) = rec.as_mut() {
+                let start = self.start.saturating_duration_since(rec.origin);
+                rec.phases.push(Phase {
+                    name,
+                    start,
+                    duration,
+                });
+            }
+        }
+    }
+}
+
+/// Combines the recorded phases sharing the same name into their total duration
+/// and number of occurrences, in the order each phase first began.
+fn summarize(phases: &[Phase]) -> Vec<(&str, Duration, usize)> {
+    let mut ordered: Vec<&Phase> = phases.iter().collect();
+    ordered.sort_by_key(|p| p.start);
+    let mut totals: Vec<(&str, Duration, usize)> = Vec::new();
+    for phase in ordered {
+        match totals.iter_mut().find(|(n, _, _)| *n == phase.name) {
+            Some(entry) => {
+                entry.1 += phase.duration;
+                entry.2 += 1;
+            }
+            None => totals.push((&phase.name, phase.duration, 1)),
+        }
+    }
+    totals
+}
+
+fn fmt_summary(phases: &[Phase]) -> String {
+    let mut table = String::from("Timings:\n");
+    for (name, total, count) in summarize(phases) {
+        let name = match count {
+            1 => name.to_string(),
+            _ => format!("{} (x{})", name, count),
+        };
+        table += &format!(
+            "  {:<32}{:>10.3} ms\n",
+            name,
+            total.as_secs_f64() * 1000.0
+        );
+    }
+    table
+}
+
+/// An event in the trace event format read by tools such as chrome://tracing,
+/// perfetto, and speedscope.
+#[derive(Debug, Serialize)]
+struct TraceEvent<'a> {
+    name: &'a str,
+    ph: &'a str,
+    /// Start time in microseconds.
+    ts: u64,
+    /// Duration in microseconds.
+    dur: u64,
+    pid: u32,
+    tid: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace<'a> {
+    trace_events: Vec<TraceEvent<'a>>,
+}
+
+fn to_trace(phases: &[Phase]) -> Trace<'_> {
+    Trace {
+        trace_events: phases
+            .iter()
+            .map(|p| TraceEvent {
+                name: &p.name,
+                ph: "X",
+                ts: p.start.as_micros() as u64,
+                dur: p.duration.as_micros() as u64,
+                pid: std::process::id(),
+                tid: 0,
+            })
+            .collect(),
+    }
+}
+
+/// Displays the summary of the recorded phases to stderr and writes the
+/// detailed trace to the `trace` file, if provided.
+pub fn report(trace: Option<&PathBuf>) -> Result<(), Fault> {
+    let rec = match RECORDER.lock() {
+        Ok(r) => r,
+        Err(_) => return Ok(()),
+    };
+    let rec = match rec.as_ref() {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+    eprint!("{}", fmt_summary(&rec.phases));
+    if let Some(path) = trace {
+        std::fs::write(path, serde_json::to_string_pretty(&to_trace(&rec.phases))?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn phase(name: &str, start: u64, duration: u64) -> Phase {
+        Phase {
+            name: name.to_string(),
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(duration),
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let phases = vec![
+            phase("parse", 2, 3),
+            phase("gather files", 0, 2),
+            phase("parse", 5, 4),
+        ];
+        assert_eq!(
+            summarize(&phases),
+            vec![
+                ("gather files", Duration::from_millis(2), 1),
+                ("parse", Duration::from_millis(7), 2),
+            ]
+        );
+        assert_eq!(
+            fmt_summary(&phases),
+            "Timings:\n  gather files                         2.000 ms\n  parse (x2)                           7.000 ms\n"
+        );
+    }
+
+    #[test]
+    fn trace() {
+        let phases = vec![phase("plan", 1, 5)];
+        let trace = to_trace(&phases);
+        assert_eq!(trace.trace_events.len(), 1);
+        assert_eq!(trace.trace_events[0].ts, 1000);
+        assert_eq!(trace.trace_events[0].dur, 5000);
+        assert_eq!(trace.trace_events[0].ph, "X");
+    }
+}
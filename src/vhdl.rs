@@ -0,0 +1,108 @@
+//! Entity interfaces read from VHDL source code.
+//!
+//! ```no_run
+//! let code = std::fs::read_to_string("and_gate.vhd").unwrap();
+//! for entity in orbit::vhdl::read_entities(&code) {
+//!     for port in entity.get_ports().iter() {
+//!         println!("{} : {} {}", port.get_name(), port.get_mode(), port.get_type());
+//!     }
+//! }
+//! ```
+
+pub use crate::core::lang::vhdl::interface::{
+    Generics, InterfaceDeclaration, InterfaceDeclarations, InterfaceKind, Ports,
+};
+pub use crate::core::lang::vhdl::symbol::Entity;
+pub use crate::core::lang::vhdl::token::Identifier;
+
+use crate::core::lang::vhdl::symbol::VHDLParser;
+
+/// Parses the VHDL source `code` and collects its entities in the order they
+/// are declared.
+///
+/// Other design units are ignored, and so are any entities that fail to parse.
+pub fn read_entities(code: &str) -> Vec<Entity> {
+    VHDLParser::read(code)
+        .into_symbols()
+        .into_iter()
+        .filter_map(|s| s.into_entity())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    const DESIGN: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity fifo is
+    generic (
+        DEPTH : positive := 16;
+        WIDTH : positive
+    );
+    port (
+        clk, rst : in std_logic;
+        data_in : std_logic_vector(WIDTH-1 downto 0);
+        data_out : out std_logic_vector(WIDTH-1 downto 0) := (others => '0');
+        count : buffer natural
+    );
+end entity;
+
+package fifo_pkg is
+end package;
+";
+
+    #[test]
+    fn interface_data() {
+        let entities = read_entities(DESIGN);
+        assert_eq!(entities.len(), 1);
+        let fifo = &entities[0];
+        assert_eq!(fifo.get_name().to_string(), "fifo");
+
+        let generics: Vec<(String, Option<String>)> = fifo
+            .get_generics()
+            .iter()
+            .map(|g| (g.get_name().to_string(), g.get_default()))
+            .collect();
+        assert_eq!(
+            generics,
+            vec![
+                (String::from("DEPTH"), Some(String::from("16"))),
+                (String::from("WIDTH"), None),
+            ]
+        );
+
+        let ports: Vec<(String, String)> = fifo
+            .get_ports()
+            .iter()
+            .map(|p| (p.get_name().to_string(), p.get_mode()))
+            .collect();
+        assert_eq!(
+            ports,
+            vec![
+                (String::from("clk"), String::from("in")),
+                (String::from("rst"), String::from("in")),
+                (String::from("data_in"), String::from("in")),
+                (String::from("data_out"), String::from("out")),
+                (String::from("count"), String::from("buffer")),
+            ]
+        );
+
+        let data_out = fifo
+            .get_ports()
+            .get(&Identifier::from_str("data_out").unwrap())
+            .unwrap();
+        assert_eq!(data_out.get_type(), "std_logic_vector(WIDTH-1 downto 0)");
+        assert_eq!(data_out.get_default(), Some(String::from("(others => '0')")));
+        assert_eq!(data_out.is_bus(), false);
+        assert_eq!(
+            fifo.get_ports()
+                .get(&Identifier::from_str("missing").unwrap())
+                .is_none(),
+            true
+        );
+    }
+}
@@ -18,6 +18,7 @@ COMMANDS = [
     'init',
     'show',
     'read',
+    'status',
     'get',
     'tree',
     'plan',
@@ -26,11 +27,28 @@ COMMANDS = [
     'search',
     'download',
     'install',
+    'clone',
+    'fetch',
+    'vendor',
     'env',
     'config',
     'uninstall',
+    'explain',
+    'self',
+    'lsp',
+    'ci',
+    'test',
+    'graph',
+    'hash',
+    'stats',
+    'mv',
 ]
 
+# rust module names for commands that are reserved words in rust
+MODULES = {
+    'self': 'selfcmd',
+}
+
 CWD, _ = os.path.split(sys.argv[0])
 # define the path to the TOML file
 INPUT_TOML_PATH = str(CWD)+"/manuals.toml"
@@ -137,6 +155,7 @@ def write_rs_manual(table, dest, command) -> int:
     Rust format.
     '''
     table = table[command]
+    module = MODULES.get(command, command)
     
     # fill in intermediate directory structure
     os.makedirs(dest, exist_ok=True)
@@ -144,13 +163,13 @@ def write_rs_manual(table, dest, command) -> int:
     # check if added to mod.rs
     mod_exists = False
     with open(dest+'/'+'mod.rs', 'r') as mod:
-        mod_exists = mod.read().count('pub mod '+command+';') > 0
+        mod_exists = mod.read().count('pub mod '+module+';') > 0
     if mod_exists == False:
         with open(dest+'/'+'mod.rs', 'a') as mod:
-            mod.write('pub mod '+command+';')
+            mod.write('pub mod '+module+';')
         pass
     
-    path = dest+'/'+command+'.rs'
+    path = dest+'/'+module+'.rs'
 
     with open(path, 'w') as rs:
         # comment
@@ -213,6 +232,7 @@ def write_rs_help(table, dest, command, footer=True) -> int:
     if is_populated(table, HELP) == False:
         print('WARNING: No help text for command:', command)
         return 0
+    module = MODULES.get(command, command)
     
     # fill in intermediate directory structure
     os.makedirs(dest, exist_ok=True)
@@ -220,13 +240,13 @@ def write_rs_help(table, dest, command, footer=True) -> int:
     # check if added to mod.rs
     mod_exists = False
     with open(dest+'/'+'mod.rs', 'r') as mod:
-        mod_exists = mod.read().count('pub mod '+command+';') > 0
+        mod_exists = mod.read().count('pub mod '+module+';') > 0
     if mod_exists == False:
         with open(dest+'/'+'mod.rs', 'a') as mod:
-            mod.write('pub mod '+command+';'+END)
+            mod.write('pub mod '+module+';'+END)
         pass
 
-    path = dest+'/'+command+'.rs'
+    path = dest+'/'+module+'.rs'
 
     with open(path, 'w') as rs:
         # comment